@@ -0,0 +1,213 @@
+//! Caches the imports found in each file by the hash of its contents, so that
+//! tracing again only has to parse the files that changed.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+use turbopath::AbsoluteSystemPath;
+
+use crate::{import_finder::ImportType, tracer::ImportTraceType};
+
+// Bump this when the format of the cache or the way imports are found changes
+const CACHE_VERSION: u32 = 1;
+// Past this many entries, entries that weren't used are dropped when saving
+const MAX_ENTRIES: usize = 50_000;
+
+/// An import found in a file, with everything needed to check it without
+/// parsing the file again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileImport {
+    pub specifier: String,
+    pub import_type: ImportType,
+    /// Character offsets of the import in the file
+    pub start: usize,
+    pub end: usize,
+    /// The zero-indexed line that the import is on
+    pub line: usize,
+    /// The text of the comments directly before the import
+    pub leading_comments: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    entries: HashMap<String, Vec<FileImport>>,
+}
+
+/// Imports by file, keyed by [`ImportCache::key`]. The cache is shared by all
+/// of the tasks tracing files, so it uses interior mutability.
+#[derive(Debug, Default)]
+pub struct ImportCache {
+    entries: Mutex<HashMap<String, Vec<FileImport>>>,
+    // Entries added since the cache was loaded
+    added: Mutex<HashSet<String>>,
+    // Entries that were looked up since the cache was loaded
+    used: Mutex<HashSet<String>>,
+}
+
+impl ImportCache {
+    /// Loads the cache from `path`. A missing or outdated cache is empty.
+    #[allow(dead_code)]
+    pub fn load(path: &AbsoluteSystemPath) -> Self {
+        let entries = path
+            .read_existing_to_string()
+            .ok()
+            .flatten()
+            .and_then(|contents| serde_json::from_str::<CacheFile>(&contents).ok())
+            .filter(|file| file.version == CACHE_VERSION)
+            .map(|file| file.entries)
+            .unwrap_or_default();
+        debug!("loaded {} cached file imports from {path}", entries.len());
+
+        Self {
+            entries: Mutex::new(entries),
+            ..Default::default()
+        }
+    }
+
+    /// Writes the cache to `path` if any entries were added.
+    #[allow(dead_code)]
+    pub fn save(&self, path: &AbsoluteSystemPath) -> io::Result<()> {
+        if self.added.lock().expect("lock poisoned").is_empty() {
+            return Ok(());
+        }
+
+        let mut entries = self.entries.lock().expect("lock poisoned").clone();
+        if entries.len() > MAX_ENTRIES {
+            let added = self.added.lock().expect("lock poisoned");
+            let used = self.used.lock().expect("lock poisoned");
+            entries.retain(|key, _| added.contains(key) || used.contains(key));
+        }
+
+        let contents = serde_json::to_string(&CacheFile {
+            version: CACHE_VERSION,
+            entries,
+        })?;
+        path.ensure_dir()?;
+        path.create_with_contents(contents)
+    }
+
+    /// The key for a file's imports. Besides the contents, the imports depend
+    /// on the file's extension, which determines how it's parsed, and the
+    /// type of imports that are being traced.
+    pub fn key(
+        file_path: &AbsoluteSystemPath,
+        import_type: ImportTraceType,
+        contents: &str,
+    ) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(file_path.extension().unwrap_or_default());
+        hasher.update(format!("{import_type:?}"));
+        hasher.update(contents);
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<FileImport>> {
+        let imports = self
+            .entries
+            .lock()
+            .expect("lock poisoned")
+            .get(key)
+            .cloned()?;
+        self.used
+            .lock()
+            .expect("lock poisoned")
+            .insert(key.to_string());
+        Some(imports)
+    }
+
+    pub fn insert(&self, key: String, imports: Vec<FileImport>) {
+        self.added
+            .lock()
+            .expect("lock poisoned")
+            .insert(key.clone());
+        self.entries
+            .lock()
+            .expect("lock poisoned")
+            .insert(key, imports);
+    }
+
+    /// Adds entries from another cache, like the daemon's, without marking
+    /// them as added.
+    #[allow(dead_code)]
+    pub fn extend(&self, entries: impl IntoIterator<Item = (String, Vec<FileImport>)>) {
+        self.entries.lock().expect("lock poisoned").extend(entries);
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries
+            .lock()
+            .expect("lock poisoned")
+            .contains_key(key)
+    }
+
+    /// The entries that were added since the cache was loaded.
+    #[allow(dead_code)]
+    pub fn added(&self) -> HashMap<String, Vec<FileImport>> {
+        let added = self.added.lock().expect("lock poisoned");
+        let entries = self.entries.lock().expect("lock poisoned");
+        added
+            .iter()
+            .filter_map(|key| Some((key.clone(), entries.get(key)?.clone())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::TempDir;
+    use turbopath::AbsoluteSystemPathBuf;
+
+    use super::{FileImport, ImportCache};
+    use crate::{import_finder::ImportType, tracer::ImportTraceType};
+
+    fn import(specifier: &str) -> FileImport {
+        FileImport {
+            specifier: specifier.to_string(),
+            import_type: ImportType::Value,
+            start: 0,
+            end: 10,
+            line: 0,
+            leading_comments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_key() {
+        let dir = TempDir::new().unwrap();
+        let root = AbsoluteSystemPathBuf::try_from(dir.path()).unwrap();
+        let ts = root.join_component("index.ts");
+        let js = root.join_component("index.js");
+        let contents = "import foo from './foo';";
+
+        let key = ImportCache::key(&ts, ImportTraceType::All, contents);
+        assert_eq!(key, ImportCache::key(&ts, ImportTraceType::All, contents));
+        assert_ne!(key, ImportCache::key(&js, ImportTraceType::All, contents));
+        assert_ne!(key, ImportCache::key(&ts, ImportTraceType::Types, contents));
+        assert_ne!(key, ImportCache::key(&ts, ImportTraceType::All, ""));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = TempDir::new().unwrap();
+        let path = AbsoluteSystemPathBuf::try_from(dir.path())
+            .unwrap()
+            .join_components(&[".turbo", "imports.json"]);
+
+        let cache = ImportCache::load(&path);
+        assert!(cache.get("a").is_none());
+        cache.insert("a".to_string(), vec![import("./foo")]);
+        cache.save(&path).unwrap();
+
+        let cache = ImportCache::load(&path);
+        assert_eq!(cache.get("a"), Some(vec![import("./foo")]));
+        assert!(cache.added().is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use swc_common::{Span, Spanned};
 use swc_ecma_ast::{Decl, ModuleDecl, Stmt};
 use swc_ecma_visit::{Visit, VisitWith};
@@ -9,7 +10,7 @@ use crate::tracer::ImportTraceType;
 /// Either an import with a `type` keyword (indicating that it is importing only
 /// types) or an import without the `type` keyword (indicating that it is
 /// importing values and possibly types).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ImportType {
     Type,
     Value,
@@ -1,6 +1,13 @@
 #![deny(clippy::all)]
+mod cache;
 mod import_finder;
+mod stylesheet;
 mod tracer;
 
+pub use cache::{FileImport, ImportCache};
 pub use import_finder::{ImportFinder, ImportType};
+pub use stylesheet::{
+    StylesheetImport, StylesheetImportKind, find_stylesheet_imports,
+    resolve_relative_stylesheet_import, resolve_stylesheet_import,
+};
 pub use tracer::{ImportTraceType, TraceError, TraceResult, Tracer};
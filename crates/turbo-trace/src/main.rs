@@ -1,4 +1,6 @@
+mod cache;
 mod import_finder;
+mod stylesheet;
 mod tracer;
 
 use camino::Utf8PathBuf;
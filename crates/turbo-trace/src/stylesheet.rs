@@ -0,0 +1,394 @@
+//! Finds the files that CSS and SCSS stylesheets import. Stylesheets reference
+//! other files through `@import`, Sass's `@use` and `@forward`, and `url()`,
+//! which we can find by scanning the stylesheet instead of fully parsing it.
+
+use std::ops::Range;
+
+use oxc_resolver::Resolver;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, RelativeUnixPath};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StylesheetImportKind {
+    /// `@import`, `@use` or `@forward`, which import another stylesheet
+    Stylesheet,
+    /// `url()`, which references an asset like an image or a font
+    Url,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StylesheetImport {
+    pub specifier: String,
+    pub kind: StylesheetImportKind,
+    /// The byte range of the specifier in the stylesheet
+    pub span: Range<usize>,
+    /// The comment at the start of the statement that contains the import
+    pub leading_comment: Option<String>,
+}
+
+impl StylesheetImport {
+    /// The specifier without any query string or fragment, which are common
+    /// in `url()`s for fonts, e.g. `font.woff2?v=4` or `font.svg#icons`
+    pub fn path(&self) -> &str {
+        self.specifier
+            .split(['?', '#'])
+            .next()
+            .unwrap_or(&self.specifier)
+    }
+
+    /// Whether the import can refer to a file in a package, like
+    /// `@import "tailwindcss"` or webpack's `@import
+    /// "~bootstrap/scss/bootstrap"`
+    pub fn is_package_import(&self) -> bool {
+        let path = self.path();
+        path.starts_with('~')
+            || (self.kind == StylesheetImportKind::Stylesheet
+                && !path.starts_with('.')
+                && !path.starts_with('/'))
+    }
+}
+
+/// Finds the imports in a stylesheet that refer to files. URLs, data URIs,
+/// absolute paths that are served by a web server, Sass built-in modules and
+/// specifiers that use Sass interpolation are skipped.
+///
+/// `line_comments` should be set for SCSS, which supports `//` comments.
+pub fn find_stylesheet_imports(source: &str, line_comments: bool) -> Vec<StylesheetImport> {
+    let bytes = source.as_bytes();
+    let mut imports = Vec::new();
+    let mut pos = 0;
+    let mut leading_comment: Option<String> = None;
+    let mut at_statement_start = true;
+
+    let mut push = |imports: &mut Vec<StylesheetImport>,
+                    (specifier, span): (&str, Range<usize>),
+                    kind,
+                    leading_comment: &Option<String>| {
+        if refers_to_file(specifier) {
+            imports.push(StylesheetImport {
+                specifier: specifier.to_string(),
+                kind,
+                span,
+                leading_comment: leading_comment.clone(),
+            });
+        }
+    };
+
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                let (body_end, end) = match source[pos + 2..].find("*/") {
+                    Some(idx) => (pos + 2 + idx, pos + 2 + idx + 2),
+                    None => (bytes.len(), bytes.len()),
+                };
+                if at_statement_start {
+                    leading_comment = Some(source[pos + 2..body_end].trim().to_string());
+                }
+                pos = end;
+            }
+            b'/' if line_comments && bytes.get(pos + 1) == Some(&b'/') => {
+                let end = source[pos..]
+                    .find('\n')
+                    .map_or(bytes.len(), |idx| pos + idx);
+                if at_statement_start {
+                    leading_comment = Some(source[pos + 2..end].trim().to_string());
+                }
+                pos = end;
+            }
+            b'"' | b'\'' => {
+                at_statement_start = false;
+                pos = quoted(source, pos).map_or(bytes.len(), |(_, _, end)| end);
+            }
+            b';' | b'{' | b'}' => {
+                leading_comment = None;
+                at_statement_start = true;
+                pos += 1;
+            }
+            b'@' => {
+                at_statement_start = false;
+                let name_end = ident_end(bytes, pos + 1);
+                let name = &source[pos + 1..name_end];
+                pos = name_end;
+                if !matches!(name, "import" | "use" | "forward") {
+                    continue;
+                }
+                // `@import` can list several stylesheets
+                loop {
+                    pos = skip_whitespace(bytes, pos);
+                    let Some((specifier, span, end)) =
+                        quoted(source, pos).or_else(|| url(source, pos))
+                    else {
+                        break;
+                    };
+                    push(
+                        &mut imports,
+                        (specifier, span),
+                        StylesheetImportKind::Stylesheet,
+                        &leading_comment,
+                    );
+                    pos = skip_whitespace(bytes, end);
+                    if bytes.get(pos) != Some(&b',') {
+                        break;
+                    }
+                    pos += 1;
+                }
+            }
+            b'u' | b'U' if pos == 0 || !is_ident_byte(bytes[pos - 1]) => {
+                at_statement_start = false;
+                match url(source, pos) {
+                    Some((specifier, span, end)) => {
+                        push(
+                            &mut imports,
+                            (specifier, span),
+                            StylesheetImportKind::Url,
+                            &leading_comment,
+                        );
+                        pos = end;
+                    }
+                    None => pos += 1,
+                }
+            }
+            byte if byte.is_ascii_whitespace() => pos += 1,
+            _ => {
+                at_statement_start = false;
+                pos += 1;
+            }
+        }
+    }
+
+    imports
+}
+
+/// Resolves an import from a stylesheet in `dir` to a file. Like browsers,
+/// specifiers without a `./` are relative to the stylesheet. Sass also looks
+/// for partials, e.g. `_variables.scss` for `@use "variables"`, and falls back
+/// to loading stylesheets from packages.
+pub fn resolve_stylesheet_import(
+    resolver: &Resolver,
+    dir: &AbsoluteSystemPath,
+    import: &StylesheetImport,
+    sass: bool,
+) -> Option<AbsoluteSystemPathBuf> {
+    let path = import.path();
+    // Webpack's `~` prefix marks an import from a package
+    if let Some(module) = path.strip_prefix('~') {
+        return resolve_module(resolver, dir, module);
+    }
+    if let Some(resolved) = resolve_relative_stylesheet_import(dir, import, sass) {
+        return Some(resolved);
+    }
+    if import.is_package_import() {
+        return resolve_module(resolver, dir, path);
+    }
+
+    None
+}
+
+/// Resolves an import from a stylesheet in `dir` to a file relative to the
+/// stylesheet, without falling back to packages.
+pub fn resolve_relative_stylesheet_import(
+    dir: &AbsoluteSystemPath,
+    import: &StylesheetImport,
+    sass: bool,
+) -> Option<AbsoluteSystemPathBuf> {
+    let path = import.path();
+    if path.starts_with('~') {
+        return None;
+    }
+
+    let relative = dir
+        .join_unix_path(RelativeUnixPath::new(path).ok()?)
+        .clean()
+        .ok()?;
+    let candidates = if sass && import.kind == StylesheetImportKind::Stylesheet {
+        sass_candidates(&relative)
+    } else {
+        vec![relative]
+    };
+    candidates.into_iter().find(|path| path.exists())
+}
+
+fn resolve_module(
+    resolver: &Resolver,
+    dir: &AbsoluteSystemPath,
+    module: &str,
+) -> Option<AbsoluteSystemPathBuf> {
+    resolver
+        .resolve(dir, module)
+        .ok()
+        .and_then(|resolved| resolved.into_path_buf().try_into().ok())
+}
+
+// The files that Sass tries when loading `path`
+fn sass_candidates(path: &AbsoluteSystemPath) -> Vec<AbsoluteSystemPathBuf> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return vec![path.to_owned()];
+    };
+    if matches!(path.extension(), Some("scss" | "sass" | "css")) {
+        return vec![path.to_owned(), dir.join_component(&format!("_{name}"))];
+    }
+
+    let mut candidates = Vec::new();
+    for extension in ["scss", "sass", "css"] {
+        candidates.push(dir.join_component(&format!("_{name}.{extension}")));
+        candidates.push(dir.join_component(&format!("{name}.{extension}")));
+    }
+    candidates.push(path.join_component("_index.scss"));
+    candidates.push(path.join_component("index.scss"));
+    candidates
+}
+
+fn refers_to_file(specifier: &str) -> bool {
+    !specifier.is_empty()
+        && !specifier.starts_with('/')
+        && !specifier.starts_with('#')
+        && !specifier.contains("#{")
+        && !specifier.contains(':')
+}
+
+fn is_ident_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_') || !byte.is_ascii()
+}
+
+fn ident_end(bytes: &[u8], start: usize) -> usize {
+    bytes[start..]
+        .iter()
+        .position(|byte| !is_ident_byte(*byte))
+        .map_or(bytes.len(), |idx| start + idx)
+}
+
+fn skip_whitespace(bytes: &[u8], start: usize) -> usize {
+    bytes[start.min(bytes.len())..]
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .map_or(bytes.len(), |idx| start + idx)
+}
+
+// A quoted string starting at `start`, returning its contents, the range of
+// its contents and the position after the closing quote
+fn quoted(source: &str, start: usize) -> Option<(&str, Range<usize>, usize)> {
+    let bytes = source.as_bytes();
+    let quote = *bytes.get(start)?;
+    if !matches!(quote, b'"' | b'\'') {
+        return None;
+    }
+    let mut pos = start + 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'\\' => pos += 2,
+            byte if byte == quote => {
+                return Some((&source[start + 1..pos], start + 1..pos, pos + 1));
+            }
+            // Strings can't span lines without an escape
+            b'\n' => return None,
+            _ => pos += 1,
+        }
+    }
+    None
+}
+
+// A `url()` starting at `start`, returning its argument, the range of the
+// argument and the position after the closing parenthesis
+fn url(source: &str, start: usize) -> Option<(&str, Range<usize>, usize)> {
+    let bytes = source.as_bytes();
+    if !bytes.get(start..start + 4)?.eq_ignore_ascii_case(b"url(") {
+        return None;
+    }
+    let pos = skip_whitespace(bytes, start + 4);
+    if let Some((contents, range, end)) = quoted(source, pos) {
+        let end = skip_whitespace(bytes, end);
+        return (bytes.get(end) == Some(&b')')).then_some((contents, range, end + 1));
+    }
+    let close = pos + source[pos..].find(')')?;
+    let contents = source[pos..close].trim_end();
+    Some((contents, pos..pos + contents.len(), close + 1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{StylesheetImportKind, find_stylesheet_imports};
+
+    fn specifiers(source: &str, line_comments: bool) -> Vec<(String, StylesheetImportKind)> {
+        find_stylesheet_imports(source, line_comments)
+            .into_iter()
+            .map(|import| (import.specifier, import.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_css_imports() {
+        let source = r##"
+            @import "./reset.css";
+            @import url('../../ui/theme.css') screen;
+            /* @import "commented.css"; */
+            .logo {
+                background: url(./logo.svg) no-repeat;
+                mask: URL( "../icons.svg#logo" );
+            }
+            @font-face {
+                src: url(data:font/woff2;base64,AAAA), url("https://example.com/font.woff2");
+            }
+            .hero { background: url(/images/hero.png); }
+        "##;
+        assert_eq!(
+            specifiers(source, false),
+            vec![
+                ("./reset.css".to_string(), StylesheetImportKind::Stylesheet),
+                (
+                    "../../ui/theme.css".to_string(),
+                    StylesheetImportKind::Stylesheet
+                ),
+                ("./logo.svg".to_string(), StylesheetImportKind::Url),
+                ("../icons.svg#logo".to_string(), StylesheetImportKind::Url),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scss_imports() {
+        let source = r##"
+            @use "sass:math";
+            @use "../../ui/tokens" as tokens;
+            @forward "mixins";
+            @import "a", "b";
+            // @use "commented";
+            .icon { background: url("#{$path}/icon.svg"); }
+        "##;
+        assert_eq!(
+            specifiers(source, true),
+            vec![
+                (
+                    "../../ui/tokens".to_string(),
+                    StylesheetImportKind::Stylesheet
+                ),
+                ("mixins".to_string(), StylesheetImportKind::Stylesheet),
+                ("a".to_string(), StylesheetImportKind::Stylesheet),
+                ("b".to_string(), StylesheetImportKind::Stylesheet),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_leading_comment() {
+        let source = "@import \"a.css\";\n/* @boundaries-ignore shared theme */\n@import \
+                      \"../b.css\";\n.a { background: url(c.png); }";
+        let comments = find_stylesheet_imports(source, false)
+            .into_iter()
+            .map(|import| import.leading_comment)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            comments,
+            vec![
+                None,
+                Some("@boundaries-ignore shared theme".to_string()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_span() {
+        let source = "@import 'theme.css';";
+        let imports = find_stylesheet_imports(source, false);
+        assert_eq!(&source[imports[0].span.clone()], "theme.css");
+    }
+}
@@ -1,16 +1,22 @@
-use std::{collections::HashMap, fmt, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
 
 use camino::{Utf8Path, Utf8PathBuf};
 use globwalk::WalkType;
 use miette::{Diagnostic, Report, SourceSpan};
 use oxc_resolver::{
-    EnforceExtension, ResolveError, ResolveOptions, Resolver, TsconfigOptions, TsconfigReferences,
+    AliasValue, EnforceExtension, ResolveError, ResolveOptions, Resolver, TsconfigOptions,
+    TsconfigReferences,
 };
 use swc_common::{
-    FileName, SourceMap,
-    comments::SingleThreadedComments,
+    FileName, SourceFile, SourceMap,
+    comments::{Comments, SingleThreadedComments},
     errors::{ColorConfig, Handler},
     input::StringInput,
+    sync::Lrc,
 };
 use swc_ecma_ast::EsVersion;
 use swc_ecma_parser::{Capturing, EsSyntax, Parser, Syntax, TsSyntax, lexer::Lexer};
@@ -20,7 +26,11 @@ use tokio::task::JoinSet;
 use tracing::{debug, error};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, PathError};
 
-use crate::import_finder::ImportFinder;
+use crate::{
+    cache::{FileImport, ImportCache},
+    import_finder::ImportFinder,
+    stylesheet::{find_stylesheet_imports, resolve_stylesheet_import},
+};
 
 #[derive(Debug, Default)]
 pub struct SeenFile {
@@ -32,6 +42,13 @@ pub struct SeenFile {
     pub ast: Option<swc_ecma_ast::Module>,
 }
 
+// A parsed module, along with its source file and comments
+type ParsedFile = (
+    swc_ecma_ast::Module,
+    Lrc<SourceFile>,
+    SingleThreadedComments,
+);
+
 pub struct Tracer {
     files: Vec<(AbsoluteSystemPathBuf, usize)>,
     ts_config: Option<AbsoluteSystemPathBuf>,
@@ -39,6 +56,11 @@ pub struct Tracer {
     cwd: AbsoluteSystemPathBuf,
     errors: Vec<TraceError>,
     import_type: ImportTraceType,
+    // Workspace packages that are resolved directly to their directory
+    package_aliases: Vec<(String, AbsoluteSystemPathBuf)>,
+    yarn_pnp: bool,
+    transitive: bool,
+    cache: Option<Arc<ImportCache>>,
 }
 
 #[derive(Clone, Debug, Error, Diagnostic)]
@@ -134,6 +156,10 @@ impl Tracer {
             import_type: ImportTraceType::All,
             errors: Vec::new(),
             source_map: Arc::new(SourceMap::default()),
+            package_aliases: Vec::new(),
+            yarn_pnp: false,
+            transitive: false,
+            cache: None,
         }
     }
 
@@ -142,26 +168,59 @@ impl Tracer {
         self.import_type = import_type;
     }
 
-    #[tracing::instrument(skip(resolver, source_map))]
-    pub async fn get_imports_from_file(
+    /// Makes `reverse_trace` also find the files that import the tracer's
+    /// files indirectly, through any number of other files.
+    #[allow(dead_code)]
+    pub fn set_transitive(&mut self) {
+        self.transitive = true;
+    }
+
+    /// Configures the tracer for a Yarn Plug'n'Play install, where there is no
+    /// `node_modules` directory. Workspace packages are resolved directly to
+    /// their directories and imports of external packages are skipped instead
+    /// of reported as errors.
+    #[allow(dead_code)]
+    pub fn set_yarn_pnp(&mut self, workspace_packages: Vec<(String, AbsoluteSystemPathBuf)>) {
+        self.package_aliases = workspace_packages;
+        self.yarn_pnp = true;
+    }
+
+    /// Looks up the imports of files in `cache` before parsing them, and adds
+    /// the imports of the files that had to be parsed to it.
+    #[allow(dead_code)]
+    pub fn set_cache(&mut self, cache: Arc<ImportCache>) {
+        self.cache = Some(cache);
+    }
+
+    async fn parse_file(
         source_map: &SourceMap,
         errors: &mut Vec<TraceError>,
-        resolver: &Resolver,
         file_path: &AbsoluteSystemPath,
-        import_type: ImportTraceType,
-    ) -> Option<(Vec<AbsoluteSystemPathBuf>, SeenFile)> {
+    ) -> Option<swc_ecma_ast::Module> {
         // Read the file content
         let Ok(file_content) = tokio::fs::read_to_string(&file_path).await else {
             errors.push(TraceError::FileNotFound(file_path.to_owned()));
             return None;
         };
 
+        match Self::parse_source(source_map, file_path, file_content) {
+            Ok((module, _, _)) => Some(module),
+            Err(err) => {
+                errors.push(TraceError::ParseError(file_path.to_owned(), err));
+                None
+            }
+        }
+    }
+
+    fn parse_source(
+        source_map: &SourceMap,
+        file_path: &AbsoluteSystemPath,
+        file_content: String,
+    ) -> Result<ParsedFile, swc_ecma_parser::error::Error> {
         let comments = SingleThreadedComments::default();
 
-        let source_file = source_map.new_source_file(
-            FileName::Custom(file_path.to_string()).into(),
-            file_content.clone(),
-        );
+        let source_file = source_map
+            .new_source_file(FileName::Custom(file_path.to_string()).into(), file_content);
 
         let syntax = if matches!(file_path.extension(), Some("ts") | Some("tsx")) {
             Syntax::Typescript(TsSyntax {
@@ -187,21 +246,106 @@ impl Tracer {
         let mut parser = Parser::new_from(Capturing::new(lexer));
 
         // Parse the file as a module
-        let module = match parser.parse_module() {
-            Ok(module) => module,
-            Err(err) => {
-                errors.push(TraceError::ParseError(file_path.to_owned(), err));
-                return None;
-            }
-        };
+        let module = parser.parse_module()?;
+
+        Ok((module, source_file, comments))
+    }
+
+    /// Finds the imports in a JavaScript or TypeScript file. If the file's
+    /// imports are in `cache`, the file isn't parsed and no AST is returned.
+    pub fn find_imports(
+        source_map: &SourceMap,
+        file_path: &AbsoluteSystemPath,
+        file_content: &str,
+        import_type: ImportTraceType,
+        cache: Option<&ImportCache>,
+    ) -> Result<(Vec<FileImport>, Option<swc_ecma_ast::Module>), swc_ecma_parser::error::Error>
+    {
+        let key = cache.map(|_| ImportCache::key(file_path, import_type, file_content));
+        if let Some(cache) = cache
+            && let Some(key) = &key
+            && let Some(imports) = cache.get(key)
+        {
+            return Ok((imports, None));
+        }
+
+        let (module, source_file, comments) =
+            Self::parse_source(source_map, file_path, file_content.to_string())?;
 
         // Visit the AST and find imports
         let mut finder = ImportFinder::new(import_type);
         module.visit_with(&mut finder);
+
+        let imports = finder
+            .imports()
+            .iter()
+            .map(|(specifier, span, import_type)| {
+                let (start, end) = source_map.span_to_char_offset(&source_file, *span);
+                let line = source_map
+                    .lookup_line(span.lo)
+                    .map(|line| line.line)
+                    .unwrap_or_default();
+                let leading_comments = comments
+                    .get_leading(span.lo)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|comment| comment.text.to_string())
+                    .collect();
+
+                FileImport {
+                    specifier: specifier.clone(),
+                    import_type: *import_type,
+                    start: start as usize,
+                    end: end as usize,
+                    line,
+                    leading_comments,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(cache) = cache
+            && let Some(key) = key
+        {
+            cache.insert(key, imports.clone());
+        }
+
+        Ok((imports, Some(module)))
+    }
+
+    #[tracing::instrument(skip(resolver, source_map))]
+    pub async fn get_imports_from_file(
+        source_map: &SourceMap,
+        errors: &mut Vec<TraceError>,
+        resolver: &Resolver,
+        file_path: &AbsoluteSystemPath,
+        import_type: ImportTraceType,
+        yarn_pnp: bool,
+        cache: Option<&ImportCache>,
+    ) -> Option<(Vec<AbsoluteSystemPathBuf>, SeenFile)> {
+        if matches!(file_path.extension(), Some("css") | Some("scss")) {
+            return Self::get_imports_from_stylesheet(errors, resolver, file_path).await;
+        }
+
+        // Read the file content
+        let Ok(file_content) = tokio::fs::read_to_string(&file_path).await else {
+            errors.push(TraceError::FileNotFound(file_path.to_owned()));
+            return None;
+        };
+
+        let (imports, module) =
+            match Self::find_imports(source_map, file_path, &file_content, import_type, cache) {
+                Ok(found) => found,
+                Err(err) => {
+                    errors.push(TraceError::ParseError(file_path.to_owned(), err));
+                    return None;
+                }
+            };
+
         // Convert found imports/requires to absolute paths and add them to files to
         // visit
         let mut files = Vec::new();
-        for (import, span, _) in finder.imports() {
+        for file_import in &imports {
+            let import = file_import.specifier.as_str();
             debug!("processing {} in {}", import, file_path);
             let Some(file_dir) = file_path.parent() else {
                 errors.push(TraceError::RootFile(file_path.to_owned()));
@@ -255,10 +399,15 @@ impl Tracer {
                         continue;
                     }
 
+                    if yarn_pnp && !import.starts_with('.') && !import.starts_with('/') {
+                        // Packages aren't installed to `node_modules` with PnP, so we can't
+                        // follow imports of external packages.
+                        debug!("skipping external import with Yarn PnP: {import}");
+                        continue;
+                    }
+
                     debug!("failed to resolve: {:?}", err);
-                    let (start, end) = source_map.span_to_char_offset(&source_file, *span);
-                    let start = start as usize;
-                    let end = end as usize;
+                    let FileImport { start, end, .. } = *file_import;
 
                     errors.push(TraceError::Resolve {
                         import: import.to_string(),
@@ -271,7 +420,50 @@ impl Tracer {
             }
         }
 
-        Some((files, SeenFile { ast: Some(module) }))
+        Some((files, SeenFile { ast: module }))
+    }
+
+    /// Finds the files imported by a CSS or SCSS stylesheet. Stylesheets
+    /// don't have an AST, so the returned `SeenFile` is empty.
+    async fn get_imports_from_stylesheet(
+        errors: &mut Vec<TraceError>,
+        resolver: &Resolver,
+        file_path: &AbsoluteSystemPath,
+    ) -> Option<(Vec<AbsoluteSystemPathBuf>, SeenFile)> {
+        let Ok(file_content) = tokio::fs::read_to_string(&file_path).await else {
+            errors.push(TraceError::FileNotFound(file_path.to_owned()));
+            return None;
+        };
+        let Some(file_dir) = file_path.parent() else {
+            errors.push(TraceError::RootFile(file_path.to_owned()));
+            return None;
+        };
+
+        let sass = file_path.extension() == Some("scss");
+        let mut files = Vec::new();
+        for import in find_stylesheet_imports(&file_content, sass) {
+            debug!("processing {} in {}", import.specifier, file_path);
+            match resolve_stylesheet_import(resolver, file_dir, &import, sass) {
+                Some(path) => files.push(path),
+                // The package may only be available to the bundler, e.g. through
+                // a plugin, so we can't report it as missing
+                None if import.is_package_import() => {
+                    debug!("skipping unresolved package import: {}", import.specifier);
+                }
+                None => {
+                    debug!("failed to resolve: {}", import.specifier);
+                    errors.push(TraceError::Resolve {
+                        span: SourceSpan::new(import.span.start.into(), import.span.len()),
+                        import: import.specifier,
+                        file_path: file_path.to_string(),
+                        text: file_content.clone(),
+                        reason: "file does not exist".to_string(),
+                    });
+                }
+            }
+        }
+
+        Some((files, SeenFile::default()))
     }
 
     pub async fn trace_file(
@@ -290,7 +482,7 @@ impl Tracer {
 
         let entry = seen.entry(file_path.clone()).or_default();
 
-        if matches!(file_path.extension(), Some("css") | Some("json")) {
+        if file_path.extension() == Some("json") {
             return;
         }
 
@@ -300,6 +492,8 @@ impl Tracer {
             resolver,
             &file_path,
             self.import_type,
+            self.yarn_pnp,
+            self.cache.as_deref(),
         )
         .await
         else {
@@ -354,7 +548,10 @@ impl Tracer {
         }
     }
 
-    pub fn create_resolver(ts_config: Option<&AbsoluteSystemPath>) -> Resolver {
+    pub fn create_resolver(
+        ts_config: Option<&AbsoluteSystemPath>,
+        package_aliases: &[(String, AbsoluteSystemPathBuf)],
+    ) -> Resolver {
         let mut options = ResolveOptions::default()
             .with_builtin_modules(true)
             .with_force_extension(EnforceExtension::Disabled)
@@ -379,12 +576,18 @@ impl Tracer {
             });
         }
 
+        options.alias.extend(
+            package_aliases
+                .iter()
+                .map(|(name, dir)| (name.clone(), vec![AliasValue::Path(dir.to_string())])),
+        );
+
         Resolver::new(options)
     }
 
     pub async fn trace(mut self, max_depth: Option<usize>) -> TraceResult {
         let mut seen: HashMap<AbsoluteSystemPathBuf, SeenFile> = HashMap::new();
-        let resolver = Self::create_resolver(self.ts_config.as_deref());
+        let resolver = Self::create_resolver(self.ts_config.as_deref(), &self.package_aliases);
 
         while let Some((file_path, file_depth)) = self.files.pop() {
             if let Some(max_depth) = max_depth
@@ -403,6 +606,30 @@ impl Tracer {
         }
     }
 
+    /// Parses the tracer's files without resolving or following any of their
+    /// imports. Useful when only the import specifiers themselves are needed.
+    pub async fn parse_files(mut self) -> TraceResult {
+        let mut seen: HashMap<AbsoluteSystemPathBuf, SeenFile> = HashMap::new();
+
+        for (file_path, _) in std::mem::take(&mut self.files) {
+            if seen.contains_key(&file_path) {
+                continue;
+            }
+            let Some(module) =
+                Self::parse_file(&self.source_map, &mut self.errors, &file_path).await
+            else {
+                continue;
+            };
+            seen.insert(file_path, SeenFile { ast: Some(module) });
+        }
+
+        TraceResult {
+            source_map: self.source_map.clone(),
+            files: seen,
+            errors: self.errors,
+        }
+    }
+
     pub async fn reverse_trace(self) -> TraceResult {
         let files = match globwalk::globwalk(
             &self.cwd,
@@ -411,6 +638,8 @@ impl Tracer {
                 "**/*.jsx".parse().expect("valid glob"),
                 "**/*.ts".parse().expect("valid glob"),
                 "**/*.tsx".parse().expect("valid glob"),
+                "**/*.css".parse().expect("valid glob"),
+                "**/*.scss".parse().expect("valid glob"),
             ],
             &[
                 "**/node_modules/**".parse().expect("valid glob"),
@@ -430,7 +659,10 @@ impl Tracer {
 
         let mut futures = JoinSet::new();
 
-        let resolver = Arc::new(Self::create_resolver(self.ts_config.as_deref()));
+        let resolver = Arc::new(Self::create_resolver(
+            self.ts_config.as_deref(),
+            &self.package_aliases,
+        ));
         let source_map = self.source_map.clone();
         let shared_self = Arc::new(self);
 
@@ -448,12 +680,15 @@ impl Tracer {
                     resolver,
                     &file,
                     shared_self.import_type,
+                    shared_self.yarn_pnp,
+                    shared_self.cache.as_deref(),
                 )
                 .await
                 else {
                     return (errors, None);
                 };
 
+                let mut imports = Vec::with_capacity(imported_files.len());
                 for mut import in imported_files {
                     // Windows has this annoying habit of abbreviating paths
                     // like `C:\Users\Admini~1` instead of `C:\Users\Administrator`
@@ -469,30 +704,60 @@ impl Tracer {
                             }
                         }
                     }
+                    imports.push(import);
+                }
 
-                    if shared_self
-                        .files
-                        .iter()
-                        .any(|(source, _)| import.as_path() == source.as_path())
-                    {
-                        return (errors, Some((file, seen_file)));
-                    }
+                // Only direct importers can match, so there's no need to keep the others
+                if !shared_self.transitive
+                    && !imports.iter().any(|import| {
+                        shared_self
+                            .files
+                            .iter()
+                            .any(|(source, _)| import.as_path() == source.as_path())
+                    })
+                {
+                    return (errors, None);
                 }
 
-                (errors, None)
+                (errors, Some((file, imports, seen_file)))
             });
         }
 
-        let mut usages = HashMap::new();
+        let mut importers = Vec::new();
         let mut errors = Vec::new();
 
         while let Some(result) = futures.join_next().await {
             let (errs, file) = result.unwrap();
             errors.extend(errs);
+            importers.extend(file);
+        }
 
-            if let Some((path, seen_file)) = file {
+        let mut targets = shared_self
+            .files
+            .iter()
+            .map(|(file, _)| file.clone())
+            .collect::<HashSet<_>>();
+        let mut usages = HashMap::new();
+        // Without `transitive`, we only need one pass to find the direct importers.
+        // Otherwise we keep adding the importers of importers until no new files
+        // are found.
+        loop {
+            let (found, rest): (Vec<_>, Vec<_>) = importers
+                .into_iter()
+                .partition(|(_, imports, _)| imports.iter().any(|import| targets.contains(import)));
+            importers = rest;
+            if found.is_empty() {
+                break;
+            }
+            for (path, _, seen_file) in found {
+                if shared_self.transitive {
+                    targets.insert(path.clone());
+                }
                 usages.insert(path, seen_file);
             }
+            if !shared_self.transitive {
+                break;
+            }
         }
 
         TraceResult {
@@ -502,3 +767,187 @@ impl Tracer {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeSet, sync::Arc};
+
+    use swc_common::SourceMap;
+    use tempfile::TempDir;
+    use turbopath::{AbsoluteSystemPathBuf, RelativeUnixPath};
+
+    use super::{ImportTraceType, TraceResult, Tracer};
+    use crate::{
+        cache::{FileImport, ImportCache},
+        import_finder::ImportType,
+    };
+
+    // Writes `files` to a new directory, returning it along with its real path
+    fn setup(files: &[(&str, &str)]) -> (TempDir, AbsoluteSystemPathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = AbsoluteSystemPathBuf::try_from(tmp.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap();
+        for (path, contents) in files {
+            let file = root.join_unix_path(RelativeUnixPath::new(path).unwrap());
+            file.ensure_dir().unwrap();
+            file.create_with_contents(contents).unwrap();
+        }
+        (tmp, root)
+    }
+
+    fn file_names(root: &AbsoluteSystemPathBuf, result: &TraceResult) -> BTreeSet<String> {
+        result
+            .files
+            .keys()
+            .map(|file| root.anchor(file).unwrap().to_string())
+            .collect()
+    }
+
+    // `a` imports `b`, which imports `c`
+    const CHAIN: &[(&str, &str)] = &[
+        ("a.ts", "import { b } from './b';\n"),
+        ("b.ts", "import { c } from './c';\nexport const b = c;\n"),
+        ("c.ts", "export const c = 'c';\n"),
+        ("unrelated.ts", "export const d = 'd';\n"),
+    ];
+
+    #[tokio::test]
+    async fn test_reverse_trace_direct_importers() {
+        let (_tmp, root) = setup(CHAIN);
+        let tracer = Tracer::new(root.clone(), vec![root.join_component("c.ts")], None);
+
+        let result = tracer.reverse_trace().await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(file_names(&root, &result), BTreeSet::from(["b.ts".into()]));
+    }
+
+    #[tokio::test]
+    async fn test_reverse_trace_transitive_importers() {
+        let (_tmp, root) = setup(CHAIN);
+        let mut tracer = Tracer::new(root.clone(), vec![root.join_component("c.ts")], None);
+        tracer.set_transitive();
+
+        let result = tracer.reverse_trace().await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(
+            file_names(&root, &result),
+            BTreeSet::from(["a.ts".into(), "b.ts".into()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trace_stylesheet_package_imports() {
+        let (_tmp, root) = setup(&[
+            (
+                "styles.css",
+                "@import \"tailwindcss\";\n@import \"missing-package\";\n@import \
+                 \"./theme.css\";\n",
+            ),
+            ("theme.css", ".a { color: red; }\n"),
+            (
+                "node_modules/tailwindcss/package.json",
+                r#"{ "name": "tailwindcss", "main": "index.css" }"#,
+            ),
+            (
+                "node_modules/tailwindcss/index.css",
+                ".b { color: blue; }\n",
+            ),
+        ]);
+        let tracer = Tracer::new(root.clone(), vec![root.join_component("styles.css")], None);
+
+        let result = tracer.trace(None).await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(
+            file_names(&root, &result),
+            BTreeSet::from([
+                "styles.css".into(),
+                "theme.css".into(),
+                ["node_modules", "tailwindcss", "index.css"].join(std::path::MAIN_SEPARATOR_STR),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trace_stylesheet_missing_relative_import() {
+        let (_tmp, root) = setup(&[("styles.css", "@import \"./missing.css\";\n")]);
+        let tracer = Tracer::new(root.clone(), vec![root.join_component("styles.css")], None);
+
+        let result = tracer.trace(None).await;
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_find_imports_skips_parsing_cached_files() {
+        let (_tmp, root) = setup(&[]);
+        let file = root.join_component("index.ts");
+        let source_map = SourceMap::default();
+        let cache = ImportCache::default();
+        let find_imports = |contents: &str| {
+            Tracer::find_imports(
+                &source_map,
+                &file,
+                contents,
+                ImportTraceType::All,
+                Some(&cache),
+            )
+            .unwrap()
+        };
+
+        let (imports, module) = find_imports("import { a } from './a';\n");
+        assert!(module.is_some());
+        assert_eq!(imports.len(), 1);
+
+        let (cached, module) = find_imports("import { a } from './a';\n");
+        assert!(module.is_none(), "cached file should not be parsed");
+        assert_eq!(cached, imports);
+
+        let (changed, module) = find_imports("import { b } from './b';\n");
+        assert!(module.is_some(), "changed file should be parsed again");
+        assert_eq!(changed[0].specifier, "./b");
+    }
+
+    #[tokio::test]
+    async fn test_trace_uses_cached_imports() {
+        let (_tmp, root) = setup(CHAIN);
+        let a = root.join_component("a.ts");
+        let contents = a.read_to_string().unwrap();
+
+        // A cached entry that doesn't match the file's contents shows that the
+        // file wasn't parsed
+        let cache = Arc::new(ImportCache::default());
+        cache.insert(
+            ImportCache::key(&a, ImportTraceType::All, &contents),
+            vec![FileImport {
+                specifier: "./c".to_string(),
+                import_type: ImportType::Value,
+                start: 0,
+                end: 0,
+                line: 0,
+                leading_comments: Vec::new(),
+            }],
+        );
+        let mut tracer = Tracer::new(root.clone(), vec![a.clone()], None);
+        tracer.set_cache(cache.clone());
+        let result = tracer.trace(None).await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(
+            file_names(&root, &result),
+            BTreeSet::from(["a.ts".into(), "c.ts".into()])
+        );
+
+        // Changing the file changes its key, so it's parsed again
+        let contents = "import { b } from './b';\nexport const a = b;\n";
+        a.create_with_contents(contents).unwrap();
+        let mut tracer = Tracer::new(root.clone(), vec![a.clone()], None);
+        tracer.set_cache(cache.clone());
+        let result = tracer.trace(None).await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(
+            file_names(&root, &result),
+            BTreeSet::from(["a.ts".into(), "b.ts".into(), "c.ts".into()])
+        );
+        assert!(cache.contains(&ImportCache::key(&a, ImportTraceType::All, contents)));
+    }
+}
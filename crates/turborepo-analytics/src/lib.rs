@@ -1,13 +1,17 @@
 #![deny(clippy::all)]
 
-//! Turborepo's analytics library. Handles sending analytics events to the
-//! Vercel API in the background. We only record cache usage events,
+//! Turborepo's analytics library. Handles sending analytics events to an
+//! `AnalyticsSink` in the background. We only record cache usage events,
 //! so when the cache is hit or missed for the file system or the HTTP cache.
-//! Requires the user to be logged in to Vercel.
+//! By default events are sent to the Vercel API, which requires the user to be
+//! logged in to Vercel.
+
+mod sink;
 
 use std::time::Duration;
 
 use futures::{StreamExt, stream::FuturesUnordered};
+pub use sink::{AnalyticsSink, FileSink, SinkError, StdoutSink, VercelSink, WebhookSink};
 use thiserror::Error;
 use tokio::{
     select,
@@ -42,15 +46,22 @@ pub struct AnalyticsHandle {
     handle: JoinHandle<()>,
 }
 
+/// Starts a `Worker` that sends events to the Vercel API.
+pub fn start_analytics(
+    api_auth: APIAuth,
+    client: impl AnalyticsClient + Clone + Send + Sync + 'static,
+) -> (AnalyticsSender, AnalyticsHandle) {
+    start_analytics_with_sink(VercelSink::new(api_auth, client))
+}
+
 /// Starts the `Worker` on a separate tokio thread. Returns an `AnalyticsSender`
 /// and an `AnalyticsHandle`.
 ///
 /// We have two different types because the AnalyticsSender should be shared
 /// across threads (i.e. Clone + Send), while the AnalyticsHandle cannot be
 /// shared since it contains the structs necessary to shut down the worker.
-pub fn start_analytics(
-    api_auth: APIAuth,
-    client: impl AnalyticsClient + Clone + Send + Sync + 'static,
+pub fn start_analytics_with_sink(
+    sink: impl AnalyticsSink + Clone + Send + Sync + 'static,
 ) -> (AnalyticsSender, AnalyticsHandle) {
     let (tx, rx) = mpsc::unbounded_channel();
     let (cancel_tx, cancel_rx) = oneshot::channel();
@@ -59,10 +70,9 @@ pub fn start_analytics(
         rx,
         buffer: Vec::new(),
         session_id,
-        api_auth,
         senders: FuturesUnordered::new(),
         exit_ch: cancel_tx,
-        client,
+        sink,
     };
     let handle = worker.start();
 
@@ -92,18 +102,17 @@ impl AnalyticsHandle {
     }
 }
 
-struct Worker<C> {
+struct Worker<S> {
     rx: mpsc::UnboundedReceiver<AnalyticsEvent>,
     buffer: Vec<AnalyticsEvent>,
     session_id: Uuid,
-    api_auth: APIAuth,
     senders: FuturesUnordered<JoinHandle<()>>,
     // Used to cancel the worker
     exit_ch: oneshot::Sender<()>,
-    client: C,
+    sink: S,
 }
 
-impl<C: AnalyticsClient + Clone + Send + Sync + 'static> Worker<C> {
+impl<S: AnalyticsSink + Clone + Send + Sync + 'static> Worker<S> {
     pub fn start(mut self) -> JoinHandle<()> {
         tokio::spawn(async move {
             let mut timeout = tokio::time::sleep(NO_TIMEOUT);
@@ -153,16 +162,14 @@ impl<C: AnalyticsClient + Clone + Send + Sync + 'static> Worker<C> {
 
     fn send_events(&self, mut events: Vec<AnalyticsEvent>) -> JoinHandle<()> {
         let session_id = self.session_id;
-        let client = self.client.clone();
-        let api_auth = self.api_auth.clone();
+        let sink = self.sink.clone();
         add_session_id(session_id, &mut events);
 
         tokio::spawn(async move {
             // We don't log an error for a timeout because
             // that's what the Go code does.
             if let Ok(Err(err)) =
-                tokio::time::timeout(REQUEST_TIMEOUT, client.record_analytics(&api_auth, events))
-                    .await
+                tokio::time::timeout(REQUEST_TIMEOUT, sink.record_events(events)).await
             {
                 debug!("failed to record cache usage analytics. error: {}", err)
             }
@@ -0,0 +1,364 @@
+//! Destinations for batches of analytics events. By default events are sent
+//! to the Vercel API, but teams using a self-hosted remote cache can write
+//! them to a local file, stdout, or their own HTTP endpoint instead.
+
+use std::{
+    future::Future,
+    io::{self, Stdout, Write},
+    sync::{self, Arc},
+};
+
+use thiserror::Error;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt, sync::Mutex};
+use turbopath::AbsoluteSystemPathBuf;
+use turborepo_api_client::{APIAuth, analytics::AnalyticsClient};
+use turborepo_vercel_api::AnalyticsEvent;
+
+#[derive(Debug, Error)]
+pub enum SinkError {
+    #[error(transparent)]
+    Api(#[from] turborepo_api_client::Error),
+    #[error("Failed to write analytics events: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to serialize analytics events: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("Failed to send analytics events to webhook: {0}")]
+    Webhook(#[from] reqwest::Error),
+}
+
+pub trait AnalyticsSink {
+    fn record_events(
+        &self,
+        events: Vec<AnalyticsEvent>,
+    ) -> impl Future<Output = Result<(), SinkError>> + Send;
+}
+
+/// Sends events to the Vercel API. Requires the repo to be linked.
+#[derive(Clone)]
+pub struct VercelSink<C> {
+    api_auth: APIAuth,
+    client: C,
+}
+
+impl<C> VercelSink<C> {
+    pub fn new(api_auth: APIAuth, client: C) -> Self {
+        Self { api_auth, client }
+    }
+}
+
+impl<C: AnalyticsClient + Sync> AnalyticsSink for VercelSink<C> {
+    async fn record_events(&self, events: Vec<AnalyticsEvent>) -> Result<(), SinkError> {
+        self.client.record_analytics(&self.api_auth, events).await?;
+        Ok(())
+    }
+}
+
+/// Appends each event as a line of JSON to a file.
+#[derive(Clone)]
+pub struct FileSink {
+    path: AbsoluteSystemPathBuf,
+    // Batches are written concurrently, so we serialize writes to avoid
+    // interleaving lines from different batches
+    lock: Arc<Mutex<()>>,
+}
+
+impl FileSink {
+    pub fn new(path: AbsoluteSystemPathBuf) -> Self {
+        Self {
+            path,
+            lock: Arc::default(),
+        }
+    }
+}
+
+impl AnalyticsSink for FileSink {
+    async fn record_events(&self, events: Vec<AnalyticsEvent>) -> Result<(), SinkError> {
+        let lines = to_json_lines(&events)?;
+        let _guard = self.lock.lock().await;
+        self.path.ensure_dir()?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&lines).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+/// Writes each event as a line of JSON to stdout. Events are written as
+/// they're flushed, so they can be interleaved with the run's own output.
+pub struct StdoutSink<W = Stdout> {
+    // Batches are written concurrently, so we hold the lock for a whole batch
+    // to keep lines from different batches from interleaving
+    writer: Arc<sync::Mutex<W>>,
+}
+
+// Derived `Clone` would require `W: Clone`, which `Stdout` isn't
+impl<W> Clone for StdoutSink<W> {
+    fn clone(&self) -> Self {
+        Self {
+            writer: self.writer.clone(),
+        }
+    }
+}
+
+impl StdoutSink {
+    pub fn new() -> Self {
+        Self::with_writer(io::stdout())
+    }
+}
+
+impl Default for StdoutSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write> StdoutSink<W> {
+    /// Writes events to `writer` instead of stdout.
+    pub fn with_writer(writer: W) -> Self {
+        Self {
+            writer: Arc::new(sync::Mutex::new(writer)),
+        }
+    }
+}
+
+impl<W: Write + Send> AnalyticsSink for StdoutSink<W> {
+    async fn record_events(&self, events: Vec<AnalyticsEvent>) -> Result<(), SinkError> {
+        let mut writer = self.writer.lock().expect("analytics writer lock poisoned");
+        write_json_lines(&mut *writer, &events)
+    }
+}
+
+/// POSTs each batch of events as a JSON array to a URL.
+#[derive(Clone)]
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+    // Sent as the `Authorization` header
+    auth_header: Option<String>,
+}
+
+impl WebhookSink {
+    pub fn new(url: String, auth_header: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            auth_header,
+        }
+    }
+}
+
+impl AnalyticsSink for WebhookSink {
+    async fn record_events(&self, events: Vec<AnalyticsEvent>) -> Result<(), SinkError> {
+        let mut request = self.client.post(&self.url).json(&events);
+        if let Some(auth_header) = &self.auth_header {
+            request = request.header(reqwest::header::AUTHORIZATION, auth_header);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+fn write_json_lines(writer: &mut impl Write, events: &[AnalyticsEvent]) -> Result<(), SinkError> {
+    writer.write_all(&to_json_lines(events)?)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn to_json_lines(events: &[AnalyticsEvent]) -> Result<Vec<u8>, serde_json::Error> {
+    let mut lines = Vec::new();
+    for event in events {
+        serde_json::to_writer(&mut lines, event)?;
+        lines.push(b'\n');
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Write},
+        sync::{Arc, Mutex},
+    };
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+    use turbopath::AbsoluteSystemPathBuf;
+    use turborepo_vercel_api::{AnalyticsEvent, CacheEvent, CacheSource};
+
+    use super::{AnalyticsSink, FileSink, SinkError, StdoutSink, WebhookSink, write_json_lines};
+
+    fn event(hash: &str) -> AnalyticsEvent {
+        AnalyticsEvent {
+            session_id: Some("session".to_string()),
+            source: CacheSource::Remote,
+            event: CacheEvent::Hit,
+            hash: hash.to_string(),
+            duration: 42,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_appends_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = AbsoluteSystemPathBuf::try_from(tmp.path())
+            .unwrap()
+            .join_components(&[".turbo", "analytics.jsonl"]);
+        let sink = FileSink::new(path.clone());
+
+        sink.record_events(vec![event("a"), event("b")])
+            .await
+            .unwrap();
+        sink.record_events(vec![event("c")]).await.unwrap();
+
+        let contents = path.read_to_string().unwrap();
+        let hashes = contents
+            .lines()
+            .map(|line| {
+                let event: AnalyticsEvent = serde_json::from_str(line).unwrap();
+                event.hash
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(hashes, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_write_json_lines() {
+        let mut output = Vec::new();
+        write_json_lines(&mut output, &[event("a"), event("b")]).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        for (line, hash) in lines.into_iter().zip(["a", "b"]) {
+            let event: AnalyticsEvent = serde_json::from_str(line).unwrap();
+            assert_eq!(event.hash, hash);
+        }
+    }
+
+    // A writer whose contents can be read after it has been handed to a sink
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stdout_sink_writes_lines() {
+        let output = SharedBuffer::default();
+        let sink = StdoutSink::with_writer(output.clone());
+
+        sink.record_events(vec![event("a"), event("b")])
+            .await
+            .unwrap();
+        sink.record_events(vec![event("c")]).await.unwrap();
+
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let events = output
+            .lines()
+            .map(|line| serde_json::from_str::<AnalyticsEvent>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| event.hash.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        assert!(events.iter().all(|event| event.duration == 42
+            && event.session_id.as_deref() == Some("session")));
+    }
+
+    // Accepts a single request, responding with `status` and returning the
+    // raw request
+    async fn serve_once(status: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/events", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            // Read until we have the headers and the body they describe
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+                    let content_length = headers
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or_default();
+                    if body.len() >= content_length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            stream
+                .write_all(
+                    format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                        .as_bytes(),
+                )
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink_posts_events() {
+        let (url, server) = serve_once("200 OK").await;
+        let sink = WebhookSink::new(url, Some("Bearer secret".to_string()));
+
+        sink.record_events(vec![event("a"), event("b")])
+            .await
+            .unwrap();
+
+        let request = server.await.unwrap();
+        let (headers, body) = request.split_once("\r\n\r\n").unwrap();
+        assert!(headers.starts_with("POST /events HTTP/1.1"), "{headers}");
+        assert!(
+            headers
+                .lines()
+                .any(|line| line.eq_ignore_ascii_case("authorization: Bearer secret")),
+            "{headers}"
+        );
+        let events: Vec<AnalyticsEvent> = serde_json::from_str(body).unwrap();
+        assert_eq!(
+            events
+                .into_iter()
+                .map(|event| event.hash)
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink_error_status() {
+        let (url, server) = serve_once("500 Internal Server Error").await;
+        let sink = WebhookSink::new(url, None);
+
+        let result = sink.record_events(vec![event("a")]).await;
+        assert!(matches!(result, Err(SinkError::Webhook(_))), "{result:?}");
+        server.await.unwrap();
+    }
+}
@@ -8,7 +8,8 @@ use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{APIAuth, APIClient};
 
 use crate::{
-    CacheError, CacheHitMetadata, CacheOpts, http::UploadMap, multiplexer::CacheMultiplexer,
+    CacheConfig, CacheError, CacheHitMetadata, CacheOpts, http::UploadMap,
+    multiplexer::CacheMultiplexer,
 };
 
 const WARNING_CUTOFF: u8 = 4;
@@ -25,6 +26,7 @@ enum WorkerRequest {
         key: String,
         duration: u64,
         files: Vec<AnchoredSystemPathBuf>,
+        cache_config: CacheConfig,
     },
     Flush(oneshot::Sender<()>),
     /// Shutdown the cache. The first oneshot notifies when shutdown starts and
@@ -67,6 +69,7 @@ impl AsyncCache {
                         key,
                         duration,
                         files,
+                        cache_config,
                     } => {
                         let permit = semaphore.clone().acquire_owned().await.unwrap();
                         let real_cache = real_cache.clone();
@@ -74,8 +77,9 @@ impl AsyncCache {
                         let worker_span = tracing::span!(Level::TRACE, "cache worker: cache PUT");
                         workers.push(tokio::spawn(
                             async move {
-                                if let Err(err) =
-                                    real_cache.put(&anchor, &key, &files, duration).await
+                                if let Err(err) = real_cache
+                                    .put(&anchor, &key, &files, duration, cache_config)
+                                    .await
                                 {
                                     let num_warnings =
                                         warnings.load(std::sync::atomic::Ordering::Acquire);
@@ -139,6 +143,22 @@ impl AsyncCache {
         key: String,
         files: Vec<AnchoredSystemPathBuf>,
         duration: u64,
+    ) -> Result<(), CacheError> {
+        let cache_config = self.real_cache.cache_config();
+        self.put_with_config(anchor, key, files, duration, cache_config)
+            .await
+    }
+
+    /// Like `put`, but only writes to the caches allowed by `cache_config`
+    /// instead of the run-wide configuration.
+    #[tracing::instrument(skip_all)]
+    pub async fn put_with_config(
+        &self,
+        anchor: AbsoluteSystemPathBuf,
+        key: String,
+        files: Vec<AnchoredSystemPathBuf>,
+        duration: u64,
+        cache_config: CacheConfig,
     ) -> Result<(), CacheError> {
         if self
             .writer_sender
@@ -147,6 +167,7 @@ impl AsyncCache {
                 key,
                 duration,
                 files,
+                cache_config,
             })
             .await
             .is_err()
@@ -159,7 +180,17 @@ impl AsyncCache {
 
     #[tracing::instrument(skip_all)]
     pub async fn exists(&self, key: &str) -> Result<Option<CacheHitMetadata>, CacheError> {
-        self.real_cache.exists(key).await
+        self.exists_with_config(key, self.real_cache.cache_config())
+            .await
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn exists_with_config(
+        &self,
+        key: &str,
+        cache_config: CacheConfig,
+    ) -> Result<Option<CacheHitMetadata>, CacheError> {
+        self.real_cache.exists(key, cache_config).await
     }
 
     #[tracing::instrument(skip_all)]
@@ -168,7 +199,19 @@ impl AsyncCache {
         anchor: &AbsoluteSystemPath,
         key: &str,
     ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
-        self.real_cache.fetch(anchor, key).await
+        self.fetch_with_config(anchor, key, self.real_cache.cache_config())
+            .await
+    }
+
+    /// Like `fetch`, but only reads from the caches allowed by `cache_config`
+    #[tracing::instrument(skip_all)]
+    pub async fn fetch_with_config(
+        &self,
+        anchor: &AbsoluteSystemPath,
+        key: &str,
+        cache_config: CacheConfig,
+    ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
+        self.real_cache.fetch(anchor, key, cache_config).await
     }
 
     // Used for testing to ensure that the workers resolve
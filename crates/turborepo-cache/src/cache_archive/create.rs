@@ -2,17 +2,39 @@ use std::{
     backtrace::Backtrace,
     fs,
     fs::OpenOptions,
+    io,
     io::{BufWriter, Read, Write},
     path::Path,
 };
 
+use sha2::{Digest, Sha256};
 use tar::{EntryType, Header};
 use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, IntoUnix};
 
-use crate::CacheError;
+use crate::{
+    CacheError,
+    cache_archive::manifest::{ContentManifest, ManifestSigner},
+    signature_authentication::ArtifactSignatureAuthenticator,
+};
 
 pub struct CacheWriter<'a> {
     builder: tar::Builder<Box<dyn Write + 'a>>,
+    manifest: ContentManifest,
+    signer: Option<ManifestSigner<'a>>,
+}
+
+// Hashes a file's contents as the tar builder reads them
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
 }
 
 impl<'a> CacheWriter<'a> {
@@ -35,7 +57,28 @@ impl<'a> CacheWriter<'a> {
         Ok(self.builder.append_link(header, path, target)?)
     }
 
+    /// Signs the manifest of content hashes for the artifact `hash`, so that
+    /// restores can tell whether any file was changed after it was signed.
+    pub fn sign_manifest(
+        mut self,
+        signer: &'a ArtifactSignatureAuthenticator,
+        hash: &'a str,
+    ) -> Self {
+        self.signer = Some((signer, hash));
+        self
+    }
+
+    // Writes the manifest of every file's content hash as a PAX global header
+    // at the end of the archive, and then the end of archive marker.
     pub fn finish(mut self) -> Result<(), CacheError> {
+        let manifest = self.manifest.to_pax(self.signer)?;
+        let mut header = Header::new_ustar();
+        header.set_entry_type(EntryType::XGlobalHeader);
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        self.append_data(&mut header, "pax_global_header", manifest.as_slice())?;
+
         Ok(self.builder.finish()?)
     }
 
@@ -44,10 +87,14 @@ impl<'a> CacheWriter<'a> {
             let zw = zstd::Encoder::new(writer, 0)?.auto_finish();
             Ok(CacheWriter {
                 builder: tar::Builder::new(Box::new(zw)),
+                manifest: ContentManifest::default(),
+                signer: None,
             })
         } else {
             Ok(CacheWriter {
                 builder: tar::Builder::new(Box::new(writer)),
+                manifest: ContentManifest::default(),
+                signer: None,
             })
         }
     }
@@ -71,16 +118,20 @@ impl<'a> CacheWriter<'a> {
 
             Ok(CacheWriter {
                 builder: tar::Builder::new(Box::new(zw)),
+                manifest: ContentManifest::default(),
+                signer: None,
             })
         } else {
             Ok(CacheWriter {
                 builder: tar::Builder::new(Box::new(file_buffer)),
+                manifest: ContentManifest::default(),
+                signer: None,
             })
         }
     }
 
     // Adds a user-cached item to the tar
-    pub(crate) fn add_file(
+    pub fn add_file(
         &mut self,
         anchor: &AbsoluteSystemPath,
         file_path: &AnchoredSystemPath,
@@ -97,9 +148,21 @@ impl<'a> CacheWriter<'a> {
 
         let mut header = Self::create_header(&file_info)?;
 
-        if matches!(header.entry_type(), EntryType::Regular) && file_info.len() > 0 {
-            let file = source_path.open()?;
-            self.append_data(&mut header, file_path.as_str(), file)?;
+        if matches!(header.entry_type(), EntryType::Regular) {
+            // Hash the contents as they're streamed into the archive, the
+            // hashes are written out together once the archive is finished.
+            let hasher = if file_info.len() > 0 {
+                let mut reader = HashingReader {
+                    inner: source_path.open()?,
+                    hasher: Sha256::new(),
+                };
+                self.append_data(&mut header, file_path.as_str(), &mut reader)?;
+                reader.hasher
+            } else {
+                self.append_data(&mut header, file_path.as_str(), &mut std::io::empty())?;
+                Sha256::new()
+            };
+            self.manifest.insert(file_path.to_string(), hasher);
         } else if matches!(header.entry_type(), EntryType::Symlink) {
             // We convert to a Unix path because all paths in tar should be
             // Unix-style. This will get restored to a system path.
@@ -435,6 +498,72 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_signed_manifest() -> Result<()> {
+        let input_dir = tempdir()?;
+        let input_dir_path = AbsoluteSystemPath::from_std_path(input_dir.path())?;
+        let files =
+            ["one.txt", "two.txt"].map(|file| AnchoredSystemPathBuf::from_raw(file).unwrap());
+        for file in &files {
+            input_dir_path
+                .resolve(file)
+                .create_with_contents(file.as_str())?;
+        }
+        let signer =
+            ArtifactSignatureAuthenticator::new(b"team".to_vec(), Some(b"secret".to_vec()));
+
+        let mut signed = Vec::new();
+        let mut archive =
+            CacheWriter::from_writer(&mut signed, false)?.sign_manifest(&signer, "hash");
+        for file in &files {
+            archive.add_file(input_dir_path, file)?;
+        }
+        archive.finish()?;
+
+        // Archives from before manifests were written have no hashes at all
+        let mut legacy = Vec::new();
+        {
+            let mut archive = CacheWriter::from_writer(&mut legacy, false)?;
+            for file in &files {
+                archive.add_file(input_dir_path, file)?;
+            }
+        }
+
+        let restore = |archive: &[u8], hash: Option<&str>| {
+            let restore_dir = tempdir().unwrap();
+            let restore_dir_path = AbsoluteSystemPathBuf::try_from(restore_dir.path()).unwrap();
+            let mut reader = CacheReader::from_reader(archive, false).unwrap();
+            if let Some(hash) = hash {
+                reader = reader.verify_manifest(&signer, hash);
+            }
+            let result = reader.restore(&restore_dir_path);
+            let left_behind = files
+                .iter()
+                .filter(|file| restore_dir_path.resolve(file).exists())
+                .count();
+            (result, left_behind)
+        };
+
+        let (result, _) = restore(&signed, Some("hash"));
+        assert_eq!(result?, files);
+
+        // The manifest was signed for a different artifact, none of the files
+        // it restored can be trusted
+        let (result, left_behind) = restore(&signed, Some("other"));
+        assert!(matches!(result, Err(CacheError::InvalidTag(_))));
+        assert_eq!(left_behind, 0);
+
+        let (result, left_behind) = restore(&legacy, Some("hash"));
+        assert!(matches!(result, Err(CacheError::ContentHashMissing(..))));
+        assert_eq!(left_behind, 0);
+
+        let (result, left_behind) = restore(&legacy, None);
+        assert_eq!(result?, files);
+        assert_eq!(left_behind, 2);
+
+        Ok(())
+    }
+
     #[test]
     fn test_compression() -> Result<()> {
         let mut buffer = Vec::new();
@@ -0,0 +1,187 @@
+//! The content hashes of every regular file in an archive. Files are hashed
+//! as they're streamed into the archive, so the manifest can only be written
+//! once the last entry is done. It goes at the end of the archive as a PAX
+//! global header. Versions of turbo from before the manifest was added fail
+//! to restore the archive when they reach it, and run the task instead.
+
+use std::{backtrace::Backtrace, collections::BTreeMap};
+
+use sha2::{Digest, Sha256};
+
+use crate::{CacheError, signature_authentication::ArtifactSignatureAuthenticator};
+
+const MANIFEST_PAX_KEY: &str = "TURBO.manifest";
+const SIGNATURE_PAX_KEY: &str = "TURBO.manifest.signature";
+
+/// Used to sign a manifest on write, or to require a signed one on restore,
+/// along with the hash of the artifact the manifest belongs to.
+pub(crate) type ManifestSigner<'a> = (&'a ArtifactSignatureAuthenticator, &'a str);
+
+/// The SHA-256 of each regular file's contents, keyed by the file's path in
+/// the archive.
+#[derive(Debug, Default)]
+pub(crate) struct ContentManifest(BTreeMap<String, String>);
+
+impl ContentManifest {
+    pub fn insert(&mut self, path: String, hasher: Sha256) {
+        self.0.insert(path, hex::encode(hasher.finalize()));
+    }
+
+    /// Encodes the manifest as the body of a PAX global header. If there is
+    /// a signer, the manifest is signed for the artifact's hash so that it
+    /// can't be moved to a different artifact.
+    pub fn to_pax(&self, signer: Option<ManifestSigner>) -> Result<Vec<u8>, CacheError> {
+        let manifest = serde_json::to_string(&self.0).expect("manifest is serializable");
+        let mut body = pax_record(MANIFEST_PAX_KEY, &manifest);
+        if let Some((signer, hash)) = signer {
+            let signature = signer.generate_tag(hash.as_bytes(), manifest.as_bytes())?;
+            body.push_str(&pax_record(SIGNATURE_PAX_KEY, &signature));
+        }
+
+        Ok(body.into_bytes())
+    }
+
+    /// Checks the hashes of the restored files against the manifest recorded
+    /// in the archive. When a verifier is given the manifest has to be signed
+    /// and has to cover every file.
+    pub fn verify(
+        &self,
+        recorded: Option<&RecordedManifest>,
+        verifier: Option<ManifestSigner>,
+    ) -> Result<(), CacheError> {
+        let unrecorded = ContentManifest::default();
+        let recorded = match (recorded, verifier) {
+            (Some(recorded), Some((verifier, hash))) => {
+                let Some(signature) = &recorded.signature else {
+                    return Err(CacheError::InvalidTag(Backtrace::capture()));
+                };
+                if !verifier.validate(hash.as_bytes(), recorded.raw.as_bytes(), signature)? {
+                    return Err(CacheError::InvalidTag(Backtrace::capture()));
+                }
+                &recorded.manifest
+            }
+            (Some(recorded), None) => &recorded.manifest,
+            // A signed artifact without a manifest has nothing vouching for its files
+            (None, Some(_)) => &unrecorded,
+            // Archives written before manifests were recorded have nothing to
+            // verify against
+            (None, None) => return Ok(()),
+        };
+
+        for (path, hash) in &self.0 {
+            match recorded.0.get(path) {
+                Some(expected) if expected == hash => {}
+                Some(_) => {
+                    return Err(CacheError::ContentHashMismatch(
+                        path.clone(),
+                        Backtrace::capture(),
+                    ));
+                }
+                None => {
+                    return Err(CacheError::ContentHashMissing(
+                        path.clone(),
+                        Backtrace::capture(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A manifest read back from an archive, along with the exact text that was
+/// signed.
+#[derive(Debug)]
+pub(crate) struct RecordedManifest {
+    manifest: ContentManifest,
+    raw: String,
+    signature: Option<String>,
+}
+
+impl RecordedManifest {
+    /// Parses the body of a PAX global header. Returns `None` if the header
+    /// isn't a manifest.
+    pub fn from_pax(body: &[u8]) -> Result<Option<Self>, CacheError> {
+        let mut raw = None;
+        let mut signature = None;
+        for (key, value) in pax_records(body)? {
+            match key {
+                MANIFEST_PAX_KEY => raw = Some(value.to_string()),
+                SIGNATURE_PAX_KEY => signature = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        let Some(raw) = raw else {
+            return Ok(None);
+        };
+        let manifest = serde_json::from_str(&raw)
+            .map_err(|_| CacheError::MalformedTar(Backtrace::capture()))?;
+
+        Ok(Some(Self {
+            manifest: ContentManifest(manifest),
+            raw,
+            signature,
+        }))
+    }
+}
+
+// A PAX record is `<length> <key>=<value>\n`, where the length counts itself
+fn pax_record(key: &str, value: &str) -> String {
+    let record = format!(" {key}={value}\n");
+    let mut len = record.len();
+    while len.to_string().len() + record.len() != len {
+        len = len.to_string().len() + record.len();
+    }
+    format!("{len}{record}")
+}
+
+fn pax_records(body: &[u8]) -> Result<Vec<(&str, &str)>, CacheError> {
+    let malformed = || CacheError::MalformedTar(Backtrace::capture());
+    let mut rest = std::str::from_utf8(body).map_err(|_| malformed())?;
+    let mut records = Vec::new();
+    while !rest.is_empty() {
+        let (len, _) = rest.split_once(' ').ok_or_else(malformed)?;
+        let len = len.parse::<usize>().map_err(|_| malformed())?;
+        let record = rest.get(..len).ok_or_else(malformed)?;
+        rest = &rest[len..];
+
+        let (key, value) = record
+            .split_once(' ')
+            .and_then(|(_, record)| record.strip_suffix('\n'))
+            .and_then(|record| record.split_once('='))
+            .ok_or_else(malformed)?;
+        records.push((key, value));
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pax_round_trip() {
+        let mut manifest = ContentManifest::default();
+        let mut hasher = Sha256::new();
+        hasher.update(b"file contents");
+        manifest.insert("dist/index.js".to_string(), hasher);
+
+        let body = manifest.to_pax(None).unwrap();
+        let recorded = RecordedManifest::from_pax(&body).unwrap().unwrap();
+        assert_eq!(recorded.manifest.0, manifest.0);
+        assert!(recorded.signature.is_none());
+        assert!(manifest.verify(Some(&recorded), None).is_ok());
+    }
+
+    #[test]
+    fn test_other_global_headers_are_ignored() {
+        let body = pax_record("comment", "hello");
+        assert!(
+            RecordedManifest::from_pax(body.as_bytes())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 mod create;
+mod manifest;
 mod restore;
 mod restore_directory;
 mod restore_regular;
@@ -3,21 +3,25 @@ use std::{backtrace::Backtrace, collections::HashMap, io::Read};
 use petgraph::graph::DiGraph;
 use sha2::{Digest, Sha512};
 use tar::Entry;
+use tracing::debug;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
 
 use crate::{
     CacheError,
     cache_archive::{
+        manifest::{ContentManifest, ManifestSigner, RecordedManifest},
         restore_directory::{CachedDirTree, restore_directory},
         restore_regular::restore_regular,
         restore_symlink::{
             canonicalize_linkname, restore_symlink, restore_symlink_allow_missing_target,
         },
     },
+    signature_authentication::ArtifactSignatureAuthenticator,
 };
 
 pub struct CacheReader<'a> {
     reader: Box<dyn Read + 'a>,
+    verifier: Option<ManifestSigner<'a>>,
 }
 
 impl<'a> CacheReader<'a> {
@@ -28,7 +32,10 @@ impl<'a> CacheReader<'a> {
             Box::new(reader)
         };
 
-        Ok(CacheReader { reader })
+        Ok(CacheReader {
+            reader,
+            verifier: None,
+        })
     }
 
     pub fn open(path: &AbsoluteSystemPathBuf) -> Result<Self, CacheError> {
@@ -41,7 +48,21 @@ impl<'a> CacheReader<'a> {
             Box::new(file)
         };
 
-        Ok(CacheReader { reader })
+        Ok(CacheReader {
+            reader,
+            verifier: None,
+        })
+    }
+
+    /// Requires the archive to have a manifest signed for the artifact `hash`
+    /// that covers every file it restores.
+    pub fn verify_manifest(
+        mut self,
+        verifier: &'a ArtifactSignatureAuthenticator,
+        hash: &'a str,
+    ) -> Self {
+        self.verifier = Some((verifier, hash));
+        self
     }
 
     pub fn get_sha(mut self) -> Result<Vec<u8>, CacheError> {
@@ -82,7 +103,23 @@ impl<'a> CacheReader<'a> {
         let dir_cache = CachedDirTree::new(anchor.to_owned());
         let mut tr = tar::Archive::new(&mut self.reader);
 
-        Self::restore_entries(&mut tr, &mut restored, dir_cache, anchor)?;
+        let mut content_hashes = ContentManifest::default();
+        let mut manifest = None;
+        Self::restore_entries(
+            &mut tr,
+            &mut restored,
+            dir_cache,
+            anchor,
+            &mut content_hashes,
+            &mut manifest,
+        )?;
+
+        if let Err(e) = content_hashes.verify(manifest.as_ref(), self.verifier) {
+            // Don't leave contents we can't vouch for behind in the workspace
+            remove_restored(anchor, &restored);
+            return Err(e);
+        }
+
         Ok(restored)
     }
 
@@ -91,6 +128,8 @@ impl<'a> CacheReader<'a> {
         restored: &mut Vec<AnchoredSystemPathBuf>,
         mut dir_cache: CachedDirTree,
         anchor: &AbsoluteSystemPath,
+        content_hashes: &mut ContentManifest,
+        manifest: &mut Option<RecordedManifest>,
     ) -> Result<(), CacheError> {
         // On first attempt to restore it's possible that a link target doesn't exist.
         // Save them and topologically sort them.
@@ -98,7 +137,16 @@ impl<'a> CacheReader<'a> {
 
         for entry in tr.entries()? {
             let mut entry = entry?;
-            match restore_entry(&mut dir_cache, anchor, &mut entry) {
+            if entry.header().entry_type().is_pax_global_extensions() {
+                let mut body = Vec::new();
+                entry.read_to_end(&mut body)?;
+                if let Some(recorded) = RecordedManifest::from_pax(&body)? {
+                    *manifest = Some(recorded);
+                }
+                continue;
+            }
+
+            match restore_entry(&mut dir_cache, anchor, &mut entry, content_hashes) {
                 Err(CacheError::LinkTargetDoesNotExist(_, _)) => {
                     symlinks.push(entry);
                 }
@@ -161,16 +209,32 @@ impl<'a> CacheReader<'a> {
     }
 }
 
+// Removes the files and symlinks restored from an archive that failed
+// verification. Directories are kept since they may have existed before.
+fn remove_restored(anchor: &AbsoluteSystemPath, restored: &[AnchoredSystemPathBuf]) {
+    for path in restored {
+        let path = anchor.resolve(path);
+        if path
+            .symlink_metadata()
+            .is_ok_and(|metadata| !metadata.is_dir())
+            && let Err(e) = path.remove_file()
+        {
+            debug!("failed to remove {path}: {e}");
+        }
+    }
+}
+
 fn restore_entry<T: Read>(
     dir_cache: &mut CachedDirTree,
     anchor: &AbsoluteSystemPath,
     entry: &mut Entry<T>,
+    content_hashes: &mut ContentManifest,
 ) -> Result<AnchoredSystemPathBuf, CacheError> {
     let header = entry.header();
 
     match header.entry_type() {
         tar::EntryType::Directory => restore_directory(dir_cache, anchor, entry),
-        tar::EntryType::Regular => restore_regular(dir_cache, anchor, entry),
+        tar::EntryType::Regular => restore_regular(dir_cache, anchor, entry, content_hashes),
         tar::EntryType::Symlink => restore_symlink(dir_cache, anchor, entry),
         ty => Err(CacheError::RestoreUnsupportedFileType(
             ty,
@@ -1,14 +1,24 @@
-use std::{fs::OpenOptions, io, io::Read, path::Path};
+use std::{
+    fs::OpenOptions,
+    io,
+    io::{Read, Write},
+    path::Path,
+};
 
+use sha2::{Digest, Sha256};
 use tar::Entry;
 use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, AnchoredSystemPathBuf};
 
-use crate::{CacheError, cache_archive::restore_directory::CachedDirTree};
+use crate::{
+    CacheError,
+    cache_archive::{manifest::ContentManifest, restore_directory::CachedDirTree},
+};
 
 pub fn restore_regular(
     dir_cache: &mut CachedDirTree,
     anchor: &AbsoluteSystemPath,
     entry: &mut Entry<impl Read>,
+    content_hashes: &mut ContentManifest,
 ) -> Result<AnchoredSystemPathBuf, CacheError> {
     // Assuming this was a `turbo`-created input, we currently have an
     // RelativeUnixPath. Assuming this is malicious input we don't really care
@@ -35,12 +45,36 @@ pub fn restore_regular(
         open_options.mode(header.mode()?);
     }
 
-    let mut file = open_options.open(resolved_path.as_path())?;
-    io::copy(entry, &mut file)?;
+    let file = open_options.open(resolved_path.as_path())?;
+    let mut writer = HashingWriter {
+        inner: file,
+        hasher: Sha256::new(),
+    };
+    io::copy(entry, &mut writer)?;
+    // The hashes are checked against the archive's manifest once every entry
+    // is restored, since the manifest comes last
+    content_hashes.insert(processed_name.to_unix().to_string(), writer.hasher);
 
     Ok(processed_name)
 }
 
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 impl CachedDirTree {
     pub fn safe_mkdir_file(
         &mut self,
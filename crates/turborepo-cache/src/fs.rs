@@ -2,6 +2,7 @@ use std::{backtrace::Backtrace, fs::OpenOptions};
 
 use camino::Utf8Path;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
 use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{analytics, analytics::AnalyticsEvent};
@@ -89,7 +90,15 @@ impl FSCache {
 
         let mut cache_reader = CacheReader::open(&cache_path)?;
 
-        let restored_files = cache_reader.restore(anchor)?;
+        let restored_files = match cache_reader.restore(anchor) {
+            Ok(restored_files) => restored_files,
+            Err(e @ (CacheError::ContentHashMismatch(..) | CacheError::ContentHashMissing(..))) => {
+                warn!("local cache entry {hash} has been modified ({e}), ignoring it");
+                self.log_fetch(analytics::CacheEvent::Miss, hash, 0);
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
 
         let meta = CacheMetadata::read(
             &self
@@ -151,6 +160,8 @@ impl FSCache {
             cache_item.add_file(anchor, file)?;
         }
 
+        cache_item.finish()?;
+
         let metadata_path = self
             .cache_directory
             .join_component(&format!("{hash}-meta.json"));
@@ -205,6 +216,45 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_tampered_entry_is_a_miss() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let file = AnchoredSystemPathBuf::from_raw("dist/index.js")?;
+        repo_root_path.resolve(&file).ensure_dir()?;
+        repo_root_path
+            .resolve(&file)
+            .create_with_contents("console.log('hello')")?;
+
+        let cache = FSCache::new(Utf8Path::new("cache"), repo_root_path, None)?;
+        let hash = "tampered";
+        // Write an uncompressed entry so we can modify the file contents in place
+        let cache_path = repo_root_path.join_components(&["cache", "tampered.tar"]);
+        let mut writer = CacheWriter::create(&cache_path)?;
+        writer.add_file(repo_root_path, &file)?;
+        writer.finish()?;
+        repo_root_path
+            .join_components(&["cache", "tampered-meta.json"])
+            .create_with_contents(r#"{"hash":"tampered","duration":10}"#)?;
+
+        assert!(cache.fetch(repo_root_path, hash)?.is_some());
+
+        let contents = cache_path.read()?;
+        let needle = b"hello";
+        let offset = contents
+            .windows(needle.len())
+            .position(|window| window == needle)
+            .unwrap();
+        let mut tampered = contents.clone();
+        tampered[offset..offset + needle.len()].copy_from_slice(b"pwned");
+        cache_path.create_with_contents(tampered)?;
+
+        assert!(cache.fetch(repo_root_path, hash)?.is_none());
+        assert!(!repo_root_path.resolve(&file).exists());
+
+        Ok(())
+    }
+
     async fn round_trip_test(test_case: &TestCase, port: u16) -> Result<()> {
         let repo_root = tempdir()?;
         let repo_root_path = AbsoluteSystemPath::from_std_path(repo_root.path())?;
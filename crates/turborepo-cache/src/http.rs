@@ -6,7 +6,7 @@ use std::{
 };
 
 use tokio_stream::StreamExt;
-use tracing::debug;
+use tracing::{debug, warn};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
 use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{
@@ -46,15 +46,14 @@ impl HTTPCache {
             .as_ref()
             .is_some_and(|remote_cache_opts| remote_cache_opts.signature)
         {
-            Some(ArtifactSignatureAuthenticator {
-                team_id: api_auth
+            Some(ArtifactSignatureAuthenticator::from_env(
+                api_auth
                     .team_id
                     .as_deref()
                     .unwrap_or_default()
                     .as_bytes()
                     .to_vec(),
-                secret_key_override: None,
-            })
+            ))
         } else {
             None
         };
@@ -77,8 +76,19 @@ impl HTTPCache {
         files: &[AnchoredSystemPathBuf],
         duration: u64,
     ) -> Result<(), CacheError> {
+        if self
+            .signer_verifier
+            .as_ref()
+            .is_some_and(|signer| !signer.can_sign())
+        {
+            // Verify-only machines hold just the public key, anything they
+            // uploaded would be rejected by everyone else.
+            debug!("no signature private key available, skipping upload of {hash}");
+            return Ok(());
+        }
+
         let mut artifact_body = Vec::new();
-        self.write(&mut artifact_body, anchor, files).await?;
+        self.write(&mut artifact_body, anchor, hash, files).await?;
         let bytes = artifact_body.len();
 
         let tag = self
@@ -127,12 +137,17 @@ impl HTTPCache {
         &self,
         writer: impl Write,
         anchor: &AbsoluteSystemPath,
+        hash: &str,
         files: &[AnchoredSystemPathBuf],
     ) -> Result<(), CacheError> {
         let mut cache_archive = CacheWriter::from_writer(writer, true)?;
+        if let Some(signer) = &self.signer_verifier {
+            cache_archive = cache_archive.sign_manifest(signer, hash);
+        }
         for file in files {
             cache_archive.add_file(anchor, file)?;
         }
+        cache_archive.finish()?;
 
         Ok(())
     }
@@ -243,7 +258,19 @@ impl HTTPCache {
             })?
         };
 
-        let files = Self::restore_tar(&self.repo_root, &body)?;
+        let files = match self.restore_tar(&self.repo_root, hash, &body) {
+            Ok(files) => files,
+            Err(
+                e @ (CacheError::ContentHashMismatch(..)
+                | CacheError::ContentHashMissing(..)
+                | CacheError::InvalidTag(..)),
+            ) => {
+                warn!("remote cache artifact {hash} failed verification ({e}), ignoring it");
+                self.log_fetch(analytics::CacheEvent::Miss, hash, 0);
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
 
         self.log_fetch(analytics::CacheEvent::Hit, hash, duration);
         Ok(Some((
@@ -261,10 +288,16 @@ impl HTTPCache {
 
     #[tracing::instrument(skip_all)]
     pub(crate) fn restore_tar(
+        &self,
         root: &AbsoluteSystemPath,
+        hash: &str,
         body: &[u8],
     ) -> Result<Vec<AnchoredSystemPathBuf>, CacheError> {
         let mut cache_reader = CacheReader::from_reader(body, true)?;
+        // Signed artifacts also have to carry a signed hash for every file
+        if let Some(verifier) = &self.signer_verifier {
+            cache_reader = cache_reader.verify_manifest(verifier, hash);
+        }
         cache_reader.restore(root)
     }
 
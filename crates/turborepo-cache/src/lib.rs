@@ -42,6 +42,10 @@ pub enum CacheError {
     ArtifactTagMissing(#[backtrace] Backtrace),
     #[error("invalid artifact verification tag")]
     InvalidTag(#[backtrace] Backtrace),
+    #[error("cache artifact content does not match its recorded hash: {0}")]
+    ContentHashMismatch(String, #[backtrace] Backtrace),
+    #[error("cache artifact has no signed hash for {0}")]
+    ContentHashMissing(String, #[backtrace] Backtrace),
     #[error("cannot untar file to {0}")]
     InvalidFilePath(String, #[backtrace] Backtrace),
     #[error("failed to contact remote cache: {0}")]
@@ -132,6 +136,14 @@ impl CacheActions {
             write: true,
         }
     }
+
+    /// Only allows the actions that are allowed by both `self` and `other`
+    pub fn restrict(&self, other: &CacheActions) -> Self {
+        Self {
+            read: self.read && other.read,
+            write: self.write && other.write,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize)]
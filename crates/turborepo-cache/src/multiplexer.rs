@@ -87,6 +87,10 @@ impl CacheMultiplexer {
         self.http.as_ref().map(|http| http.requests())
     }
 
+    pub fn cache_config(&self) -> CacheConfig {
+        self.cache_config
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn put(
         &self,
@@ -94,8 +98,9 @@ impl CacheMultiplexer {
         key: &str,
         files: &[AnchoredSystemPathBuf],
         duration: u64,
+        cache_config: CacheConfig,
     ) -> Result<(), CacheError> {
-        if self.cache_config.local.write {
+        if cache_config.local.write {
             self.fs
                 .as_ref()
                 .map(|fs| fs.put(anchor, key, files, duration))
@@ -104,14 +109,17 @@ impl CacheMultiplexer {
 
         let http_result = match self.get_http_cache() {
             Some(http) => {
-                if self.cache_config.remote.write {
+                if cache_config.remote.write {
                     let http_result = http.put(anchor, key, files, duration).await;
 
                     Some(http_result)
                 } else {
-                    if self
-                        .should_print_skipping_remote_put
-                        .load(Ordering::Relaxed)
+                    // Only warn if the whole run is read-only, tasks can opt out of
+                    // remote writes on their own
+                    if !self.cache_config.remote.write
+                        && self
+                            .should_print_skipping_remote_put
+                            .load(Ordering::Relaxed)
                     {
                         // Warn once per build, not per task
                         warn!("Remote cache is read-only, skipping upload");
@@ -145,15 +153,16 @@ impl CacheMultiplexer {
         &self,
         anchor: &AbsoluteSystemPath,
         key: &str,
+        cache_config: CacheConfig,
     ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
-        if self.cache_config.local.read
+        if cache_config.local.read
             && let Some(fs) = &self.fs
             && let response @ Ok(Some(_)) = fs.fetch(anchor, key)
         {
             return response;
         }
 
-        if self.cache_config.remote.read
+        if cache_config.remote.read
             && let Some(http) = self.get_http_cache()
             && let Ok(Some((CacheHitMetadata { source, time_saved }, files))) =
                 http.fetch(key).await
@@ -162,7 +171,7 @@ impl CacheMultiplexer {
             // we have previously successfully stored in HTTP cache, and so the overall
             // result is a success at fetching. Storing in lower-priority caches is an
             // optimization.
-            if self.cache_config.local.write
+            if cache_config.local.write
                 && let Some(fs) = &self.fs
             {
                 let _ = fs.put(anchor, key, &files, time_saved);
@@ -175,8 +184,12 @@ impl CacheMultiplexer {
     }
 
     #[tracing::instrument(skip_all)]
-    pub async fn exists(&self, key: &str) -> Result<Option<CacheHitMetadata>, CacheError> {
-        if self.cache_config.local.read
+    pub async fn exists(
+        &self,
+        key: &str,
+        cache_config: CacheConfig,
+    ) -> Result<Option<CacheHitMetadata>, CacheError> {
+        if cache_config.local.read
             && let Some(fs) = &self.fs
         {
             match fs.exists(key) {
@@ -188,7 +201,7 @@ impl CacheMultiplexer {
             }
         }
 
-        if self.cache_config.remote.read
+        if cache_config.remote.read
             && let Some(http) = self.get_http_cache()
         {
             match http.exists(key).await {
@@ -3,11 +3,16 @@ use std::env;
 use base64::{Engine, prelude::BASE64_STANDARD};
 use hmac::{Hmac, Mac};
 use os_str_bytes::OsStringBytes;
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
 use sha2::Sha256;
 use thiserror::Error;
 
 type HmacSha256 = Hmac<Sha256>;
 
+const SECRET_KEY_ENV: &str = "TURBO_REMOTE_CACHE_SIGNATURE_KEY";
+const PRIVATE_KEY_ENV: &str = "TURBO_REMOTE_CACHE_SIGNATURE_PRIVATE_KEY";
+const PUBLIC_KEY_ENV: &str = "TURBO_REMOTE_CACHE_SIGNATURE_PUBLIC_KEY";
+
 #[derive(Debug, Error)]
 pub enum SignatureError {
     #[error(
@@ -21,6 +26,50 @@ pub enum SignatureError {
     Base64EncodingError(#[from] base64::DecodeError),
     #[error(transparent)]
     Hmac(#[from] hmac::digest::InvalidLength),
+    #[error(
+        "signature private key not found. Only a public key was provided in the \
+         TURBO_REMOTE_CACHE_SIGNATURE_PUBLIC_KEY environment variable, so artifacts can be \
+         verified but not signed"
+    )]
+    NoSignaturePrivateKey,
+    #[error("invalid Ed25519 private key: {0}")]
+    InvalidPrivateKey(String),
+}
+
+/// Ed25519 keys, base64 encoded. The private key is a PKCS#8 document and the
+/// public key is the raw 32 byte key. Machines that only restore artifacts
+/// need nothing but the public key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ed25519Keys {
+    pub private_key: Option<String>,
+    pub public_key: Option<String>,
+}
+
+impl Ed25519Keys {
+    fn from_env() -> Option<Self> {
+        let keys = Self {
+            private_key: env::var(PRIVATE_KEY_ENV).ok(),
+            public_key: env::var(PUBLIC_KEY_ENV).ok(),
+        };
+        (keys.private_key.is_some() || keys.public_key.is_some()).then_some(keys)
+    }
+
+    fn key_pair(&self) -> Result<Ed25519KeyPair, SignatureError> {
+        let private_key = self
+            .private_key
+            .as_deref()
+            .ok_or(SignatureError::NoSignaturePrivateKey)?;
+        let pkcs8 = BASE64_STANDARD.decode(private_key.trim())?;
+        Ed25519KeyPair::from_pkcs8_maybe_unchecked(&pkcs8)
+            .map_err(|e| SignatureError::InvalidPrivateKey(e.to_string()))
+    }
+
+    fn public_key(&self) -> Result<Vec<u8>, SignatureError> {
+        match &self.public_key {
+            Some(public_key) => Ok(BASE64_STANDARD.decode(public_key.trim())?),
+            None => Ok(self.key_pair()?.public_key().as_ref().to_vec()),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -28,6 +77,8 @@ pub struct ArtifactSignatureAuthenticator {
     pub(crate) team_id: Vec<u8>,
     // An override for testing purposes (to avoid env var race conditions)
     pub(crate) secret_key_override: Option<Vec<u8>>,
+    // When set, artifacts are signed with Ed25519 instead of HMAC-SHA256
+    pub(crate) ed25519_keys: Option<Ed25519Keys>,
 }
 
 impl ArtifactSignatureAuthenticator {
@@ -35,9 +86,34 @@ impl ArtifactSignatureAuthenticator {
         Self {
             team_id,
             secret_key_override,
+            ed25519_keys: None,
         }
     }
 
+    /// Picks the signing mode from the environment. If either Ed25519 key is
+    /// set we use asymmetric signatures, otherwise we fall back to the shared
+    /// HMAC secret.
+    pub fn from_env(team_id: Vec<u8>) -> Self {
+        Self {
+            team_id,
+            secret_key_override: None,
+            ed25519_keys: Ed25519Keys::from_env(),
+        }
+    }
+
+    pub fn with_ed25519_keys(mut self, keys: Ed25519Keys) -> Self {
+        self.ed25519_keys = Some(keys);
+        self
+    }
+
+    /// Whether this authenticator holds a key that can produce signatures.
+    /// In Ed25519 mode a public key alone is only enough to verify.
+    pub fn can_sign(&self) -> bool {
+        self.ed25519_keys
+            .as_ref()
+            .is_none_or(|keys| keys.private_key.is_some())
+    }
+
     // Gets secret key from either secret key override or environment variable.
     // HMAC_SHA256 has no key length limit, although it's generally recommended
     // to keep key length under 64 bytes since anything longer is hashed using
@@ -47,7 +123,7 @@ impl ArtifactSignatureAuthenticator {
             return Ok(secret_key.to_vec());
         }
 
-        Ok(env::var_os("TURBO_REMOTE_CACHE_SIGNATURE_KEY")
+        Ok(env::var_os(SECRET_KEY_ENV)
             .ok_or(SignatureError::NoSignatureSecretKey)?
             .into_raw_vec())
     }
@@ -74,6 +150,10 @@ impl ArtifactSignatureAuthenticator {
         hash: &[u8],
         artifact_body: &[u8],
     ) -> Result<Vec<u8>, SignatureError> {
+        if let Some(keys) = &self.ed25519_keys {
+            return self.sign_ed25519(keys, hash, artifact_body);
+        }
+
         let mut mac = self.get_tag_generator(hash)?;
 
         mac.update(artifact_body);
@@ -87,6 +167,11 @@ impl ArtifactSignatureAuthenticator {
         hash: &[u8],
         artifact_body: &[u8],
     ) -> Result<String, SignatureError> {
+        if let Some(keys) = &self.ed25519_keys {
+            let signature = self.sign_ed25519(keys, hash, artifact_body)?;
+            return Ok(BASE64_STANDARD.encode(signature));
+        }
+
         let mut hmac_ctx = self.get_tag_generator(hash)?;
 
         hmac_ctx.update(artifact_body);
@@ -101,14 +186,35 @@ impl ArtifactSignatureAuthenticator {
         artifact_body: &[u8],
         expected_tag: &str,
     ) -> Result<bool, SignatureError> {
+        let expected_bytes = BASE64_STANDARD.decode(expected_tag)?;
+        if let Some(keys) = &self.ed25519_keys {
+            let public_key = keys.public_key()?;
+            let mut message = self.construct_metadata(hash)?;
+            message.extend_from_slice(artifact_body);
+            return Ok(UnparsedPublicKey::new(&ED25519, public_key)
+                .verify(&message, &expected_bytes)
+                .is_ok());
+        }
+
         let mut mac = HmacSha256::new_from_slice(&self.secret_key()?)?;
         let message = self.construct_metadata(hash)?;
         mac.update(&message);
         mac.update(artifact_body);
 
-        let expected_bytes = BASE64_STANDARD.decode(expected_tag)?;
         Ok(mac.verify_slice(&expected_bytes).is_ok())
     }
+
+    fn sign_ed25519(
+        &self,
+        keys: &Ed25519Keys,
+        hash: &[u8],
+        artifact_body: &[u8],
+    ) -> Result<Vec<u8>, SignatureError> {
+        let key_pair = keys.key_pair()?;
+        let mut message = self.construct_metadata(hash)?;
+        message.extend_from_slice(artifact_body);
+        Ok(key_pair.sign(&message).as_ref().to_vec())
+    }
 }
 
 #[cfg(test)]
@@ -248,6 +354,7 @@ mod tests {
         let signature = ArtifactSignatureAuthenticator {
             team_id: test_case.team_id.to_vec(),
             secret_key_override: None,
+            ed25519_keys: None,
         };
 
         let hash = test_case.artifact_hash;
@@ -273,4 +380,47 @@ mod tests {
         assert!(signature.validate(hash, artifact_body, &tag)?);
         Ok(())
     }
+
+    #[test]
+    fn test_ed25519_signatures() -> Result<()> {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let private_key = BASE64_STANDARD.encode(pkcs8.as_ref());
+        let public_key = BASE64_STANDARD.encode(
+            Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())
+                .unwrap()
+                .public_key()
+                .as_ref(),
+        );
+
+        for test_case in get_test_cases() {
+            let signer = ArtifactSignatureAuthenticator::new(test_case.team_id.to_vec(), None)
+                .with_ed25519_keys(Ed25519Keys {
+                    private_key: Some(private_key.clone()),
+                    public_key: None,
+                });
+            let verifier = ArtifactSignatureAuthenticator::new(test_case.team_id.to_vec(), None)
+                .with_ed25519_keys(Ed25519Keys {
+                    private_key: None,
+                    public_key: Some(public_key.clone()),
+                });
+            assert!(signer.can_sign());
+            assert!(!verifier.can_sign());
+
+            let hash = test_case.artifact_hash;
+            let artifact_body = test_case.artifact_body;
+            let tag = signer.generate_tag(hash, artifact_body)?;
+
+            assert!(verifier.validate(hash, artifact_body, &tag)?);
+            assert!(signer.validate(hash, artifact_body, &tag)?);
+            assert!(!verifier.validate(b"other hash", artifact_body, &tag)?);
+            assert!(!verifier.validate(hash, b"tampered", &tag)?);
+            assert!(matches!(
+                verifier.generate_tag(hash, artifact_body),
+                Err(SignatureError::NoSignaturePrivateKey)
+            ));
+        }
+
+        Ok(())
+    }
 }
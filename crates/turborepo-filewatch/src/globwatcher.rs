@@ -78,7 +78,7 @@ impl Display for GlobError {
     }
 }
 
-fn compile_glob(raw: &str) -> Result<Glob<'static>, GlobError> {
+pub(crate) fn compile_glob(raw: &str) -> Result<Glob<'static>, GlobError> {
     Glob::from_str(raw)
         .map(|g| g.to_owned())
         .map_err(|e| GlobError {
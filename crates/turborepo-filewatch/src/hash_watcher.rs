@@ -23,6 +23,7 @@ use crate::{
     NotifyError, OptionalWatch,
     debouncer::Debouncer,
     globwatcher::{GlobError, GlobSet},
+    metrics::{HashCounters, HashMetrics},
     package_watcher::DiscoveryData,
     scm_resource::SCMResource,
 };
@@ -31,6 +32,7 @@ pub struct HashWatcher {
     _exit_tx: oneshot::Sender<()>,
     _handle: tokio::task::JoinHandle<()>,
     query_tx: mpsc::Sender<Query>,
+    counters: Arc<HashCounters>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -131,15 +133,27 @@ impl HashWatcher {
     ) -> Self {
         let (exit_tx, exit_rx) = oneshot::channel();
         let (query_tx, query_rx) = mpsc::channel(16);
-        let subscriber = Subscriber::new(repo_root, package_discovery, scm, query_rx);
+        let counters = Arc::new(HashCounters::default());
+        let subscriber = Subscriber::new(
+            repo_root,
+            package_discovery,
+            scm,
+            query_rx,
+            counters.clone(),
+        );
         let handle = tokio::spawn(subscriber.watch(exit_rx, file_events));
         Self {
             _exit_tx: exit_tx,
             _handle: handle,
             query_tx,
+            counters,
         }
     }
 
+    pub fn metrics(&self) -> HashMetrics {
+        self.counters.snapshot()
+    }
+
     // Note that this does not wait for any sort of ready signal. The watching
     // process won't respond until filewatching is ready, but there is no
     // guarantee that package data or file hashing will be done before
@@ -159,6 +173,7 @@ struct Subscriber {
     query_rx: mpsc::Receiver<Query>,
     scm: SCMResource,
     next_version: AtomicUsize,
+    counters: Arc<HashCounters>,
 }
 
 #[derive(Debug)]
@@ -325,6 +340,7 @@ impl Subscriber {
         package_discovery: watch::Receiver<Option<DiscoveryData>>,
         scm: SCM,
         query_rx: mpsc::Receiver<Query>,
+        counters: Arc<HashCounters>,
     ) -> Self {
         Self {
             repo_root,
@@ -332,6 +348,7 @@ impl Subscriber {
             scm: SCMResource::new(scm),
             query_rx,
             next_version: AtomicUsize::new(0),
+            counters,
         }
     }
 
@@ -442,6 +459,7 @@ impl Subscriber {
                 // mapping them back to packages. It is doable if we want to
                 // attempt it in the future.
                 if !spec.is_package_local() {
+                    self.counters.record_miss();
                     let _ = tx.send(Err(Error::UnsupportedGlob));
                     trace!("unsupported glob in query {:?}", spec);
                     return;
@@ -449,12 +467,15 @@ impl Subscriber {
                 if let Some(state) = hashes.get_mut(&spec) {
                     match state {
                         HashState::Hashes(hashes) => {
+                            self.counters.record_hit();
                             tx.send(Ok(hashes.clone())).unwrap();
                         }
                         HashState::Pending(_, _, txs) => {
+                            self.counters.record_pending();
                             txs.push(tx);
                         }
                         HashState::Unavailable(e) => {
+                            self.counters.record_miss();
                             let _ = tx.send(Err(Error::HashingError(e.clone())));
                         }
                     }
@@ -466,12 +487,14 @@ impl Subscriber {
                 {
                     // in this scenario, we know the package exists, but we aren't tracking these
                     // particular inputs. Queue a hash request for them.
+                    self.counters.record_miss();
                     let (version, debouncer) = self.queue_package_hash(&spec, hash_update_tx, true);
                     // this request will likely time out. However, if the client has asked for
                     // this spec once, they might ask again, and we can start tracking it.
                     hashes.insert(spec, HashState::Pending(version, debouncer, vec![tx]));
                 } else {
                     // We don't know anything about this package.
+                    self.counters.record_miss();
                     let _ = tx.send(Err(Error::UnknownPackage(spec)));
                 }
             }
@@ -64,16 +64,53 @@ mod debouncer;
 mod fsevent;
 pub mod globwatcher;
 pub mod hash_watcher;
+pub mod metrics;
 mod optional_watch;
 pub mod package_watcher;
+mod poll;
 mod scm_resource;
 
+use metrics::{WatcherCounters, WatcherMetrics};
 pub use optional_watch::OptionalWatch;
+pub use poll::PollOptions;
+use poll::PollWatcher;
+
+// Either the platform's native watcher or a `PollWatcher`
+type Backend = Box<dyn Watcher + Send>;
+
+/// How long we wait for the initial cookie when using a native backend
+const INITIAL_COOKIE_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// Which mechanism `FileSystemWatcher` uses to find out about changes.
+#[derive(Clone, Debug)]
+pub enum WatcherBackend {
+    /// Poll file systems that are known not to deliver change notifications,
+    /// and use the native backend everywhere else.
+    Auto(PollOptions),
+    /// Use the platform's native notifications.
+    Native,
+    /// Periodically scan the file system for changes.
+    Polling(PollOptions),
+}
 
-#[cfg(not(target_os = "macos"))]
-type Backend = RecommendedWatcher;
-#[cfg(target_os = "macos")]
-type Backend = FsEventWatcher;
+impl Default for WatcherBackend {
+    fn default() -> Self {
+        WatcherBackend::Auto(PollOptions::default())
+    }
+}
+
+impl WatcherBackend {
+    fn resolve(self, root: &AbsoluteSystemPath) -> Self {
+        match self {
+            WatcherBackend::Auto(options) if is_unsupported_file_system(root) => {
+                debug!("{root} does not support change notifications, polling instead");
+                WatcherBackend::Polling(options)
+            }
+            WatcherBackend::Auto(_) => WatcherBackend::Native,
+            backend => backend,
+        }
+    }
+}
 
 type EventResult = Result<Event, notify::Error>;
 
@@ -115,6 +152,7 @@ pub struct FileSystemWatcher {
     // to be notified of a close.
     _exit_ch: tokio::sync::oneshot::Sender<()>,
     cookie_dir: AbsoluteSystemPathBuf,
+    counters: Arc<WatcherCounters>,
 }
 
 impl FileSystemWatcher {
@@ -128,6 +166,14 @@ impl FileSystemWatcher {
     pub fn new(
         root: &AbsoluteSystemPath,
         cookie_dir: AbsoluteSystemPathBuf,
+    ) -> Result<Self, WatchError> {
+        Self::new_with_backend(root, cookie_dir, WatcherBackend::default())
+    }
+
+    pub fn new_with_backend(
+        root: &AbsoluteSystemPath,
+        cookie_dir: AbsoluteSystemPathBuf,
+        backend: WatcherBackend,
     ) -> Result<Self, WatchError> {
         tracing::debug!("initing file-system watcher");
 
@@ -137,12 +183,21 @@ impl FileSystemWatcher {
             )));
         }
 
+        let backend = backend.resolve(root);
+        // A polling backend won't see the cookie until its next scan
+        let initial_cookie_timeout = match &backend {
+            WatcherBackend::Polling(options) => INITIAL_COOKIE_TIMEOUT.max(options.interval * 4),
+            _ => INITIAL_COOKIE_TIMEOUT,
+        };
+
         let (file_events_receiver_tx, file_events_receiver_lazy) = OptionalWatch::new();
         let (send_file_events, mut recv_file_events) = mpsc::channel(1024);
         let (exit_ch, exit_signal) = tokio::sync::oneshot::channel();
+        let counters = Arc::new(WatcherCounters::default());
 
         tokio::task::spawn({
             let cookie_dir = cookie_dir.clone();
+            let counters = counters.clone();
             let watch_root = root.to_owned();
             async move {
                 // this task never yields, so run it in the blocking threadpool
@@ -150,7 +205,12 @@ impl FileSystemWatcher {
                 let cookie_dir_task = cookie_dir.clone();
                 let task = tokio::task::spawn_blocking(move || {
                     setup_cookie_dir(&cookie_dir_task)?;
-                    run_watcher(&watch_root_task, send_file_events)
+                    run_watcher(
+                        &watch_root_task,
+                        &cookie_dir_task,
+                        backend,
+                        send_file_events,
+                    )
                 });
 
                 let Ok(Ok(watcher)) = task.await else {
@@ -161,7 +221,10 @@ impl FileSystemWatcher {
 
                 // Ensure we are ready to receive new events, not events for existing state
                 debug!("waiting for initial filesystem cookie");
-                if let Err(e) = wait_for_cookie(&cookie_dir, &mut recv_file_events).await {
+                if let Err(e) =
+                    wait_for_cookie(&cookie_dir, &mut recv_file_events, initial_cookie_timeout)
+                        .await
+                {
                     // if we can't get a cookie here, we should not make the file
                     // watching available to downstream services
                     warn!("failed to wait for initial filesystem cookie: {}", e);
@@ -179,7 +242,15 @@ impl FileSystemWatcher {
                     return;
                 }
 
-                watch_events(watcher, watch_root, recv_file_events, exit_signal, sender).await;
+                watch_events(
+                    watcher,
+                    watch_root,
+                    recv_file_events,
+                    exit_signal,
+                    sender,
+                    counters,
+                )
+                .await;
             }
         });
 
@@ -187,6 +258,7 @@ impl FileSystemWatcher {
             receiver: file_events_receiver_lazy,
             _exit_ch: exit_ch,
             cookie_dir,
+            counters,
         })
     }
 
@@ -206,6 +278,10 @@ impl FileSystemWatcher {
     pub fn cookie_dir(&self) -> &AbsoluteSystemPath {
         &self.cookie_dir
     }
+
+    pub fn metrics(&self) -> WatcherMetrics {
+        self.counters.snapshot()
+    }
 }
 
 fn setup_cookie_dir(cookie_dir: &AbsoluteSystemPath) -> Result<(), WatchError> {
@@ -231,14 +307,20 @@ async fn watch_events(
     mut recv_file_events: mpsc::Receiver<EventResult>,
     exit_signal: tokio::sync::oneshot::Receiver<()>,
     broadcast_sender: broadcast::Sender<Result<Event, NotifyError>>,
+    counters: Arc<WatcherCounters>,
 ) {
     let mut exit_signal = exit_signal;
     'outer: loop {
         tokio::select! {
             _ = &mut exit_signal => break 'outer,
             Some(event) = recv_file_events.recv().into_future() => {
+                match &event {
+                    Ok(event) => counters.record_event(event),
+                    Err(_) => counters.record_error(),
+                }
                 // we don't care if we fail to send, it just means no one is currently watching
                 let _ = broadcast_sender.send(event.map_err(NotifyError::from));
+                counters.record_queued(broadcast_sender.len());
             }
         }
     }
@@ -252,6 +334,7 @@ async fn watch_events(
     mut recv_file_events: mpsc::Receiver<EventResult>,
     exit_signal: tokio::sync::oneshot::Receiver<()>,
     broadcast_sender: broadcast::Sender<Result<Event, NotifyError>>,
+    counters: Arc<WatcherCounters>,
 ) {
     let mut exit_signal = exit_signal;
     'outer: loop {
@@ -260,6 +343,7 @@ async fn watch_events(
             Some(event) = recv_file_events.recv().into_future() => {
                 match event {
                     Ok(mut event) => {
+                        counters.record_event(&event);
                         // Note that we need to filter relevant events
                         // before doing manual recursive watching so that
                         // we don't try to add watches to siblings of the
@@ -292,10 +376,12 @@ async fn watch_events(
                         let _ = broadcast_sender.send(Ok(event));
                     },
                     Err(error) => {
+                        counters.record_error();
                         // we don't care if we fail to send, it just means no one is currently watching
                         let _ = broadcast_sender.send(Err(NotifyError::from(error)));
                     }
                 }
+                counters.record_queued(broadcast_sender.len());
             }
         }
     }
@@ -421,11 +507,24 @@ fn manually_add_recursive_watches(
 
 fn run_watcher(
     root: &AbsoluteSystemPath,
+    cookie_dir: &AbsoluteSystemPath,
+    backend: WatcherBackend,
     sender: mpsc::Sender<EventResult>,
 ) -> Result<Backend, WatchError> {
-    let mut watcher = make_watcher(move |res| {
+    let event_handler = move |res| {
         let _ = sender.blocking_send(res);
-    })?;
+    };
+    let mut watcher: Backend = match backend {
+        WatcherBackend::Polling(options) => Box::new(
+            PollWatcher::with_options(
+                event_handler,
+                options,
+                vec![cookie_dir.as_std_path().to_owned()],
+            )
+            .map_err(|e| WatchError::Setup(format!("invalid polling ignore glob: {e}")))?,
+        ),
+        WatcherBackend::Auto(_) | WatcherBackend::Native => Box::new(make_watcher(event_handler)?),
+    };
 
     watch_recursively(root, &mut watcher)?;
 
@@ -435,21 +534,65 @@ fn run_watcher(
 }
 
 #[cfg(not(target_os = "macos"))]
-fn make_watcher<F: EventHandler>(event_handler: F) -> Result<Backend, notify::Error> {
+fn make_watcher<F: EventHandler>(event_handler: F) -> Result<RecommendedWatcher, notify::Error> {
     RecommendedWatcher::new(event_handler, Config::default())
 }
 
 #[cfg(target_os = "macos")]
-fn make_watcher<F: EventHandler>(event_handler: F) -> Result<Backend, notify::Error> {
+fn make_watcher<F: EventHandler>(event_handler: F) -> Result<FsEventWatcher, notify::Error> {
     FsEventWatcher::new(event_handler, notify::Config::default())
 }
 
+/// Returns true if `root` is on a file system that doesn't reliably deliver
+/// change notifications, e.g. network file systems or directories shared into
+/// a container from the host.
+#[cfg(target_os = "linux")]
+fn is_unsupported_file_system(root: &AbsoluteSystemPath) -> bool {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    // Magic numbers from statfs(2)
+    const NFS_SUPER_MAGIC: u64 = 0x6969;
+    const SMB_SUPER_MAGIC: u64 = 0x517b;
+    const CIFS_MAGIC_NUMBER: u64 = 0xff534d42;
+    const SMB2_MAGIC_NUMBER: u64 = 0xfe534d42;
+    const V9FS_MAGIC: u64 = 0x01021997;
+    const FUSE_SUPER_MAGIC: u64 = 0x65735546;
+
+    let Ok(path) = CString::new(root.as_std_path().as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: `statfs` is plain old data, so an all-zero value is valid
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is a valid C string and `stat` is a valid pointer for the
+    // duration of the call
+    if unsafe { libc::statfs(path.as_ptr(), &mut stat) } != 0 {
+        return false;
+    }
+    // `f_type` is signed on some platforms, so only compare the low 32 bits
+    let f_type = (stat.f_type as u64) & 0xffff_ffff;
+    matches!(
+        f_type,
+        NFS_SUPER_MAGIC
+            | SMB_SUPER_MAGIC
+            | CIFS_MAGIC_NUMBER
+            | SMB2_MAGIC_NUMBER
+            | V9FS_MAGIC
+            | FUSE_SUPER_MAGIC
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_unsupported_file_system(_root: &AbsoluteSystemPath) -> bool {
+    false
+}
+
 /// wait_for_cookie performs a roundtrip through the filewatching mechanism.
 /// This ensures that we are ready to receive *new* filesystem events, rather
 /// than receiving events from existing state, which some backends can do.
 async fn wait_for_cookie(
     cookie_dir: &AbsoluteSystemPath,
     recv: &mut mpsc::Receiver<EventResult>,
+    timeout: Duration,
 ) -> Result<(), WatchError> {
     // TODO: should this be passed in? Currently the caller guarantees that the
     // directory is empty, but it could be the responsibility of the
@@ -459,7 +602,7 @@ async fn wait_for_cookie(
         .create_with_contents("cookie")
         .map_err(|e| WatchError::Setup(format!("failed to write cookie to {cookie_path}: {e}")))?;
     loop {
-        let event = tokio::time::timeout(Duration::from_millis(2000), recv.recv())
+        let event = tokio::time::timeout(timeout, recv.recv())
             .await
             .map_err(|e| WatchError::Setup(format!("waiting for cookie timed out: {e}")))?
             .ok_or_else(|| {
@@ -0,0 +1,149 @@
+//! Counters that describe how file watching and hashing are behaving. These
+//! are cheap to update from the hot path and are read by the daemon when a
+//! client asks for its metrics.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use notify::Event;
+
+/// What the `FileSystemWatcher` has seen since it started.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WatcherMetrics {
+    /// Events delivered by the backend
+    pub events: u64,
+    /// Errors delivered by the backend
+    pub errors: u64,
+    /// Events where the backend reported it dropped changes and consumers
+    /// need to rescan
+    pub rescans: u64,
+    /// Events waiting to be processed by the slowest consumer
+    pub queued_events: u64,
+    /// The most events that have been waiting at once. If this approaches
+    /// the size of the channel, consumers are falling behind.
+    pub max_queued_events: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct WatcherCounters {
+    events: AtomicU64,
+    errors: AtomicU64,
+    rescans: AtomicU64,
+    queued_events: AtomicU64,
+    max_queued_events: AtomicU64,
+}
+
+impl WatcherCounters {
+    pub(crate) fn record_event(&self, event: &Event) {
+        self.events.fetch_add(1, Ordering::Relaxed);
+        if event.need_rescan() {
+            self.rescans.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn record_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_queued(&self, queued: usize) {
+        let queued = queued as u64;
+        self.queued_events.store(queued, Ordering::Relaxed);
+        self.max_queued_events.fetch_max(queued, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> WatcherMetrics {
+        WatcherMetrics {
+            events: self.events.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            rescans: self.rescans.load(Ordering::Relaxed),
+            queued_events: self.queued_events.load(Ordering::Relaxed),
+            max_queued_events: self.max_queued_events.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// How file hash queries to the `HashWatcher` have been answered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HashMetrics {
+    /// Queries answered from already computed hashes
+    pub hits: u64,
+    /// Queries that had to wait for hashing to finish
+    pub pending: u64,
+    /// Queries for packages or inputs that weren't being tracked, or that
+    /// failed to hash
+    pub misses: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct HashCounters {
+    hits: AtomicU64,
+    pending: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl HashCounters {
+    pub(crate) fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_pending(&self) {
+        self.pending.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> HashMetrics {
+        HashMetrics {
+            hits: self.hits.load(Ordering::Relaxed),
+            pending: self.pending.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use notify::{Event, EventKind, event::Flag};
+
+    use super::{HashCounters, HashMetrics, WatcherCounters, WatcherMetrics};
+
+    #[test]
+    fn test_watcher_counters() {
+        let counters = WatcherCounters::default();
+        counters.record_event(&Event::new(EventKind::Any));
+        counters.record_event(&Event::new(EventKind::Other).set_flag(Flag::Rescan));
+        counters.record_error();
+        counters.record_queued(5);
+        counters.record_queued(2);
+
+        assert_eq!(
+            counters.snapshot(),
+            WatcherMetrics {
+                events: 2,
+                errors: 1,
+                rescans: 1,
+                queued_events: 2,
+                max_queued_events: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_hash_counters() {
+        let counters = HashCounters::default();
+        counters.record_hit();
+        counters.record_hit();
+        counters.record_pending();
+        counters.record_miss();
+
+        assert_eq!(
+            counters.snapshot(),
+            HashMetrics {
+                hits: 2,
+                pending: 1,
+                misses: 1,
+            }
+        );
+    }
+}
@@ -11,7 +11,6 @@ use std::{
     time::Duration,
 };
 
-use futures::FutureExt;
 use notify::Event;
 use thiserror::Error;
 use tokio::{
@@ -182,6 +181,8 @@ const INVALIDATION_PATHS: &[&str] = &[
     package_manager::yarn::LOCKFILE,
     package_manager::bun::LOCKFILE_BINARY,
     package_manager::bun::LOCKFILE,
+    package_manager::deno::LOCKFILE,
+    package_manager::deno::WORKSPACE_CONFIGURATION_PATH,
 ];
 
 impl Subscriber {
@@ -402,13 +403,14 @@ impl Subscriber {
         // If we don't have a valid package manager and workspace globs, nothing to be
         // done here
         let PackageState::ValidWorkspaces {
+            ref package_manager,
             ref filter,
             ref mut workspaces,
-            ..
         } = **package_state
         else {
             return;
         };
+        let manifest_names = package_manager.manifest_names();
 
         // here, we can only update if we have a valid package state
         let mut changed = false;
@@ -419,48 +421,60 @@ impl Subscriber {
             .filter_map(|p| p.as_os_str().to_str())
         {
             let path_file = AbsoluteSystemPathBuf::new(path).expect("watched paths are absolute");
-            let path_workspace: &AbsoluteSystemPath =
-                if path_file.file_name() == Some("package.json") {
-                    // The file event is for a package.json file. Check if the parent is a workspace
-                    let path_parent = path_file
-                        .parent()
-                        .expect("watched paths will not be at the root");
-                    if filter
-                        .target_is_workspace(&self.repo_root, path_parent)
-                        .unwrap_or(false)
-                    {
-                        path_parent
-                    } else {
-                        // irrelevant package.json file update, it's not in a directory
-                        // matching workspace globs
-                        continue;
-                    }
-                } else if filter
-                    .target_is_workspace(&self.repo_root, &path_file)
+            let path_workspace: &AbsoluteSystemPath = if path_file
+                .file_name()
+                .is_some_and(|name| manifest_names.contains(&name))
+            {
+                // The file event is for a package.json file. Check if the parent is a workspace
+                let path_parent = path_file
+                    .parent()
+                    .expect("watched paths will not be at the root");
+                if filter
+                    .target_is_workspace(&self.repo_root, path_parent)
                     .unwrap_or(false)
                 {
-                    // The file event is for a workspace directory itself
-                    &path_file
+                    path_parent
                 } else {
-                    // irrelevant file update, it's not a package.json file or a workspace directory
+                    // irrelevant package.json file update, it's not in a directory
+                    // matching workspace globs
                     continue;
-                };
+                }
+            } else if filter
+                .target_is_workspace(&self.repo_root, &path_file)
+                .unwrap_or(false)
+            {
+                // The file event is for a workspace directory itself
+                &path_file
+            } else {
+                // irrelevant file update, it's not a package.json file or a workspace directory
+                continue;
+            };
 
             tracing::debug!("handling change to workspace {path_workspace}");
-            let package_json = path_workspace.join_component("package.json");
             let turbo_json = path_workspace.join_component("turbo.json");
             let turbo_jsonc = path_workspace.join_component("turbo.jsonc");
 
-            let (package_exists, turbo_json_exists, turbo_jsonc_exists) = join!(
-                // It's possible that an IO error could occur other than the file not existing, but
-                // we will treat it like the file doesn't exist. It's possible we'll need to
-                // revisit this, depending on what kind of errors occur.
-                tokio::fs::try_exists(&package_json).map(|result| result.unwrap_or(false)),
+            // Use the first manifest that exists, package managers might support more than
+            // just package.json
+            let mut package_json = None;
+            for manifest_name in manifest_names {
+                let manifest = path_workspace.join_component(manifest_name);
+                // It's possible that an IO error could occur other than the file not existing,
+                // but we will treat it like the file doesn't exist. It's
+                // possible we'll need to revisit this, depending on what kind
+                // of errors occur.
+                if tokio::fs::try_exists(&manifest).await.unwrap_or(false) {
+                    package_json = Some(manifest);
+                    break;
+                }
+            }
+
+            let (turbo_json_exists, turbo_jsonc_exists) = join!(
                 tokio::fs::try_exists(&turbo_json),
                 tokio::fs::try_exists(&turbo_jsonc)
             );
 
-            changed |= if package_exists {
+            changed |= if let Some(package_json) = package_json {
                 workspaces
                     .insert(
                         path_workspace.to_owned(),
@@ -0,0 +1,324 @@
+//! A file watcher that periodically scans the file system instead of relying
+//! on notifications from the OS.
+//!
+//! Some file systems never deliver change notifications for edits made outside
+//! of the current machine, e.g. NFS mounts or directories bind mounted into a
+//! container. Polling is much more expensive than native watching, so we only
+//! use it when asked to or when we detect one of these file systems.
+//!
+//! Each scan is diffed against the previous one to synthesize `notify` events.
+//! Events for the cookie directory are always sent last, so that observing a
+//! cookie still guarantees that every change made before it was written has
+//! been delivered.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, mpsc},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use notify::{
+    Config, Event, EventHandler, EventKind, RecursiveMode, Watcher, WatcherKind,
+    event::{CreateKind, DataChange, ModifyKind, RemoveKind},
+};
+use tracing::{debug, trace};
+use walkdir::WalkDir;
+use wax::{Glob, Program};
+
+use crate::globwatcher::{GlobError, compile_glob};
+
+/// Options for the polling backend.
+#[derive(Clone, Debug)]
+pub struct PollOptions {
+    /// How long to wait between scans.
+    pub interval: Duration,
+    /// Globs, relative to the watched root, for paths that should not be
+    /// scanned. Matching directories are skipped entirely.
+    pub ignore: Vec<String>,
+}
+
+impl Default for PollOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(500),
+            ignore: vec!["**/node_modules".to_string(), "**/.git".to_string()],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Metadata {
+    is_dir: bool,
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+type Snapshot = HashMap<PathBuf, Metadata>;
+
+struct Filter {
+    ignore: Vec<Glob<'static>>,
+    // Paths that are scanned even if they match an ignore glob, i.e. the
+    // cookie directory
+    always_include: Vec<PathBuf>,
+}
+
+impl Filter {
+    fn is_ignored(&self, root: &Path, path: &Path) -> bool {
+        if self.is_always_included(path)
+            || self
+                .always_include
+                .iter()
+                .any(|include| include.starts_with(path))
+        {
+            return false;
+        }
+        let Ok(relative) = path.strip_prefix(root) else {
+            return false;
+        };
+        !relative.as_os_str().is_empty() && self.ignore.iter().any(|glob| glob.is_match(relative))
+    }
+
+    fn is_always_included(&self, path: &Path) -> bool {
+        self.always_include
+            .iter()
+            .any(|include| path.starts_with(include))
+    }
+}
+
+#[derive(Default)]
+struct State {
+    roots: HashMap<PathBuf, RecursiveMode>,
+    snapshot: Snapshot,
+}
+
+pub struct PollWatcher {
+    state: Arc<Mutex<State>>,
+    filter: Arc<Filter>,
+    // Dropping this disconnects the channel, which stops the polling thread
+    _stop_tx: mpsc::Sender<()>,
+}
+
+impl PollWatcher {
+    pub fn with_options<F: EventHandler>(
+        mut event_handler: F,
+        options: PollOptions,
+        always_include: Vec<PathBuf>,
+    ) -> Result<Self, GlobError> {
+        let ignore = options
+            .ignore
+            .iter()
+            .map(|raw| compile_glob(raw))
+            .collect::<Result<Vec<_>, _>>()?;
+        let filter = Arc::new(Filter {
+            ignore,
+            always_include,
+        });
+        let state = Arc::new(Mutex::new(State::default()));
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+
+        let interval = options.interval;
+        let thread_state = state.clone();
+        let thread_filter = filter.clone();
+        thread::spawn(move || {
+            debug!("polling file system every {interval:?}");
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                let events = {
+                    // We hold the lock for the entire scan so that a root added
+                    // mid-scan doesn't show up as a flood of creations or removals.
+                    let mut state = thread_state.lock().expect("lock poisoned");
+                    let mut snapshot = Snapshot::new();
+                    for (root, mode) in &state.roots {
+                        scan(root, *mode, &thread_filter, &mut snapshot);
+                    }
+                    let events = diff(&state.snapshot, &snapshot, &thread_filter);
+                    state.snapshot = snapshot;
+                    events
+                };
+
+                for event in events {
+                    trace!("polled event: {event:?}");
+                    event_handler.handle_event(Ok(event));
+                }
+            }
+            debug!("polling stopped");
+        });
+
+        Ok(Self {
+            state,
+            filter,
+            _stop_tx: stop_tx,
+        })
+    }
+}
+
+impl Watcher for PollWatcher {
+    fn new<F: EventHandler>(event_handler: F, _config: Config) -> notify::Result<Self> {
+        Self::with_options(event_handler, PollOptions::default(), Vec::new())
+            .map_err(|e| notify::Error::generic(&e.to_string()))
+    }
+
+    fn watch(&mut self, path: &Path, recursive_mode: RecursiveMode) -> notify::Result<()> {
+        if !path.exists() {
+            return Err(notify::Error::path_not_found().add_path(path.to_owned()));
+        }
+        let mut state = self.state.lock().expect("lock poisoned");
+        // Record the current state of the new root so that we only report changes
+        // made after this point, matching the native backends.
+        scan(path, recursive_mode, &self.filter, &mut state.snapshot);
+        state.roots.insert(path.to_owned(), recursive_mode);
+        Ok(())
+    }
+
+    fn unwatch(&mut self, path: &Path) -> notify::Result<()> {
+        let mut state = self.state.lock().expect("lock poisoned");
+        if state.roots.remove(path).is_none() {
+            return Err(notify::Error::watch_not_found().add_path(path.to_owned()));
+        }
+        state.snapshot.retain(|entry, _| !entry.starts_with(path));
+        Ok(())
+    }
+
+    fn kind() -> WatcherKind {
+        WatcherKind::PollWatcher
+    }
+}
+
+fn scan(root: &Path, mode: RecursiveMode, filter: &Filter, snapshot: &mut Snapshot) {
+    let max_depth = match mode {
+        RecursiveMode::Recursive => usize::MAX,
+        RecursiveMode::NonRecursive => 1,
+    };
+    let entries = WalkDir::new(root)
+        .follow_links(false)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_entry(|entry| !filter.is_ignored(root, entry.path()));
+    for entry in entries {
+        // Files can disappear while we're scanning. We'll report them as removed
+        // on the next scan.
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        snapshot.insert(
+            entry.into_path(),
+            Metadata {
+                is_dir: metadata.is_dir(),
+                modified: metadata.modified().ok(),
+                len: metadata.len(),
+            },
+        );
+    }
+}
+
+fn diff(previous: &Snapshot, current: &Snapshot, filter: &Filter) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut cookie_events = Vec::new();
+
+    for (path, metadata) in current {
+        let kind = match previous.get(path) {
+            Some(previous) if previous.is_dir == metadata.is_dir => {
+                if metadata.is_dir || previous == metadata {
+                    continue;
+                }
+                EventKind::Modify(ModifyKind::Data(DataChange::Any))
+            }
+            // A new path, or a path that changed between being a file and a directory
+            _ if metadata.is_dir => EventKind::Create(CreateKind::Folder),
+            _ => EventKind::Create(CreateKind::File),
+        };
+        let event = Event::new(kind).add_path(path.clone());
+        if filter.is_always_included(path) {
+            cookie_events.push(event);
+        } else {
+            events.push(event);
+        }
+    }
+
+    for (path, metadata) in previous {
+        if current.contains_key(path) {
+            continue;
+        }
+        let kind = if metadata.is_dir {
+            RemoveKind::Folder
+        } else {
+            RemoveKind::File
+        };
+        events.push(Event::new(EventKind::Remove(kind)).add_path(path.clone()));
+    }
+
+    events.extend(cookie_events);
+    events
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use notify::{EventKind, RecursiveMode, Watcher};
+    use tokio::sync::mpsc;
+
+    use super::{PollOptions, PollWatcher};
+
+    #[tokio::test]
+    async fn test_polling_reports_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let cookie_dir = root.join(".git");
+        std::fs::create_dir_all(&cookie_dir).unwrap();
+        std::fs::create_dir_all(root.join("node_modules")).unwrap();
+        std::fs::write(root.join("existing.txt"), "hello").unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = PollWatcher::with_options(
+            move |event| {
+                let _ = tx.send(event);
+            },
+            PollOptions {
+                interval: Duration::from_millis(10),
+                ..Default::default()
+            },
+            vec![cookie_dir.clone()],
+        )
+        .unwrap();
+        watcher.watch(&root, RecursiveMode::Recursive).unwrap();
+
+        std::fs::write(root.join("node_modules").join("ignored.txt"), "").unwrap();
+        std::fs::write(root.join("new.txt"), "").unwrap();
+        std::fs::remove_file(root.join("existing.txt")).unwrap();
+        std::fs::write(cookie_dir.join("1.cookie"), "").unwrap();
+
+        let mut events = Vec::new();
+        while !events
+            .iter()
+            .any(|(_, path)| *path == cookie_dir.join("1.cookie"))
+        {
+            let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            for path in event.paths {
+                events.push((event.kind, path));
+            }
+        }
+
+        assert!(events.contains(&(
+            EventKind::Create(notify::event::CreateKind::File),
+            root.join("new.txt")
+        )));
+        assert!(events.contains(&(
+            EventKind::Remove(notify::event::RemoveKind::File),
+            root.join("existing.txt")
+        )));
+        assert!(
+            !events
+                .iter()
+                .any(|(_, path)| path.starts_with(root.join("node_modules")))
+        );
+    }
+}
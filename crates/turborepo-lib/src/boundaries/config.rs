@@ -17,6 +17,10 @@ pub struct BoundariesConfig {
     pub dependencies: Option<Spanned<Permissions>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dependents: Option<Spanned<Permissions>>,
+    /// If in a package `turbo.json`, restricts the files that files matching
+    /// each pattern can import
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<Spanned<FileRulesMap>>,
 }
 
 pub type RulesMap = HashMap<String, Spanned<Rule>>;
@@ -29,6 +33,14 @@ pub struct Rule {
     pub dependents: Option<Spanned<Permissions>>,
 }
 
+pub type FileRulesMap = HashMap<String, Spanned<FileRule>>;
+
+#[derive(Serialize, Default, Debug, Clone, Iterable, Deserializable, PartialEq)]
+pub struct FileRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imports: Option<Spanned<Permissions>>,
+}
+
 #[derive(Serialize, Default, Debug, Clone, Iterable, Deserializable, PartialEq)]
 pub struct Permissions {
     pub allow: Option<Spanned<Vec<Spanned<String>>>>,
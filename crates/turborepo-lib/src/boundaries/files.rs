@@ -0,0 +1,333 @@
+use miette::{NamedSource, SourceSpan};
+use oxc_resolver::Resolver;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, PathRelation};
+use turborepo_errors::Spanned;
+use wax::{Glob, Program};
+
+use crate::boundaries::{config::FileRulesMap, BoundariesDiagnostic, SecondaryDiagnostic};
+
+struct FilePattern {
+    glob: Glob<'static>,
+    source: Spanned<String>,
+}
+
+impl FilePattern {
+    fn new(source: Spanned<String>) -> Result<Self, BoundariesDiagnostic> {
+        match Glob::new(source.as_inner()) {
+            Ok(glob) => Ok(Self {
+                glob: glob.into_owned(),
+                source,
+            }),
+            Err(err) => {
+                let (span, text) = source.span_and_text("turbo.json");
+                Err(BoundariesDiagnostic::InvalidFilePattern {
+                    pattern: source.into_inner(),
+                    span,
+                    text,
+                    reason: err.to_string(),
+                })
+            }
+        }
+    }
+
+    fn is_match(&self, path: &str) -> bool {
+        self.glob.is_match(path)
+    }
+}
+
+fn file_patterns(
+    sources: &Spanned<Vec<Spanned<String>>>,
+    diagnostics: &mut Vec<BoundariesDiagnostic>,
+) -> Vec<FilePattern> {
+    let mut patterns = Vec::new();
+    for source in sources.as_inner() {
+        match FilePattern::new(source.clone()) {
+            Ok(pattern) => patterns.push(pattern),
+            Err(diagnostic) => diagnostics.push(diagnostic),
+        }
+    }
+    patterns
+}
+
+struct ProcessedFileRule {
+    pattern: FilePattern,
+    allow: Option<Spanned<Vec<FilePattern>>>,
+    deny: Vec<FilePattern>,
+}
+
+/// The `files` rules from a package's boundaries config, which restrict what
+/// files in the package can import from the rest of the package
+#[derive(Default)]
+pub struct FileRules(Vec<ProcessedFileRule>);
+
+impl FileRules {
+    /// Processes the rules, adding a diagnostic for any invalid pattern
+    pub fn new(
+        rules: Option<&Spanned<FileRulesMap>>,
+        diagnostics: &mut Vec<BoundariesDiagnostic>,
+    ) -> Self {
+        let mut processed = Vec::new();
+        for (pattern, rule) in rules.into_iter().flat_map(|rules| rules.as_inner()) {
+            let imports = rule.imports.as_ref().map(|imports| imports.as_inner());
+            let allow = imports
+                .and_then(|imports| imports.allow.as_ref())
+                .map(|allow| allow.to(file_patterns(allow, diagnostics)));
+            let deny = imports
+                .and_then(|imports| imports.deny.as_ref())
+                .map(|deny| file_patterns(deny, diagnostics))
+                .unwrap_or_default();
+            match FilePattern::new(rule.to(pattern.clone())) {
+                Ok(pattern) => processed.push(ProcessedFileRule {
+                    pattern,
+                    allow,
+                    deny,
+                }),
+                Err(diagnostic) => diagnostics.push(diagnostic),
+            }
+        }
+        processed.sort_by(|a, b| a.pattern.source.as_inner().cmp(b.pattern.source.as_inner()));
+
+        Self(processed)
+    }
+
+    /// Checks an import by `file_path` that resolves to `resolved_import_path`
+    /// against the rules for the patterns that `file_path` matches. Both paths
+    /// must be inside of the package at `package_path`.
+    ///
+    /// The rules are matched against the file the import resolves to on disk,
+    /// so `./button` is matched as `./button.tsx`. Imports that don't resolve
+    /// to a file in the package are matched by their path as written.
+    #[allow(clippy::too_many_arguments)]
+    pub fn check_import(
+        &self,
+        file_path: &AbsoluteSystemPath,
+        package_path: &AbsoluteSystemPath,
+        import: &str,
+        resolved_import_path: &AbsoluteSystemPath,
+        span: SourceSpan,
+        file_content: &str,
+        resolver: &Resolver,
+    ) -> Option<BoundariesDiagnostic> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let file = package_path.anchor(file_path).ok()?.to_unix();
+        let resolved_file = resolver
+            .resolve(package_path, resolved_import_path.as_str())
+            .ok()
+            .and_then(|resolution| AbsoluteSystemPathBuf::try_from(resolution.path()).ok())
+            .filter(|path| matches!(package_path.relation_to_path(path), PathRelation::Parent));
+        let imported = package_path
+            .anchor(resolved_file.as_deref().unwrap_or(resolved_import_path))
+            .ok()?
+            .to_unix();
+
+        for rule in self
+            .0
+            .iter()
+            .filter(|rule| rule.pattern.is_match(file.as_str()))
+        {
+            if let Some(denied) = rule
+                .deny
+                .iter()
+                .find(|denied| denied.is_match(imported.as_str()))
+            {
+                let (deny_span, deny_text) = denied.source.span_and_text("turbo.json");
+                return Some(BoundariesDiagnostic::DeniedFileImport {
+                    path: file_path.to_owned(),
+                    import: import.to_string(),
+                    resolved_import_path: imported.to_string(),
+                    pattern: rule.pattern.source.as_inner().clone(),
+                    denied: denied.source.as_inner().clone(),
+                    span,
+                    text: NamedSource::new(file_path.as_str(), file_content.to_string()),
+                    secondary: [SecondaryDiagnostic::Denylist {
+                        span: deny_span,
+                        text: deny_text,
+                    }],
+                });
+            }
+
+            let Some(allow) = &rule.allow else {
+                continue;
+            };
+            // Files can always import other files that match the same pattern
+            let is_allowed = rule.pattern.is_match(imported.as_str())
+                || allow
+                    .as_inner()
+                    .iter()
+                    .any(|allowed| allowed.is_match(imported.as_str()));
+            if !is_allowed {
+                let (allow_span, allow_text) = allow.span_and_text("turbo.json");
+                return Some(BoundariesDiagnostic::FileImportNotInAllowlist {
+                    path: file_path.to_owned(),
+                    import: import.to_string(),
+                    resolved_import_path: imported.to_string(),
+                    pattern: rule.pattern.source.as_inner().clone(),
+                    span,
+                    text: NamedSource::new(file_path.as_str(), file_content.to_string()),
+                    secondary: [SecondaryDiagnostic::FileAllowlist {
+                        span: allow_span,
+                        text: allow_text,
+                    }],
+                });
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use miette::SourceSpan;
+    use turbo_trace::Tracer;
+    use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, RelativeUnixPath};
+    use turborepo_errors::Spanned;
+
+    use super::FileRules;
+    use crate::boundaries::{
+        config::{FileRule, FileRulesMap},
+        BoundariesDiagnostic, Permissions,
+    };
+
+    fn patterns(patterns: &[&str]) -> Option<Spanned<Vec<Spanned<String>>>> {
+        Some(Spanned::new(
+            patterns
+                .iter()
+                .map(|pattern| Spanned::new(pattern.to_string()))
+                .collect(),
+        ))
+    }
+
+    fn rules(rules: &[(&str, &[&str], &[&str])]) -> Spanned<FileRulesMap> {
+        Spanned::new(
+            rules
+                .iter()
+                .map(|(pattern, allow, deny)| {
+                    let permissions = Permissions {
+                        allow: (!allow.is_empty()).then(|| patterns(allow)).flatten(),
+                        deny: (!deny.is_empty()).then(|| patterns(deny)).flatten(),
+                    };
+                    (
+                        pattern.to_string(),
+                        Spanned::new(FileRule {
+                            imports: Some(Spanned::new(permissions)),
+                        }),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn check(rules: &FileRules, file: &str, imported: &str) -> Option<BoundariesDiagnostic> {
+        let package_path = AbsoluteSystemPathBuf::new(if cfg!(windows) {
+            "C:\\repo\\apps\\web"
+        } else {
+            "/repo/apps/web"
+        })
+        .unwrap();
+        check_in(rules, &package_path, file, imported)
+    }
+
+    fn check_in(
+        rules: &FileRules,
+        package_path: &AbsoluteSystemPath,
+        file: &str,
+        imported: &str,
+    ) -> Option<BoundariesDiagnostic> {
+        let file_path = package_path.join_unix_path(RelativeUnixPath::new(file).unwrap());
+        let imported_path = package_path.join_unix_path(RelativeUnixPath::new(imported).unwrap());
+        rules.check_import(
+            &file_path,
+            package_path,
+            "../import",
+            &imported_path,
+            SourceSpan::new(0.into(), 0),
+            "",
+            &Tracer::create_resolver(None, &[]),
+        )
+    }
+
+    #[test]
+    fn test_denied_import() {
+        let mut diagnostics = Vec::new();
+        let rules = FileRules::new(
+            Some(&rules(&[("src/ui/**", &[], &["src/db/**"])])),
+            &mut diagnostics,
+        );
+        assert!(diagnostics.is_empty());
+
+        assert!(matches!(
+            check(&rules, "src/ui/button.tsx", "src/db/client"),
+            Some(BoundariesDiagnostic::DeniedFileImport { .. })
+        ));
+        assert!(check(&rules, "src/ui/button.tsx", "src/lib/format").is_none());
+        assert!(check(&rules, "src/api/route.ts", "src/db/client").is_none());
+    }
+
+    #[test]
+    fn test_allowed_imports() {
+        let mut diagnostics = Vec::new();
+        let rules = FileRules::new(
+            Some(&rules(&[("src/ui/**", &["src/lib/**"], &[])])),
+            &mut diagnostics,
+        );
+        assert!(diagnostics.is_empty());
+
+        assert!(check(&rules, "src/ui/button.tsx", "src/lib/format").is_none());
+        assert!(check(&rules, "src/ui/button.tsx", "src/ui/icon").is_none());
+        assert!(matches!(
+            check(&rules, "src/ui/button.tsx", "src/db/client"),
+            Some(BoundariesDiagnostic::FileImportNotInAllowlist { .. })
+        ));
+    }
+
+    #[test]
+    fn test_matches_resolved_file() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let package_path = AbsoluteSystemPathBuf::try_from(tempdir.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap();
+        for file in ["src/ui/button.tsx", "src/db/client.ts", "src/db/index.ts"] {
+            let path = package_path.join_unix_path(RelativeUnixPath::new(file).unwrap());
+            path.ensure_dir().unwrap();
+            path.create_with_contents("").unwrap();
+        }
+
+        let mut diagnostics = Vec::new();
+        let rules = FileRules::new(
+            Some(&rules(&[("src/ui/*.tsx", &[], &["src/db/*.ts"])])),
+            &mut diagnostics,
+        );
+        assert!(diagnostics.is_empty());
+
+        // Neither import has an extension, but they resolve to files that do
+        assert!(matches!(
+            check_in(&rules, &package_path, "src/ui/button.tsx", "src/db/client"),
+            Some(BoundariesDiagnostic::DeniedFileImport { resolved_import_path, .. })
+                if resolved_import_path == "src/db/client.ts"
+        ));
+        assert!(matches!(
+            check_in(&rules, &package_path, "src/ui/button.tsx", "src/db"),
+            Some(BoundariesDiagnostic::DeniedFileImport { resolved_import_path, .. })
+                if resolved_import_path == "src/db/index.ts"
+        ));
+        // An import that doesn't resolve is matched as written
+        assert!(check_in(&rules, &package_path, "src/ui/button.tsx", "src/db/missing").is_none());
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let mut diagnostics = Vec::new();
+        FileRules::new(
+            Some(&rules(&[("src/ui/**", &[], &["src/{db"])])),
+            &mut diagnostics,
+        );
+        assert!(matches!(
+            diagnostics.as_slice(),
+            [BoundariesDiagnostic::InvalidFilePattern { .. }]
+        ));
+    }
+}
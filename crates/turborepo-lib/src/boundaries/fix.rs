@@ -0,0 +1,259 @@
+use std::collections::{BTreeMap, HashSet};
+
+use node_semver::Version;
+use tracing::log::warn;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath};
+use turborepo_lockfiles::Lockfile;
+use turborepo_repository::package_graph::{PackageInfo, PackageName};
+
+use crate::{
+    boundaries::{BoundariesDiagnostic, BoundariesResult, Error},
+    deps::{
+        compare_versions, resolve_version, supports_workspace_protocol, WORKSPACE_PROTOCOL_VERSION,
+    },
+    rewrite_json::insert_sorted_member,
+    run::Run,
+};
+
+impl Run {
+    /// Adds packages that are imported without being declared as a dependency
+    /// to the `package.json` of the importing package. Diagnostics that were
+    /// fixed are removed from `result`, and the `package.json` files that were
+    /// changed are returned.
+    pub fn add_missing_dependencies(
+        &self,
+        result: &mut BoundariesResult,
+    ) -> Result<Vec<AbsoluteSystemPathBuf>, Error> {
+        // Dependency names and whether they are only imported by development
+        // files, by the package that imports them
+        let mut missing: BTreeMap<&PackageName, BTreeMap<&str, bool>> = BTreeMap::new();
+        for diagnostic in &result.diagnostics {
+            let BoundariesDiagnostic::PackageNotFound { path, name, .. } = diagnostic else {
+                continue;
+            };
+            let Some((package_name, package_info)) = self.package_containing(path) else {
+                continue;
+            };
+            // A package importing itself isn't something we can declare
+            if package_name.as_str() == name.as_str() {
+                continue;
+            }
+            let is_dev_file = self
+                .repo_root()
+                .resolve(package_info.package_path())
+                .anchor(path)
+                .is_ok_and(|path| is_dev_file(&path));
+            let dev_only = missing
+                .entry(package_name)
+                .or_default()
+                .entry(name.as_str())
+                .or_insert(true);
+            *dev_only &= is_dev_file;
+        }
+
+        let mut fixed = HashSet::new();
+        let mut patched = Vec::new();
+        for (package_name, dependencies) in missing {
+            let Some(package_info) = self.pkg_dep_graph().package_info(package_name) else {
+                continue;
+            };
+            let package_json_path = self.repo_root().resolve(package_info.package_json_path());
+            let mut contents = package_json_path
+                .read_to_string()
+                .map_err(|_| Error::FileNotFound(package_json_path.clone()))?;
+
+            let mut changed = false;
+            for (dependency, dev_only) in dependencies {
+                let Some(version) = self.missing_dependency_version(package_name, dependency)
+                else {
+                    warn!(
+                        "could not find a version of `{dependency}` to add to `{package_name}`, \
+                         it needs to be added manually"
+                    );
+                    continue;
+                };
+                let group = if dev_only {
+                    "devDependencies"
+                } else {
+                    "dependencies"
+                };
+                contents = insert_sorted_member(
+                    &contents,
+                    group,
+                    dependency,
+                    &serde_json::Value::from(version).to_string(),
+                )?;
+                fixed.insert((package_name.clone(), dependency.to_string()));
+                changed = true;
+            }
+
+            if changed {
+                package_json_path
+                    .create_with_contents(contents)
+                    .map_err(|_| Error::FileWrite(package_json_path.clone()))?;
+                patched.push(package_json_path);
+            }
+        }
+
+        result.diagnostics.retain(|diagnostic| {
+            let BoundariesDiagnostic::PackageNotFound { path, name, .. } = diagnostic else {
+                return true;
+            };
+            !self
+                .package_containing(path)
+                .is_some_and(|(package_name, _)| {
+                    fixed.contains(&(package_name.clone(), name.clone()))
+                })
+        });
+
+        Ok(patched)
+    }
+
+    // The innermost package that contains `path`
+    fn package_containing(
+        &self,
+        path: &AbsoluteSystemPath,
+    ) -> Option<(&PackageName, &PackageInfo)> {
+        let path = self.repo_root().anchor(path).ok()?;
+        self.pkg_dep_graph().package_containing(&path)
+    }
+
+    // Internal packages are declared with the `workspace:` protocol where it's
+    // supported. External packages use the specifier that other packages in
+    // the repository declare, preferring the one that resolves to the newest
+    // version, or a caret range of the version the lockfile installs if no
+    // other package declares it.
+    fn missing_dependency_version(
+        &self,
+        package: &PackageName,
+        dependency: &str,
+    ) -> Option<String> {
+        let graph = self.pkg_dep_graph();
+        if graph
+            .package_info(&PackageName::Other(dependency.to_string()))
+            .is_some()
+        {
+            return Some(if supports_workspace_protocol(graph.package_manager()) {
+                WORKSPACE_PROTOCOL_VERSION.to_string()
+            } else {
+                "*".to_string()
+            });
+        }
+
+        let lockfile = graph.lockfile();
+        graph
+            .packages()
+            .filter(|(name, _)| *name != package)
+            .flat_map(|(_, info)| {
+                let package_json = &info.package_json;
+                [
+                    &package_json.dependencies,
+                    &package_json.dev_dependencies,
+                    &package_json.optional_dependencies,
+                ]
+                .into_iter()
+                .flatten()
+                .filter_map(|dependencies| dependencies.get(dependency))
+                .map(move |specifier| {
+                    let workspace_path = info.package_path().to_unix().to_string();
+                    let resolved = lockfile.and_then(|lockfile| {
+                        resolve_version(lockfile, &workspace_path, dependency, specifier)
+                    });
+                    (resolved, specifier)
+                })
+            })
+            .max_by(|(a_resolved, a), (b_resolved, b)| {
+                compare_versions(
+                    a_resolved.as_deref().unwrap_or_default(),
+                    b_resolved.as_deref().unwrap_or_default(),
+                )
+                // Prefer the first specifier alphabetically for a stable result
+                .then_with(|| b.cmp(a))
+            })
+            .map(|(_, specifier)| specifier.clone())
+            .or_else(|| {
+                let workspace_path = graph.package_info(package)?.package_path().to_unix();
+                lockfile_version(lockfile?, workspace_path.as_str(), dependency)
+            })
+    }
+}
+
+// A caret range of the version that the lockfile installs for a package that
+// the workspace at `workspace_path` doesn't declare, e.g. one that is hoisted
+// because another package depends on it
+fn lockfile_version(
+    lockfile: &dyn Lockfile,
+    workspace_path: &str,
+    dependency: &str,
+) -> Option<String> {
+    let version = resolve_version(lockfile, workspace_path, dependency, "*")?;
+    Version::parse(&version).ok()?;
+    Some(format!("^{version}"))
+}
+
+// Files that only run during development, so the packages they import belong
+// in `devDependencies`
+fn is_dev_file(path: &AnchoredSystemPath) -> bool {
+    let components = path
+        .components()
+        .map(|component| component.as_str())
+        .collect::<Vec<_>>();
+    let Some((file_name, dirs)) = components.split_last() else {
+        return false;
+    };
+    dirs.iter()
+        .any(|dir| matches!(*dir, "__tests__" | "__mocks__" | "test" | "tests" | "e2e"))
+        || [".test.", ".spec.", ".stories.", ".config."]
+            .iter()
+            .any(|pattern| file_name.contains(pattern))
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+    use turbopath::AnchoredSystemPath;
+    use turborepo_lockfiles::NpmLockfile;
+
+    use super::{is_dev_file, lockfile_version};
+
+    #[test_case("src/index.ts", false ; "source file")]
+    #[test_case("src/button.test.tsx", true ; "test file")]
+    #[test_case("src/__tests__/button.tsx", true ; "tests directory")]
+    #[test_case("src/button.stories.tsx", true ; "stories")]
+    #[test_case("vitest.config.ts", true ; "config file")]
+    #[test_case("src/testing.ts", false ; "similar name")]
+    fn test_is_dev_file(path: &str, expected: bool) {
+        assert_eq!(
+            is_dev_file(AnchoredSystemPath::new(path).unwrap()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_lockfile_version() {
+        // `is-number` is only installed because `is-odd` depends on it
+        let lockfile = NpmLockfile::load(
+            br#"{
+  "name": "repo",
+  "lockfileVersion": 3,
+  "requires": true,
+  "packages": {
+    "": { "name": "repo", "workspaces": ["apps/*"] },
+    "apps/web": { "name": "web", "dependencies": { "is-odd": "^3.0.1" } },
+    "node_modules/web": { "resolved": "apps/web", "link": true },
+    "node_modules/is-odd": {
+      "version": "3.0.1",
+      "dependencies": { "is-number": "^6.0.0" }
+    },
+    "node_modules/is-number": { "version": "6.0.0" }
+  }
+}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            lockfile_version(&lockfile, "apps/web", "is-number").as_deref(),
+            Some("^6.0.0")
+        );
+        assert_eq!(lockfile_version(&lockfile, "apps/web", "react"), None);
+    }
+}
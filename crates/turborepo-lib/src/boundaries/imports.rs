@@ -1,14 +1,12 @@
-use std::{
-    collections::{BTreeMap, HashMap, HashSet},
-    sync::Arc,
-};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use camino::Utf8Path;
 use itertools::Itertools;
 use miette::{NamedSource, SourceSpan};
 use oxc_resolver::{ResolveError, Resolver, TsConfig};
-use swc_common::{comments::SingleThreadedComments, SourceFile, Span};
-use turbo_trace::ImportType;
+use turbo_trace::{
+    find_stylesheet_imports, resolve_relative_stylesheet_import, FileImport, ImportType,
+};
 use turbopath::{AbsoluteSystemPath, AnchoredSystemPathBuf, PathRelation, RelativeUnixPath};
 use turborepo_errors::Spanned;
 use turborepo_repository::{
@@ -17,7 +15,9 @@ use turborepo_repository::{
 };
 
 use crate::{
-    boundaries::{tsconfig::TsConfigLoader, BoundariesDiagnostic, BoundariesResult, Error},
+    boundaries::{
+        files::FileRules, tsconfig::TsConfigLoader, BoundariesDiagnostic, BoundariesResult, Error,
+    },
     run::Run,
 };
 
@@ -94,6 +94,8 @@ impl Run {
         file_path: &AbsoluteSystemPath,
         file_content: &str,
         import: &str,
+        file_rules: &FileRules,
+        resolver: &Resolver,
         result: &mut BoundariesResult,
     ) -> Result<bool, Error> {
         let dir = file_path.parent().expect("file_path must have a parent");
@@ -118,6 +120,8 @@ impl Run {
                 resolved_import_path,
                 span,
                 file_content,
+                file_rules,
+                resolver,
             )?);
         }
 
@@ -127,23 +131,20 @@ impl Run {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn check_import(
         &self,
-        comments: &SingleThreadedComments,
         tsconfig_loader: &mut TsConfigLoader,
         result: &mut BoundariesResult,
-        source_file: &Arc<SourceFile>,
         package_name: &PackageName,
         package_root: &AbsoluteSystemPath,
-        import: &str,
-        import_type: &ImportType,
-        span: &Span,
+        file_import: &FileImport,
         file_path: &AbsoluteSystemPath,
         file_content: &str,
         dependency_locations: DependencyLocations<'_>,
+        file_rules: &FileRules,
         resolver: &Resolver,
     ) -> Result<(), Error> {
         // If the import is prefixed with `@boundaries-ignore`, we ignore it, but print
         // a warning
-        match Self::get_ignored_comment(comments, *span) {
+        match Self::get_ignored_comment(&file_import.leading_comments) {
             Some(reason) if reason.is_empty() => {
                 result.warnings.push(
                     "@boundaries-ignore requires a reason, e.g. `// @boundaries-ignore implicit \
@@ -152,28 +153,24 @@ impl Run {
                 );
             }
             Some(_) => {
-                // Try to get the line number for warning
-                let line = result.source_map.lookup_line(span.lo()).map(|l| l.line);
-                if let Ok(line) = line {
-                    result
-                        .warnings
-                        .push(format!("ignoring import on line {line} in {file_path}"));
-                } else {
-                    result
-                        .warnings
-                        .push(format!("ignoring import in {file_path}"));
-                }
+                result.warnings.push(format!(
+                    "ignoring import on line {} in {file_path}",
+                    file_import.line
+                ));
 
                 return Ok(());
             }
             None => {}
         }
 
-        let (start, end) = result.source_map.span_to_char_offset(source_file, *span);
-        let start = start as usize;
-        let end = end as usize;
-
-        let span = SourceSpan::new(start.into(), end - start);
+        let FileImport {
+            specifier: import,
+            import_type,
+            start,
+            end,
+            ..
+        } = file_import;
+        let span = SourceSpan::new((*start).into(), end - start);
 
         if self.check_import_as_tsconfig_path_alias(
             tsconfig_loader,
@@ -183,6 +180,8 @@ impl Run {
             file_path,
             file_content,
             import,
+            file_rules,
+            resolver,
             result,
         )? {
             return Ok(());
@@ -203,6 +202,8 @@ impl Run {
                 &resolved_import_path,
                 span,
                 file_content,
+                file_rules,
+                resolver,
             )?
         } else if Self::is_potential_package_name(import) {
             self.check_package_import(
@@ -223,6 +224,109 @@ impl Run {
         Ok(())
     }
 
+    /// Checks the imports in a CSS or SCSS stylesheet. Stylesheets import
+    /// files relative to themselves, and packages with the `~` prefix or, in
+    /// Sass, with a bare specifier that isn't a file.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn check_stylesheet(
+        &self,
+        result: &mut BoundariesResult,
+        package_name: &PackageName,
+        package_root: &AbsoluteSystemPath,
+        file_path: &AbsoluteSystemPath,
+        file_content: &str,
+        dependency_locations: DependencyLocations<'_>,
+        file_rules: &FileRules,
+        resolver: &Resolver,
+    ) -> Result<(), Error> {
+        let sass = file_path.extension() == Some("scss");
+        let dir_path = file_path
+            .parent()
+            .ok_or_else(|| Error::NoParentDir(file_path.to_owned()))?;
+
+        for import in find_stylesheet_imports(file_content, sass) {
+            let ignore_reason = import
+                .leading_comment
+                .as_deref()
+                .and_then(|comment| comment.strip_prefix("@boundaries-ignore"));
+            match ignore_reason {
+                Some(reason) if reason.trim().is_empty() => {
+                    result.warnings.push(
+                        "@boundaries-ignore requires a reason, e.g. `/* @boundaries-ignore shared \
+                         styles */`"
+                            .to_string(),
+                    );
+                }
+                Some(_) => {
+                    let line = file_content[..import.span.start].matches('\n').count() + 1;
+                    result
+                        .warnings
+                        .push(format!("ignoring import on line {line} in {file_path}"));
+                    continue;
+                }
+                None => {}
+            }
+
+            let span = SourceSpan::new(import.span.start.into(), import.span.len());
+            let path = import.path();
+            let check_result = if let Some(module) = path.strip_prefix('~') {
+                self.check_package_import(
+                    module,
+                    ImportType::Value,
+                    span,
+                    file_path,
+                    file_content,
+                    dependency_locations,
+                    resolver,
+                )
+            } else if let Some(resolved_import_path) =
+                resolve_relative_stylesheet_import(dir_path, &import, sass)
+            {
+                self.check_file_import(
+                    file_path,
+                    package_root,
+                    package_name,
+                    &import.specifier,
+                    &resolved_import_path,
+                    span,
+                    file_content,
+                    file_rules,
+                    resolver,
+                )?
+            } else if import.is_package_import() && Self::is_potential_package_name(path) {
+                self.check_package_import(
+                    path,
+                    ImportType::Value,
+                    span,
+                    file_path,
+                    file_content,
+                    dependency_locations,
+                    resolver,
+                )
+            } else {
+                // The file doesn't exist, but we can still check where it would be
+                let resolved_import_path = dir_path
+                    .join_unix_path(RelativeUnixPath::new(path)?)
+                    .clean()?;
+                self.check_file_import(
+                    file_path,
+                    package_root,
+                    package_name,
+                    &import.specifier,
+                    &resolved_import_path,
+                    span,
+                    file_content,
+                    file_rules,
+                    resolver,
+                )?
+            };
+
+            result.diagnostics.extend(check_result);
+        }
+
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn check_file_import(
         &self,
@@ -233,6 +337,8 @@ impl Run {
         resolved_import_path: &AbsoluteSystemPath,
         source_span: SourceSpan,
         file_content: &str,
+        file_rules: &FileRules,
+        resolver: &Resolver,
     ) -> Result<Option<BoundariesDiagnostic>, Error> {
         // We have to check for this case because `relation_to_path` returns `Parent` if
         // the paths are equal and there's nothing wrong with importing the
@@ -259,7 +365,15 @@ impl Run {
                 text: NamedSource::new(file_path.as_str(), file_content.to_string()),
             }))
         } else {
-            Ok(None)
+            Ok(file_rules.check_import(
+                file_path,
+                package_path,
+                import,
+                resolved_import_path,
+                source_span,
+                file_content,
+                resolver,
+            ))
         }
     }
 
@@ -1,6 +1,8 @@
 #![allow(clippy::sliced_string_as_bytes)]
 
 mod config;
+mod files;
+mod fix;
 mod imports;
 mod tags;
 mod tsconfig;
@@ -12,31 +14,27 @@ use std::{
     sync::{Arc, LazyLock, Mutex},
 };
 
-pub use config::{BoundariesConfig, Permissions, Rule};
+pub use config::{BoundariesConfig, FileRule, Permissions, Rule};
 use git2::Repository;
 use globwalk::Settings;
 use indicatif::{ProgressBar, ProgressIterator};
 use miette::{Diagnostic, NamedSource, Report, SourceSpan};
 use regex::Regex;
-use swc_common::{
-    comments::{Comments, SingleThreadedComments},
-    errors::Handler,
-    input::StringInput,
-    FileName, SourceMap, Span,
-};
-use swc_ecma_ast::EsVersion;
-use swc_ecma_parser::{lexer::Lexer, Capturing, EsSyntax, Parser, Syntax, TsSyntax};
-use swc_ecma_visit::VisitWith;
+use swc_common::{errors::Handler, SourceMap};
 use thiserror::Error;
 use tracing::log::warn;
-use turbo_trace::{ImportFinder, Tracer};
+use turbo_trace::{ImportCache, ImportTraceType, Tracer};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 use turborepo_errors::Spanned;
 use turborepo_repository::package_graph::{PackageInfo, PackageName, PackageNode};
 use turborepo_ui::{color, ColorConfig, BOLD_GREEN, BOLD_RED};
 
 use crate::{
-    boundaries::{imports::DependencyLocations, tags::ProcessedRulesMap, tsconfig::TsConfigLoader},
+    boundaries::{
+        files::FileRules, imports::DependencyLocations, tags::ProcessedRulesMap,
+        tsconfig::TsConfigLoader,
+    },
+    rewrite_json::RewriteError,
     run::Run,
 };
 
@@ -64,6 +62,13 @@ pub enum SecondaryDiagnostic {
         #[source_code]
         text: NamedSource<String>,
     },
+    #[error("allowlist defined here")]
+    FileAllowlist {
+        #[label]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
 }
 
 #[derive(Clone, Debug, Error, Diagnostic)]
@@ -157,6 +162,50 @@ pub enum BoundariesDiagnostic {
         #[source_code]
         text: NamedSource<String>,
     },
+    #[error("Invalid file pattern `{pattern}` in boundaries rules")]
+    InvalidFilePattern {
+        pattern: String,
+        #[label("pattern defined here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+        #[help]
+        reason: String,
+    },
+    #[error("files matching `{pattern}` cannot import `{import}`")]
+    #[diagnostic(help(
+        "`{import}` resolves to `{resolved_import_path}`, which matches `{denied}`"
+    ))]
+    DeniedFileImport {
+        path: AbsoluteSystemPathBuf,
+        import: String,
+        resolved_import_path: String,
+        pattern: String,
+        denied: String,
+        #[label("file imported here")]
+        span: SourceSpan,
+        #[source_code]
+        text: NamedSource<String>,
+        #[related]
+        secondary: [SecondaryDiagnostic; 1],
+    },
+    #[error("files matching `{pattern}` cannot import `{import}`")]
+    #[diagnostic(help(
+        "`{import}` resolves to `{resolved_import_path}`, which doesn't match any of the allowed \
+         patterns"
+    ))]
+    FileImportNotInAllowlist {
+        path: AbsoluteSystemPathBuf,
+        import: String,
+        resolved_import_path: String,
+        pattern: String,
+        #[label("file imported here")]
+        span: SourceSpan,
+        #[source_code]
+        text: NamedSource<String>,
+        #[related]
+        secondary: [SecondaryDiagnostic; 1],
+    },
     #[error("failed to parse file {0}")]
     ParseError(AbsoluteSystemPathBuf, swc_ecma_parser::error::Error),
 }
@@ -177,6 +226,8 @@ pub enum Error {
     FileNotFound(AbsoluteSystemPathBuf),
     #[error("failed to write to file: {0}")]
     FileWrite(AbsoluteSystemPathBuf),
+    #[error(transparent)]
+    Rewrite(#[from] RewriteError),
 }
 
 impl BoundariesDiagnostic {
@@ -185,6 +236,8 @@ impl BoundariesDiagnostic {
             Self::ImportLeavesPackage { path, span, .. } => Some((path, *span)),
             Self::PackageNotFound { path, span, .. } => Some((path, *span)),
             Self::NotTypeOnlyImport { path, span, .. } => Some((path, *span)),
+            Self::DeniedFileImport { path, span, .. } => Some((path, *span)),
+            Self::FileImportNotInAllowlist { path, span, .. } => Some((path, *span)),
             _ => None,
         }
     }
@@ -260,6 +313,7 @@ impl Run {
     pub async fn check_boundaries(&self, show_progress: bool) -> Result<BoundariesResult, Error> {
         let rules_map = self.get_processed_rules_map();
         let packages: Vec<_> = self.pkg_dep_graph().packages().collect();
+        let import_cache = self.import_cache();
         let repo = Repository::discover(self.repo_root()).ok().map(Mutex::new);
         let mut result = BoundariesResult::default();
         let global_implicit_dependencies = self.get_implicit_dependencies(&PackageName::Root);
@@ -284,25 +338,25 @@ impl Run {
                 package_info,
                 &rules_map,
                 &global_implicit_dependencies,
+                &import_cache,
                 &mut result,
             )
             .await?;
         }
 
+        self.save_import_cache(&import_cache).await;
+
         Ok(result)
     }
 
     /// Returns the underlying reason if an import has been marked as ignored
-    fn get_ignored_comment(comments: &SingleThreadedComments, span: Span) -> Option<String> {
-        if let Some(import_comments) = comments.get_leading(span.lo()) {
-            for comment in import_comments {
-                if let Some(reason) = comment.text.trim().strip_prefix("@boundaries-ignore") {
-                    return Some(reason.to_string());
-                }
-            }
-        }
-
-        None
+    fn get_ignored_comment(leading_comments: &[String]) -> Option<String> {
+        leading_comments.iter().find_map(|comment| {
+            comment
+                .trim()
+                .strip_prefix("@boundaries-ignore")
+                .map(|reason| reason.to_string())
+        })
     }
 
     pub fn get_implicit_dependencies(&self, pkg: &PackageName) -> HashMap<String, Spanned<()>> {
@@ -327,6 +381,7 @@ impl Run {
         package_info: &PackageInfo,
         tag_rules: &Option<ProcessedRulesMap>,
         global_implicit_dependencies: &HashMap<String, Spanned<()>>,
+        import_cache: &ImportCache,
         result: &mut BoundariesResult,
     ) -> Result<(), Error> {
         let implicit_dependencies = self.get_implicit_dependencies(package_name);
@@ -336,6 +391,7 @@ impl Run {
             package_info,
             implicit_dependencies,
             global_implicit_dependencies,
+            import_cache,
             result,
         )
         .await?;
@@ -354,6 +410,10 @@ impl Run {
         Ok(())
     }
 
+    fn is_stylesheet(file_path: &AbsoluteSystemPath) -> bool {
+        matches!(file_path.extension(), Some("css") | Some("scss"))
+    }
+
     fn is_potential_package_name(import: &str) -> bool {
         PACKAGE_NAME_REGEX.is_match(import)
     }
@@ -365,6 +425,7 @@ impl Run {
         package_info: &PackageInfo,
         implicit_dependencies: HashMap<String, Spanned<()>>,
         global_implicit_dependencies: &HashMap<String, Spanned<()>>,
+        import_cache: &ImportCache,
         result: &mut BoundariesResult,
     ) -> Result<(), Error> {
         let package_root = self.repo_root().resolve(package_info.package_path());
@@ -384,6 +445,8 @@ impl Run {
                 "**/*.tsx".parse().unwrap(),
                 "**/*.cjs".parse().unwrap(),
                 "**/*.mjs".parse().unwrap(),
+                "**/*.css".parse().unwrap(),
+                "**/*.scss".parse().unwrap(),
                 "**/*.svelte".parse().unwrap(),
                 "**/*.vue".parse().unwrap(),
             ],
@@ -395,12 +458,33 @@ impl Run {
         // We assume the tsconfig.json is at the root of the package
         let tsconfig_path = package_root.join_component("tsconfig.json");
 
-        let resolver =
-            Tracer::create_resolver(tsconfig_path.exists().then(|| tsconfig_path.as_ref()));
+        // Yarn PnP has no `node_modules` to find workspace packages in, which we
+        // need for a tsconfig that extends a shared config package.
+        let package_aliases = self.yarn_pnp_workspace_packages().unwrap_or_default();
+        let resolver = Tracer::create_resolver(
+            tsconfig_path.exists().then(|| tsconfig_path.as_ref()),
+            &package_aliases,
+        );
 
         let mut not_supported_extensions = HashSet::new();
         let mut tsconfig_loader = TsConfigLoader::new(&resolver);
+        let file_rules = FileRules::new(
+            self.turbo_json_loader()
+                .load(package_name)
+                .ok()
+                .and_then(|turbo_json| turbo_json.boundaries.as_ref())
+                .and_then(|boundaries| boundaries.files.as_ref()),
+            &mut result.diagnostics,
+        );
+        let dependency_locations = DependencyLocations {
+            internal_dependencies: &internal_dependencies,
+            package_json: &package_info.package_json,
+            implicit_dependencies: &implicit_dependencies,
+            global_implicit_dependencies,
+            unresolved_external_dependencies,
+        };
 
+        let mut sources = Vec::with_capacity(files.len());
         for file_path in &files {
             if let Some(ext @ ("svelte" | "vue")) = file_path.extension() {
                 not_supported_extensions.insert(ext.to_string());
@@ -418,38 +502,43 @@ impl Run {
                 return Err(Error::FileNotFound(file_path.to_owned()));
             };
 
-            let comments = SingleThreadedComments::default();
-
-            let source_file = result.source_map.new_source_file(
-                FileName::Custom(file_path.to_string()).into(),
-                file_content.clone(),
-            );
-
-            let syntax = if matches!(file_path.extension(), Some("ts") | Some("tsx")) {
-                Syntax::Typescript(TsSyntax {
-                    tsx: file_path.extension() == Some("tsx"),
-                    decorators: true,
-                    ..Default::default()
-                })
-            } else {
-                Syntax::Es(EsSyntax {
-                    jsx: true,
-                    ..Default::default()
-                })
-            };
-
-            let lexer = Lexer::new(
-                syntax,
-                EsVersion::EsNext,
-                StringInput::from(&*source_file),
-                Some(&comments),
-            );
+            sources.push((file_path, file_content));
+        }
 
-            let mut parser = Parser::new_from(Capturing::new(lexer));
+        // Get the imports of this package's files that aren't cached on disk from
+        // the daemon in one request, rather than parsing them
+        let keys = sources
+            .iter()
+            .filter(|(file_path, _)| !Self::is_stylesheet(file_path))
+            .map(|(file_path, file_content)| {
+                ImportCache::key(file_path, ImportTraceType::All, file_content)
+            })
+            .collect();
+        self.fetch_daemon_imports(import_cache, keys).await;
+
+        for (file_path, file_content) in sources {
+            if Self::is_stylesheet(file_path) {
+                self.check_stylesheet(
+                    result,
+                    package_name,
+                    &package_root,
+                    file_path,
+                    &file_content,
+                    dependency_locations,
+                    &file_rules,
+                    &resolver,
+                )?;
+                continue;
+            }
 
-            // Parse the file as a module
-            let module = match parser.parse_module() {
-                Ok(module) => module,
+            let imports = match Tracer::find_imports(
+                &result.source_map,
+                file_path,
+                &file_content,
+                ImportTraceType::All,
+                Some(import_cache),
+            ) {
+                Ok((imports, _)) => imports,
                 Err(err) => {
                     result
                         .diagnostics
@@ -458,31 +547,17 @@ impl Run {
                 }
             };
 
-            // Visit the AST and find imports
-            let mut finder = ImportFinder::default();
-            module.visit_with(&mut finder);
-            let dependency_locations = DependencyLocations {
-                internal_dependencies: &internal_dependencies,
-                package_json: &package_info.package_json,
-                implicit_dependencies: &implicit_dependencies,
-                global_implicit_dependencies,
-                unresolved_external_dependencies,
-            };
-
-            for (import, span, import_type) in finder.imports() {
+            for import in &imports {
                 self.check_import(
-                    &comments,
                     &mut tsconfig_loader,
                     result,
-                    &source_file,
                     package_name,
                     &package_root,
                     import,
-                    import_type,
-                    span,
                     file_path,
                     &file_content,
                     dependency_locations,
+                    &file_rules,
                     &resolver,
                 )?;
             }
@@ -520,30 +595,34 @@ impl Run {
             .open_with_options(options)
             .map_err(|_| Error::FileNotFound(file_path.to_owned()))?;
 
+        // Plain CSS doesn't support line comments
+        let comment = if file_path.extension() == Some("css") {
+            ("/* @boundaries-ignore ", " */\n")
+        } else {
+            ("// @boundaries-ignore ", "\n")
+        };
+
         let mut last_idx = 0;
         for (idx, reason) in file_patches {
             let contents_before_span = &contents[last_idx..idx];
 
-            // Find the last newline before the span (note this is the index into the slice,
-            // not the full file)
-            let newline_idx = contents_before_span.rfind('\n');
-
-            // If newline exists, we write all the contents before newline
-            if let Some(newline_idx) = newline_idx {
-                file.write_all(contents[last_idx..(last_idx + newline_idx)].as_bytes())
-                    .map_err(|_| Error::FileWrite(file_path.to_owned()))?;
-                file.write_all(b"\n")
-                    .map_err(|_| Error::FileWrite(file_path.to_owned()))?;
-            }
+            // The comment goes at the start of the line that contains the span, which
+            // doesn't have to be the start of the statement, e.g. the specifier of a
+            // stylesheet `@import`
+            let line_start = contents_before_span
+                .rfind('\n')
+                .map_or(last_idx, |newline_idx| last_idx + newline_idx + 1);
+            file.write_all(contents[last_idx..line_start].as_bytes())
+                .map_err(|_| Error::FileWrite(file_path.to_owned()))?;
 
-            file.write_all(b"// @boundaries-ignore ")
+            file.write_all(comment.0.as_bytes())
                 .map_err(|_| Error::FileWrite(file_path.to_owned()))?;
             file.write_all(reason.as_bytes())
                 .map_err(|_| Error::FileWrite(file_path.to_owned()))?;
-            file.write_all(b"\n")
+            file.write_all(comment.1.as_bytes())
                 .map_err(|_| Error::FileWrite(file_path.to_owned()))?;
 
-            last_idx = idx;
+            last_idx = line_start;
         }
 
         file.write_all(contents[last_idx..].as_bytes())
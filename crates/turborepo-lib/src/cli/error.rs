@@ -28,6 +28,9 @@ pub enum Error {
     #[error(transparent)]
     Clone(#[from] crate::commands::clone::Error),
     #[error(transparent)]
+    #[diagnostic(transparent)]
+    Deps(#[from] crate::deps::Error),
+    #[error(transparent)]
     Path(#[from] turbopath::PathError),
     #[error(transparent)]
     #[diagnostic(transparent)]
@@ -51,6 +54,9 @@ pub enum Error {
     #[error(transparent)]
     Login(#[from] login::Error),
     #[error(transparent)]
+    #[diagnostic(transparent)]
+    Licenses(#[from] crate::licenses::Error),
+    #[error(transparent)]
     Link(#[from] link::Error),
     #[error(transparent)]
     #[diagnostic(transparent)]
@@ -66,6 +72,9 @@ pub enum Error {
     #[diagnostic(transparent)]
     Query(#[from] query::Error),
     #[error(transparent)]
+    #[diagnostic(transparent)]
+    Sbom(#[from] crate::sbom::Error),
+    #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
     #[diagnostic(transparent)]
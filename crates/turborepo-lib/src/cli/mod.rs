@@ -28,8 +28,8 @@ use turborepo_ui::{ColorConfig, GREY};
 use crate::{
     cli::error::print_potential_tasks,
     commands::{
-        bin, boundaries, clone, config, daemon, generate, info, link, login, logout, ls, prune,
-        query, run, scan, telemetry, unlink, CommandBase,
+        bin, boundaries, clone, config, daemon, deps, generate, info, licenses, link, login,
+        logout, ls, prune, query, run, sbom, scan, telemetry, unlink, CommandBase,
     },
     get_version,
     run::watch::WatchClient,
@@ -45,6 +45,7 @@ pub const INVOCATION_DIR_ENV_VAR: &str = "TURBO_INVOCATION_DIR";
 
 // Default value for the --cache-workers argument
 const DEFAULT_NUM_WORKERS: u32 = 10;
+const DEFAULT_COMPARE_THRESHOLD: f64 = 10.0;
 const SUPPORTED_GRAPH_FILE_EXTENSIONS: [&str; 8] =
     ["svg", "png", "jpg", "pdf", "json", "html", "mermaid", "dot"];
 
@@ -141,6 +142,42 @@ impl Display for DryRunMode {
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum PruneFormat {
+    /// Write the pruned monorepo to the output directory
+    #[default]
+    Dir,
+    /// Stream a reproducible tarball of the pruned monorepo to stdout
+    Tar,
+}
+
+impl Display for PruneFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PruneFormat::Dir => "dir",
+            PruneFormat::Tar => "tar",
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON
+    #[default]
+    CyclonedxJson,
+    /// SPDX 2.3 JSON
+    SpdxJson,
+}
+
+impl Display for SbomFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SbomFormat::CyclonedxJson => "cyclonedx-json",
+            SbomFormat::SpdxJson => "spdx-json",
+        })
+    }
+}
+
 #[derive(
     Copy, Clone, Debug, Default, PartialEq, Serialize, ValueEnum, Deserialize, Eq, Deserializable,
 )]
@@ -341,6 +378,19 @@ impl fmt::Display for OutputFormat {
     }
 }
 
+#[derive(Subcommand, Copy, Clone, Debug, PartialEq)]
+pub enum DepsCommand {
+    /// Check dependency versions against the policies in turbo.json
+    Check {
+        /// Update package.json files to fix any issues that can be fixed
+        /// automatically
+        #[clap(long)]
+        fix: bool,
+    },
+    /// Report dependencies that aren't imported by any source file
+    Unused,
+}
+
 #[derive(Subcommand, Copy, Clone, Debug, PartialEq)]
 pub enum TelemetryCommand {
     /// Enables anonymous telemetry
@@ -592,6 +642,8 @@ pub enum Command {
         ignore: Option<BoundariesIgnore>,
         #[clap(long, requires = "ignore")]
         reason: Option<String>,
+        #[clap(long, conflicts_with = "ignore")]
+        fix: bool,
     },
     #[clap(hide = true)]
     Clone {
@@ -617,6 +669,11 @@ pub enum Command {
         #[clap(subcommand)]
         command: Option<DaemonCommand>,
     },
+    /// Enforce dependency version policies across workspaces
+    Deps {
+        #[clap(subcommand)]
+        command: DepsCommand,
+    },
     /// Generate a new app / package
     #[clap(aliases = ["g", "gen"])]
     Generate {
@@ -654,6 +711,10 @@ pub enum Command {
         /// the current branch and `main`
         #[clap(long, group = "scope-filter-group")]
         affected: bool,
+        /// With `--affected`, only show the dependents of changed packages
+        /// that import the changed files
+        #[clap(long, requires = "affected")]
+        affected_by_imports: bool,
         /// Use the given selector to specify package(s) to act as
         /// entry points. The syntax mirrors pnpm's syntax, and
         /// additional documentation and examples can be found in
@@ -667,6 +728,15 @@ pub enum Command {
         #[clap(long, value_enum)]
         output: Option<OutputFormat>,
     },
+    /// Report the licenses of external dependencies and check them against
+    /// the policies in turbo.json
+    Licenses {
+        /// The packages to report on. Defaults to every package
+        packages: Vec<String>,
+        /// Output format
+        #[clap(long, value_enum)]
+        output: Option<OutputFormat>,
+    },
     /// Link your local directory to a Vercel organization and enable remote
     /// caching.
     Link {
@@ -718,8 +788,27 @@ pub enum Command {
             value_name = "SCOPE"
         )]
         scope_arg: Option<Vec<String>>,
+        /// Only include packages that have a task in the task graph of the
+        /// given task(s) for the scoped workspaces
+        #[clap(long = "task", value_name = "TASK")]
+        tasks: Vec<String>,
+        /// Only follow `dependencies` and `optionalDependencies` between
+        /// workspaces
+        #[clap(long)]
+        prod: bool,
         #[clap(long)]
         docker: bool,
+        /// Generate a multi-stage Dockerfile for the pruned monorepo. Implies
+        /// `--docker`
+        #[clap(long)]
+        dockerfile: bool,
+        /// Format of the pruned output. `tar` streams a reproducible tarball
+        /// to stdout instead of writing to <OUT-DIR>
+        #[clap(long, value_enum, default_value_t = PruneFormat::Dir)]
+        format: PruneFormat,
+        /// Only print the pruned lockfile to stdout
+        #[clap(long, conflicts_with_all = ["docker", "dockerfile", "format", "output_dir"])]
+        lockfile_only: bool,
         #[clap(long = "out-dir", default_value_t = String::from(prune::DEFAULT_OUTPUT_DIR), value_parser)]
         output_dir: String,
         /// Respect `.gitignore` when copying files to <OUT-DIR>
@@ -753,6 +842,14 @@ pub enum Command {
         /// The query to run, either a file path or a query string
         query: Option<String>,
     },
+    /// Generate a software bill of materials for a package
+    Sbom {
+        /// The package to generate the SBOM for
+        package: String,
+        /// Format of the SBOM
+        #[clap(long, value_enum, default_value_t = SbomFormat::CyclonedxJson)]
+        format: SbomFormat,
+    },
     Watch {
         #[clap(flatten)]
         execution_args: Box<ExecutionArgs>,
@@ -814,17 +911,17 @@ pub struct GenerateWorkspaceArgs {
 #[derive(Parser, Clone, Debug, Default, PartialEq, Serialize)]
 pub struct GeneratorCustomArgs {
     /// The name of the generator to run
-    generator_name: Option<String>,
+    pub generator_name: Option<String>,
     /// Generator configuration file
     #[clap(short = 'c', long)]
-    config: Option<String>,
+    pub config: Option<String>,
     /// The root of your repository (default: directory with root
     /// turbo.json)
     #[clap(short = 'r', long)]
-    root: Option<String>,
+    pub root: Option<String>,
     /// Answers passed directly to generator
     #[clap(short = 'a', long, value_delimiter = ' ', num_args = 1..)]
-    args: Vec<String>,
+    pub args: Vec<String>,
 }
 
 #[derive(Subcommand, Clone, Debug, PartialEq)]
@@ -908,6 +1005,11 @@ pub struct ExecutionArgs {
     #[clap(long, group = "scope-filter-group", conflicts_with = "filter")]
     pub affected: bool,
 
+    /// With `--affected`, only include the dependents of changed packages
+    /// that import the changed files, found by tracing their imports
+    #[clap(long, requires = "affected")]
+    pub affected_by_imports: bool,
+
     /// Set type of process output logging. Use "full" to show
     /// all output. Use "hash-only" to show only turbo-computed
     /// task hashes. Use "new-only" to show only new output with
@@ -1062,6 +1164,17 @@ pub struct RunArgs {
     /// Generate a summary of the turbo run
     #[clap(long, default_missing_value = "true")]
     pub summarize: Option<Option<bool>>,
+    /// Compare this run with a previous run summary from `.turbo/runs`.
+    /// Pass a run ID, or `last` to use the most recent run
+    #[clap(long, value_name = "RUN_ID", value_parser = NonEmptyStringValueParser::new())]
+    pub compare_to: Option<String>,
+    /// The percentage a task's duration has to change by to be reported by
+    /// `--compare-to`
+    #[clap(long, default_value_t = DEFAULT_COMPARE_THRESHOLD, requires = "compare_to")]
+    pub compare_threshold: f64,
+    /// Exit with a non-zero code if `--compare-to` finds regressions
+    #[clap(long, requires = "compare_to")]
+    pub fail_on_regression: bool,
 
     /// Execute all tasks in parallel.
     #[clap(long)]
@@ -1084,6 +1197,9 @@ impl Default for RunArgs {
             anon_profile: None,
             remote_cache_read_only: None,
             summarize: None,
+            compare_to: None,
+            compare_threshold: DEFAULT_COMPARE_THRESHOLD,
+            fail_on_regression: false,
             parallel: false,
         }
     }
@@ -1134,6 +1250,7 @@ impl RunArgs {
         track_usage!(telemetry, self.daemon, |val| val);
         track_usage!(telemetry, self.no_daemon, |val| val);
         track_usage!(telemetry, self.parallel, |val| val);
+        track_usage!(telemetry, self.fail_on_regression, |val| val);
         track_usage!(
             telemetry,
             self.remote_cache_read_only().unwrap_or_default(),
@@ -1144,6 +1261,7 @@ impl RunArgs {
         track_usage!(telemetry, &self.profile, Option::is_some);
         track_usage!(telemetry, &self.anon_profile, Option::is_some);
         track_usage!(telemetry, &self.summarize, Option::is_some);
+        track_usage!(telemetry, &self.compare_to, Option::is_some);
 
         // track values
         if let Some(dry_run) = &self.dry_run {
@@ -1408,15 +1526,21 @@ pub async fn run(
 
             Ok(0)
         }
-        Command::Boundaries { ignore, reason, .. } => {
+        Command::Boundaries {
+            ignore,
+            reason,
+            fix,
+            ..
+        } => {
             let event = CommandEventBuilder::new("boundaries").with_parent(&root_telemetry);
             let ignore = *ignore;
             let reason = reason.clone();
+            let fix = *fix;
 
             event.track_call();
             let base = CommandBase::new(cli_args.clone(), repo_root, version, color_config)?;
 
-            Ok(boundaries::run(base, event, ignore, reason).await?)
+            Ok(boundaries::run(base, event, ignore, reason, fix).await?)
         }
         Command::Clone {
             url,
@@ -1452,6 +1576,16 @@ pub async fn run(
 
             Ok(0)
         }
+        Command::Deps { command } => {
+            let event = CommandEventBuilder::new("deps").with_parent(&root_telemetry);
+            event.track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, color_config)?;
+
+            match *command {
+                DepsCommand::Check { fix } => Ok(deps::check(base, event, fix).await?),
+                DepsCommand::Unused => Ok(deps::unused(base, event).await?),
+            }
+        }
         Command::Generate {
             tag,
             generator_name,
@@ -1470,7 +1604,7 @@ pub async fn run(
                 args: args.clone(),
             };
             let child_event = event.child();
-            generate::run(tag, command, &args, child_event)?;
+            generate::run(&repo_root, tag, command, &args, color_config, child_event).await?;
             Ok(0)
         }
         Command::Info => {
@@ -1524,6 +1658,15 @@ pub async fn run(
 
             Ok(0)
         }
+        Command::Licenses { packages, output } => {
+            let event = CommandEventBuilder::new("licenses").with_parent(&root_telemetry);
+            let packages = packages.clone();
+            let output = *output;
+            event.track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, color_config)?;
+
+            Ok(licenses::run(base, event, packages, output).await?)
+        }
         Command::Link {
             no_gitignore,
             scope,
@@ -1658,6 +1801,15 @@ pub async fn run(
 
             Ok(query)
         }
+        Command::Sbom { package, format } => {
+            let event = CommandEventBuilder::new("sbom").with_parent(&root_telemetry);
+            let package = package.clone();
+            let format = *format;
+            event.track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, color_config)?;
+
+            Ok(sbom::run(base, event, &package, format).await?)
+        }
         Command::Watch {
             execution_args,
             experimental_write_cache,
@@ -1683,7 +1835,12 @@ pub async fn run(
         Command::Prune {
             scope,
             scope_arg,
+            tasks,
+            prod,
             docker,
+            dockerfile,
+            format,
+            lockfile_only,
             output_dir,
             use_gitignore,
         } => {
@@ -1694,7 +1851,11 @@ pub async fn run(
                 .or(scope.as_ref())
                 .cloned()
                 .unwrap_or_default();
+            let prod = *prod;
             let docker = *docker;
+            let dockerfile = *dockerfile;
+            let format = *format;
+            let lockfile_only = *lockfile_only;
             let output_dir = output_dir.clone();
             let use_gitignore = use_gitignore.unwrap_or(true);
             let base = CommandBase::new(cli_args, repo_root, version, color_config)?;
@@ -1703,7 +1864,12 @@ pub async fn run(
             prune::prune(
                 &base,
                 &scope,
+                tasks,
+                prod,
                 docker,
+                dockerfile,
+                format,
+                lockfile_only,
                 &output_dir,
                 use_gitignore,
                 event_child,
@@ -1795,7 +1961,9 @@ mod test {
         }
     }
 
-    use crate::cli::{Args, Command, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputLogsMode};
+    use crate::cli::{
+        Args, Command, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputLogsMode, PruneFormat,
+    };
 
     #[test_case::test_case(
         &["turbo", "run", "build"],
@@ -2836,7 +3004,12 @@ mod test {
         let default_prune = Command::Prune {
             scope: None,
             scope_arg: Some(vec!["foo".into()]),
+            tasks: vec![],
+            prod: false,
             docker: false,
+            dockerfile: false,
+            format: PruneFormat::Dir,
+            lockfile_only: false,
             output_dir: "out".to_string(),
             use_gitignore: None,
         };
@@ -2867,7 +3040,12 @@ mod test {
                 command: Some(Command::Prune {
                     scope: Some(vec!["bar".to_string()]),
                     scope_arg: None,
+                    tasks: vec![],
+                    prod: false,
                     docker: false,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
                     output_dir: "out".to_string(),
                     use_gitignore: None,
                 }),
@@ -2881,7 +3059,12 @@ mod test {
                 command: Some(Command::Prune {
                     scope: None,
                     scope_arg: Some(vec!["foo".to_string(), "bar".to_string()]),
+                    tasks: vec![],
+                    prod: false,
                     docker: false,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
                     output_dir: "out".to_string(),
                     use_gitignore: None,
                 }),
@@ -2895,7 +3078,12 @@ mod test {
                 command: Some(Command::Prune {
                     scope: None,
                     scope_arg: Some(vec!["foo".into()]),
+                    tasks: vec![],
+                    prod: false,
                     docker: true,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
                     output_dir: "out".to_string(),
                     use_gitignore: None,
                 }),
@@ -2909,7 +3097,12 @@ mod test {
                 command: Some(Command::Prune {
                     scope: None,
                     scope_arg: Some(vec!["foo".into()]),
+                    tasks: vec![],
+                    prod: false,
                     docker: false,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
                     output_dir: "dist".to_string(),
                     use_gitignore: None,
                 }),
@@ -2925,7 +3118,12 @@ mod test {
                 command: Some(Command::Prune {
                     scope: None,
                     scope_arg: Some(vec!["foo".into()]),
+                    tasks: vec![],
+                    prod: false,
                     docker: true,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
                     output_dir: "dist".to_string(),
                     use_gitignore: None,
                 }),
@@ -2942,7 +3140,12 @@ mod test {
                 command: Some(Command::Prune {
                     scope: None,
                     scope_arg: Some(vec!["foo".into()]),
+                    tasks: vec![],
+                    prod: false,
                     docker: true,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
                     output_dir: "dist".to_string(),
                     use_gitignore: None,
                 }),
@@ -2964,7 +3167,12 @@ mod test {
                 command: Some(Command::Prune {
                     scope: Some(vec!["foo".to_string()]),
                     scope_arg: None,
+                    tasks: vec![],
+                    prod: false,
                     docker: true,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
                     output_dir: "dist".to_string(),
                     use_gitignore: None,
                 }),
@@ -2981,7 +3189,12 @@ mod test {
                 command: Some(Command::Prune {
                     scope: None,
                     scope_arg: Some(vec!["foo".to_string()]),
+                    tasks: vec![],
+                    prod: false,
                     docker: false,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
                     output_dir: "out".to_string(),
                     use_gitignore: Some(true),
                 }),
@@ -2998,7 +3211,12 @@ mod test {
                 command: Some(Command::Prune {
                     scope: None,
                     scope_arg: Some(vec!["foo".to_string()]),
+                    tasks: vec![],
+                    prod: false,
                     docker: false,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
                     output_dir: "out".to_string(),
                     use_gitignore: Some(true),
                 }),
@@ -3015,7 +3233,12 @@ mod test {
                 command: Some(Command::Prune {
                     scope: None,
                     scope_arg: Some(vec!["foo".to_string()]),
+                    tasks: vec![],
+                    prod: false,
                     docker: false,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
                     output_dir: "out".to_string(),
                     use_gitignore: Some(false),
                 }),
@@ -3082,6 +3305,78 @@ mod test {
         assert!(Args::try_parse_from(["turbo", "prune", "foo", "--scope", "bar"]).is_err(),);
     }
 
+    #[test]
+    fn test_parse_prune_output_modes() {
+        assert_eq!(
+            Args::try_parse_from(["turbo", "prune", "foo", "--format=tar", "--dockerfile"])
+                .unwrap(),
+            Args {
+                command: Some(Command::Prune {
+                    scope: None,
+                    scope_arg: Some(vec!["foo".into()]),
+                    tasks: vec![],
+                    prod: false,
+                    docker: false,
+                    dockerfile: true,
+                    format: PruneFormat::Tar,
+                    lockfile_only: false,
+                    output_dir: "out".to_string(),
+                    use_gitignore: None,
+                }),
+                ..Args::default()
+            }
+        );
+
+        assert_eq!(
+            Args::try_parse_from(["turbo", "prune", "foo", "--lockfile-only"]).unwrap(),
+            Args {
+                command: Some(Command::Prune {
+                    scope: None,
+                    scope_arg: Some(vec!["foo".into()]),
+                    tasks: vec![],
+                    prod: false,
+                    docker: false,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: true,
+                    output_dir: "out".to_string(),
+                    use_gitignore: None,
+                }),
+                ..Args::default()
+            }
+        );
+
+        assert_eq!(
+            Args::try_parse_from([
+                "turbo", "prune", "web", "--task", "build", "--task", "lint", "--prod"
+            ])
+            .unwrap(),
+            Args {
+                command: Some(Command::Prune {
+                    scope: None,
+                    scope_arg: Some(vec!["web".into()]),
+                    tasks: vec!["build".into(), "lint".into()],
+                    prod: true,
+                    docker: false,
+                    dockerfile: false,
+                    format: PruneFormat::Dir,
+                    lockfile_only: false,
+                    output_dir: "out".to_string(),
+                    use_gitignore: None,
+                }),
+                ..Args::default()
+            }
+        );
+
+        assert!(
+            Args::try_parse_from(["turbo", "prune", "foo", "--lockfile-only", "--docker"]).is_err()
+        );
+        assert!(
+            Args::try_parse_from(["turbo", "prune", "foo", "--lockfile-only", "--format=tar"])
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_parse_gen() {
         let default_gen = Command::Generate {
@@ -3222,6 +3517,19 @@ mod test {
         assert!(Args::try_parse_from(["turbo", "ls", "--filter", "foo", "--affected"]).is_err(),);
     }
 
+    #[test]
+    fn test_affected_by_imports_requires_affected() {
+        assert!(Args::try_parse_from(["turbo", "build", "--affected-by-imports"]).is_err());
+        assert!(Args::try_parse_from(["turbo", "ls", "--affected-by-imports"]).is_err());
+        assert!(
+            Args::try_parse_from(["turbo", "build", "--affected", "--affected-by-imports"])
+                .unwrap()
+                .execution_args()
+                .unwrap()
+                .affected_by_imports
+        );
+    }
+
     struct SinglePackageTestCase {
         args: &'static [&'static str],
         expected_is_single: bool,
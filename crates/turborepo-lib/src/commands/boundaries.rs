@@ -14,6 +14,7 @@ pub async fn run(
     telemetry: CommandEventBuilder,
     ignore: Option<BoundariesIgnore>,
     reason: Option<String>,
+    fix: bool,
 ) -> Result<i32, cli::Error> {
     let signal = get_signal()?;
     let handler = SignalHandler::new(signal);
@@ -23,7 +24,25 @@ pub async fn run(
         .build(&handler, telemetry)
         .await?;
 
-    let result = run.check_boundaries(true).await?;
+    let mut result = run.check_boundaries(true).await?;
+
+    if fix {
+        let patched = run.add_missing_dependencies(&mut result)?;
+        for path in &patched {
+            let short_path = match run.repo_root().anchor(path) {
+                Ok(path) => path.to_string(),
+                Err(_) => path.to_string(),
+            };
+            println!(
+                "{} {}",
+                color!(run.color_config(), BOLD_GREEN, "patching"),
+                short_path
+            );
+        }
+        if !patched.is_empty() {
+            println!("Run your package manager's install command to update your lockfile.");
+        }
+    }
 
     if let Some(ignore) = ignore {
         let mut patches: HashMap<&AbsoluteSystemPath, Vec<(SourceSpan, String)>> = HashMap::new();
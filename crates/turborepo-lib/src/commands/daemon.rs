@@ -15,8 +15,8 @@ use super::CommandBase;
 use crate::{
     cli::DaemonCommand,
     daemon::{
-        endpoint::SocketOpenError, CloseReason, DaemonConnector, DaemonConnectorError, DaemonError,
-        Paths,
+        endpoint::SocketOpenError, proto, CloseReason, DaemonConnector, DaemonConnectorError,
+        DaemonError, Paths,
     },
     tracing::TurboSubscriber,
 };
@@ -135,12 +135,20 @@ pub async fn daemon_client(
             };
             let status = client.status().await?;
             let log_file = log_filename(&status.log_file)?;
+            // Older daemons don't report metrics
+            let metrics = client
+                .metrics()
+                .await
+                .inspect_err(|e| trace!("unable to get daemon metrics: {e}"))
+                .ok()
+                .map(DaemonMetrics::from);
             let paths = client.paths();
             let status = DaemonStatus {
                 uptime_ms: status.uptime_msec,
                 log_file: log_file.into(),
                 pid_file: paths.pid_file.to_owned(),
                 sock_file: paths.sock_file.to_owned(),
+                metrics,
             };
 
             if *json {
@@ -171,6 +179,9 @@ pub async fn daemon_client(
                     "socket file: {}",
                     color!(base.color_config, GREY, "{}", status.sock_file)
                 );
+                if let Some(metrics) = &status.metrics {
+                    print_metrics(base, metrics);
+                }
             }
         }
         DaemonCommand::Logs => {
@@ -366,4 +377,95 @@ pub struct DaemonStatus {
     pub log_file: Utf8PathBuf,
     pub pid_file: turbopath::AbsoluteSystemPathBuf,
     pub sock_file: turbopath::AbsoluteSystemPathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<DaemonMetrics>,
+}
+
+#[derive(serde::Serialize)]
+pub struct DaemonMetrics {
+    pub file_events: u64,
+    pub file_event_errors: u64,
+    pub rescans: u64,
+    pub queued_file_events: u64,
+    pub max_queued_file_events: u64,
+    pub hash_hits: u64,
+    pub hash_pending: u64,
+    pub hash_misses: u64,
+    pub memory_bytes: Option<u64>,
+    pub connected_clients: u64,
+    pub total_clients: u64,
+}
+
+impl From<proto::DaemonMetrics> for DaemonMetrics {
+    fn from(metrics: proto::DaemonMetrics) -> Self {
+        Self {
+            file_events: metrics.file_events,
+            file_event_errors: metrics.file_event_errors,
+            rescans: metrics.rescans,
+            queued_file_events: metrics.queued_file_events,
+            max_queued_file_events: metrics.max_queued_file_events,
+            hash_hits: metrics.hash_hits,
+            hash_pending: metrics.hash_pending,
+            hash_misses: metrics.hash_misses,
+            memory_bytes: metrics.memory_bytes,
+            connected_clients: metrics.connected_clients,
+            total_clients: metrics.total_clients,
+        }
+    }
+}
+
+fn print_metrics(base: &CommandBase, metrics: &DaemonMetrics) {
+    println!(
+        "file events: {}",
+        color!(
+            base.color_config,
+            GREY,
+            "{} ({} errors, {} rescans)",
+            metrics.file_events,
+            metrics.file_event_errors,
+            metrics.rescans
+        )
+    );
+    println!(
+        "queued file events: {}",
+        color!(
+            base.color_config,
+            GREY,
+            "{} (max {})",
+            metrics.queued_file_events,
+            metrics.max_queued_file_events
+        )
+    );
+    println!(
+        "file hash queries: {}",
+        color!(
+            base.color_config,
+            GREY,
+            "{} hits, {} pending, {} misses",
+            metrics.hash_hits,
+            metrics.hash_pending,
+            metrics.hash_misses
+        )
+    );
+    if let Some(memory_bytes) = metrics.memory_bytes {
+        println!(
+            "memory: {}",
+            color!(
+                base.color_config,
+                GREY,
+                "{:.1} MiB",
+                memory_bytes as f64 / (1024.0 * 1024.0)
+            )
+        );
+    }
+    println!(
+        "clients: {}",
+        color!(
+            base.color_config,
+            GREY,
+            "{} connected, {} total",
+            metrics.connected_clients,
+            metrics.total_clients
+        )
+    );
 }
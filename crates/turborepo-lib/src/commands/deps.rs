@@ -0,0 +1,65 @@
+use tracing::warn;
+use turborepo_signals::{listeners::get_signal, SignalHandler};
+use turborepo_telemetry::events::command::CommandEventBuilder;
+use turborepo_ui::{color, BOLD_GREEN};
+
+use crate::{cli, commands::CommandBase, run::builder::RunBuilder};
+
+pub async fn check(
+    base: CommandBase,
+    telemetry: CommandEventBuilder,
+    fix: bool,
+) -> Result<i32, cli::Error> {
+    let signal = get_signal()?;
+    let handler = SignalHandler::new(signal);
+
+    let run = RunBuilder::new(base)?
+        .do_not_validate_engine()
+        .build(&handler, telemetry)
+        .await?;
+
+    if run.root_turbo_json().deps.is_none() {
+        warn!("No dependency policies are configured. Add a `deps` key to turbo.json.");
+    }
+
+    let mut result = run.check_deps()?;
+    if fix {
+        for path in result.fix()? {
+            let short_path = match run.repo_root().anchor(&path) {
+                Ok(path) => path.to_string(),
+                Err(_) => path.to_string(),
+            };
+            println!(
+                "{} {}",
+                color!(run.color_config(), BOLD_GREEN, "patching"),
+                short_path
+            );
+        }
+    }
+    result.emit(run.color_config());
+
+    if result.is_ok() {
+        Ok(0)
+    } else {
+        Ok(1)
+    }
+}
+
+pub async fn unused(base: CommandBase, telemetry: CommandEventBuilder) -> Result<i32, cli::Error> {
+    let signal = get_signal()?;
+    let handler = SignalHandler::new(signal);
+
+    let run = RunBuilder::new(base)?
+        .do_not_validate_engine()
+        .build(&handler, telemetry)
+        .await?;
+
+    let result = run.check_unused_deps().await?;
+    result.emit(run.color_config());
+
+    if result.is_ok() {
+        Ok(0)
+    } else {
+        Ok(1)
+    }
+}
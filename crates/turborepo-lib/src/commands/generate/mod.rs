@@ -0,0 +1,245 @@
+mod template;
+mod workspace;
+
+use std::{
+    collections::HashMap,
+    io::{self, IsTerminal},
+    process::{Command, Stdio},
+};
+
+use dialoguer::{Input, Select};
+use thiserror::Error;
+use tracing::debug;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
+use turborepo_telemetry::events::command::CommandEventBuilder;
+use turborepo_ui::{color, ColorConfig, BOLD, GREY};
+use which::which;
+
+use self::template::{Generator, TEMPLATES_DIR};
+use crate::{
+    child::spawn_child,
+    cli::{GenerateCommand, GeneratorCustomArgs},
+    rewrite_json::RewriteError,
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unable to run generate - missing requirements (npx): {0}")]
+    NpxNotFound(#[source] which::Error),
+    #[error("Failed to run npx: {0}")]
+    NpxFailed(#[source] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Path(#[from] turbopath::PathError),
+    #[error("Failed to read templates: {0}")]
+    Walk(#[from] ignore::Error),
+    #[error("Failed to copy workspace: {0}")]
+    Copy(#[from] turborepo_fs::Error),
+    #[error(transparent)]
+    PackageJson(#[from] turborepo_repository::package_json::Error),
+    #[error(transparent)]
+    PackageGraph(#[from] turborepo_repository::package_graph::Error),
+    #[error("Failed to update package.json: {0}")]
+    Rewrite(#[from] RewriteError),
+    #[error("Prompt canceled: {0}")]
+    Prompt(#[from] dialoguer::Error),
+    #[error("Missing {0}, which must be passed as an argument when not running interactively.")]
+    MissingInput(&'static str),
+    #[error("Invalid workspace type `{0}`. Expected `app` or `package`.")]
+    InvalidWorkspaceType(String),
+    #[error("A workspace named `{0}` already exists.")]
+    WorkspaceExists(String),
+    #[error("Could not find a workspace named `{0}` to copy.")]
+    WorkspaceNotFound(String),
+    #[error("`{0}` already exists.")]
+    DestinationExists(String),
+    #[error("Invalid generator answer `{0}`. Answers should be `key=value` pairs.")]
+    InvalidAnswer(String),
+    #[error("Missing value for template variable `{0}`.")]
+    MissingVariable(String),
+    #[error("Unknown template helper `{0}`.")]
+    UnknownHelper(String),
+    #[error("Template path `{0}` would be generated outside of the repository.")]
+    InvalidTemplatePath(String),
+    #[error("`{0}` already exists. Generators never overwrite existing files.")]
+    FileExists(AnchoredSystemPathBuf),
+}
+
+fn call_turbo_gen(command: &str, tag: &String, raw_args: &str) -> Result<i32, Error> {
+    debug!(
+        "Running @turbo/gen@{} with command `{}` and args {:?}",
+        tag, command, raw_args
+    );
+    let npx_path = which("npx").map_err(Error::NpxNotFound)?;
+    let mut npx = Command::new(npx_path);
+    npx.arg("--yes")
+        .arg(format!("@turbo/gen@{tag}"))
+        .arg("raw")
+        .arg(command)
+        .args(["--json", raw_args])
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+
+    let child = spawn_child(npx).map_err(Error::NpxFailed)?;
+    let exit_code = child.wait().map_err(Error::NpxFailed)?.code().unwrap_or(2);
+    Ok(exit_code)
+}
+
+pub async fn run(
+    repo_root: &AbsoluteSystemPath,
+    tag: &String,
+    command: &Option<Box<GenerateCommand>>,
+    args: &GeneratorCustomArgs,
+    color_config: ColorConfig,
+    telemetry: CommandEventBuilder,
+) -> Result<(), Error> {
+    telemetry.track_generator_tag(tag);
+    // check if a subcommand was passed
+    if let Some(box GenerateCommand::Workspace(workspace_args)) = command {
+        telemetry.track_generator_option("workspace");
+        // Examples hosted on GitHub need to be downloaded, so we leave those to
+        // `@turbo/gen`
+        if workspace_args.copy.as_deref().is_some_and(is_remote) {
+            let raw_args = serde_json::to_string(&workspace_args)?;
+            call_turbo_gen("workspace", tag, &raw_args)?;
+        } else {
+            let repo_root = resolve_root(repo_root, workspace_args.root.as_deref())?;
+            workspace::run(&repo_root, workspace_args, color_config).await?;
+        }
+    } else {
+        // if no subcommand was passed, run the generate command as default
+        telemetry.track_generator_option("run");
+        let repo_root = resolve_root(repo_root, args.root.as_deref())?;
+        if let Some(generator) = native_generator(&repo_root, args)? {
+            run_generator(&repo_root, &generator, &args.args, color_config)?;
+        } else {
+            let raw_args = serde_json::to_string(&args)?;
+            call_turbo_gen("run", tag, &raw_args)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_root(
+    repo_root: &AbsoluteSystemPath,
+    root: Option<&str>,
+) -> Result<AbsoluteSystemPathBuf, Error> {
+    Ok(match root {
+        Some(root) => AbsoluteSystemPathBuf::from_cwd(root)?,
+        None => repo_root.to_owned(),
+    })
+}
+
+fn is_remote(copy: &str) -> bool {
+    copy.starts_with("https://") || copy.starts_with("http://") || copy.starts_with("github.com/")
+}
+
+// Finds the native generator to run, if any. Generators configured with
+// `turbo/generators/config.ts` are run by `@turbo/gen`.
+fn native_generator(
+    repo_root: &AbsoluteSystemPath,
+    args: &GeneratorCustomArgs,
+) -> Result<Option<Generator>, Error> {
+    if args.config.is_some() {
+        return Ok(None);
+    }
+    let mut generators = Generator::discover(repo_root)?;
+    match &args.generator_name {
+        Some(name) => Ok(generators
+            .into_iter()
+            .find(|generator| generator.name() == name)),
+        // Only prompt when there aren't any `@turbo/gen` generators to choose
+        // from as well
+        None if !generators.is_empty()
+            && !repo_root.join_components(&["turbo", "generators"]).exists() =>
+        {
+            if !io::stdin().is_terminal() {
+                return Err(Error::MissingInput("a generator name"));
+            }
+            let names = generators
+                .iter()
+                .map(|generator| generator.name())
+                .collect::<Vec<_>>();
+            let selection = Select::new()
+                .with_prompt("Select a generator to run")
+                .items(&names)
+                .default(0)
+                .interact()?;
+            Ok(Some(generators.swap_remove(selection)))
+        }
+        None => Ok(None),
+    }
+}
+
+fn run_generator(
+    repo_root: &AbsoluteSystemPath,
+    generator: &Generator,
+    answers: &[String],
+    color_config: ColorConfig,
+) -> Result<(), Error> {
+    let mut variables = parse_answers(answers)?;
+    let interactive = io::stdin().is_terminal();
+    for variable in generator.variables()? {
+        if variables.contains_key(&variable) {
+            continue;
+        }
+        if !interactive {
+            return Err(Error::MissingVariable(variable));
+        }
+        let value = Input::new()
+            .with_prompt(format!("What is the value of \"{variable}\"?"))
+            .interact_text()?;
+        variables.insert(variable, value);
+    }
+
+    let created = generator.generate(repo_root, &variables)?;
+    println!(
+        "\n{} Generated {} from {}",
+        color!(color_config, BOLD, ">>>"),
+        generator.name(),
+        TEMPLATES_DIR.join("/"),
+    );
+    for path in created {
+        println!("{}", color!(color_config, GREY, "    {}", path));
+    }
+    Ok(())
+}
+
+// Answers are passed as `--args key=value`
+fn parse_answers(answers: &[String]) -> Result<HashMap<String, String>, Error> {
+    answers
+        .iter()
+        .map(|answer| {
+            answer
+                .split_once('=')
+                .filter(|(key, _)| !key.is_empty())
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .ok_or_else(|| Error::InvalidAnswer(answer.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_remote, parse_answers};
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = parse_answers(&["name=button".to_string(), "path=a=b".to_string()]).unwrap();
+        assert_eq!(answers["name"], "button");
+        assert_eq!(answers["path"], "a=b");
+        assert!(parse_answers(&["button".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_is_remote() {
+        assert!(is_remote(
+            "https://github.com/vercel/turborepo/tree/main/examples/basic"
+        ));
+        assert!(!is_remote("@repo/ui"));
+    }
+}
@@ -0,0 +1,275 @@
+//! Native custom generators. A generator is a directory of templates under
+//! `turbo/templates/<generator>` whose layout mirrors the repository root.
+//! File contents and paths can reference variables with Handlebars-style
+//! `{{ name }}` expressions, optionally transformed with one of the case
+//! helpers that `@turbo/gen` provides, e.g. `{{ kebabCase name }}`. A trailing
+//! `.hbs` extension is removed from generated files.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs, io,
+    sync::LazyLock,
+};
+
+use convert_case::{Case, Casing};
+use ignore::WalkBuilder;
+use regex::{Captures, Regex};
+use turbopath::{
+    AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf, RelativeUnixPathBuf,
+};
+
+use super::Error;
+
+pub const TEMPLATES_DIR: &[&str] = &["turbo", "templates"];
+
+const TEMPLATE_EXTENSION: &str = ".hbs";
+
+static EXPRESSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*(?:([A-Za-z]+)\s+)?([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap()
+});
+
+pub struct Generator {
+    name: String,
+    root: AbsoluteSystemPathBuf,
+}
+
+struct TemplateFile {
+    path: AbsoluteSystemPathBuf,
+    // Path relative to the generator root, which may contain expressions
+    relative_path: String,
+    // `None` for files that aren't UTF-8, which are copied as is
+    contents: Option<String>,
+}
+
+impl Generator {
+    /// Lists the generators available in the repository
+    pub fn discover(repo_root: &AbsoluteSystemPath) -> Result<Vec<Generator>, Error> {
+        let templates_dir = repo_root.join_components(TEMPLATES_DIR);
+        let entries = match fs::read_dir(templates_dir.as_std_path()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut generators = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let Some(name) = entry.file_name().to_str().map(|name| name.to_string()) else {
+                continue;
+            };
+            let root = templates_dir.join_component(&name);
+            generators.push(Generator { name, root });
+        }
+        generators.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(generators)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The variables referenced by the generator's templates, in sorted order
+    pub fn variables(&self) -> Result<BTreeSet<String>, Error> {
+        let mut variables = BTreeSet::new();
+        for file in self.files()? {
+            variables.extend(expression_variables(&file.relative_path));
+            if let Some(contents) = &file.contents {
+                variables.extend(expression_variables(contents));
+            }
+        }
+        Ok(variables)
+    }
+
+    /// Renders every template into the repository, returning the paths of
+    /// the created files. Existing files are never overwritten.
+    pub fn generate(
+        &self,
+        repo_root: &AbsoluteSystemPath,
+        variables: &HashMap<String, String>,
+    ) -> Result<Vec<AnchoredSystemPathBuf>, Error> {
+        // Render everything up front so that a bad template doesn't leave
+        // a partially generated result behind.
+        let mut outputs = Vec::new();
+        for file in self.files()? {
+            let rendered = render(&file.relative_path, variables)?;
+            let rendered = rendered
+                .strip_suffix(TEMPLATE_EXTENSION)
+                .unwrap_or(&rendered);
+            // Variables shouldn't be able to move files outside of the repository
+            if rendered.split('/').any(|component| component == "..") {
+                return Err(Error::InvalidTemplatePath(rendered.to_string()));
+            }
+            let target = repo_root.join_unix_path(RelativeUnixPathBuf::new(rendered)?);
+            let relative_path = repo_root.anchor(&target)?;
+            if target.exists() {
+                return Err(Error::FileExists(relative_path));
+            }
+            let contents = match &file.contents {
+                Some(contents) => render(contents, variables)?.into_bytes(),
+                None => file.path.read()?,
+            };
+            outputs.push((relative_path, target, contents));
+        }
+
+        let mut created = Vec::with_capacity(outputs.len());
+        for (relative_path, target, contents) in outputs {
+            target.ensure_dir()?;
+            target.create_with_contents(contents)?;
+            created.push(relative_path);
+        }
+        Ok(created)
+    }
+
+    fn files(&self) -> Result<Vec<TemplateFile>, Error> {
+        let walker = WalkBuilder::new(self.root.as_path())
+            .hidden(false)
+            .git_ignore(false)
+            .git_global(false)
+            .git_exclude(false)
+            .ignore(false)
+            .build();
+        let mut files = Vec::new();
+        for entry in walker {
+            let entry = entry?;
+            if !entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+            {
+                continue;
+            }
+            let path = AbsoluteSystemPathBuf::try_from(entry.path())?;
+            let relative_path = self.root.anchor(&path)?.to_unix().to_string();
+            let contents = String::from_utf8(path.read()?).ok();
+            files.push(TemplateFile {
+                path,
+                relative_path,
+                contents,
+            });
+        }
+        files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        Ok(files)
+    }
+}
+
+fn expression_variables(text: &str) -> impl Iterator<Item = String> + '_ {
+    EXPRESSION
+        .captures_iter(text)
+        .map(|captures| captures[2].to_string())
+}
+
+/// Replaces every expression in `text` with the value of its variable
+pub fn render(text: &str, variables: &HashMap<String, String>) -> Result<String, Error> {
+    let mut error = None;
+    let rendered = EXPRESSION.replace_all(text, |captures: &Captures| {
+        let variable = &captures[2];
+        let Some(value) = variables.get(variable) else {
+            error.get_or_insert_with(|| Error::MissingVariable(variable.to_string()));
+            return String::new();
+        };
+        match captures.get(1).map(|helper| helper.as_str()) {
+            None => value.clone(),
+            Some(helper) => apply_helper(helper, value).unwrap_or_else(|| {
+                error.get_or_insert_with(|| Error::UnknownHelper(helper.to_string()));
+                String::new()
+            }),
+        }
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(rendered.into_owned()),
+    }
+}
+
+fn apply_helper(helper: &str, value: &str) -> Option<String> {
+    let case = match helper {
+        "camelCase" => Case::Camel,
+        "pascalCase" | "properCase" => Case::Pascal,
+        "kebabCase" | "dashCase" => Case::Kebab,
+        "snakeCase" => Case::Snake,
+        "constantCase" => Case::UpperSnake,
+        "titleCase" => Case::Title,
+        "lowerCase" => return Some(value.to_lowercase()),
+        "upperCase" => return Some(value.to_uppercase()),
+        _ => return None,
+    };
+    Some(value.to_case(case))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use turbopath::AbsoluteSystemPathBuf;
+
+    use super::{render, Generator};
+    use crate::commands::generate::Error;
+
+    #[test]
+    fn test_render() {
+        let variables = HashMap::from([("name".to_string(), "my button".to_string())]);
+        assert_eq!(
+            render(
+                "export const {{ pascalCase name }} = '{{name}}'; // {{ kebabCase name }}",
+                &variables
+            )
+            .unwrap(),
+            "export const MyButton = 'my button'; // my-button"
+        );
+        assert!(matches!(
+            render("{{ shout name }}", &variables),
+            Err(Error::UnknownHelper(helper)) if helper == "shout"
+        ));
+        assert!(matches!(
+            render("{{ description }}", &variables),
+            Err(Error::MissingVariable(variable)) if variable == "description"
+        ));
+    }
+
+    #[test]
+    fn test_generate() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        let template = repo_root.join_components(&[
+            "turbo",
+            "templates",
+            "component",
+            "packages",
+            "ui",
+            "src",
+            "{{ kebabCase name }}.tsx.hbs",
+        ]);
+        template.ensure_dir().unwrap();
+        template
+            .create_with_contents("export const {{ pascalCase name }} = () => null;\n")
+            .unwrap();
+
+        let generators = Generator::discover(&repo_root).unwrap();
+        assert_eq!(generators.len(), 1);
+        let generator = &generators[0];
+        assert_eq!(generator.name(), "component");
+        assert_eq!(
+            generator
+                .variables()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["name"]
+        );
+
+        let variables = HashMap::from([("name".to_string(), "date picker".to_string())]);
+        generator.generate(&repo_root, &variables).unwrap();
+        let generated = repo_root.join_components(&["packages", "ui", "src", "date-picker.tsx"]);
+        assert_eq!(
+            generated.read_to_string().unwrap(),
+            "export const DatePicker = () => null;\n"
+        );
+
+        // Generating again would overwrite the file
+        assert!(matches!(
+            generator.generate(&repo_root, &variables),
+            Err(Error::FileExists(_))
+        ));
+    }
+}
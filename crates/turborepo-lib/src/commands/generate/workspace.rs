@@ -0,0 +1,434 @@
+//! Native implementation of `turbo gen workspace`. Creates an empty workspace
+//! or copies an existing one, then adds the internal dependencies that the
+//! user selects to its `package.json`.
+
+use std::io::{self, IsTerminal};
+
+use dialoguer::{Confirm, FuzzySelect, Input, MultiSelect, Select};
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_repository::{
+    package_graph::{PackageGraph, PackageName},
+    package_json::PackageJson,
+    package_manager::PackageManager,
+};
+use turborepo_ui::{color, ColorConfig, BOLD, GREY};
+
+use super::Error;
+use crate::{cli::GenerateWorkspaceArgs, rewrite_json::set_path};
+
+const DEPENDENCY_GROUPS: &[&str] = &["dependencies", "devDependencies", "peerDependencies"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WorkspaceType {
+    App,
+    Package,
+}
+
+impl WorkspaceType {
+    fn parse(raw: &str) -> Result<Self, Error> {
+        match raw {
+            "app" => Ok(Self::App),
+            "package" => Ok(Self::Package),
+            _ => Err(Error::InvalidWorkspaceType(raw.to_string())),
+        }
+    }
+
+    // The directory that workspaces of this type usually live in
+    fn directory(&self) -> &'static str {
+        match self {
+            Self::App => "apps",
+            Self::Package => "packages",
+        }
+    }
+}
+
+pub async fn run(
+    repo_root: &AbsoluteSystemPath,
+    args: &GenerateWorkspaceArgs,
+    color_config: ColorConfig,
+) -> Result<(), Error> {
+    generate(repo_root, args, color_config, io::stdin().is_terminal()).await
+}
+
+// Prompts are only shown when `interactive`, otherwise everything comes from
+// `args`
+async fn generate(
+    repo_root: &AbsoluteSystemPath,
+    args: &GenerateWorkspaceArgs,
+    color_config: ColorConfig,
+    interactive: bool,
+) -> Result<(), Error> {
+    let root_package_json = PackageJson::load(&repo_root.join_component("package.json"))?;
+    let package_graph = PackageGraph::builder(repo_root, root_package_json)
+        .build()
+        .await?;
+
+    let workspace_type = match &args.r#type {
+        Some(raw) => WorkspaceType::parse(raw)?,
+        None if interactive => {
+            let selection = Select::new()
+                .with_prompt("What type of workspace should be added?")
+                .items(&["app", "package"])
+                .default(0)
+                .interact()?;
+            if selection == 0 {
+                WorkspaceType::App
+            } else {
+                WorkspaceType::Package
+            }
+        }
+        None => WorkspaceType::Package,
+    };
+
+    let source = match args.copy.as_deref() {
+        None => None,
+        Some("") if interactive => {
+            let workspaces = workspace_names(&package_graph);
+            let selection = FuzzySelect::new()
+                .with_prompt("Select a workspace to copy")
+                .items(&workspaces)
+                .default(0)
+                .interact()?;
+            Some(workspaces[selection].clone())
+        }
+        Some("") => return Err(Error::MissingInput("a workspace to `--copy`")),
+        Some(name) => Some(name.to_string()),
+    };
+    let source_dir = source
+        .map(|name| {
+            package_graph
+                .package_info(&PackageName::from(name.as_str()))
+                .map(|info| repo_root.resolve(info.package_path()))
+                .ok_or(Error::WorkspaceNotFound(name))
+        })
+        .transpose()?;
+
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None if interactive => Input::new()
+            .with_prompt("What is the name of the workspace?")
+            .interact_text()?,
+        None => return Err(Error::MissingInput("`--name`")),
+    };
+    if package_graph
+        .package_info(&PackageName::from(name.as_str()))
+        .is_some()
+    {
+        return Err(Error::WorkspaceExists(name));
+    }
+
+    let destination = match &args.destination {
+        Some(destination) => destination.clone(),
+        None => {
+            let default = default_destination(&package_graph, repo_root, workspace_type, &name);
+            if interactive {
+                Input::new()
+                    .with_prompt(format!("Where should \"{name}\" be added?"))
+                    .default(default)
+                    .interact_text()?
+            } else {
+                default
+            }
+        }
+    };
+    let destination = AbsoluteSystemPathBuf::from_unknown(repo_root, destination);
+    if destination.exists() {
+        return Err(Error::DestinationExists(
+            repo_root.anchor(&destination)?.to_string(),
+        ));
+    }
+
+    let dependencies = if interactive {
+        select_dependencies(&package_graph, &name, args.show_all_dependencies)?
+    } else {
+        Vec::new()
+    };
+
+    let package_json_path = destination.join_component("package.json");
+    let mut package_json = match &source_dir {
+        Some(source_dir) => {
+            turborepo_fs::recursive_copy(source_dir, &destination, true)?;
+            let contents = package_json_path.read_to_string()?;
+            set_path(&contents, &["name"], &serde_json::to_string(&name)?)?
+        }
+        None => {
+            destination.create_dir_all()?;
+            destination
+                .join_component("README.md")
+                .create_with_contents(format!("# `{name}`\n"))?;
+            empty_package_json(&name)?
+        }
+    };
+    let version = internal_dependency_version(package_graph.package_manager());
+    for (group, dependency) in &dependencies {
+        package_json = set_path(
+            &package_json,
+            &[*group, dependency.as_str()],
+            &serde_json::to_string(version)?,
+        )?;
+    }
+    package_json_path.create_with_contents(package_json)?;
+
+    let anchored_destination = repo_root.anchor(&destination)?;
+    println!(
+        "\n{} Created \"{name}\" at {anchored_destination}",
+        color!(color_config, BOLD, ">>>"),
+    );
+    let outside_workspaces = package_graph
+        .package_manager()
+        .get_workspace_globs(repo_root)
+        .is_ok_and(|globs| {
+            !globs
+                .target_is_workspace(repo_root, &destination)
+                .unwrap_or(true)
+        });
+    if outside_workspaces {
+        println!(
+            "{}",
+            color!(
+                color_config,
+                GREY,
+                "{} isn't matched by your workspace globs, so it won't be part of your monorepo \
+                 until you add it.",
+                anchored_destination
+            )
+        );
+    }
+    println!(
+        "{}",
+        color!(
+            color_config,
+            GREY,
+            "Run your package manager's install command to link the new workspace."
+        )
+    );
+
+    Ok(())
+}
+
+fn workspace_names(package_graph: &PackageGraph) -> Vec<String> {
+    let mut names = package_graph
+        .packages()
+        .filter_map(|(name, _)| match name {
+            PackageName::Root => None,
+            PackageName::Other(name) => Some(name.clone()),
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+// Picks the directory from the workspace globs that matches the workspace
+// type, e.g. `apps/*` for apps, falling back to `apps` or `packages`.
+fn default_destination(
+    package_graph: &PackageGraph,
+    repo_root: &AbsoluteSystemPath,
+    workspace_type: WorkspaceType,
+    name: &str,
+) -> String {
+    // Scoped names like `@repo/ui` go in a directory named `ui`
+    let directory_name = name.rsplit('/').next().unwrap_or(name);
+    let parent = package_graph
+        .package_manager()
+        .get_workspace_globs(repo_root)
+        .ok()
+        .and_then(|globs| {
+            globs.raw_inclusions.iter().find_map(|glob| {
+                let parent = glob.strip_suffix("/*")?;
+                (parent.split('/').next() == Some(workspace_type.directory()))
+                    .then(|| parent.to_string())
+            })
+        })
+        .unwrap_or_else(|| workspace_type.directory().to_string());
+    format!("{parent}/{directory_name}")
+}
+
+fn select_dependencies(
+    package_graph: &PackageGraph,
+    name: &str,
+    show_all_dependencies: bool,
+) -> Result<Vec<(&'static str, String)>, Error> {
+    if !Confirm::new()
+        .with_prompt(format!("Add workspace dependencies to \"{name}\"?"))
+        .default(true)
+        .interact()?
+    {
+        return Ok(Vec::new());
+    }
+
+    // Apps are rarely depended on, so unless asked to we only offer
+    // workspaces that live outside of `apps`
+    let candidates = workspace_names(package_graph)
+        .into_iter()
+        .filter(|candidate| {
+            show_all_dependencies
+                || package_graph
+                    .package_info(&PackageName::from(candidate.as_str()))
+                    .is_some_and(|info| {
+                        info.package_path().components().next().map(|c| c.as_str())
+                            != Some(WorkspaceType::App.directory())
+                    })
+        })
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let groups = MultiSelect::new()
+        .with_prompt(format!(
+            "Select all dependencies types to modify for \"{name}\""
+        ))
+        .items(DEPENDENCY_GROUPS)
+        .interact()?;
+    let mut dependencies = Vec::new();
+    for group in groups.into_iter().map(|i| DEPENDENCY_GROUPS[i]) {
+        let selected = MultiSelect::new()
+            .with_prompt(format!("Which workspaces should be added as {group}?"))
+            .items(&candidates)
+            .interact()?;
+        dependencies.extend(selected.into_iter().map(|i| (group, candidates[i].clone())));
+    }
+    Ok(dependencies)
+}
+
+fn empty_package_json(name: &str) -> Result<String, serde_json::Error> {
+    Ok(format!(
+        "{{\n  \"name\": {},\n  \"version\": \"0.0.0\",\n  \"private\": true\n}}\n",
+        serde_json::to_string(name)?
+    ))
+}
+
+// Package managers that support the workspace protocol use it so that
+// internal dependencies are never resolved from the registry
+fn internal_dependency_version(package_manager: &PackageManager) -> &'static str {
+    match package_manager {
+        PackageManager::Npm | PackageManager::Yarn => "*",
+        _ => "workspace:*",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use test_case::test_case;
+    use turbopath::AbsoluteSystemPathBuf;
+    use turborepo_repository::package_manager::PackageManager;
+    use turborepo_ui::ColorConfig;
+
+    use super::{empty_package_json, generate, internal_dependency_version, Error, WorkspaceType};
+    use crate::{cli::GenerateWorkspaceArgs, rewrite_json::set_path};
+
+    #[test_case(PackageManager::Npm, "*" ; "npm")]
+    #[test_case(PackageManager::Yarn, "*" ; "yarn")]
+    #[test_case(PackageManager::Berry, "workspace:*" ; "berry")]
+    #[test_case(PackageManager::Pnpm9, "workspace:*" ; "pnpm")]
+    #[test_case(PackageManager::Bun, "workspace:*" ; "bun")]
+    fn test_internal_dependency_version(package_manager: PackageManager, expected: &str) {
+        assert_eq!(internal_dependency_version(&package_manager), expected);
+    }
+
+    #[test]
+    fn test_empty_package_json_with_dependencies() {
+        let package_json = empty_package_json("@repo/ui").unwrap();
+        let package_json = set_path(
+            &package_json,
+            &["dependencies", "@repo/utils"],
+            "\"workspace:*\"",
+        )
+        .unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&package_json).unwrap();
+        assert_eq!(parsed["name"], "@repo/ui");
+        assert_eq!(parsed["dependencies"]["@repo/utils"], "workspace:*");
+    }
+
+    #[test]
+    fn test_workspace_type() {
+        assert_eq!(WorkspaceType::parse("app").unwrap(), WorkspaceType::App);
+        assert_eq!(
+            WorkspaceType::parse("package").unwrap().directory(),
+            "packages"
+        );
+        assert!(WorkspaceType::parse("library").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generate_copy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap();
+        repo_root
+            .join_component("package.json")
+            .create_with_contents(
+                json!({
+                    "name": "monorepo",
+                    "packageManager": "npm@10.5.0",
+                    "workspaces": ["apps/*", "packages/*"]
+                })
+                .to_string(),
+            )
+            .unwrap();
+        let ui = repo_root.join_components(&["packages", "ui"]);
+        ui.join_component("src").create_dir_all().unwrap();
+        ui.join_component("package.json")
+            .create_with_contents(
+                json!({
+                    "name": "@repo/ui",
+                    "version": "1.0.0",
+                    "scripts": { "build": "tsc" }
+                })
+                .to_string(),
+            )
+            .unwrap();
+        ui.join_components(&["src", "index.ts"])
+            .create_with_contents("export const Button = () => null;\n")
+            .unwrap();
+
+        let args = GenerateWorkspaceArgs {
+            name: Some("@repo/design".to_string()),
+            copy: Some("@repo/ui".to_string()),
+            ..Default::default()
+        };
+        generate(&repo_root, &args, ColorConfig::new(true), false)
+            .await
+            .unwrap();
+
+        // Scoped names go in a directory named after the package
+        let design = repo_root.join_components(&["packages", "design"]);
+        let package_json: serde_json::Value = serde_json::from_str(
+            &design
+                .join_component("package.json")
+                .read_to_string()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            package_json,
+            json!({
+                "name": "@repo/design",
+                "version": "1.0.0",
+                "scripts": { "build": "tsc" }
+            })
+        );
+        assert_eq!(
+            design
+                .join_components(&["src", "index.ts"])
+                .read_to_string()
+                .unwrap(),
+            "export const Button = () => null;\n"
+        );
+        // The source workspace is left alone
+        let source: serde_json::Value =
+            serde_json::from_str(&ui.join_component("package.json").read_to_string().unwrap())
+                .unwrap();
+        assert_eq!(source["name"], "@repo/ui");
+
+        // The new workspace is now part of the repo
+        let result = generate(&repo_root, &args, ColorConfig::new(true), false).await;
+        assert!(
+            matches!(result, Err(Error::WorkspaceExists(ref name)) if name == "@repo/design"),
+            "{result:?}"
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use tracing::warn;
+use turborepo_signals::{listeners::get_signal, SignalHandler};
+use turborepo_telemetry::events::command::CommandEventBuilder;
+
+use crate::{
+    cli::{self, OutputFormat},
+    commands::CommandBase,
+    run::builder::RunBuilder,
+};
+
+pub async fn run(
+    base: CommandBase,
+    telemetry: CommandEventBuilder,
+    packages: Vec<String>,
+    output: Option<OutputFormat>,
+) -> Result<i32, cli::Error> {
+    let signal = get_signal()?;
+    let handler = SignalHandler::new(signal);
+
+    let run = RunBuilder::new(base)?
+        .do_not_validate_engine()
+        .build(&handler, telemetry)
+        .await?;
+
+    if run.root_turbo_json().licenses.is_none() {
+        warn!("No license policies are configured. Add a `licenses` key to turbo.json.");
+    }
+
+    let result = run.check_licenses(&packages)?;
+    match output {
+        Some(OutputFormat::Json) => {
+            println!("{}", serde_json::to_string_pretty(&result.workspaces)?);
+            result.emit_diagnostics();
+        }
+        Some(OutputFormat::Pretty) | None => {
+            result.print(run.color_config());
+            result.emit(run.color_config());
+        }
+    }
+
+    if result.is_ok() {
+        Ok(0)
+    } else {
+        Ok(1)
+    }
+}
@@ -21,8 +21,10 @@ pub(crate) mod boundaries;
 pub(crate) mod clone;
 pub(crate) mod config;
 pub(crate) mod daemon;
+pub(crate) mod deps;
 pub(crate) mod generate;
 pub(crate) mod info;
+pub(crate) mod licenses;
 pub(crate) mod link;
 pub(crate) mod login;
 pub(crate) mod logout;
@@ -30,6 +32,7 @@ pub(crate) mod ls;
 pub(crate) mod prune;
 pub(crate) mod query;
 pub(crate) mod run;
+pub(crate) mod sbom;
 pub(crate) mod scan;
 pub(crate) mod telemetry;
 pub(crate) mod unlink;
@@ -1,24 +1,37 @@
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
-use std::sync::OnceLock;
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    str::FromStr,
+    sync::OnceLock,
+};
 
+use globwalk::ValidatedGlob;
 use lazy_static::lazy_static;
 use miette::Diagnostic;
 use tracing::trace;
 use turbopath::{
     AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf, RelativeUnixPath,
 };
+use turborepo_cache::cache_archive::CacheWriter;
+use turborepo_errors::Spanned;
+use turborepo_lockfiles::Lockfile;
 use turborepo_repository::{
     package_graph::{self, PackageGraph, PackageName, PackageNode},
     package_json::PackageJson,
+    package_manager::PackageManager,
 };
-use turborepo_telemetry::events::command::CommandEventBuilder;
+use turborepo_task_id::TaskName;
+use turborepo_telemetry::events::{command::CommandEventBuilder, EventType};
 use turborepo_ui::BOLD;
 
 use super::CommandBase;
 use crate::{
+    cli::PruneFormat,
     config::{CONFIG_FILE, CONFIG_FILE_JSONC},
-    turbo_json::RawTurboJson,
+    engine::{self, EngineBuilder},
+    turbo_json::{RawTurboJson, TurboJsonLoader, TurboJsonReader},
 };
 
 pub const DEFAULT_OUTPUT_DIR: &str = "out";
@@ -52,6 +65,13 @@ pub enum Error {
     MissingLockfile,
     #[error("Unable to read config: {0}")]
     Config(#[from] crate::config::Error),
+    #[error("Failed to write pruned tarball: {0}")]
+    Tarball(#[from] turborepo_cache::CacheError),
+    #[error("Failed to collect files for pruned tarball: {0}")]
+    Walk(#[from] globwalk::WalkError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Engine(#[from] engine::BuilderError),
 }
 
 // Files that should be copied from root and if they're required for install
@@ -97,146 +117,97 @@ fn turbo_jsonc() -> &'static AnchoredSystemPath {
 pub async fn prune(
     base: &CommandBase,
     scope: &[String],
+    tasks: &[String],
+    prod: bool,
     docker: bool,
+    dockerfile: bool,
+    format: PruneFormat,
+    lockfile_only: bool,
     output_dir: &str,
     use_gitignore: bool,
     telemetry: CommandEventBuilder,
 ) -> Result<(), Error> {
+    telemetry.track_arg_usage("task", !tasks.is_empty());
+    telemetry.track_arg_usage("prod", prod);
     telemetry.track_arg_usage("docker", docker);
+    telemetry.track_arg_usage("dockerfile", dockerfile);
+    telemetry.track_arg_usage("lockfile-only", lockfile_only);
+    telemetry.track_arg_value("format", format, EventType::NonSensitive);
     telemetry.track_arg_usage("out-dir", output_dir != DEFAULT_OUTPUT_DIR);
 
-    let prune = Prune::new(base, scope, docker, output_dir, use_gitignore, telemetry).await?;
-
-    println!(
-        "Generating pruned monorepo for {} in {}",
-        base.color_config.apply(BOLD.apply_to(scope.join(", "))),
-        base.color_config.apply(BOLD.apply_to(&prune.out_directory)),
-    );
-
-    if let Some(workspace_config_path) = prune
-        .package_graph
-        .package_manager()
-        .workspace_configuration_path()
-    {
-        prune.copy_file(
-            &AnchoredSystemPathBuf::from_raw(workspace_config_path)?,
-            Some(CopyDestination::All),
-        )?;
-    }
+    // The generated Dockerfile relies on the `json` and `full` split
+    let docker = docker || dockerfile;
 
-    let mut workspace_paths = Vec::new();
-    let mut workspace_names = Vec::new();
-    let workspaces = prune.internal_dependencies();
-    let lockfile_keys: Vec<_> = prune
-        .package_graph
-        .transitive_external_dependencies(workspaces.iter())
-        .into_iter()
-        .map(|pkg| pkg.key.clone())
-        .collect();
-    for workspace in workspaces {
-        let entry = prune
-            .package_graph
-            .package_info(&workspace)
-            .ok_or_else(|| Error::MissingWorkspace(workspace.clone()))?;
-
-        // We don't want to do any copying for the root workspace
-        if let PackageName::Other(workspace) = workspace {
-            prune.copy_workspace(entry.package_json_path())?;
-            workspace_paths.push(
-                entry
-                    .package_json_path()
-                    .parent()
-                    .unwrap()
-                    .to_unix()
-                    .to_string(),
-            );
-
-            println!(" - Added {workspace}");
-            workspace_names.push(workspace);
-        }
-    }
-    trace!("new workspaces: {}", workspace_paths.join(", "));
-    trace!("lockfile keys: {}", lockfile_keys.join(", "));
-
-    let lockfile = prune
-        .package_graph
-        .lockfile()
-        .expect("Lockfile presence already checked")
-        .subgraph(&workspace_paths, &lockfile_keys)?;
-
-    let lockfile_contents = lockfile.encode()?;
-    let lockfile_name = prune.package_graph.package_manager().lockfile_name();
-    let lockfile_path = prune.out_directory.join_component(lockfile_name);
-    lockfile_path.create_with_contents(&lockfile_contents)?;
-    if prune.docker {
-        prune
-            .docker_directory()
-            .join_component(lockfile_name)
-            .create_with_contents(&lockfile_contents)?;
-    }
-
-    for (relative_path, required_for_install) in ADDITIONAL_FILES.as_slice() {
-        let path = relative_path.to_anchored_system_path_buf();
-        prune.copy_file(&path, *required_for_install)?;
-    }
-
-    for (relative_path, required_for_install) in ADDITIONAL_DIRECTORIES.as_slice() {
-        let path = relative_path.to_anchored_system_path_buf();
-        prune.copy_directory(&path, *required_for_install)?;
+    if lockfile_only {
+        let out_directory = AbsoluteSystemPathBuf::from_unknown(&base.repo_root, output_dir);
+        let prune = Prune::new(
+            base,
+            scope,
+            tasks,
+            prod,
+            false,
+            out_directory,
+            use_gitignore,
+            false,
+            telemetry,
+        )
+        .await?;
+        let workspaces = prune.internal_dependencies();
+        let workspace_paths = prune.workspace_paths(&workspaces)?;
+        let lockfile = prune.pruned_lockfile(&workspaces, &workspace_paths)?;
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(&lockfile.encode()?)?;
+        stdout.flush()?;
+        return Ok(());
     }
 
-    prune.copy_turbo_json(&workspace_names)?;
-
-    let original_patches = prune
-        .package_graph
-        .lockfile()
-        .expect("lockfile presence checked earlier")
-        .patches()?;
-    if !original_patches.is_empty() {
-        let pruned_patches = lockfile.patches()?;
-        trace!(
-            "original patches: {:?}, pruned patches: {:?}",
-            original_patches,
-            pruned_patches
-        );
-
-        let repo_root = &prune.root;
-        let package_manager = prune.package_graph.package_manager();
-
-        let pruned_json = package_manager.prune_patched_packages(
-            prune.package_graph.root_package_json(),
-            &pruned_patches,
-            repo_root,
-        );
-        let mut pruned_json_contents = serde_json::to_string_pretty(&pruned_json)?;
-        // Add trailing newline to match Go behavior
-        pruned_json_contents.push('\n');
-
-        let original = prune.root.resolve(package_json());
-        let permissions = original.symlink_metadata()?.permissions();
-        let new_package_json_path = prune.full_directory.resolve(package_json());
-        new_package_json_path.create_with_contents(&pruned_json_contents)?;
-        #[cfg(unix)]
-        new_package_json_path.set_mode(permissions.mode())?;
-        #[cfg(windows)]
-        if permissions.readonly() {
-            new_package_json_path.set_readonly()?
-        }
-        if prune.docker {
-            turborepo_fs::copy_file(
-                new_package_json_path,
-                prune.docker_directory().resolve(package_json()),
-            )?;
+    match format {
+        PruneFormat::Dir => {
+            let out_directory = AbsoluteSystemPathBuf::from_unknown(&base.repo_root, output_dir);
+            let prune = Prune::new(
+                base,
+                scope,
+                tasks,
+                prod,
+                docker,
+                out_directory,
+                use_gitignore,
+                false,
+                telemetry,
+            )
+            .await?;
+            println!(
+                "Generating pruned monorepo for {} in {}",
+                base.color_config.apply(BOLD.apply_to(scope.join(", "))),
+                base.color_config.apply(BOLD.apply_to(&prune.out_directory)),
+            );
+            prune.write_output(dockerfile)?;
         }
-
-        for patch in pruned_patches {
-            prune.copy_file(
-                &patch.to_anchored_system_path_buf(),
-                Some(CopyDestination::Docker),
-            )?;
+        PruneFormat::Tar => {
+            // Stage the pruned monorepo in a temporary directory so the tarball can
+            // be streamed without touching <OUT-DIR>
+            let staging_directory = tempfile::tempdir()?;
+            let out_directory = AbsoluteSystemPathBuf::try_from(staging_directory.path())?;
+            let prune = Prune::new(
+                base,
+                scope,
+                tasks,
+                prod,
+                docker,
+                out_directory,
+                use_gitignore,
+                true,
+                telemetry,
+            )
+            .await?;
+            // stdout is reserved for the tarball
+            eprintln!(
+                "Generating pruned monorepo tarball for {}",
+                base.color_config.apply(BOLD.apply_to(scope.join(", "))),
+            );
+            prune.write_output(dockerfile)?;
+            prune.write_tarball(io::stdout().lock())?;
         }
-    } else {
-        prune.copy_file(package_json(), Some(CopyDestination::Docker))?;
     }
 
     Ok(())
@@ -249,7 +220,13 @@ struct Prune<'a> {
     full_directory: AbsoluteSystemPathBuf,
     docker: bool,
     scope: &'a [String],
+    // Only follow `dependencies` and `optionalDependencies` between workspaces
+    prod: bool,
+    // Packages with at least one task in the task graph of the requested tasks
+    task_packages: Option<HashSet<PackageName>>,
     use_gitignore: bool,
+    // When the pruned output is streamed, stdout is reserved for it
+    stream_output: bool,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -265,9 +242,12 @@ impl<'a> Prune<'a> {
     async fn new(
         base: &CommandBase,
         scope: &'a [String],
+        tasks: &[String],
+        prod: bool,
         docker: bool,
-        output_dir: &str,
+        out_directory: AbsoluteSystemPathBuf,
         use_gitignore: bool,
+        stream_output: bool,
         telemetry: CommandEventBuilder,
     ) -> Result<Self, Error> {
         let allow_missing_package_manager = base.opts().repo_opts.allow_no_package_manager;
@@ -288,8 +268,6 @@ impl<'a> Prune<'a> {
             .build()
             .await?;
 
-        let out_directory = AbsoluteSystemPathBuf::from_unknown(&base.repo_root, output_dir);
-
         let full_directory = match docker {
             true => out_directory.join_component("full"),
             false => out_directory.clone(),
@@ -323,13 +301,11 @@ impl<'a> Prune<'a> {
             return Err(Error::MissingLockfile);
         }
 
-        full_directory.resolve(package_json()).ensure_dir()?;
-        if docker {
-            out_directory
-                .join_component("json")
-                .resolve(package_json())
-                .ensure_dir()?;
-        }
+        let task_packages = if tasks.is_empty() {
+            None
+        } else {
+            Some(Self::task_packages(base, &package_graph, scope, tasks)?)
+        };
 
         Ok(Self {
             package_graph,
@@ -338,14 +314,273 @@ impl<'a> Prune<'a> {
             full_directory,
             docker,
             scope,
+            prod,
+            task_packages,
             use_gitignore,
+            stream_output,
         })
     }
 
+    // Builds the task graph for `tasks` in the targeted workspaces and returns
+    // every package that has a task in it.
+    fn task_packages(
+        base: &CommandBase,
+        package_graph: &PackageGraph,
+        scope: &[String],
+        tasks: &[String],
+    ) -> Result<HashSet<PackageName>, Error> {
+        let opts = base.opts();
+        let reader =
+            TurboJsonReader::new(base.repo_root.clone()).with_future_flags(opts.future_flags);
+        let turbo_json_loader = TurboJsonLoader::workspace(
+            reader,
+            opts.repo_opts.root_turbo_json_path.clone(),
+            package_graph.packages(),
+        );
+        let root_turbo_json = turbo_json_loader.load(&PackageName::Root)?;
+
+        let engine = EngineBuilder::new(&base.repo_root, package_graph, &turbo_json_loader, false)
+            .with_root_tasks(root_turbo_json.tasks.keys().cloned())
+            .with_workspaces(
+                scope
+                    .iter()
+                    .map(|target| PackageName::Other(target.clone()))
+                    .collect(),
+            )
+            .with_tasks(
+                tasks
+                    .iter()
+                    .map(|task| Spanned::new(TaskName::from(task.as_str()).into_owned())),
+            )
+            .build()?;
+
+        let packages = engine
+            .task_ids()
+            .map(|task_id| PackageName::from(task_id.package()))
+            .collect::<HashSet<_>>();
+        trace!("task graph packages: {:?}", packages);
+        Ok(packages)
+    }
+
+    fn write_output(&self, dockerfile: bool) -> Result<(), Error> {
+        self.full_directory.resolve(package_json()).ensure_dir()?;
+        if self.docker {
+            self.docker_directory()
+                .resolve(package_json())
+                .ensure_dir()?;
+        }
+
+        if let Some(workspace_config_path) = self
+            .package_graph
+            .package_manager()
+            .workspace_configuration_path()
+        {
+            self.copy_file(
+                &AnchoredSystemPathBuf::from_raw(workspace_config_path)?,
+                Some(CopyDestination::All),
+            )?;
+        }
+
+        let mut workspace_names = Vec::new();
+        let workspaces = self.internal_dependencies();
+        for workspace in &workspaces {
+            let entry = self
+                .package_graph
+                .package_info(workspace)
+                .ok_or_else(|| Error::MissingWorkspace(workspace.clone()))?;
+
+            // We don't want to do any copying for the root workspace
+            if let PackageName::Other(workspace) = workspace {
+                self.copy_workspace(entry.package_json_path())?;
+                self.print_progress(format_args!(" - Added {workspace}"));
+                workspace_names.push(workspace.clone());
+            }
+        }
+        let workspace_paths = self.workspace_paths(&workspaces)?;
+        trace!("new workspaces: {}", workspace_paths.join(", "));
+
+        let lockfile = self.pruned_lockfile(&workspaces, &workspace_paths)?;
+
+        let lockfile_contents = lockfile.encode()?;
+        let lockfile_name = self.package_graph.package_manager().lockfile_name();
+        let lockfile_path = self.out_directory.join_component(lockfile_name);
+        lockfile_path.create_with_contents(&lockfile_contents)?;
+        if self.docker {
+            self.docker_directory()
+                .join_component(lockfile_name)
+                .create_with_contents(&lockfile_contents)?;
+        }
+
+        for (relative_path, required_for_install) in ADDITIONAL_FILES.as_slice() {
+            let path = relative_path.to_anchored_system_path_buf();
+            self.copy_file(&path, *required_for_install)?;
+        }
+
+        for (relative_path, required_for_install) in ADDITIONAL_DIRECTORIES.as_slice() {
+            let path = relative_path.to_anchored_system_path_buf();
+            self.copy_directory(&path, *required_for_install)?;
+        }
+
+        self.copy_turbo_json(&workspace_names)?;
+
+        let original_patches = self
+            .package_graph
+            .lockfile()
+            .expect("lockfile presence checked earlier")
+            .patches()?;
+        if !original_patches.is_empty() {
+            let pruned_patches = lockfile.patches()?;
+            trace!(
+                "original patches: {:?}, pruned patches: {:?}",
+                original_patches,
+                pruned_patches
+            );
+
+            let repo_root = &self.root;
+            let package_manager = self.package_graph.package_manager();
+
+            let pruned_json = package_manager.prune_patched_packages(
+                self.package_graph.root_package_json(),
+                &pruned_patches,
+                repo_root,
+            );
+            let mut pruned_json_contents = serde_json::to_string_pretty(&pruned_json)?;
+            // Add trailing newline to match Go behavior
+            pruned_json_contents.push('\n');
+
+            let original = self.root.resolve(package_json());
+            let permissions = original.symlink_metadata()?.permissions();
+            let new_package_json_path = self.full_directory.resolve(package_json());
+            new_package_json_path.create_with_contents(&pruned_json_contents)?;
+            #[cfg(unix)]
+            new_package_json_path.set_mode(permissions.mode())?;
+            #[cfg(windows)]
+            if permissions.readonly() {
+                new_package_json_path.set_readonly()?
+            }
+            if self.docker {
+                turborepo_fs::copy_file(
+                    new_package_json_path,
+                    self.docker_directory().resolve(package_json()),
+                )?;
+            }
+
+            for patch in pruned_patches {
+                self.copy_file(
+                    &patch.to_anchored_system_path_buf(),
+                    Some(CopyDestination::Docker),
+                )?;
+            }
+        } else {
+            self.copy_file(package_json(), Some(CopyDestination::Docker))?;
+        }
+
+        if dockerfile {
+            self.write_dockerfile()?;
+        }
+
+        Ok(())
+    }
+
+    fn print_progress(&self, message: impl std::fmt::Display) {
+        if self.stream_output {
+            eprintln!("{message}");
+        } else {
+            println!("{message}");
+        }
+    }
+
     fn docker_directory(&self) -> AbsoluteSystemPathBuf {
         self.out_directory.join_component("json")
     }
 
+    fn workspace_paths(&self, workspaces: &[PackageName]) -> Result<Vec<String>, Error> {
+        workspaces
+            .iter()
+            .filter(|workspace| matches!(workspace, PackageName::Other(_)))
+            .map(|workspace| {
+                let entry = self
+                    .package_graph
+                    .package_info(workspace)
+                    .ok_or_else(|| Error::MissingWorkspace(workspace.clone()))?;
+                Ok(entry
+                    .package_json_path()
+                    .parent()
+                    .unwrap()
+                    .to_unix()
+                    .to_string())
+            })
+            .collect()
+    }
+
+    fn pruned_lockfile(
+        &self,
+        workspaces: &[PackageName],
+        workspace_paths: &[String],
+    ) -> Result<Box<dyn Lockfile>, Error> {
+        let lockfile_keys: Vec<_> = self
+            .package_graph
+            .transitive_external_dependencies(workspaces.iter())
+            .into_iter()
+            .map(|pkg| pkg.key.clone())
+            .collect();
+        trace!("lockfile keys: {}", lockfile_keys.join(", "));
+
+        Ok(self
+            .package_graph
+            .lockfile()
+            .expect("Lockfile presence already checked")
+            .subgraph(workspace_paths, &lockfile_keys)?)
+    }
+
+    fn write_dockerfile(&self) -> Result<(), Error> {
+        let target_paths = self
+            .scope
+            .iter()
+            .map(|target| {
+                let workspace = PackageName::Other(target.clone());
+                self.workspace_paths(std::slice::from_ref(&workspace))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        let contents = dockerfile(
+            self.package_graph.package_manager(),
+            self.scope,
+            &target_paths,
+        );
+        self.out_directory
+            .join_component("Dockerfile")
+            .create_with_contents(contents)?;
+        Ok(())
+    }
+
+    // Writes the pruned output as an uncompressed tarball. Entries are sorted and
+    // `CacheWriter` normalizes ownership and timestamps, so identical inputs
+    // produce byte-for-byte identical tarballs.
+    fn write_tarball(&self, writer: impl Write) -> Result<(), Error> {
+        let all_files = ValidatedGlob::from_str("**").expect("`**` is a valid glob");
+        let files = globwalk::globwalk(
+            &self.out_directory,
+            &[all_files],
+            &[],
+            globwalk::WalkType::All,
+        )?;
+        let mut relative_paths = files
+            .into_iter()
+            .filter(|path| path != &self.out_directory)
+            .map(|path| AnchoredSystemPathBuf::relative_path_between(&self.out_directory, &path))
+            .collect::<Vec<_>>();
+        relative_paths.sort();
+
+        let mut archive = CacheWriter::from_writer(writer, false)?;
+        for path in &relative_paths {
+            archive.add_file(&self.out_directory, path)?;
+        }
+        archive.finish()?;
+
+        Ok(())
+    }
+
     fn copy_file(
         &self,
         path: &AnchoredSystemPath,
@@ -434,13 +669,29 @@ impl<'a> Prune<'a> {
                     .map(|workspace| PackageNode::Workspace(PackageName::Other(workspace.clone()))),
             )
             .collect::<Vec<_>>();
-        let nodes = self.package_graph.transitive_closure(workspaces.iter());
+        let nodes = match self.prod {
+            true => prod_transitive_closure(&self.package_graph, &workspaces),
+            false => self
+                .package_graph
+                .transitive_closure(workspaces.iter())
+                .into_iter()
+                .cloned()
+                .collect(),
+        };
 
         let mut names: Vec<_> = nodes
             .into_iter()
             .filter_map(|node| match node {
                 PackageNode::Root => None,
-                PackageNode::Workspace(workspace) => Some(workspace.clone()),
+                PackageNode::Workspace(workspace) => Some(workspace),
+            })
+            .filter(|workspace| {
+                let Some(task_packages) = &self.task_packages else {
+                    return true;
+                };
+                // The root and the targets are always kept, even without a task
+                task_packages.contains(workspace)
+                    || workspaces.contains(&PackageNode::Workspace(workspace.clone()))
             })
             .collect();
         names.sort();
@@ -477,3 +728,248 @@ impl<'a> Prune<'a> {
         Ok(Some((turbo_json, turbo_json_name)))
     }
 }
+
+// Like `PackageGraph::transitive_closure`, but only follows edges that come
+// from `dependencies` or `optionalDependencies`.
+fn prod_transitive_closure(
+    package_graph: &PackageGraph,
+    workspaces: &[PackageNode],
+) -> HashSet<PackageNode> {
+    let mut visited = HashSet::new();
+    let mut stack = workspaces.to_vec();
+    while let Some(node) = stack.pop() {
+        if visited.contains(&node) {
+            continue;
+        }
+        if let PackageNode::Workspace(workspace) = &node {
+            stack.extend(
+                package_graph
+                    .immediate_prod_dependencies(workspace)
+                    .into_iter()
+                    .flatten()
+                    .cloned(),
+            );
+        }
+        visited.insert(node);
+    }
+    visited
+}
+
+// Renders a multi-stage Dockerfile meant to be built from the root of the
+// pruned output directory.
+fn dockerfile(
+    package_manager: &PackageManager,
+    targets: &[String],
+    target_paths: &[String],
+) -> String {
+    let (base_image, setup, install, exec) = match package_manager {
+        PackageManager::Npm => ("node:lts-alpine", None, "npm ci", "npx"),
+        PackageManager::Pnpm | PackageManager::Pnpm6 | PackageManager::Pnpm9 => (
+            "node:lts-alpine",
+            Some("corepack enable"),
+            "pnpm install --frozen-lockfile",
+            "pnpm exec",
+        ),
+        PackageManager::Yarn => (
+            "node:lts-alpine",
+            None,
+            "yarn install --frozen-lockfile",
+            "yarn",
+        ),
+        PackageManager::Berry => (
+            "node:lts-alpine",
+            Some("corepack enable"),
+            "yarn install --immutable",
+            "yarn",
+        ),
+        PackageManager::Bun => (
+            "oven/bun:1-alpine",
+            None,
+            "bun install --frozen-lockfile",
+            "bunx",
+        ),
+        PackageManager::Deno => (
+            "denoland/deno:alpine",
+            None,
+            "deno install --frozen",
+            "deno run -A npm:turbo",
+        ),
+    };
+    let filters = targets
+        .iter()
+        .map(|target| format!("--filter={target}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut lines = vec![
+        format!(
+            "# Generated by `turbo prune {} --dockerfile`",
+            targets.join(" ")
+        ),
+        format!("FROM {base_image} AS base"),
+        "RUN apk update && apk add --no-cache libc6-compat".to_string(),
+    ];
+    if let Some(setup) = setup {
+        lines.push(format!("RUN {setup}"));
+    }
+    lines.extend([
+        String::new(),
+        "FROM base AS installer".to_string(),
+        "WORKDIR /app".to_string(),
+        "COPY json/ .".to_string(),
+        format!("RUN {install}"),
+        String::new(),
+        "FROM installer AS builder".to_string(),
+        "COPY full/ .".to_string(),
+        format!("RUN {exec} turbo run build {filters}"),
+        String::new(),
+        "FROM base AS runner".to_string(),
+        "WORKDIR /app".to_string(),
+        "COPY --from=builder /app .".to_string(),
+    ]);
+    // With a single target we know which workspace the image is for
+    if let [target_path] = target_paths {
+        lines.push(format!("WORKDIR /app/{target_path}"));
+        lines.push(format!(
+            "CMD [\"{}\", \"run\", \"start\"]",
+            package_manager.command()
+        ));
+    }
+    lines.push(String::new());
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, HashMap, HashSet};
+
+    use test_case::test_case;
+    use turbopath::AbsoluteSystemPathBuf;
+    use turborepo_errors::Spanned;
+    use turborepo_repository::{
+        discovery::{DiscoveryResponse, Error, PackageDiscovery},
+        package_graph::{PackageGraph, PackageName, PackageNode},
+        package_json::PackageJson,
+        package_manager::PackageManager,
+    };
+
+    use super::{dockerfile, prod_transitive_closure};
+
+    struct MockDiscovery;
+    impl PackageDiscovery for MockDiscovery {
+        async fn discover_packages(&self) -> Result<DiscoveryResponse, Error> {
+            Ok(DiscoveryResponse {
+                package_manager: PackageManager::Npm,
+                workspaces: vec![],
+            })
+        }
+
+        async fn discover_packages_blocking(&self) -> Result<DiscoveryResponse, Error> {
+            self.discover_packages().await
+        }
+    }
+
+    fn dependencies(dependencies: &[(&str, &str)]) -> Option<BTreeMap<String, String>> {
+        Some(
+            dependencies
+                .iter()
+                .map(|(name, version)| (name.to_string(), version.to_string()))
+                .collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_prod_transitive_closure() {
+        let root =
+            AbsoluteSystemPathBuf::new(if cfg!(windows) { r"C:\repo" } else { "/repo" }).unwrap();
+        let package_jsons = [
+            (
+                "web",
+                PackageJson {
+                    // Aliased, so the key doesn't match the workspace name
+                    dependencies: dependencies(&[("@acme/ui", "workspace:ui@*")]),
+                    dev_dependencies: dependencies(&[("tsconfig", "workspace:*")]),
+                    optional_dependencies: dependencies(&[("logger", "*")]),
+                    ..Default::default()
+                },
+            ),
+            (
+                "ui",
+                PackageJson {
+                    dependencies: dependencies(&[("utils", "workspace:*")]),
+                    dev_dependencies: dependencies(&[("eslint-config", "*")]),
+                    ..Default::default()
+                },
+            ),
+            ("utils", PackageJson::default()),
+            ("logger", PackageJson::default()),
+            ("tsconfig", PackageJson::default()),
+            ("eslint-config", PackageJson::default()),
+        ]
+        .into_iter()
+        .map(|(name, package_json)| {
+            (
+                root.join_components(&["packages", name, "package.json"]),
+                PackageJson {
+                    name: Some(Spanned::new(name.to_string())),
+                    version: Some("1.0.0".to_string()),
+                    ..package_json
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+        let package_graph = PackageGraph::builder(&root, PackageJson::default())
+            .with_package_discovery(MockDiscovery)
+            .with_package_jsons(Some(package_jsons))
+            .build()
+            .await
+            .unwrap();
+
+        let workspace = |name: &str| PackageNode::Workspace(PackageName::from(name));
+        let closure = prod_transitive_closure(
+            &package_graph,
+            &[PackageNode::Workspace(PackageName::Root), workspace("web")],
+        );
+        assert_eq!(
+            closure,
+            HashSet::from([
+                PackageNode::Workspace(PackageName::Root),
+                workspace("web"),
+                workspace("ui"),
+                workspace("utils"),
+                workspace("logger"),
+            ])
+        );
+    }
+
+    #[test_case(PackageManager::Npm, "RUN npm ci", "RUN npx turbo run build --filter=web" ; "npm")]
+    #[test_case(PackageManager::Pnpm9, "RUN pnpm install --frozen-lockfile", "RUN pnpm exec turbo run build --filter=web" ; "pnpm")]
+    #[test_case(PackageManager::Berry, "RUN yarn install --immutable", "RUN yarn turbo run build --filter=web" ; "berry")]
+    #[test_case(PackageManager::Bun, "RUN bun install --frozen-lockfile", "RUN bunx turbo run build --filter=web" ; "bun")]
+    #[test_case(PackageManager::Deno, "RUN deno install --frozen", "RUN deno run -A npm:turbo run build --filter=web" ; "deno")]
+    fn test_dockerfile_for_package_manager(
+        package_manager: PackageManager,
+        install: &str,
+        build: &str,
+    ) {
+        let contents = dockerfile(&package_manager, &["web".into()], &["apps/web".into()]);
+        assert!(contents.contains(install), "{contents}");
+        assert!(contents.contains(build), "{contents}");
+        assert!(contents.contains("WORKDIR /app/apps/web"), "{contents}");
+    }
+
+    #[test]
+    fn test_dockerfile_multiple_targets() {
+        let contents = dockerfile(
+            &PackageManager::Npm,
+            &["web".into(), "docs".into()],
+            &["apps/web".into(), "apps/docs".into()],
+        );
+        assert!(
+            contents.contains("--filter=web --filter=docs"),
+            "{contents}"
+        );
+        assert!(!contents.contains("CMD"), "{contents}");
+    }
+}
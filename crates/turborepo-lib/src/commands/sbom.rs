@@ -0,0 +1,29 @@
+use turborepo_signals::{listeners::get_signal, SignalHandler};
+use turborepo_telemetry::events::command::CommandEventBuilder;
+
+use crate::{
+    cli::{self, SbomFormat},
+    commands::CommandBase,
+    run::builder::RunBuilder,
+};
+
+pub async fn run(
+    base: CommandBase,
+    telemetry: CommandEventBuilder,
+    package: &str,
+    format: SbomFormat,
+) -> Result<i32, cli::Error> {
+    let signal = get_signal()?;
+    let handler = SignalHandler::new(signal);
+    let version = base.version();
+
+    let run = RunBuilder::new(base)?
+        .do_not_validate_engine()
+        .build(&handler, telemetry)
+        .await?;
+
+    let sbom = run.sbom(package)?;
+    println!("{}", sbom.render(format, version)?);
+
+    Ok(0)
+}
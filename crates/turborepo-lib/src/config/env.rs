@@ -9,7 +9,7 @@ use tracing::warn;
 use turbopath::AbsoluteSystemPathBuf;
 use turborepo_cache::CacheConfig;
 
-use super::{ConfigurationOptions, Error, ResolvedConfigurationOptions};
+use super::{AnalyticsSinkKind, ConfigurationOptions, Error, ResolvedConfigurationOptions};
 use crate::{
     cli::{EnvMode, LogOrder},
     turbo_json::UIMode,
@@ -46,6 +46,17 @@ const TURBO_MAPPING: &[(&str, &str)] = [
     ("turbo_concurrency", "concurrency"),
     ("turbo_no_update_notifier", "no_update_notifier"),
     ("turbo_sso_login_callback_port", "sso_login_callback_port"),
+    ("turbo_otel_enabled", "otel_enabled"),
+    ("turbo_otel_endpoint", "otel_endpoint"),
+    ("turbo_otel_headers", "otel_headers"),
+    ("turbo_otel_service_name", "otel_service_name"),
+    ("turbo_analytics_sink", "analytics_sink"),
+    ("turbo_analytics_file", "analytics_file"),
+    ("turbo_analytics_url", "analytics_url"),
+    ("turbo_analytics_auth_header", "analytics_auth_header"),
+    ("turbo_run_history", "run_history"),
+    ("turbo_run_history_max_runs", "run_history_max_runs"),
+    ("turbo_run_history_max_age_days", "run_history_max_age_days"),
 ]
 .as_slice();
 
@@ -160,6 +171,22 @@ impl ResolvedConfigurationOptions for EnvVars {
             .transpose()
             .map_err(Error::InvalidTuiScrollbackLength)?;
 
+        let run_history_max_runs = self
+            .output_map
+            .get("run_history_max_runs")
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(Error::InvalidRunHistoryMaxRuns)?;
+
+        let run_history_max_age_days = self
+            .output_map
+            .get("run_history_max_age_days")
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(Error::InvalidRunHistoryMaxAgeDays)?;
+
         // Process ui
         let ui =
             self.truthy_value("ui")
@@ -170,6 +197,10 @@ impl ResolvedConfigurationOptions for EnvVars {
 
         let no_update_notifier = self.truthy_value("no_update_notifier").flatten();
 
+        let otel_enabled = self.truthy_value("otel_enabled").flatten();
+
+        let run_history = self.truthy_value("run_history").flatten();
+
         // Process daemon
         let daemon = self.truthy_value("daemon").flatten();
 
@@ -207,6 +238,22 @@ impl ResolvedConfigurationOptions for EnvVars {
                 )
             })?;
 
+        let analytics_sink = self
+            .output_map
+            .get("analytics_sink")
+            .filter(|s| !s.is_empty())
+            .map(|s| AnalyticsSinkKind::from_str(s, true))
+            .transpose()
+            .map_err(|_| {
+                Error::InvalidAnalyticsSink(
+                    AnalyticsSinkKind::value_variants()
+                        .iter()
+                        .filter_map(|v| v.to_possible_value())
+                        .map(|v| v.get_name().to_string())
+                        .join(", "),
+                )
+            })?;
+
         let concurrency = self
             .output_map
             .get("concurrency")
@@ -229,6 +276,12 @@ impl ResolvedConfigurationOptions for EnvVars {
             token: self.output_map.get("token").cloned(),
             scm_base: self.output_map.get("scm_base").cloned(),
             scm_head: self.output_map.get("scm_head").cloned(),
+            otel_endpoint: self.output_map.get("otel_endpoint").cloned(),
+            otel_headers: self.output_map.get("otel_headers").cloned(),
+            otel_service_name: self.output_map.get("otel_service_name").cloned(),
+            analytics_file: self.output_map.get("analytics_file").cloned(),
+            analytics_url: self.output_map.get("analytics_url").cloned(),
+            analytics_auth_header: self.output_map.get("analytics_auth_header").cloned(),
             concurrency,
             cache,
             // Processed booleans
@@ -244,16 +297,21 @@ impl ResolvedConfigurationOptions for EnvVars {
             run_summary,
             allow_no_turbo_json,
             no_update_notifier,
+            otel_enabled,
+            run_history,
 
             // Processed numbers
             timeout,
             upload_timeout,
             tui_scrollback_length,
+            run_history_max_runs,
+            run_history_max_age_days,
 
             env_mode,
             cache_dir,
             root_turbo_json_path,
             log_order,
+            analytics_sink,
             sso_login_callback_port,
             // Do not allow future flags to be set by env var
             future_flags: None,
@@ -352,6 +410,18 @@ mod test {
         env.insert("turbo_tui_scrollback_length".into(), "2048".into());
         env.insert("turbo_concurrency".into(), "50%".into());
         env.insert("turbo_sso_login_callback_port".into(), "3000".into());
+        env.insert("turbo_otel_endpoint".into(), "http://localhost:4318".into());
+        env.insert("turbo_otel_headers".into(), "x-api-key=secret".into());
+        env.insert("turbo_otel_service_name".into(), "my-monorepo".into());
+        env.insert("turbo_analytics_sink".into(), "webhook".into());
+        env.insert(
+            "turbo_analytics_url".into(),
+            "https://example.com/events".into(),
+        );
+        env.insert("turbo_analytics_auth_header".into(), "Bearer secret".into());
+        env.insert("turbo_run_history".into(), "false".into());
+        env.insert("turbo_run_history_max_runs".into(), "20".into());
+        env.insert("turbo_run_history_max_age_days".into(), "7".into());
 
         let config = EnvVars::new(&env)
             .unwrap()
@@ -382,6 +452,15 @@ mod test {
             Some(AbsoluteSystemPathBuf::new(root_turbo_json).unwrap())
         );
         assert_eq!(config.concurrency, Some("50%".to_owned()));
+        assert_eq!(config.otel_endpoint(), Some("http://localhost:4318"));
+        assert_eq!(config.otel_headers(), Some("x-api-key=secret"));
+        assert_eq!(config.otel_service_name(), "my-monorepo");
+        assert_eq!(config.analytics_sink(), AnalyticsSinkKind::Webhook);
+        assert_eq!(config.analytics_url(), Some("https://example.com/events"));
+        assert_eq!(config.analytics_auth_header(), Some("Bearer secret"));
+        assert!(!config.run_history());
+        assert_eq!(config.run_history_max_runs(), 20);
+        assert_eq!(config.run_history_max_age_days(), 7);
     }
 
     #[test]
@@ -408,6 +487,14 @@ mod test {
         env.insert("turbo_tui_scrollback_length".into(), "".into());
         env.insert("turbo_concurrency".into(), "".into());
         env.insert("turbo_sso_login_callback_port".into(), "".into());
+        env.insert("turbo_otel_enabled".into(), "".into());
+        env.insert("turbo_otel_endpoint".into(), "".into());
+        env.insert("turbo_otel_service_name".into(), "".into());
+        env.insert("turbo_analytics_sink".into(), "".into());
+        env.insert("turbo_analytics_file".into(), "".into());
+        env.insert("turbo_run_history".into(), "".into());
+        env.insert("turbo_run_history_max_runs".into(), "".into());
+        env.insert("turbo_run_history_max_age_days".into(), "".into());
 
         let config = EnvVars::new(&env)
             .unwrap()
@@ -437,5 +524,12 @@ mod test {
         );
         assert_eq!(config.concurrency, None);
         assert_eq!(config.sso_login_callback_port(), None);
+        assert_eq!(config.otel_endpoint(), None);
+        assert_eq!(config.otel_service_name(), "turborepo");
+        assert_eq!(config.analytics_sink(), AnalyticsSinkKind::Vercel);
+        assert!(config.run_history());
+        assert_eq!(config.run_history_max_runs(), 100);
+        assert_eq!(config.run_history_max_age_days(), 30);
+        assert_eq!(config.analytics_file(), None);
     }
 }
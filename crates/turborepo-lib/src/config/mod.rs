@@ -6,6 +6,7 @@ mod turbo_json;
 use std::{collections::HashMap, ffi::OsString, io};
 
 use camino::{Utf8Path, Utf8PathBuf};
+use clap::ValueEnum;
 use convert_case::{Case, Casing};
 use derive_setters::Setters;
 use env::EnvVars;
@@ -170,6 +171,24 @@ pub enum Error {
         #[source_code]
         text: NamedSource<String>,
     },
+    #[error(
+        "Invalid cache actions `{actions}` in `cacheMode`. Expected `r`, `w`, `rw` or an empty \
+         string."
+    )]
+    InvalidTaskCacheMode {
+        actions: String,
+        #[label("invalid cache actions")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+    #[error("`when` in `cacheMode` must specify an `env` variable.")]
+    MissingCacheModeCondition {
+        #[label("condition defined here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
     #[error("Found `pipeline` field instead of `tasks`.")]
     #[diagnostic(help("Changed in 2.0: `pipeline` has been renamed to `tasks`."))]
     PipelineField {
@@ -194,6 +213,8 @@ pub enum Error {
     InvalidPreflight,
     #[error("TURBO_LOG_ORDER should be one of: {0}")]
     InvalidLogOrder(String),
+    #[error("TURBO_ANALYTICS_SINK should be one of: {0}")]
+    InvalidAnalyticsSink(String),
     #[error(transparent)]
     #[diagnostic(transparent)]
     TurboJsonParseError(#[from] crate::turbo_json::parser::Error),
@@ -245,6 +266,15 @@ pub enum Error {
     InvalidTuiScrollbackLength(#[source] std::num::ParseIntError),
     #[error("TURBO_SSO_LOGIN_CALLBACK_PORT: Invalid value. Use a number for the callback port.")]
     InvalidSsoLoginCallbackPort(#[source] std::num::ParseIntError),
+    #[error(
+        "TURBO_RUN_HISTORY_MAX_RUNS: Invalid value. Use a number for the number of runs to keep."
+    )]
+    InvalidRunHistoryMaxRuns(#[source] std::num::ParseIntError),
+    #[error(
+        "TURBO_RUN_HISTORY_MAX_AGE_DAYS: Invalid value. Use a number for the number of days to \
+         keep runs for."
+    )]
+    InvalidRunHistoryMaxAgeDays(#[source] std::num::ParseIntError),
 }
 
 const DEFAULT_API_URL: &str = "https://vercel.com/api";
@@ -252,6 +282,24 @@ const DEFAULT_LOGIN_URL: &str = "https://vercel.com";
 const DEFAULT_TIMEOUT: u64 = 30;
 const DEFAULT_UPLOAD_TIMEOUT: u64 = 60;
 const DEFAULT_TUI_SCROLLBACK_LENGTH: u64 = 2048;
+const DEFAULT_OTEL_SERVICE_NAME: &str = "turborepo";
+const DEFAULT_RUN_HISTORY_MAX_RUNS: u64 = 100;
+const DEFAULT_RUN_HISTORY_MAX_AGE_DAYS: u64 = 30;
+
+/// Where cache usage analytics events are sent.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsSinkKind {
+    /// The Vercel API. Only used when the repo is linked.
+    #[default]
+    Vercel,
+    /// A local file with one JSON event per line
+    File,
+    /// An HTTP endpoint that receives batches of events as JSON
+    Webhook,
+    /// Standard output, with one JSON event per line
+    Stdout,
+}
 
 // We intentionally don't derive Serialize so that different parts
 // of the code that want to display the config can tune how they
@@ -315,6 +363,19 @@ pub struct ConfigurationOptions {
     pub(crate) concurrency: Option<String>,
     pub(crate) no_update_notifier: Option<bool>,
     pub(crate) sso_login_callback_port: Option<u16>,
+    pub(crate) otel_enabled: Option<bool>,
+    pub(crate) otel_endpoint: Option<String>,
+    /// corresponds to env var TURBO_OTEL_HEADERS
+    pub(crate) otel_headers: Option<String>,
+    pub(crate) otel_service_name: Option<String>,
+    pub(crate) analytics_sink: Option<AnalyticsSinkKind>,
+    pub(crate) analytics_file: Option<String>,
+    pub(crate) analytics_url: Option<String>,
+    /// corresponds to env var TURBO_ANALYTICS_AUTH_HEADER
+    pub(crate) analytics_auth_header: Option<String>,
+    pub(crate) run_history: Option<bool>,
+    pub(crate) run_history_max_runs: Option<u64>,
+    pub(crate) run_history_max_age_days: Option<u64>,
     #[serde(skip)]
     future_flags: Option<FutureFlags>,
 }
@@ -477,6 +538,54 @@ impl ConfigurationOptions {
         self.sso_login_callback_port
     }
 
+    /// The OTLP endpoint to export runs to. Exporting is enabled whenever an
+    /// endpoint is configured, unless it is explicitly disabled.
+    pub fn otel_endpoint(&self) -> Option<&str> {
+        if self.otel_enabled == Some(false) {
+            return None;
+        }
+        non_empty_str(self.otel_endpoint.as_deref())
+    }
+
+    pub fn otel_headers(&self) -> Option<&str> {
+        non_empty_str(self.otel_headers.as_deref())
+    }
+
+    pub fn otel_service_name(&self) -> &str {
+        non_empty_str(self.otel_service_name.as_deref()).unwrap_or(DEFAULT_OTEL_SERVICE_NAME)
+    }
+
+    pub fn analytics_sink(&self) -> AnalyticsSinkKind {
+        self.analytics_sink.unwrap_or_default()
+    }
+
+    pub fn analytics_file(&self) -> Option<&str> {
+        non_empty_str(self.analytics_file.as_deref())
+    }
+
+    pub fn analytics_url(&self) -> Option<&str> {
+        non_empty_str(self.analytics_url.as_deref())
+    }
+
+    pub fn analytics_auth_header(&self) -> Option<&str> {
+        non_empty_str(self.analytics_auth_header.as_deref())
+    }
+
+    /// Whether runs are recorded in the run history, which is on by default
+    pub fn run_history(&self) -> bool {
+        self.run_history.unwrap_or(true)
+    }
+
+    pub fn run_history_max_runs(&self) -> u64 {
+        self.run_history_max_runs
+            .unwrap_or(DEFAULT_RUN_HISTORY_MAX_RUNS)
+    }
+
+    pub fn run_history_max_age_days(&self) -> u64 {
+        self.run_history_max_age_days
+            .unwrap_or(DEFAULT_RUN_HISTORY_MAX_AGE_DAYS)
+    }
+
     pub fn future_flags(&self) -> FutureFlags {
         self.future_flags.unwrap_or_default()
     }
@@ -78,6 +78,16 @@ impl<'a> TurboJsonReader<'a> {
         opts.env_mode = turbo_json.env_mode.map(|mode| *mode.as_inner());
         opts.cache_dir = cache_dir;
         opts.concurrency = turbo_json.concurrency.map(|c| c.as_inner().clone());
+        if let Some(otel) = turbo_json.otel {
+            opts.otel_enabled = otel.enabled.map(|enabled| *enabled.as_inner());
+            opts.otel_endpoint = otel.endpoint.map(|endpoint| endpoint.into_inner());
+            opts.otel_service_name = otel.service_name.map(|name| name.into_inner());
+        }
+        if let Some(run_history) = turbo_json.run_history {
+            opts.run_history = run_history.enabled.map(|enabled| *enabled.as_inner());
+            opts.run_history_max_runs = run_history.max_runs.map(|max| *max.as_inner());
+            opts.run_history_max_age_days = run_history.max_age_days.map(|max| *max.as_inner());
+        }
         opts.future_flags = turbo_json.future_flags.map(|f| *f.as_inner());
         Ok(opts)
     }
@@ -194,4 +204,55 @@ mod test {
         assert!(config.signature());
         assert!(config.preflight());
     }
+
+    #[test]
+    fn test_otel_options() {
+        let turbo_json = RawTurboJson::parse(
+            &serde_json::to_string_pretty(&json!({
+                "otel": {
+                    "endpoint": "http://localhost:4318",
+                    "serviceName": "acme"
+                }
+            }))
+            .unwrap(),
+            "junk",
+        )
+        .unwrap();
+        let config = TurboJsonReader::turbo_json_to_config_options(turbo_json).unwrap();
+        assert_eq!(config.otel_endpoint(), Some("http://localhost:4318"));
+        assert_eq!(config.otel_service_name(), "acme");
+
+        let turbo_json = RawTurboJson::parse(
+            &serde_json::to_string_pretty(&json!({
+                "otel": {
+                    "enabled": false,
+                    "endpoint": "http://localhost:4318"
+                }
+            }))
+            .unwrap(),
+            "junk",
+        )
+        .unwrap();
+        let config = TurboJsonReader::turbo_json_to_config_options(turbo_json).unwrap();
+        assert_eq!(config.otel_endpoint(), None);
+    }
+
+    #[test]
+    fn test_run_history_options() {
+        let turbo_json = RawTurboJson::parse(
+            &serde_json::to_string_pretty(&json!({
+                "runHistory": {
+                    "maxRuns": 50,
+                    "maxAgeDays": 14
+                }
+            }))
+            .unwrap(),
+            "junk",
+        )
+        .unwrap();
+        let config = TurboJsonReader::turbo_json_to_config_options(turbo_json).unwrap();
+        assert!(config.run_history());
+        assert_eq!(config.run_history_max_runs(), 50);
+        assert_eq!(config.run_history_max_age_days(), 14);
+    }
 }
@@ -1,4 +1,4 @@
-use std::{io, time::Duration};
+use std::{collections::HashMap, io, time::Duration};
 
 use globwalk::ValidatedGlob;
 use miette::Diagnostic;
@@ -10,7 +10,7 @@ use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPath};
 use super::{
     connector::{DaemonConnector, DaemonConnectorError},
     endpoint::SocketOpenError,
-    proto::{DiscoverPackagesResponse, GetFileHashesResponse},
+    proto::{DiscoverPackagesResponse, GetFileHashesResponse, GetTaskHashesResponse},
     Paths,
 };
 use crate::{
@@ -128,6 +128,16 @@ impl<T> DaemonClient<T> {
             .ok_or(DaemonError::MalformedResponse)
     }
 
+    /// Get counters describing file watching and hashing in the daemon.
+    pub async fn metrics(&mut self) -> Result<proto::DaemonMetrics, DaemonError> {
+        self.client
+            .get_metrics(proto::GetMetricsRequest {})
+            .await?
+            .into_inner()
+            .metrics
+            .ok_or(DaemonError::MalformedResponse)
+    }
+
     pub async fn discover_packages(&mut self) -> Result<DiscoverPackagesResponse, DaemonError> {
         let req = proto::DiscoverPackagesRequest {};
         let mut req = req.into_request();
@@ -176,6 +186,70 @@ impl<T> DaemonClient<T> {
             .into_inner();
         Ok(response)
     }
+
+    /// Hashes the inputs of all of the given tasks in a single request, and
+    /// gets any task hashes the daemon recorded for `key`.
+    pub async fn get_task_hashes<'a>(
+        &mut self,
+        key: String,
+        tasks: impl IntoIterator<Item = (String, &'a AnchoredSystemPath, &'a TaskInputs)>,
+    ) -> Result<GetTaskHashesResponse, DaemonError> {
+        let tasks = tasks
+            .into_iter()
+            .map(|(task_id, package_path, inputs)| proto::TaskInputGlobs {
+                task_id,
+                package_path: package_path.to_string(),
+                input_globs: inputs.globs.to_vec(),
+                include_default: inputs.default,
+            })
+            .collect();
+        let mut req = proto::GetTaskHashesRequest { key, tasks }.into_request();
+        // Hashing every task can take a while if the daemon just started, but we
+        // can always fall back to hashing locally.
+        req.set_timeout(Duration::from_secs(1));
+        let response = self.client.get_task_hashes(req).await?.into_inner();
+        Ok(response)
+    }
+
+    pub async fn notify_task_hashes(
+        &mut self,
+        key: String,
+        task_hashes: HashMap<String, String>,
+        input_hashes: HashMap<String, String>,
+    ) -> Result<(), DaemonError> {
+        self.client
+            .notify_task_hashes(proto::NotifyTaskHashesRequest {
+                key,
+                task_hashes,
+                input_hashes,
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Gets the traced imports the daemon has for `keys`, encoded as JSON.
+    pub async fn get_trace_imports(
+        &mut self,
+        keys: Vec<String>,
+    ) -> Result<HashMap<String, String>, DaemonError> {
+        let mut req = proto::GetTraceImportsRequest { keys }.into_request();
+        // Parsing the files ourselves is always an option
+        req.set_timeout(Duration::from_secs(1));
+        let response = self.client.get_trace_imports(req).await?.into_inner();
+        Ok(response.imports)
+    }
+
+    pub async fn notify_trace_imports(
+        &mut self,
+        imports: HashMap<String, String>,
+    ) -> Result<(), DaemonError> {
+        self.client
+            .notify_trace_imports(proto::NotifyTraceImportsRequest { imports })
+            .await?;
+
+        Ok(())
+    }
 }
 
 impl DaemonClient<DaemonConnector> {
@@ -667,6 +667,41 @@ mod test {
         ) -> Result<tonic::Response<proto::GetFileHashesResponse>, tonic::Status> {
             unimplemented!()
         }
+
+        async fn get_task_hashes(
+            &self,
+            _req: tonic::Request<proto::GetTaskHashesRequest>,
+        ) -> Result<tonic::Response<proto::GetTaskHashesResponse>, tonic::Status> {
+            unimplemented!()
+        }
+
+        async fn get_metrics(
+            &self,
+            _req: tonic::Request<proto::GetMetricsRequest>,
+        ) -> tonic::Result<tonic::Response<proto::GetMetricsResponse>> {
+            unimplemented!()
+        }
+
+        async fn notify_task_hashes(
+            &self,
+            _req: tonic::Request<proto::NotifyTaskHashesRequest>,
+        ) -> Result<tonic::Response<proto::NotifyTaskHashesResponse>, tonic::Status> {
+            unimplemented!()
+        }
+
+        async fn get_trace_imports(
+            &self,
+            _req: tonic::Request<proto::GetTraceImportsRequest>,
+        ) -> Result<tonic::Response<proto::GetTraceImportsResponse>, tonic::Status> {
+            unimplemented!()
+        }
+
+        async fn notify_trace_imports(
+            &self,
+            _req: tonic::Request<proto::NotifyTraceImportsRequest>,
+        ) -> Result<tonic::Response<proto::NotifyTraceImportsResponse>, tonic::Status> {
+            unimplemented!()
+        }
     }
 
     #[tokio::test]
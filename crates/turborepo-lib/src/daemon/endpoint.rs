@@ -1,6 +1,9 @@
-use std::sync::{atomic::AtomicBool, Arc};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc,
+};
 #[cfg(windows)]
-use std::{io::ErrorKind, sync::atomic::Ordering, time::Duration};
+use std::{io::ErrorKind, time::Duration};
 
 use futures::Stream;
 use tokio::io::{AsyncRead, AsyncWrite};
@@ -103,6 +106,86 @@ pub async fn listen_socket(
     }
 }
 
+/// Counts the clients connected to the daemon.
+#[derive(Debug, Default)]
+pub struct ClientCounter {
+    connected: AtomicU64,
+    total: AtomicU64,
+}
+
+impl ClientCounter {
+    /// The number of clients currently connected
+    pub fn connected(&self) -> u64 {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// The number of clients that have connected since the daemon started
+    pub fn total(&self) -> u64 {
+        self.total.load(Ordering::Relaxed)
+    }
+}
+
+/// A connection that counts as connected until it is dropped.
+pub struct CountedConnection<T> {
+    inner: T,
+    counter: Arc<ClientCounter>,
+}
+
+impl<T> CountedConnection<T> {
+    pub fn new(inner: T, counter: Arc<ClientCounter>) -> Self {
+        counter.connected.fetch_add(1, Ordering::Relaxed);
+        counter.total.fetch_add(1, Ordering::Relaxed);
+        Self { inner, counter }
+    }
+}
+
+impl<T> Drop for CountedConnection<T> {
+    fn drop(&mut self) {
+        self.counter.connected.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for CountedConnection<T> {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for CountedConnection<T> {
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<Result<usize, std::io::Error>> {
+        std::pin::Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), std::io::Error>> {
+        std::pin::Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), std::io::Error>> {
+        std::pin::Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+impl<T: Connected> Connected for CountedConnection<T> {
+    type ConnectInfo = T::ConnectInfo;
+    fn connect_info(&self) -> Self::ConnectInfo {
+        self.inner.connect_info()
+    }
+}
+
 /// An adaptor over uds_windows that implements AsyncRead and AsyncWrite.
 ///
 /// It utilizes structural pinning to forward async read and write
@@ -184,7 +267,7 @@ mod test {
     use pidlock::PidlockError;
     use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 
-    use super::listen_socket;
+    use super::{listen_socket, ClientCounter, CountedConnection};
     use crate::daemon::{endpoint::SocketOpenError, Paths};
 
     fn pid_path(daemon_root: &AbsoluteSystemPath) -> AbsoluteSystemPathBuf {
@@ -245,4 +328,22 @@ mod test {
         // Make sure to wait on the child to not leave a zombie process
         let _ = child.wait();
     }
+
+    #[test]
+    fn test_client_counter() {
+        let counter = Arc::new(ClientCounter::default());
+        let first = CountedConnection::new((), counter.clone());
+        let second = CountedConnection::new((), counter.clone());
+        assert_eq!(counter.connected(), 2);
+        assert_eq!(counter.total(), 2);
+
+        drop(first);
+        assert_eq!(counter.connected(), 1);
+        assert_eq!(counter.total(), 2);
+
+        drop(second);
+        let _third = CountedConnection::new((), counter.clone());
+        assert_eq!(counter.connected(), 1);
+        assert_eq!(counter.total(), 3);
+    }
 }
@@ -30,7 +30,9 @@ mod server;
 
 pub use client::{DaemonClient, DaemonError};
 pub use connector::{DaemonConnector, DaemonConnectorError};
-pub use server::{CloseReason, TurboGrpcService};
+#[cfg(test)]
+pub(crate) use server::InProcessDaemon;
+pub use server::{package_change_event_to_proto, CloseReason, FileWatching, TurboGrpcService};
 use sha2::{Digest, Sha256};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 
@@ -112,6 +114,7 @@ pub(crate) mod proto {
                 PackageManager::Pnpm6 => Self::Pnpm6,
                 PackageManager::Pnpm9 => Self::Pnpm9,
                 PackageManager::Bun => Self::Bun,
+                PackageManager::Deno => Self::Deno,
             }
         }
     }
@@ -126,6 +129,7 @@ pub(crate) mod proto {
                 turborepo_repository::package_manager::PackageManager::Pnpm6 => Self::Pnpm6,
                 turborepo_repository::package_manager::PackageManager::Pnpm9 => Self::Pnpm9,
                 turborepo_repository::package_manager::PackageManager::Bun => Self::Bun,
+                turborepo_repository::package_manager::PackageManager::Deno => Self::Deno,
             }
         }
     }
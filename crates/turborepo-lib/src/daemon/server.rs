@@ -12,9 +12,10 @@ use std::{
     time::{Duration, Instant},
 };
 
-use futures::Future;
+use futures::{future::try_join_all, Future, StreamExt};
 use prost::DecodeError;
 use semver::Version;
+use sysinfo::{ProcessExt, ProcessRefreshKind, SystemExt};
 use thiserror::Error;
 use tokio::{
     select,
@@ -31,17 +32,20 @@ use turborepo_filewatch::{
     globwatcher::{Error as GlobWatcherError, GlobError, GlobSet, GlobWatcher},
     hash_watcher::{Error as HashWatcherError, HashSpec, HashWatcher, InputGlobs},
     package_watcher::{PackageWatchError, PackageWatcher},
-    FileSystemWatcher, WatchError,
+    FileSystemWatcher, PollOptions, WatchError, WatcherBackend,
 };
 use turborepo_repository::package_manager;
-use turborepo_scm::SCM;
+use turborepo_scm::{GitHashes, SCM};
 
 use super::{bump_timeout::BumpTimeout, endpoint::SocketOpenError, proto};
 use crate::{
     daemon::{
-        bump_timeout_layer::BumpTimeoutLayer, default_timeout_layer::DefaultTimeoutLayer,
-        endpoint::listen_socket, Paths,
+        bump_timeout_layer::BumpTimeoutLayer,
+        default_timeout_layer::DefaultTimeoutLayer,
+        endpoint::{listen_socket, ClientCounter, CountedConnection},
+        Paths,
     },
+    hash::{FileHashes, TurboHash},
     package_changes_watcher::{PackageChangeEvent, PackageChangesWatcher},
 };
 
@@ -116,7 +120,23 @@ impl FileWatching {
         repo_root: AbsoluteSystemPathBuf,
         custom_turbo_json_path: Option<AbsoluteSystemPathBuf>,
     ) -> Result<FileWatching, WatchError> {
-        let watcher = Arc::new(FileSystemWatcher::new_with_default_cookie_dir(&repo_root)?);
+        let cookie_dir = repo_root.join_components(&[".turbo", "cookies"]);
+        Self::new_with_cookie_dir(repo_root, cookie_dir, custom_turbo_json_path)
+    }
+
+    /// Like `FileWatching::new`, but writes cookie files to `cookie_dir`. This
+    /// allows a process other than the daemon to watch the same repository
+    /// without the two watchers consuming each other's cookies.
+    pub fn new_with_cookie_dir(
+        repo_root: AbsoluteSystemPathBuf,
+        cookie_dir: AbsoluteSystemPathBuf,
+        custom_turbo_json_path: Option<AbsoluteSystemPathBuf>,
+    ) -> Result<FileWatching, WatchError> {
+        let watcher = Arc::new(FileSystemWatcher::new_with_backend(
+            &repo_root,
+            cookie_dir,
+            watcher_backend_from_env(),
+        )?);
         let recv = watcher.watch();
 
         let cookie_writer = CookieWriter::new(
@@ -167,6 +187,42 @@ impl FileWatching {
     }
 }
 
+const FILE_WATCHER_ENV: &str = "TURBO_FILE_WATCHER";
+const POLL_INTERVAL_ENV: &str = "TURBO_FILE_WATCHER_POLL_INTERVAL";
+const POLL_IGNORE_ENV: &str = "TURBO_FILE_WATCHER_POLL_IGNORE";
+
+/// Picks the file watching backend. Defaults to detecting file systems that
+/// need polling, but can be forced with `TURBO_FILE_WATCHER=native|polling`.
+/// The polling interval (in milliseconds) and a comma-separated list of globs
+/// to skip while polling can also be configured.
+fn watcher_backend_from_env() -> WatcherBackend {
+    let mut poll_options = PollOptions::default();
+    if let Some(interval) = std::env::var(POLL_INTERVAL_ENV)
+        .ok()
+        .and_then(|interval| interval.parse().ok())
+    {
+        poll_options.interval = Duration::from_millis(interval);
+    }
+    if let Ok(ignore) = std::env::var(POLL_IGNORE_ENV) {
+        poll_options.ignore = ignore
+            .split(',')
+            .map(str::trim)
+            .filter(|glob| !glob.is_empty())
+            .map(String::from)
+            .collect();
+    }
+
+    match std::env::var(FILE_WATCHER_ENV).as_deref() {
+        Ok("native") => WatcherBackend::Native,
+        Ok("polling") => WatcherBackend::Polling(poll_options),
+        Ok("auto") | Err(_) => WatcherBackend::Auto(poll_options),
+        Ok(other) => {
+            warn!("unknown {FILE_WATCHER_ENV} value '{other}', detecting file watcher");
+            WatcherBackend::Auto(poll_options)
+        }
+    }
+}
+
 /// Timeout for every RPC the server handles
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
@@ -235,6 +291,10 @@ where
                 Err(e) => return Ok(CloseReason::SocketOpenError(e)),
             };
         trace!("acquired connection stream for socket");
+        let clients = service.clients.clone();
+        let stream = stream.map(move |connection| {
+            connection.map(|connection| CountedConnection::new(connection, clients.clone()))
+        });
 
         let bump_timeout = Arc::new(BumpTimeout::new(timeout));
         let timeout_fut = bump_timeout.wait();
@@ -287,10 +347,27 @@ where
     }
 }
 
+/// The maximum number of runs we remember task hashes for
+const MAX_RECORDED_TASK_HASHES: usize = 16;
+
+/// Task hashes recorded by a run, along with the hashes of the task inputs
+/// they were computed from.
+struct RecordedTaskHashes {
+    task_hashes: HashMap<String, String>,
+    input_hashes: HashMap<String, String>,
+    recorded_at: Instant,
+}
+
+/// The maximum number of files we remember traced imports for
+const MAX_RECORDED_TRACE_IMPORTS: usize = 100_000;
+
 struct TurboGrpcServiceInner {
     shutdown: mpsc::Sender<()>,
     file_watching: FileWatching,
     times_saved: Arc<Mutex<HashMap<String, u64>>>,
+    task_hashes: Mutex<HashMap<String, RecordedTaskHashes>>,
+    trace_imports: Mutex<HashMap<String, String>>,
+    clients: Arc<ClientCounter>,
     start_time: Instant,
     log_file: AbsoluteSystemPathBuf,
     package_watcher: Arc<PackageWatcher>,
@@ -333,6 +410,9 @@ impl TurboGrpcServiceInner {
                 shutdown: trigger_shutdown,
                 file_watching,
                 times_saved: Arc::new(Mutex::new(HashMap::new())),
+                task_hashes: Mutex::new(HashMap::new()),
+                trace_imports: Mutex::new(HashMap::new()),
+                clients: Arc::new(ClientCounter::default()),
                 start_time: Instant::now(),
                 log_file,
             },
@@ -388,6 +468,22 @@ impl TurboGrpcServiceInner {
         inputs: Vec<String>,
         include_default: bool,
     ) -> Result<HashMap<String, String>, RpcError> {
+        self.get_git_hashes(package_path, inputs, include_default)
+            .await
+            .map(|hashes| {
+                hashes
+                    .into_iter()
+                    .map(|(path, hash)| (path.to_string(), hash))
+                    .collect()
+            })
+    }
+
+    async fn get_git_hashes(
+        &self,
+        package_path: String,
+        inputs: Vec<String>,
+        include_default: bool,
+    ) -> Result<GitHashes, RpcError> {
         let inputs = InputGlobs::from_raw(inputs, include_default)?;
         let package_path = AnchoredSystemPathBuf::try_from(package_path.as_str())
             .map_err(|e| RpcError::InvalidAnchoredPath(package_path, e))?;
@@ -400,12 +496,125 @@ impl TurboGrpcServiceInner {
             .get_file_hashes(hash_spec)
             .await
             .map_err(RpcError::FileHashing)
-            .map(|hashes| {
-                hashes
+    }
+
+    async fn get_task_hashes(
+        &self,
+        key: String,
+        tasks: Vec<proto::TaskInputGlobs>,
+    ) -> Result<proto::GetTaskHashesResponse, RpcError> {
+        let task_file_hashes = try_join_all(tasks.into_iter().map(|task| async move {
+            let file_hashes = self
+                .get_git_hashes(task.package_path, task.input_globs, task.include_default)
+                .await?;
+            Ok::<_, RpcError>((task.task_id, file_hashes))
+        }))
+        .await?;
+
+        let input_hashes: HashMap<_, _> = task_file_hashes
+            .iter()
+            .map(|(task_id, file_hashes)| (task_id.clone(), FileHashes(file_hashes.clone()).hash()))
+            .collect();
+
+        // Recorded task hashes are only valid if every task's inputs are unchanged
+        let task_hashes = {
+            let recorded = self.task_hashes.lock().expect("task hashes lock poisoned");
+            recorded
+                .get(&key)
+                .filter(|recorded| {
+                    input_hashes
+                        .iter()
+                        .all(|(task_id, hash)| recorded.input_hashes.get(task_id) == Some(hash))
+                })
+                .map(|recorded| recorded.task_hashes.clone())
+                .unwrap_or_default()
+        };
+
+        let file_hashes = task_file_hashes
+            .into_iter()
+            .map(|(task_id, file_hashes)| {
+                let file_hashes = file_hashes
                     .into_iter()
                     .map(|(path, hash)| (path.to_string(), hash))
-                    .collect()
+                    .collect();
+                (task_id, proto::TaskFileHashes { file_hashes })
             })
+            .collect();
+
+        Ok(proto::GetTaskHashesResponse {
+            file_hashes,
+            task_hashes,
+        })
+    }
+
+    fn metrics(&self) -> proto::DaemonMetrics {
+        let watcher = self.file_watching.watcher.metrics();
+        let hashes = self.file_watching.hash_watcher.metrics();
+        proto::DaemonMetrics {
+            file_events: watcher.events,
+            file_event_errors: watcher.errors,
+            rescans: watcher.rescans,
+            queued_file_events: watcher.queued_events,
+            max_queued_file_events: watcher.max_queued_events,
+            hash_hits: hashes.hits,
+            hash_pending: hashes.pending,
+            hash_misses: hashes.misses,
+            memory_bytes: memory_usage(),
+            connected_clients: self.clients.connected(),
+            total_clients: self.clients.total(),
+        }
+    }
+
+    fn notify_task_hashes(
+        &self,
+        key: String,
+        task_hashes: HashMap<String, String>,
+        input_hashes: HashMap<String, String>,
+    ) {
+        let mut recorded = self.task_hashes.lock().expect("task hashes lock poisoned");
+        if recorded.len() >= MAX_RECORDED_TASK_HASHES && !recorded.contains_key(&key) {
+            let oldest = recorded
+                .iter()
+                .min_by_key(|(_, recorded)| recorded.recorded_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                recorded.remove(&oldest);
+            }
+        }
+        recorded.insert(
+            key,
+            RecordedTaskHashes {
+                task_hashes,
+                input_hashes,
+                recorded_at: Instant::now(),
+            },
+        );
+    }
+
+    fn get_trace_imports(&self, keys: Vec<String>) -> HashMap<String, String> {
+        let recorded = self
+            .trace_imports
+            .lock()
+            .expect("trace imports lock poisoned");
+        keys.into_iter()
+            .filter_map(|key| {
+                let imports = recorded.get(&key)?.clone();
+                Some((key, imports))
+            })
+            .collect()
+    }
+
+    fn notify_trace_imports(&self, imports: HashMap<String, String>) {
+        let mut recorded = self
+            .trace_imports
+            .lock()
+            .expect("trace imports lock poisoned");
+        // Entries aren't tied to a file path, so there's no way to tell which ones
+        // are stale. Start over once we've seen too many.
+        if recorded.len() + imports.len() > MAX_RECORDED_TRACE_IMPORTS {
+            recorded.clear();
+        }
+        recorded.extend(imports);
     }
 }
 
@@ -511,6 +720,15 @@ impl proto::turbod_server::Turbod for TurboGrpcServiceInner {
         }))
     }
 
+    async fn get_metrics(
+        &self,
+        _request: tonic::Request<proto::GetMetricsRequest>,
+    ) -> Result<tonic::Response<proto::GetMetricsResponse>, tonic::Status> {
+        Ok(tonic::Response::new(proto::GetMetricsResponse {
+            metrics: Some(self.metrics()),
+        }))
+    }
+
     async fn notify_outputs_written(
         &self,
         request: tonic::Request<proto::NotifyOutputsWrittenRequest>,
@@ -563,6 +781,46 @@ impl proto::turbod_server::Turbod for TurboGrpcServiceInner {
         }))
     }
 
+    // Like `get_file_hashes`, this is a blocking call that the client is expected
+    // to set a timeout for.
+    async fn get_task_hashes(
+        &self,
+        request: tonic::Request<proto::GetTaskHashesRequest>,
+    ) -> Result<tonic::Response<proto::GetTaskHashesResponse>, tonic::Status> {
+        let inner = request.into_inner();
+        let response = self.get_task_hashes(inner.key, inner.tasks).await?;
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn notify_task_hashes(
+        &self,
+        request: tonic::Request<proto::NotifyTaskHashesRequest>,
+    ) -> Result<tonic::Response<proto::NotifyTaskHashesResponse>, tonic::Status> {
+        let inner = request.into_inner();
+        self.notify_task_hashes(inner.key, inner.task_hashes, inner.input_hashes);
+        Ok(tonic::Response::new(proto::NotifyTaskHashesResponse {}))
+    }
+
+    async fn get_trace_imports(
+        &self,
+        request: tonic::Request<proto::GetTraceImportsRequest>,
+    ) -> Result<tonic::Response<proto::GetTraceImportsResponse>, tonic::Status> {
+        let inner = request.into_inner();
+        let imports = self.get_trace_imports(inner.keys);
+        Ok(tonic::Response::new(proto::GetTraceImportsResponse {
+            imports,
+        }))
+    }
+
+    async fn notify_trace_imports(
+        &self,
+        request: tonic::Request<proto::NotifyTraceImportsRequest>,
+    ) -> Result<tonic::Response<proto::NotifyTraceImportsResponse>, tonic::Status> {
+        let inner = request.into_inner();
+        self.notify_trace_imports(inner.imports);
+        Ok(tonic::Response::new(proto::NotifyTraceImportsResponse {}))
+    }
+
     async fn discover_packages(
         &self,
         _request: tonic::Request<proto::DiscoverPackagesRequest>,
@@ -637,35 +895,7 @@ impl proto::turbod_server::Turbod for TurboGrpcServiceInner {
 
         tokio::spawn(async move {
             loop {
-                let event = match package_changes_rx.recv().await {
-                    Err(RecvError::Lagged(_)) => {
-                        warn!("package changes stream lagged");
-                        proto::PackageChangeEvent {
-                            event: Some(proto::package_change_event::Event::RediscoverPackages(
-                                proto::RediscoverPackages {},
-                            )),
-                        }
-                    }
-                    Err(err) => proto::PackageChangeEvent {
-                        event: Some(proto::package_change_event::Event::Error(
-                            proto::PackageChangeError {
-                                message: err.to_string(),
-                            },
-                        )),
-                    },
-                    Ok(PackageChangeEvent::Package { name }) => proto::PackageChangeEvent {
-                        event: Some(proto::package_change_event::Event::PackageChanged(
-                            proto::PackageChanged {
-                                package_name: name.to_string(),
-                            },
-                        )),
-                    },
-                    Ok(PackageChangeEvent::Rediscover) => proto::PackageChangeEvent {
-                        event: Some(proto::package_change_event::Event::RediscoverPackages(
-                            proto::RediscoverPackages {},
-                        )),
-                    },
-                };
+                let event = package_change_event_to_proto(package_changes_rx.recv().await);
 
                 if let Err(err) = tx.send(Ok(event)).await {
                     error!("package changes stream closed: {}", err);
@@ -678,6 +908,41 @@ impl proto::turbod_server::Turbod for TurboGrpcServiceInner {
     }
 }
 
+/// Converts an event received from a `PackageChangesWatcher` into the event
+/// we send to clients. A lagging receiver is reported as a rediscovery, since
+/// we can no longer tell which packages changed.
+pub fn package_change_event_to_proto(
+    event: Result<PackageChangeEvent, RecvError>,
+) -> proto::PackageChangeEvent {
+    let event = match event {
+        Err(RecvError::Lagged(_)) => {
+            warn!("package changes stream lagged");
+            proto::package_change_event::Event::RediscoverPackages(proto::RediscoverPackages {})
+        }
+        Err(err) => proto::package_change_event::Event::Error(proto::PackageChangeError {
+            message: err.to_string(),
+        }),
+        Ok(PackageChangeEvent::Package { name }) => {
+            proto::package_change_event::Event::PackageChanged(proto::PackageChanged {
+                package_name: name.to_string(),
+            })
+        }
+        Ok(PackageChangeEvent::Rediscover) => {
+            proto::package_change_event::Event::RediscoverPackages(proto::RediscoverPackages {})
+        }
+    };
+
+    proto::PackageChangeEvent { event: Some(event) }
+}
+
+/// The resident memory of the daemon process in bytes
+fn memory_usage() -> Option<u64> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    let mut system = sysinfo::System::new();
+    system.refresh_process_specifics(pid, ProcessRefreshKind::new());
+    system.process(pid).map(|process| process.memory())
+}
+
 /// Determine whether a server can serve a client's request based on its
 /// version.
 ///
@@ -705,25 +970,88 @@ impl NamedService for TurboGrpcServiceInner {
     const NAME: &'static str = "turborepo.Daemon";
 }
 
+/// A daemon served in-process, for tests that need to talk to it through a
+/// real client. The daemon stops watching files once this is dropped.
+#[cfg(test)]
+pub(crate) struct InProcessDaemon {
+    pub client: super::DaemonClient<super::DaemonConnector>,
+    _exit_root_watch: oneshot::Sender<()>,
+    _shutdown_signal: mpsc::Receiver<()>,
+}
+
+#[cfg(test)]
+impl InProcessDaemon {
+    pub async fn serve(repo_root: &AbsoluteSystemPath) -> Self {
+        let (trigger_shutdown, shutdown_signal) = mpsc::channel(1);
+        let (service, exit_root_watch, _watch_root_handle) = TurboGrpcServiceInner::new(
+            repo_root.to_owned(),
+            trigger_shutdown,
+            repo_root.join_component("turbod.log"),
+            None,
+        );
+
+        // Each connection the client makes gets one end of a duplex stream, and
+        // the server the other
+        let (tx, mut rx) = mpsc::channel(1);
+        let incoming = async_stream::stream! {
+            while let Some(item) = rx.recv().await {
+                yield item;
+            }
+        };
+        tokio::spawn(
+            Server::builder()
+                .add_service(proto::turbod_server::TurbodServer::new(service))
+                .serve_with_incoming(incoming),
+        );
+        let client = tonic::transport::Endpoint::try_from("http://[::]:50051")
+            .expect("this is a valid uri")
+            .connect_with_connector(tower::service_fn(move |_| {
+                let tx = tx.clone();
+                async move {
+                    let (client, server) = tokio::io::duplex(1024);
+                    tx.send(Ok::<_, std::io::Error>(server))
+                        .await
+                        .expect("server is running");
+                    Ok::<_, std::io::Error>(client)
+                }
+            }))
+            .await
+            .map(proto::turbod_client::TurbodClient::new)
+            .expect("in-process daemon is reachable");
+
+        Self {
+            client: super::DaemonClient::new(client)
+                .with_connect_settings(super::DaemonConnector::new(false, false, repo_root, None)),
+            _exit_root_watch: exit_root_watch,
+            _shutdown_signal: shutdown_signal,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::{
         assert_matches::{self, assert_matches},
+        collections::HashMap,
         time::{Duration, Instant},
     };
 
     use futures::FutureExt;
     use semver::Version;
     use test_case::test_case;
-    use tokio::sync::oneshot;
-    use turbopath::AbsoluteSystemPathBuf;
+    use tokio::sync::{mpsc, oneshot};
+    use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
     use turborepo_repository::{
         discovery::{DiscoveryResponse, PackageDiscovery},
         package_manager::PackageManager,
     };
 
-    use super::compare_versions;
-    use crate::daemon::{proto::VersionRange, CloseReason, Paths, TurboGrpcService};
+    use super::{compare_versions, TurboGrpcServiceInner};
+    use crate::daemon::{
+        endpoint::CountedConnection,
+        proto::{self, turbod_server::Turbod, VersionRange},
+        CloseReason, Paths, TurboGrpcService,
+    };
 
     #[test_case("1.2.3", "1.2.3", VersionRange::Exact, true ; "exact match")]
     #[test_case("1.2.3", "1.2.3", VersionRange::Patch, true ; "patch match")]
@@ -931,4 +1259,130 @@ mod test {
             .expect("server exited");
         assert_matches!(close_reason, Ok(CloseReason::Shutdown));
     }
+
+    // Creates the service without serving it, so that its RPCs can be called
+    // directly. The returned channels have to be kept alive for the service to
+    // keep watching files.
+    fn new_service(
+        repo_root: &AbsoluteSystemPath,
+    ) -> (
+        TurboGrpcServiceInner,
+        oneshot::Sender<()>,
+        mpsc::Receiver<()>,
+    ) {
+        repo_root.create_dir_all().unwrap();
+        repo_root
+            .join_component("package.json")
+            .create_with_contents(r#"{"workspaces": ["packages/*"]}"#)
+            .unwrap();
+        repo_root
+            .join_component("package-lock.json")
+            .create_with_contents("")
+            .unwrap();
+
+        let (trigger_shutdown, shutdown_signal) = mpsc::channel(1);
+        let (service, exit_root_watch, _watch_root_handle) = TurboGrpcServiceInner::new(
+            repo_root.to_owned(),
+            trigger_shutdown,
+            repo_root.join_component("turbod.log"),
+            None,
+        );
+        (service, exit_root_watch, shutdown_signal)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_metrics() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tempdir.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap()
+            .join_component("repo");
+        let (service, _exit_root_watch, _shutdown_signal) = new_service(&repo_root);
+        let _connection = CountedConnection::new((), service.clients.clone());
+
+        let metrics = service
+            .get_metrics(tonic::Request::new(proto::GetMetricsRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .metrics
+            .unwrap();
+
+        assert_eq!(metrics.connected_clients, 1);
+        assert_eq!(metrics.total_clients, 1);
+        // Nothing has asked for file hashes yet
+        assert_eq!(
+            (metrics.hash_hits, metrics.hash_pending, metrics.hash_misses),
+            (0, 0, 0)
+        );
+        assert!(metrics.memory_bytes.is_some_and(|bytes| bytes > 0));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_task_hashes_reused_for_same_key() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tempdir.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap()
+            .join_component("repo");
+        let (service, _exit_root_watch, _shutdown_signal) = new_service(&repo_root);
+
+        let task_hashes = HashMap::from([("web#build".to_string(), "abc123".to_string())]);
+        service.notify_task_hashes("key".to_string(), task_hashes.clone(), HashMap::new());
+
+        let response = service
+            .get_task_hashes("key".to_string(), Vec::new())
+            .await
+            .unwrap();
+        assert_eq!(response.task_hashes, task_hashes);
+
+        // A run with a different key can't use them
+        let response = service
+            .get_task_hashes("other".to_string(), Vec::new())
+            .await
+            .unwrap();
+        assert!(response.task_hashes.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_trace_imports_round_trip() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tempdir.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap()
+            .join_component("repo");
+        let (service, _exit_root_watch, _shutdown_signal) = new_service(&repo_root);
+
+        let imports = HashMap::from([
+            ("a".to_string(), r#"[{"specifier":"./b"}]"#.to_string()),
+            ("b".to_string(), "[]".to_string()),
+        ]);
+        Turbod::notify_trace_imports(
+            &service,
+            tonic::Request::new(proto::NotifyTraceImportsRequest {
+                imports: imports.clone(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let response = Turbod::get_trace_imports(
+            &service,
+            tonic::Request::new(proto::GetTraceImportsRequest {
+                keys: vec!["a".to_string(), "missing".to_string()],
+            }),
+        )
+        .await
+        .unwrap()
+        .into_inner();
+
+        // Only the keys that were asked for and recorded are returned
+        assert_eq!(
+            response.imports,
+            HashMap::from([("a".to_string(), imports["a"].clone())])
+        );
+    }
 }
@@ -0,0 +1,31 @@
+use std::collections::BTreeMap;
+
+use biome_deserialize_macros::Deserializable;
+use serde::Serialize;
+use struct_iterable::Iterable;
+use turborepo_errors::Spanned;
+
+#[derive(Serialize, Default, Debug, Clone, Iterable, Deserializable, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DepsConfig {
+    /// Dependencies that must be declared with exactly this version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<Spanned<VersionMap>>,
+    /// Dependencies whose resolved version must satisfy this range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_ranges: Option<Spanned<VersionMap>>,
+    /// Every workspace must resolve a dependency to the same version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub single_version: Option<Spanned<bool>>,
+    /// Internal packages must be declared with the `workspace:` protocol
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_protocol: Option<Spanned<bool>>,
+    /// Dependencies that are exempt from `singleVersion`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore: Option<Spanned<Vec<Spanned<String>>>>,
+    /// Dependencies that are used without being imported, like CLI tools
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_unused: Option<Spanned<Vec<Spanned<String>>>>,
+}
+
+pub type VersionMap = BTreeMap<String, Spanned<String>>;
@@ -0,0 +1,925 @@
+//! Dependency version policies, checked by `turbo deps check`. Policies are
+//! configured with the `deps` key of the root `turbo.json` and apply to the
+//! dependencies declared in every workspace's `package.json`, using the
+//! lockfile to find which version each declaration resolves to.
+
+mod config;
+mod unused;
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+};
+
+pub use config::DepsConfig;
+use jsonc_parser::{ast::Value, parse_to_ast};
+use miette::{Diagnostic, NamedSource, Report, SourceSpan};
+use node_semver::{Range, Version};
+use thiserror::Error;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_errors::Spanned;
+use turborepo_lockfiles::Lockfile;
+use turborepo_repository::{
+    package_graph::{PackageInfo, PackageName},
+    package_manager::PackageManager,
+};
+use turborepo_ui::{color, ColorConfig, BOLD_GREEN, BOLD_RED};
+
+use crate::{
+    rewrite_json::{set_path, RewriteError},
+    run::Run,
+};
+
+const WORKSPACE_PROTOCOL: &str = "workspace:";
+// What internal dependencies are rewritten to by `--fix`
+pub(crate) const WORKSPACE_PROTOCOL_VERSION: &str = "workspace:*";
+
+#[derive(Clone, Debug, Error, Diagnostic)]
+pub enum SecondaryDiagnostic {
+    #[error("policy defined here")]
+    PolicyDefinedHere {
+        #[label]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+}
+
+impl SecondaryDiagnostic {
+    fn policy(policy: &Spanned<String>) -> [Self; 1] {
+        let (span, text) = policy.span_and_text("turbo.json");
+        [Self::PolicyDefinedHere { span, text }]
+    }
+}
+
+#[derive(Clone, Debug, Error, Diagnostic)]
+pub enum DepsDiagnostic {
+    #[error("`{dependency}` must be pinned to `{expected}` in `{package}`, found `{found}`")]
+    NotPinned {
+        package: PackageName,
+        dependency: String,
+        found: String,
+        expected: String,
+        path: AbsoluteSystemPathBuf,
+        group: &'static str,
+        #[label("declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+        #[related]
+        secondary: [SecondaryDiagnostic; 1],
+    },
+    #[error(
+        "`{package}` resolves `{dependency}` to `{version}`, but other packages use \
+         `{expected_version}`"
+    )]
+    #[diagnostic(help("use a single version of `{dependency}` across the repository"))]
+    MultipleVersions {
+        package: PackageName,
+        dependency: String,
+        version: String,
+        expected_version: String,
+        // The specifier of a declaration that resolves to `expected_version`
+        expected: String,
+        path: AbsoluteSystemPathBuf,
+        group: &'static str,
+        #[label("declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+    #[error(
+        "`{package}` resolves `{dependency}` to `{version}`, which is outside of the allowed \
+         range `{range}`"
+    )]
+    OutsideAllowedRange {
+        package: PackageName,
+        dependency: String,
+        version: String,
+        range: String,
+        #[label("declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+        #[related]
+        secondary: [SecondaryDiagnostic; 1],
+    },
+    #[error(
+        "`{package}` declares `{dependency}` as `{specifier}`, which doesn't overlap the allowed \
+         range `{range}`"
+    )]
+    DeclaredOutsideAllowedRange {
+        package: PackageName,
+        dependency: String,
+        specifier: String,
+        range: String,
+        #[label("declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+        #[related]
+        secondary: [SecondaryDiagnostic; 1],
+    },
+    #[error(
+        "`{package}` declares `{dependency}` as `{specifier}`, which can't be checked against the \
+         allowed range `{range}`"
+    )]
+    #[diagnostic(help(
+        "use a version range, or install dependencies so the lockfile resolves `{dependency}` to \
+         a version"
+    ))]
+    UnverifiableVersion {
+        package: PackageName,
+        dependency: String,
+        specifier: String,
+        range: String,
+        #[label("declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+        #[related]
+        secondary: [SecondaryDiagnostic; 1],
+    },
+    #[error(
+        "internal package `{dependency}` must use the `workspace:` protocol in `{package}`, found \
+         `{found}`"
+    )]
+    MissingWorkspaceProtocol {
+        package: PackageName,
+        dependency: String,
+        found: String,
+        path: AbsoluteSystemPathBuf,
+        group: &'static str,
+        #[label("declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+    #[error("`{dependency}` is declared in `{package}` but never imported")]
+    #[diagnostic(help(
+        "remove it from `{group}`, or add it to `deps.allowUnused` in turbo.json if it's used \
+         some other way"
+    ))]
+    Unused {
+        package: PackageName,
+        dependency: String,
+        group: &'static str,
+        #[label("declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+}
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error("Invalid version range `{range}` for `{dependency}` in `allowedRanges`.")]
+    InvalidRange {
+        dependency: String,
+        range: String,
+        #[label("range defined here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+    #[error(
+        "The `workspaceProtocol` policy can't be used with {0}, which doesn't support the \
+         `workspace:` protocol."
+    )]
+    WorkspaceProtocolUnsupported(String),
+    #[error("failed to read file: {0}")]
+    FileNotFound(AbsoluteSystemPathBuf),
+    #[error("failed to write to file: {0}")]
+    FileWrite(AbsoluteSystemPathBuf),
+    #[error("Failed to update package.json: {0}")]
+    Rewrite(#[from] RewriteError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    GlobWalk(#[from] globwalk::WalkError),
+}
+
+/// An edit to a `package.json` that resolves a diagnostic
+pub struct Fix<'a> {
+    pub path: &'a AbsoluteSystemPath,
+    pub group: &'static str,
+    pub dependency: &'a str,
+    pub version: &'a str,
+}
+
+impl DepsDiagnostic {
+    /// Returns the fix for the diagnostic, if it can be fixed automatically
+    pub fn fix(&self) -> Option<Fix<'_>> {
+        match self {
+            Self::NotPinned {
+                path,
+                group,
+                dependency,
+                expected,
+                ..
+            }
+            | Self::MultipleVersions {
+                path,
+                group,
+                dependency,
+                expected,
+                ..
+            } => Some(Fix {
+                path,
+                group: *group,
+                dependency,
+                version: expected,
+            }),
+            Self::MissingWorkspaceProtocol {
+                path,
+                group,
+                dependency,
+                ..
+            } => Some(Fix {
+                path,
+                group: *group,
+                dependency,
+                version: WORKSPACE_PROTOCOL_VERSION,
+            }),
+            Self::OutsideAllowedRange { .. }
+            | Self::DeclaredOutsideAllowedRange { .. }
+            | Self::UnverifiableVersion { .. }
+            | Self::Unused { .. } => None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DepsResult {
+    packages_checked: usize,
+    pub diagnostics: Vec<DepsDiagnostic>,
+}
+
+impl DepsResult {
+    pub fn is_ok(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Applies every fix to the `package.json` files, removing the fixed
+    /// diagnostics. Returns the paths of the files that were changed.
+    pub fn fix(&mut self) -> Result<Vec<AbsoluteSystemPathBuf>, Error> {
+        let mut edits: HashMap<&AbsoluteSystemPath, Vec<Fix>> = HashMap::new();
+        for fix in self.diagnostics.iter().filter_map(|d| d.fix()) {
+            edits.entry(fix.path).or_default().push(fix);
+        }
+
+        let mut patched = Vec::with_capacity(edits.len());
+        for (path, fixes) in edits {
+            let mut contents = path
+                .read_to_string()
+                .map_err(|_| Error::FileNotFound(path.to_owned()))?;
+            for fix in fixes {
+                contents = set_path(
+                    &contents,
+                    &[fix.group, fix.dependency],
+                    &serde_json::to_string(fix.version)?,
+                )?;
+            }
+            path.create_with_contents(contents)
+                .map_err(|_| Error::FileWrite(path.to_owned()))?;
+            patched.push(path.to_owned());
+        }
+
+        self.diagnostics
+            .retain(|diagnostic| diagnostic.fix().is_none());
+        Ok(patched)
+    }
+
+    pub fn emit(&self, color_config: ColorConfig) {
+        for diagnostic in &self.diagnostics {
+            eprintln!("{:?}", Report::new(diagnostic.clone()));
+        }
+        let result_message = match self.diagnostics.len() {
+            0 => color!(color_config, BOLD_GREEN, "no issues found"),
+            1 => color!(color_config, BOLD_RED, "1 issue found"),
+            _ => color!(
+                color_config,
+                BOLD_RED,
+                "{} issues found",
+                self.diagnostics.len()
+            ),
+        };
+        println!(
+            "Checked dependencies of {} packages, {}",
+            self.packages_checked, result_message
+        );
+    }
+}
+
+// The policies from `DepsConfig`, with ranges parsed
+#[derive(Default)]
+struct Policies {
+    pinned: BTreeMap<String, Spanned<String>>,
+    allowed_ranges: BTreeMap<String, (Range, Spanned<String>)>,
+    single_version: bool,
+    workspace_protocol: bool,
+    ignore: HashSet<String>,
+}
+
+impl Policies {
+    fn new(config: &DepsConfig) -> Result<Self, Error> {
+        let allowed_ranges = config
+            .allowed_ranges
+            .as_ref()
+            .map(|ranges| ranges.as_inner().clone())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(dependency, range)| match Range::parse(range.as_inner()) {
+                Ok(parsed) => Ok((dependency, (parsed, range))),
+                Err(_) => {
+                    let (span, text) = range.span_and_text("turbo.json");
+                    Err(Error::InvalidRange {
+                        dependency,
+                        range: range.into_inner(),
+                        span,
+                        text,
+                    })
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            pinned: config
+                .pinned
+                .as_ref()
+                .map(|pinned| pinned.as_inner().clone())
+                .unwrap_or_default(),
+            allowed_ranges,
+            single_version: config
+                .single_version
+                .as_ref()
+                .is_some_and(|single_version| *single_version.as_inner()),
+            workspace_protocol: config
+                .workspace_protocol
+                .as_ref()
+                .is_some_and(|workspace_protocol| *workspace_protocol.as_inner()),
+            ignore: config
+                .ignore
+                .as_ref()
+                .into_iter()
+                .flat_map(|ignore| ignore.as_inner())
+                .map(|dependency| dependency.as_inner().clone())
+                .collect(),
+        })
+    }
+}
+
+struct PackageJsonSource {
+    path: AbsoluteSystemPathBuf,
+    // Path relative to the repository root, used as the name of the source
+    name: String,
+    text: String,
+}
+
+impl PackageJsonSource {
+    fn load(repo_root: &AbsoluteSystemPath, info: &PackageInfo) -> Result<Self, Error> {
+        let path = repo_root.resolve(info.package_json_path());
+        let text = path
+            .read_to_string()
+            .map_err(|_| Error::FileNotFound(path.clone()))?;
+        Ok(Self {
+            name: info.package_json_path().to_string(),
+            path,
+            text,
+        })
+    }
+
+    fn span_and_text(
+        &self,
+        group: &str,
+        dependency: &str,
+    ) -> (Option<SourceSpan>, NamedSource<String>) {
+        (
+            version_span(&self.text, group, dependency),
+            NamedSource::new(&self.name, self.text.clone()),
+        )
+    }
+}
+
+// A dependency as declared in a `package.json`
+struct Declaration<'a> {
+    package: &'a PackageName,
+    source: &'a PackageJsonSource,
+    group: &'static str,
+    name: &'a str,
+    specifier: &'a str,
+    // The version the lockfile resolves the specifier to
+    resolved: Option<String>,
+}
+
+impl Declaration<'_> {
+    // Falls back to the specifier when the lockfile can't resolve it
+    fn version(&self) -> &str {
+        self.resolved.as_deref().unwrap_or(self.specifier)
+    }
+}
+
+impl Run {
+    pub fn check_deps(&self) -> Result<DepsResult, Error> {
+        let policies = match &self.root_turbo_json().deps {
+            Some(config) => Policies::new(config.as_inner())?,
+            None => Policies::default(),
+        };
+        let package_graph = self.pkg_dep_graph();
+        if policies.workspace_protocol
+            && !supports_workspace_protocol(package_graph.package_manager())
+        {
+            return Err(Error::WorkspaceProtocolUnsupported(
+                package_graph.package_manager().name().to_string(),
+            ));
+        }
+
+        let mut packages = package_graph.packages().collect::<Vec<_>>();
+        packages.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let sources = packages
+            .iter()
+            .map(|(_, info)| PackageJsonSource::load(self.repo_root(), info))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let internal_packages = packages
+            .iter()
+            .filter_map(|(name, _)| match name {
+                PackageName::Root => None,
+                PackageName::Other(name) => Some(name.as_str()),
+            })
+            .collect::<HashSet<_>>();
+        let declarations = packages
+            .iter()
+            .zip(&sources)
+            .flat_map(|((package, info), source)| {
+                self.declarations(package, info, source, &internal_packages)
+            })
+            .collect::<Vec<_>>();
+
+        Ok(DepsResult {
+            packages_checked: packages.len(),
+            diagnostics: check(&policies, &declarations, &internal_packages),
+        })
+    }
+
+    fn declarations<'a>(
+        &self,
+        package: &'a PackageName,
+        info: &'a PackageInfo,
+        source: &'a PackageJsonSource,
+        internal_packages: &HashSet<&str>,
+    ) -> Vec<Declaration<'a>> {
+        let lockfile = self.pkg_dep_graph().lockfile();
+        let workspace_path = info.package_path().to_unix().to_string();
+        let package_json = &info.package_json;
+        // Peer dependencies are left out since they're meant to be broad ranges
+        [
+            ("dependencies", &package_json.dependencies),
+            ("devDependencies", &package_json.dev_dependencies),
+            ("optionalDependencies", &package_json.optional_dependencies),
+        ]
+        .into_iter()
+        .flat_map(|(group, dependencies)| {
+            dependencies
+                .iter()
+                .flatten()
+                .map(move |(name, specifier)| (group, name, specifier))
+        })
+        .map(|(group, name, specifier)| {
+            let resolved = lockfile
+                .filter(|_| !internal_packages.contains(name.as_str()))
+                .and_then(|lockfile| resolve_version(lockfile, &workspace_path, name, specifier));
+            Declaration {
+                package,
+                source,
+                group,
+                name,
+                specifier,
+                resolved,
+            }
+        })
+        .collect()
+    }
+}
+
+fn check(
+    policies: &Policies,
+    declarations: &[Declaration],
+    internal_packages: &HashSet<&str>,
+) -> Vec<DepsDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut external = BTreeMap::<&str, Vec<&Declaration>>::new();
+
+    for declaration in declarations {
+        let package = declaration.package.clone();
+        let dependency = declaration.name.to_string();
+        let span_and_text = || {
+            declaration
+                .source
+                .span_and_text(declaration.group, declaration.name)
+        };
+
+        // Version policies only apply to external dependencies
+        if internal_packages.contains(declaration.name) {
+            if policies.workspace_protocol && !declaration.specifier.starts_with(WORKSPACE_PROTOCOL)
+            {
+                let (span, text) = span_and_text();
+                diagnostics.push(DepsDiagnostic::MissingWorkspaceProtocol {
+                    package,
+                    dependency,
+                    found: declaration.specifier.to_string(),
+                    path: declaration.source.path.clone(),
+                    group: declaration.group,
+                    span,
+                    text,
+                });
+            }
+            continue;
+        }
+
+        if let Some(expected) = policies.pinned.get(declaration.name) {
+            if declaration.specifier != expected.as_inner() {
+                let (span, text) = span_and_text();
+                diagnostics.push(DepsDiagnostic::NotPinned {
+                    package,
+                    dependency,
+                    found: declaration.specifier.to_string(),
+                    expected: expected.as_inner().clone(),
+                    path: declaration.source.path.clone(),
+                    group: declaration.group,
+                    span,
+                    text,
+                    secondary: SecondaryDiagnostic::policy(expected),
+                });
+            }
+            // Pinning already keeps every declaration on the same version
+            continue;
+        }
+
+        if let Some((range, policy)) = policies.allowed_ranges.get(declaration.name) {
+            let version = declaration.version();
+            let (span, text) = span_and_text();
+            let secondary = SecondaryDiagnostic::policy(policy);
+            match Version::parse(version) {
+                Ok(parsed) if !range.satisfies(&parsed) => {
+                    diagnostics.push(DepsDiagnostic::OutsideAllowedRange {
+                        package,
+                        dependency,
+                        version: version.to_string(),
+                        range: policy.as_inner().clone(),
+                        span,
+                        text,
+                        secondary,
+                    });
+                }
+                Ok(_) => {}
+                // Without a resolved version we can only check that some version the
+                // declaration allows is also allowed by the policy
+                Err(_) => match declaration.specifier.parse::<Range>() {
+                    Ok(declared) if !range.allows_any(&declared) => {
+                        diagnostics.push(DepsDiagnostic::DeclaredOutsideAllowedRange {
+                            package,
+                            dependency,
+                            specifier: declaration.specifier.to_string(),
+                            range: policy.as_inner().clone(),
+                            span,
+                            text,
+                            secondary,
+                        });
+                    }
+                    Ok(_) => {}
+                    Err(_) => {
+                        diagnostics.push(DepsDiagnostic::UnverifiableVersion {
+                            package,
+                            dependency,
+                            specifier: declaration.specifier.to_string(),
+                            range: policy.as_inner().clone(),
+                            span,
+                            text,
+                            secondary,
+                        });
+                    }
+                },
+            }
+        }
+
+        if !policies.ignore.contains(declaration.name) {
+            external
+                .entry(declaration.name)
+                .or_default()
+                .push(declaration);
+        }
+    }
+
+    if policies.single_version {
+        for declarations in external.values() {
+            let Some(expected) = declarations
+                .iter()
+                .max_by(|a, b| compare_versions(a.version(), b.version()))
+            else {
+                continue;
+            };
+            for declaration in declarations {
+                if declaration.version() == expected.version() {
+                    continue;
+                }
+                let (span, text) = declaration
+                    .source
+                    .span_and_text(declaration.group, declaration.name);
+                diagnostics.push(DepsDiagnostic::MultipleVersions {
+                    package: declaration.package.clone(),
+                    dependency: declaration.name.to_string(),
+                    version: declaration.version().to_string(),
+                    expected_version: expected.version().to_string(),
+                    expected: expected.specifier.to_string(),
+                    path: declaration.source.path.clone(),
+                    group: declaration.group,
+                    span,
+                    text,
+                });
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// The version that a dependency declared in the workspace at
+/// `workspace_path` resolves to in the lockfile
+pub(crate) fn resolve_version(
+    lockfile: &dyn Lockfile,
+    workspace_path: &str,
+    name: &str,
+    specifier: &str,
+) -> Option<String> {
+    let package = lockfile
+        .resolve_package(workspace_path, name, specifier)
+        .ok()
+        .flatten()?;
+    // pnpm includes the versions of peer dependencies after the version
+    Some(match package.version.split_once('(') {
+        Some((version, _)) => version.to_string(),
+        None => package.version,
+    })
+}
+
+// Versions that aren't valid semver, like git references, sort before any
+// that are
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Greater,
+        (Err(_), Ok(_)) => Ordering::Less,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+pub(crate) fn supports_workspace_protocol(package_manager: &PackageManager) -> bool {
+    !matches!(package_manager, PackageManager::Npm | PackageManager::Yarn)
+}
+
+// Finds the span of the version of `dependency` in the `group` object of a
+// package.json, e.g. `"^18.2.0"` in `"dependencies": { "react": "^18.2.0" }`
+fn version_span(text: &str, group: &str, dependency: &str) -> Option<SourceSpan> {
+    let root = parse_to_ast(text, &Default::default(), &Default::default())
+        .ok()?
+        .value?;
+    let Value::Object(root) = root else {
+        return None;
+    };
+    // Like `rewrite_json`, the last member wins if a key is duplicated
+    let Value::Object(group) = &root
+        .properties
+        .iter()
+        .rev()
+        .find(|property| property.name.as_str() == group)?
+        .value
+    else {
+        return None;
+    };
+    let Value::StringLit(version) = &group
+        .properties
+        .iter()
+        .rev()
+        .find(|property| property.name.as_str() == dependency)?
+        .value
+    else {
+        return None;
+    };
+    Some((version.range.start, version.range.end - version.range.start).into())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, HashSet};
+
+    use turbopath::AbsoluteSystemPathBuf;
+    use turborepo_errors::Spanned;
+    use turborepo_repository::package_graph::PackageName;
+
+    use super::{check, version_span, Declaration, DepsDiagnostic, PackageJsonSource, Policies};
+
+    const PACKAGE_JSON: &str = r#"{
+  "name": "web",
+  "devDependencies": {
+    "react": "^18.3.0"
+  },
+  "dependencies": {
+    "next": "14.0.0",
+    "react": "^18.2.0"
+  }
+}"#;
+
+    #[test]
+    fn test_version_span() {
+        let span = version_span(PACKAGE_JSON, "dependencies", "react").unwrap();
+        assert_eq!(
+            &PACKAGE_JSON[span.offset()..span.offset() + span.len()],
+            "\"^18.2.0\""
+        );
+        let span = version_span(PACKAGE_JSON, "devDependencies", "react").unwrap();
+        assert_eq!(
+            &PACKAGE_JSON[span.offset()..span.offset() + span.len()],
+            "\"^18.3.0\""
+        );
+        assert!(version_span(PACKAGE_JSON, "dependencies", "vue").is_none());
+        assert!(version_span(PACKAGE_JSON, "peerDependencies", "react").is_none());
+    }
+
+    #[test]
+    fn test_version_span_ignores_nested_keys() {
+        // Brackets inside strings and objects nested in other keys shouldn't
+        // be mistaken for the dependency group
+        let package_json = r#"{
+  "description": "uses { and [",
+  "overrides": {
+    "dependencies": { "react": "18.0.0" }
+  },
+  "dependencies": {
+    "react": "^18.2.0"
+  }
+}"#;
+        let span = version_span(package_json, "dependencies", "react").unwrap();
+        assert_eq!(
+            &package_json[span.offset()..span.offset() + span.len()],
+            "\"^18.2.0\""
+        );
+    }
+
+    fn source() -> PackageJsonSource {
+        PackageJsonSource {
+            path: AbsoluteSystemPathBuf::new(if cfg!(windows) {
+                "C:\\repo\\package.json"
+            } else {
+                "/repo/package.json"
+            })
+            .unwrap(),
+            name: "package.json".to_string(),
+            text: PACKAGE_JSON.to_string(),
+        }
+    }
+
+    fn declaration<'a>(
+        package: &'a PackageName,
+        source: &'a PackageJsonSource,
+        name: &'a str,
+        specifier: &'a str,
+        resolved: Option<&str>,
+    ) -> Declaration<'a> {
+        Declaration {
+            package,
+            source,
+            group: "dependencies",
+            name,
+            specifier,
+            resolved: resolved.map(|version| version.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_single_version() {
+        let web = PackageName::from("web");
+        let docs = PackageName::from("docs");
+        let source = source();
+        let declarations = vec![
+            declaration(&web, &source, "react", "^18.2.0", Some("18.2.0")),
+            declaration(&docs, &source, "react", "^18.3.0", Some("18.3.1")),
+            declaration(&web, &source, "next", "14.0.0", None),
+            declaration(&docs, &source, "next", "14.0.0", None),
+        ];
+        let policies = Policies {
+            single_version: true,
+            ..Default::default()
+        };
+
+        let diagnostics = check(&policies, &declarations, &HashSet::new());
+        assert_eq!(diagnostics.len(), 1);
+        let DepsDiagnostic::MultipleVersions {
+            package,
+            version,
+            expected,
+            ..
+        } = &diagnostics[0]
+        else {
+            panic!("expected MultipleVersions, got {:?}", diagnostics[0]);
+        };
+        assert_eq!(package, &web);
+        assert_eq!(version, "18.2.0");
+        assert_eq!(expected, "^18.3.0");
+
+        let ignored = Policies {
+            single_version: true,
+            ignore: HashSet::from(["react".to_string()]),
+            ..Default::default()
+        };
+        assert!(check(&ignored, &declarations, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_pinned_and_workspace_protocol() {
+        let web = PackageName::from("web");
+        let source = source();
+        let declarations = vec![
+            declaration(&web, &source, "next", "^14.0.0", None),
+            declaration(&web, &source, "ui", "*", None),
+            declaration(&web, &source, "utils", "workspace:*", None),
+        ];
+        let policies = Policies {
+            pinned: BTreeMap::from([("next".to_string(), Spanned::new("14.1.0".to_string()))]),
+            workspace_protocol: true,
+            ..Default::default()
+        };
+
+        let diagnostics = check(&policies, &declarations, &HashSet::from(["ui", "utils"]));
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].fix().unwrap().version, "14.1.0");
+        assert!(matches!(
+            &diagnostics[1],
+            DepsDiagnostic::MissingWorkspaceProtocol { dependency, .. } if dependency == "ui"
+        ));
+        assert_eq!(diagnostics[1].fix().unwrap().version, "workspace:*");
+    }
+
+    #[test]
+    fn test_allowed_ranges() {
+        let web = PackageName::from("web");
+        let source = source();
+        let declarations = vec![
+            declaration(&web, &source, "react", "^17.0.0", Some("17.0.2")),
+            declaration(&web, &source, "next", "14.0.0", None),
+        ];
+        let policies = Policies {
+            allowed_ranges: BTreeMap::from([
+                (
+                    "react".to_string(),
+                    (">=18".parse().unwrap(), Spanned::new(">=18".to_string())),
+                ),
+                (
+                    "next".to_string(),
+                    ("^14".parse().unwrap(), Spanned::new("^14".to_string())),
+                ),
+            ]),
+            ..Default::default()
+        };
+
+        let diagnostics = check(&policies, &declarations, &HashSet::new());
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(
+            &diagnostics[0],
+            DepsDiagnostic::OutsideAllowedRange { version, .. } if version == "17.0.2"
+        ));
+        assert!(diagnostics[0].fix().is_none());
+    }
+
+    #[test]
+    fn test_allowed_ranges_without_lockfile() {
+        let web = PackageName::from("web");
+        let source = source();
+        // None of these resolve, so only the declared specifiers can be checked
+        let declarations = vec![
+            declaration(&web, &source, "react", "^17.0.0", None),
+            declaration(&web, &source, "react-dom", "^18.0.0", None),
+            declaration(&web, &source, "scheduler", "github:facebook/react", None),
+        ];
+        let range = |range: &str| (range.parse().unwrap(), Spanned::new(range.to_string()));
+        let policies = Policies {
+            allowed_ranges: BTreeMap::from([
+                ("react".to_string(), range(">=18")),
+                ("react-dom".to_string(), range(">=18.2.0")),
+                ("scheduler".to_string(), range("^0.23.0")),
+            ]),
+            ..Default::default()
+        };
+
+        let diagnostics = check(&policies, &declarations, &HashSet::new());
+        assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+        assert!(matches!(
+            &diagnostics[0],
+            DepsDiagnostic::DeclaredOutsideAllowedRange { dependency, specifier, .. }
+                if dependency == "react" && specifier == "^17.0.0"
+        ));
+        assert!(matches!(
+            &diagnostics[1],
+            DepsDiagnostic::UnverifiableVersion { dependency, .. } if dependency == "scheduler"
+        ));
+    }
+}
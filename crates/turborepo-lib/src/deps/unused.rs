@@ -0,0 +1,231 @@
+//! Finds the dependencies in a `package.json` that none of the package's
+//! source files import. Dependencies that are used some other way, like
+//! tools that are only configured through a config file, can be listed in
+//! `deps.allowUnused`.
+
+use std::collections::HashSet;
+
+use globwalk::{Settings, WalkType};
+use swc_ecma_visit::VisitWith;
+use tracing::warn;
+use turbo_trace::{ImportFinder, Tracer};
+use turborepo_repository::{package_graph::PackageName, package_json::PackageJson};
+
+use super::{DepsDiagnostic, DepsResult, Error, PackageJsonSource};
+use crate::run::Run;
+
+impl Run {
+    pub async fn check_unused_deps(&self) -> Result<DepsResult, Error> {
+        let allowed = self
+            .root_turbo_json()
+            .deps
+            .as_ref()
+            .and_then(|deps| deps.allow_unused.as_ref())
+            .into_iter()
+            .flat_map(|allow_unused| allow_unused.as_inner())
+            .map(|dependency| dependency.as_inner().as_str())
+            .collect::<HashSet<_>>();
+
+        let mut packages = self
+            .pkg_dep_graph()
+            .packages()
+            .filter(|(name, _)| !matches!(name, PackageName::Root))
+            .collect::<Vec<_>>();
+        packages.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut result = DepsResult::default();
+        for (package, info) in packages {
+            let package_root = self.repo_root().resolve(info.package_path());
+            let files = globwalk::globwalk_with_settings(
+                &package_root,
+                &[
+                    "**/*.js".parse().unwrap(),
+                    "**/*.jsx".parse().unwrap(),
+                    "**/*.ts".parse().unwrap(),
+                    "**/*.tsx".parse().unwrap(),
+                    "**/*.cjs".parse().unwrap(),
+                    "**/*.mjs".parse().unwrap(),
+                    "**/*.cts".parse().unwrap(),
+                    "**/*.mts".parse().unwrap(),
+                ],
+                &["**/node_modules/**".parse().unwrap()],
+                WalkType::Files,
+                Settings::default().ignore_nested_packages(),
+            )?;
+
+            let trace = Tracer::new(package_root, files.into_iter().collect(), None)
+                .parse_files()
+                .await;
+            // We can't see the imports of a file that fails to parse, so its
+            // dependencies could be reported as unused
+            for error in &trace.errors {
+                warn!("unused dependencies of {package} may be inaccurate: {error}");
+            }
+            let imported = trace
+                .files
+                .values()
+                .filter_map(|file| file.ast.as_ref())
+                .flat_map(|ast| {
+                    let mut finder = ImportFinder::default();
+                    ast.visit_with(&mut finder);
+                    finder
+                        .imports()
+                        .iter()
+                        .filter_map(|(specifier, _, _)| imported_package(specifier))
+                        .map(|name| name.to_string())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<HashSet<_>>();
+
+            let package_json = &info.package_json;
+            let groups = [
+                ("dependencies", &package_json.dependencies),
+                ("devDependencies", &package_json.dev_dependencies),
+            ];
+            let declared = groups
+                .iter()
+                .flat_map(|(_, dependencies)| dependencies.iter().flatten())
+                .map(|(name, _)| name.as_str())
+                .collect::<HashSet<_>>();
+            let script_words = script_words(package_json);
+            let source = PackageJsonSource::load(self.repo_root(), info)?;
+
+            for (group, dependencies) in groups {
+                for dependency in dependencies
+                    .iter()
+                    .flat_map(|dependencies| dependencies.keys())
+                {
+                    if allowed.contains(dependency.as_str())
+                        || is_used(dependency, &imported, &declared, &script_words)
+                    {
+                        continue;
+                    }
+                    let (span, text) = source.span_and_text(group, dependency);
+                    result.diagnostics.push(DepsDiagnostic::Unused {
+                        package: package.clone(),
+                        dependency: dependency.clone(),
+                        group,
+                        span,
+                        text,
+                    });
+                }
+            }
+            result.packages_checked += 1;
+        }
+
+        Ok(result)
+    }
+}
+
+// The package that an import specifier refers to, e.g. `@repo/ui` for
+// `@repo/ui/button`. Relative imports and Node.js builtins don't refer to one.
+fn imported_package(specifier: &str) -> Option<&str> {
+    if specifier.starts_with('.') || specifier.starts_with('/') || specifier.starts_with("node:") {
+        return None;
+    }
+    let mut segments = specifier.splitn(3, '/');
+    let first = segments.next()?;
+    if first.starts_with('@') {
+        let second = segments.next()?;
+        Some(&specifier[..first.len() + 1 + second.len()])
+    } else {
+        Some(first)
+    }
+}
+
+// The package that a `@types` package provides types for, e.g. `@scope/name`
+// for `@types/scope__name`
+fn typed_package(dependency: &str) -> Option<String> {
+    let typed = dependency.strip_prefix("@types/")?;
+    Some(match typed.split_once("__") {
+        Some((scope, name)) => format!("@{scope}/{name}"),
+        None => typed.to_string(),
+    })
+}
+
+fn script_words(package_json: &PackageJson) -> HashSet<&str> {
+    package_json
+        .scripts
+        .values()
+        .flat_map(|script| {
+            script.as_inner().split(|c: char| {
+                c.is_whitespace() || matches!(c, '&' | '|' | ';' | '(' | ')' | '"' | '\'' | '=')
+            })
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn is_used(
+    dependency: &str,
+    imported: &HashSet<String>,
+    declared: &HashSet<&str>,
+    script_words: &HashSet<&str>,
+) -> bool {
+    // Types for a package we can't see, like `@types/node`, are assumed to be
+    // used for globals
+    if let Some(typed) = typed_package(dependency) {
+        return !declared.contains(typed.as_str()) || imported.contains(&typed);
+    }
+    // CLI tools are usually run from scripts with a binary that's named after
+    // the package
+    let binary = dependency.rsplit('/').next().unwrap_or(dependency);
+    imported.contains(dependency)
+        || script_words.contains(dependency)
+        || script_words.contains(binary)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use test_case::test_case;
+
+    use super::{imported_package, is_used, typed_package};
+
+    #[test_case("react", Some("react") ; "package")]
+    #[test_case("react-dom/client", Some("react-dom") ; "subpath")]
+    #[test_case("@repo/ui", Some("@repo/ui") ; "scoped package")]
+    #[test_case("@repo/ui/button", Some("@repo/ui") ; "scoped subpath")]
+    #[test_case("./button", None ; "relative")]
+    #[test_case("node:fs", None ; "node builtin")]
+    fn test_imported_package(specifier: &str, expected: Option<&str>) {
+        assert_eq!(imported_package(specifier), expected);
+    }
+
+    #[test]
+    fn test_typed_package() {
+        assert_eq!(typed_package("@types/react").as_deref(), Some("react"));
+        assert_eq!(
+            typed_package("@types/babel__core").as_deref(),
+            Some("@babel/core")
+        );
+        assert_eq!(typed_package("react"), None);
+    }
+
+    #[test]
+    fn test_is_used() {
+        let imported = HashSet::from(["react".to_string()]);
+        let declared = HashSet::from(["react", "lodash", "@types/react", "@types/lodash"]);
+        let script_words = HashSet::from(["tsc", "biome"]);
+
+        assert!(is_used("react", &imported, &declared, &script_words));
+        assert!(!is_used("lodash", &imported, &declared, &script_words));
+        assert!(is_used("@types/react", &imported, &declared, &script_words));
+        assert!(!is_used(
+            "@types/lodash",
+            &imported,
+            &declared,
+            &script_words
+        ));
+        assert!(is_used("@types/node", &imported, &declared, &script_words));
+        assert!(is_used(
+            "@biomejs/biome",
+            &imported,
+            &declared,
+            &script_words
+        ));
+        // The `typescript` binary is `tsc`, so it needs to be allowed
+        assert!(!is_used("typescript", &imported, &declared, &script_words));
+    }
+}
@@ -169,6 +169,28 @@ impl Engine<Built> {
             .flatten()
             .collect();
 
+        self.create_engine_for_entrypoints(&entrypoint_indices)
+    }
+
+    /// Creates an instance of `Engine` that only contains the given tasks and
+    /// the tasks that depend on them. Watch mode uses this to only re-run
+    /// tasks whose inputs changed.
+    pub fn create_engine_for_tasks(
+        &self,
+        changed_tasks: &HashSet<TaskId<'static>>,
+    ) -> Engine<Built> {
+        let entrypoint_indices: Vec<_> = changed_tasks
+            .iter()
+            .filter_map(|task_id| self.task_lookup.get(task_id))
+            .collect();
+
+        self.create_engine_for_entrypoints(&entrypoint_indices)
+    }
+
+    fn create_engine_for_entrypoints(
+        &self,
+        entrypoint_indices: &[&petgraph::graph::NodeIndex],
+    ) -> Engine<Built> {
         // We reverse the graph because we want the *dependents* of entrypoint tasks
         let mut reversed_graph = self.task_graph.clone();
         reversed_graph.reverse();
@@ -900,4 +922,46 @@ mod test {
         assert!(tasks.contains(&&TaskNode::Task(a_dev_task_id)));
         assert!(tasks.contains(&&TaskNode::Task(b_build_task_id)));
     }
+
+    #[tokio::test]
+    async fn test_get_subgraph_for_tasks() {
+        // Verifies that pruning to a set of tasks keeps those tasks and their
+        // dependents, but not sibling tasks in the same package.
+
+        let mut engine = Engine::new();
+
+        let a_build_task_id = TaskId::new("a", "build");
+        let a_dev_task_id = TaskId::new("a", "dev");
+
+        let a_build_idx = engine.get_index(&a_build_task_id);
+        engine.add_definition(a_build_task_id.clone(), TaskDefinition::default());
+
+        engine.get_index(&a_dev_task_id);
+        engine.add_definition(a_dev_task_id.clone(), TaskDefinition::default());
+
+        let b_build_task_id = TaskId::new("b", "build");
+        let b_dev_task_id = TaskId::new("b", "dev");
+
+        let b_build_idx = engine.get_index(&b_build_task_id);
+        engine.add_definition(
+            b_build_task_id.clone(),
+            TaskDefinition {
+                task_dependencies: vec![Spanned::new(TaskName::from(a_build_task_id.clone()))],
+                ..Default::default()
+            },
+        );
+
+        engine.get_index(&b_dev_task_id);
+        engine.add_definition(b_dev_task_id.clone(), TaskDefinition::default());
+        engine.task_graph.add_edge(b_build_idx, a_build_idx, ());
+
+        let engine = engine.seal();
+        let subgraph = engine
+            .create_engine_for_tasks(&[a_build_task_id.clone().into_owned()].into_iter().collect());
+
+        let tasks: Vec<_> = subgraph.tasks().collect();
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks.contains(&&TaskNode::Task(a_build_task_id)));
+        assert!(tasks.contains(&&TaskNode::Task(b_build_task_id)));
+    }
 }
@@ -18,12 +18,14 @@ mod cli;
 mod commands;
 mod config;
 mod daemon;
+mod deps;
 mod diagnostics;
 mod engine;
 
 mod boundaries;
 mod gitignore;
 mod hash;
+mod licenses;
 mod microfrontends;
 mod opts;
 mod package_changes_watcher;
@@ -31,6 +33,7 @@ mod panic_handler;
 mod query;
 mod rewrite_json;
 mod run;
+mod sbom;
 mod shim;
 mod task_graph;
 mod task_hash;
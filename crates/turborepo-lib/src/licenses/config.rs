@@ -0,0 +1,19 @@
+use biome_deserialize_macros::Deserializable;
+use serde::Serialize;
+use struct_iterable::Iterable;
+use turborepo_errors::Spanned;
+
+#[derive(Serialize, Default, Debug, Clone, Iterable, Deserializable, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LicensesConfig {
+    /// SPDX license identifiers that dependencies may use. When set, any other
+    /// license is reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow: Option<Spanned<Vec<Spanned<String>>>>,
+    /// SPDX license identifiers that dependencies may not use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deny: Option<Spanned<Vec<Spanned<String>>>>,
+    /// Dependencies that are exempt from the policies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore: Option<Spanned<Vec<Spanned<String>>>>,
+}
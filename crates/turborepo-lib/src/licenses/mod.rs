@@ -0,0 +1,886 @@
+//! License compliance, checked by `turbo licenses`. The license of every
+//! external package that a workspace depends on, directly or through its
+//! internal dependencies, is read from the package's installed `package.json`
+//! and checked against the `licenses` key of the root `turbo.json`.
+
+mod config;
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    iter::Peekable,
+};
+
+pub use config::LicensesConfig;
+use miette::{Diagnostic, NamedSource, Report, SourceSpan};
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+use tracing::warn;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, RelativeUnixPath};
+use turborepo_errors::Spanned;
+use turborepo_lockfiles::ResolvedDependency;
+use turborepo_repository::package_graph::PackageName;
+use turborepo_ui::{color, ColorConfig, BOLD, BOLD_GREEN, BOLD_RED, GREY};
+
+use crate::run::Run;
+
+// How packages without a license are shown
+const UNKNOWN_LICENSE: &str = "UNKNOWN";
+
+#[derive(Clone, Debug, Error, Diagnostic)]
+pub enum LicenseDiagnostic {
+    #[error("`{dependency}@{version}` is licensed under `{license}`, which is denied")]
+    #[diagnostic(help("used by {packages}"))]
+    Denied {
+        dependency: String,
+        version: String,
+        license: String,
+        packages: String,
+        #[label("denied here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+    #[error("`{dependency}@{version}` is licensed under `{license}`, which is not allowed")]
+    #[diagnostic(help(
+        "used by {packages}. Add the license to `licenses.allow`, or the package to \
+         `licenses.ignore` in turbo.json"
+    ))]
+    NotAllowed {
+        dependency: String,
+        version: String,
+        license: String,
+        packages: String,
+        #[label("allowed licenses defined here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+    #[error("`{dependency}@{version}` does not declare a license")]
+    #[diagnostic(help(
+        "used by {packages}. Add the package to `licenses.ignore` in turbo.json once you've \
+         checked its license"
+    ))]
+    Unlicensed {
+        dependency: String,
+        version: String,
+        packages: String,
+        #[label("allowed licenses defined here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+    #[error("`{dependency}@{version}` is not installed, so its license can't be checked")]
+    #[diagnostic(help(
+        "used by {packages}. Run your package manager's install command, or add the package to \
+         `licenses.ignore` in turbo.json"
+    ))]
+    NotInstalled {
+        dependency: String,
+        version: String,
+        packages: String,
+        #[label("license policy defined here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+}
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error("Could not find package `{0}` in the workspace.")]
+    MissingPackage(String),
+    #[error("Checking licenses requires a lockfile.")]
+    #[diagnostic(help("run your package manager's install command to create one"))]
+    NoLockfile,
+    #[error(transparent)]
+    Lockfile(#[from] turborepo_lockfiles::Error),
+}
+
+/// The licenses of the external packages that a workspace depends on
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceLicenses {
+    name: PackageName,
+    path: String,
+    /// The packages using each license, as `name@version`
+    licenses: BTreeMap<String, BTreeSet<String>>,
+    /// Packages in the lockfile that aren't installed in `node_modules`
+    not_installed: BTreeSet<String>,
+}
+
+pub struct LicensesResult {
+    pub workspaces: Vec<WorkspaceLicenses>,
+    pub diagnostics: Vec<LicenseDiagnostic>,
+}
+
+impl LicensesResult {
+    pub fn is_ok(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn print(&self, color_config: ColorConfig) {
+        for workspace in &self.workspaces {
+            println!(
+                "{} {}",
+                color!(color_config, BOLD, "{}", workspace.name),
+                color!(color_config, GREY, "{}", workspace.path)
+            );
+            let mut licenses = workspace.licenses.iter().collect::<Vec<_>>();
+            // Most used licenses first
+            licenses.sort_by(|(a, a_packages), (b, b_packages)| {
+                b_packages.len().cmp(&a_packages.len()).then(a.cmp(b))
+            });
+            let width = licenses
+                .iter()
+                .map(|(license, _)| license.len())
+                .max()
+                .unwrap_or_default();
+            for (license, packages) in licenses {
+                println!("  {license:width$}  {}", packages.len());
+            }
+            if !workspace.not_installed.is_empty() {
+                println!(
+                    "  {}",
+                    color!(
+                        color_config,
+                        GREY,
+                        "{} packages not installed",
+                        workspace.not_installed.len()
+                    )
+                );
+            }
+            println!();
+        }
+    }
+
+    pub fn emit_diagnostics(&self) {
+        for diagnostic in &self.diagnostics {
+            eprintln!("{:?}", Report::new(diagnostic.clone()));
+        }
+    }
+
+    pub fn emit(&self, color_config: ColorConfig) {
+        self.emit_diagnostics();
+        let result_message = match self.diagnostics.len() {
+            0 => color!(color_config, BOLD_GREEN, "no issues found"),
+            1 => color!(color_config, BOLD_RED, "1 issue found"),
+            _ => color!(
+                color_config,
+                BOLD_RED,
+                "{} issues found",
+                self.diagnostics.len()
+            ),
+        };
+        println!(
+            "Checked licenses of {} workspaces, {}",
+            self.workspaces.len(),
+            result_message
+        );
+    }
+}
+
+// The policies from `LicensesConfig`
+#[derive(Default)]
+struct Policies<'a> {
+    allow: Option<&'a Spanned<Vec<Spanned<String>>>>,
+    deny: Vec<&'a Spanned<String>>,
+    ignore: HashSet<&'a str>,
+}
+
+impl<'a> Policies<'a> {
+    fn new(config: &'a LicensesConfig) -> Self {
+        Self {
+            allow: config.allow.as_ref(),
+            deny: config.deny.iter().flat_map(|deny| deny.iter()).collect(),
+            ignore: config
+                .ignore
+                .iter()
+                .flat_map(|ignore| ignore.iter())
+                .map(|dependency| dependency.as_str())
+                .collect(),
+        }
+    }
+
+    fn denied_by(&self, license: &str) -> Option<&'a Spanned<String>> {
+        self.deny
+            .iter()
+            .find(|denied| denied.eq_ignore_ascii_case(license))
+            .copied()
+    }
+
+    fn is_acceptable(&self, license: &str) -> bool {
+        self.denied_by(license).is_none()
+            && self.allow.is_none_or(|allow| {
+                allow
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(license))
+            })
+    }
+
+    fn is_configured(&self) -> bool {
+        self.allow.is_some() || !self.deny.is_empty()
+    }
+
+    /// A package that isn't installed can't be shown to meet the policies, so
+    /// it's reported whenever there are policies to meet.
+    fn check_not_installed(
+        &self,
+        dependency: &str,
+        version: &str,
+        packages: String,
+    ) -> Option<LicenseDiagnostic> {
+        if self.ignore.contains(dependency) {
+            return None;
+        }
+        let (span, text) = match (self.allow, self.deny.first()) {
+            (Some(allow), _) => allow.span_and_text("turbo.json"),
+            (None, Some(denied)) => denied.span_and_text("turbo.json"),
+            (None, None) => return None,
+        };
+        Some(LicenseDiagnostic::NotInstalled {
+            dependency: dependency.to_string(),
+            version: version.to_string(),
+            packages,
+            span,
+            text,
+        })
+    }
+
+    fn check(
+        &self,
+        dependency: &str,
+        version: &str,
+        license: Option<&str>,
+        packages: String,
+    ) -> Option<LicenseDiagnostic> {
+        if self.ignore.contains(dependency) {
+            return None;
+        }
+        let Some(license) = license else {
+            let (span, text) = self.allow?.span_and_text("turbo.json");
+            return Some(LicenseDiagnostic::Unlicensed {
+                dependency: dependency.to_string(),
+                version: version.to_string(),
+                packages,
+                span,
+                text,
+            });
+        };
+
+        let expression = LicenseExpression::parse(license);
+        if expression.is_satisfied_by(&|license| self.is_acceptable(license)) {
+            return None;
+        }
+        let denied = expression
+            .licenses()
+            .into_iter()
+            .find_map(|license| self.denied_by(license));
+        Some(match denied {
+            Some(denied) => {
+                let (span, text) = denied.span_and_text("turbo.json");
+                LicenseDiagnostic::Denied {
+                    dependency: dependency.to_string(),
+                    version: version.to_string(),
+                    license: license.to_string(),
+                    packages,
+                    span,
+                    text,
+                }
+            }
+            None => {
+                let (span, text) = self.allow?.span_and_text("turbo.json");
+                LicenseDiagnostic::NotAllowed {
+                    dependency: dependency.to_string(),
+                    version: version.to_string(),
+                    license: license.to_string(),
+                    packages,
+                    span,
+                    text,
+                }
+            }
+        })
+    }
+}
+
+/// An SPDX license expression, e.g. `(MIT OR Apache-2.0)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LicenseExpression {
+    License(String),
+    And(Vec<LicenseExpression>),
+    Or(Vec<LicenseExpression>),
+}
+
+impl LicenseExpression {
+    /// Parses an expression. Anything that isn't a valid expression, like
+    /// `SEE LICENSE IN LICENSE.md`, is treated as a single license.
+    fn parse(expression: &str) -> Self {
+        let spaced = expression.replace('(', " ( ").replace(')', " ) ");
+        let mut tokens = spaced.split_whitespace().peekable();
+        let parsed = Self::parse_or(&mut tokens);
+        match tokens.next() {
+            None => parsed,
+            Some(_) => Self::License(expression.trim().to_string()),
+        }
+    }
+
+    fn parse_or<'a>(tokens: &mut Peekable<impl Iterator<Item = &'a str>>) -> Self {
+        let mut terms = vec![Self::parse_and(tokens)];
+        while tokens
+            .next_if(|token| token.eq_ignore_ascii_case("or"))
+            .is_some()
+        {
+            terms.push(Self::parse_and(tokens));
+        }
+        match terms.len() {
+            1 => terms.remove(0),
+            _ => Self::Or(terms),
+        }
+    }
+
+    fn parse_and<'a>(tokens: &mut Peekable<impl Iterator<Item = &'a str>>) -> Self {
+        let mut terms = vec![Self::parse_license(tokens)];
+        while tokens
+            .next_if(|token| token.eq_ignore_ascii_case("and"))
+            .is_some()
+        {
+            terms.push(Self::parse_license(tokens));
+        }
+        match terms.len() {
+            1 => terms.remove(0),
+            _ => Self::And(terms),
+        }
+    }
+
+    fn parse_license<'a>(tokens: &mut Peekable<impl Iterator<Item = &'a str>>) -> Self {
+        if tokens.next_if_eq(&"(").is_some() {
+            let expression = Self::parse_or(tokens);
+            tokens.next_if_eq(&")");
+            return expression;
+        }
+        let license = tokens.next().unwrap_or_default();
+        // Exceptions only grant additional permissions, so the license is
+        // checked without them
+        if tokens
+            .next_if(|token| token.eq_ignore_ascii_case("with"))
+            .is_some()
+        {
+            tokens.next();
+        }
+        Self::License(license.to_string())
+    }
+
+    fn is_satisfied_by(&self, is_acceptable: &impl Fn(&str) -> bool) -> bool {
+        match self {
+            Self::License(license) => is_acceptable(license),
+            Self::And(terms) => terms.iter().all(|term| term.is_satisfied_by(is_acceptable)),
+            Self::Or(terms) => terms.iter().any(|term| term.is_satisfied_by(is_acceptable)),
+        }
+    }
+
+    fn licenses(&self) -> Vec<&str> {
+        match self {
+            Self::License(license) => vec![license.as_str()],
+            Self::And(terms) | Self::Or(terms) => {
+                terms.iter().flat_map(|term| term.licenses()).collect()
+            }
+        }
+    }
+}
+
+/// Reads the license declared in a `package.json`
+fn declared_license(package_json: &Value) -> Option<String> {
+    let license = match package_json.get("license") {
+        Some(Value::String(license)) => Some(license.as_str()),
+        // Deprecated object form, `{ "type": "MIT", "url": "..." }`
+        Some(Value::Object(license)) => license.get("type").and_then(Value::as_str),
+        _ => None,
+    };
+    if let Some(license) = license {
+        return Some(license.to_string());
+    }
+
+    // Deprecated `licenses` array, where any of the licenses can be chosen
+    let licenses = package_json
+        .get("licenses")?
+        .as_array()?
+        .iter()
+        .filter_map(|license| license.get("type")?.as_str())
+        .collect::<Vec<_>>();
+    match licenses.as_slice() {
+        [] => None,
+        [license] => Some(license.to_string()),
+        licenses => Some(format!("({})", licenses.join(" OR "))),
+    }
+}
+
+// The version and license of a package installed in `node_modules`
+#[derive(Clone)]
+struct InstalledPackage {
+    version: String,
+    license: Option<String>,
+}
+
+/// Finds the installed copy of packages from the lockfile
+struct InstalledPackages<'a> {
+    repo_root: &'a AbsoluteSystemPath,
+    package_jsons: HashMap<AbsoluteSystemPathBuf, Option<InstalledPackage>>,
+}
+
+impl<'a> InstalledPackages<'a> {
+    fn new(repo_root: &'a AbsoluteSystemPath) -> Self {
+        Self {
+            repo_root,
+            package_jsons: HashMap::new(),
+        }
+    }
+
+    fn find(
+        &mut self,
+        workspace_path: &str,
+        dependency: &ResolvedDependency,
+    ) -> Option<InstalledPackage> {
+        let name = &dependency.name;
+        let version = &dependency.package.version;
+        let mut candidates = Vec::new();
+        // npm's lockfile keys are the install location
+        if dependency
+            .package
+            .key
+            .ends_with(&format!("node_modules/{name}"))
+        {
+            candidates.push(dependency.package.key.clone());
+        }
+        if !workspace_path.is_empty() {
+            candidates.push(format!("{workspace_path}/node_modules/{name}"));
+        }
+        candidates.push(format!("node_modules/{name}"));
+        // pnpm's virtual store
+        let store_version = version.split_once('(').map_or(version.as_str(), |(v, _)| v);
+        candidates.push(format!(
+            "node_modules/.pnpm/{}@{store_version}/node_modules/{name}",
+            name.replace('/', "+")
+        ));
+        candidates.push(format!("node_modules/.pnpm/node_modules/{name}"));
+
+        candidates.into_iter().find_map(|candidate| {
+            let path = self
+                .repo_root
+                .join_unix_path(RelativeUnixPath::new(&candidate).ok()?)
+                .join_component("package.json");
+            let installed = self
+                .package_jsons
+                .entry(path)
+                .or_insert_with_key(|path| read_installed_package(path))
+                .clone()?;
+            is_same_version(&installed.version, version).then_some(installed)
+        })
+    }
+}
+
+fn read_installed_package(path: &AbsoluteSystemPath) -> Option<InstalledPackage> {
+    let contents = path.read_existing_to_string().ok()??;
+    let package_json = serde_json::from_str::<Value>(&contents).ok()?;
+    Some(InstalledPackage {
+        version: package_json.get("version")?.as_str()?.to_string(),
+        license: declared_license(&package_json),
+    })
+}
+
+// Lockfile versions can have suffixes that aren't in the installed
+// `package.json`, like pnpm's peer dependencies or Bun's patch hashes
+fn is_same_version(installed: &str, resolved: &str) -> bool {
+    let resolved = resolved.split_once('(').map_or(resolved, |(v, _)| v);
+    installed == resolved
+        || resolved
+            .strip_prefix(installed)
+            .is_some_and(|suffix| suffix.starts_with(['+', '_']))
+}
+
+impl Run {
+    /// Reads the licenses of the external packages that each of `packages`, or
+    /// every workspace if empty, depends on and checks them against the
+    /// policies in the root `turbo.json`.
+    pub fn check_licenses(&self, packages: &[String]) -> Result<LicensesResult, Error> {
+        let package_graph = self.pkg_dep_graph();
+        if package_graph.lockfile().is_none() {
+            return Err(Error::NoLockfile);
+        }
+        let policies = match &self.root_turbo_json().licenses {
+            Some(config) => Policies::new(config.as_inner()),
+            None => Policies::default(),
+        };
+
+        let mut targets = if packages.is_empty() {
+            package_graph
+                .packages()
+                .map(|(name, _)| name.clone())
+                .filter(|name| !matches!(name, PackageName::Root))
+                .collect::<Vec<_>>()
+        } else {
+            packages
+                .iter()
+                .map(|package| {
+                    let name = PackageName::from(package.clone());
+                    match package_graph.package_info(&name) {
+                        Some(_) => Ok(name),
+                        None => Err(Error::MissingPackage(package.clone())),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        targets.sort();
+
+        let mut installed_packages = InstalledPackages::new(self.repo_root());
+        // Each workspace's dependencies are shared by every target that depends
+        // on it
+        let mut resolved_by_workspace = HashMap::new();
+        // The targets using each version of each package
+        let mut usages: BTreeMap<(String, String), (Option<String>, BTreeSet<&PackageName>)> =
+            BTreeMap::new();
+        // The targets using each version of each package that isn't installed
+        let mut not_installed_usages: BTreeMap<(String, String), BTreeSet<&PackageName>> =
+            BTreeMap::new();
+        let mut workspaces = Vec::with_capacity(targets.len());
+        for target in &targets {
+            let closure = package_graph.workspace_closure(target);
+
+            let mut licenses: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+            let mut not_installed = BTreeSet::new();
+            for workspace in closure {
+                let Some(info) = package_graph.package_info(workspace) else {
+                    continue;
+                };
+                let workspace_path = info.package_path().to_unix().to_string();
+                if !resolved_by_workspace.contains_key(workspace) {
+                    let resolved = package_graph.resolved_external_dependencies(workspace)?;
+                    resolved_by_workspace.insert(workspace, resolved);
+                }
+
+                for dependency in &resolved_by_workspace[workspace] {
+                    let display_name =
+                        format!("{}@{}", dependency.name, dependency.package.version);
+                    let Some(installed) = installed_packages.find(&workspace_path, dependency)
+                    else {
+                        not_installed.insert(display_name);
+                        not_installed_usages
+                            .entry((dependency.name.clone(), dependency.package.version.clone()))
+                            .or_default()
+                            .insert(target);
+                        continue;
+                    };
+                    licenses
+                        .entry(
+                            installed
+                                .license
+                                .clone()
+                                .unwrap_or_else(|| UNKNOWN_LICENSE.to_string()),
+                        )
+                        .or_default()
+                        .insert(display_name);
+                    usages
+                        .entry((dependency.name.clone(), installed.version))
+                        .or_insert_with(|| (installed.license, BTreeSet::new()))
+                        .1
+                        .insert(target);
+                }
+            }
+
+            // With policies configured, each package that isn't installed is
+            // reported as a diagnostic instead
+            if !not_installed.is_empty() && !policies.is_configured() {
+                warn!(
+                    "{} packages that {target} depends on are not installed, run your package \
+                     manager's install command to check their licenses",
+                    not_installed.len()
+                );
+            }
+            let path = package_graph
+                .package_dir(target)
+                .map(|path| path.to_unix().to_string())
+                .unwrap_or_default();
+            workspaces.push(WorkspaceLicenses {
+                name: target.clone(),
+                path,
+                licenses,
+                not_installed,
+            });
+        }
+
+        let format_packages = |targets: &BTreeSet<&PackageName>| {
+            targets
+                .iter()
+                .map(|target| format!("`{target}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let diagnostics =
+            usages
+                .iter()
+                .filter_map(|((dependency, version), (license, targets))| {
+                    policies.check(
+                        dependency,
+                        version,
+                        license.as_deref(),
+                        format_packages(targets),
+                    )
+                })
+                .chain(not_installed_usages.iter().filter_map(
+                    |((dependency, version), targets)| {
+                        policies.check_not_installed(dependency, version, format_packages(targets))
+                    },
+                ))
+                .collect();
+
+        Ok(LicensesResult {
+            workspaces,
+            diagnostics,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use tempfile::TempDir;
+    use test_case::test_case;
+    use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, RelativeUnixPath};
+    use turborepo_errors::Spanned;
+    use turborepo_lockfiles::ResolvedDependency;
+
+    use super::{
+        declared_license, is_same_version, InstalledPackages, LicenseDiagnostic, LicenseExpression,
+        LicensesConfig, Policies,
+    };
+
+    fn config(allow: Option<&[&str]>, deny: &[&str], ignore: &[&str]) -> LicensesConfig {
+        let list = |values: &[&str]| -> Spanned<Vec<Spanned<String>>> {
+            Spanned::new(
+                values
+                    .iter()
+                    .map(|value| Spanned::new(value.to_string()))
+                    .collect(),
+            )
+        };
+        LicensesConfig {
+            allow: allow.map(list),
+            deny: Some(list(deny)),
+            ignore: Some(list(ignore)),
+        }
+    }
+
+    fn dependency(name: &str, key: &str, version: &str) -> ResolvedDependency {
+        ResolvedDependency {
+            name: name.to_string(),
+            specifier: version.to_string(),
+            package: turborepo_lockfiles::Package::new(key, version),
+            parent: None,
+        }
+    }
+
+    fn write_package(repo_root: &AbsoluteSystemPath, path: &str, version: &str, license: &str) {
+        let package_json = repo_root
+            .join_unix_path(RelativeUnixPath::new(path).unwrap())
+            .join_component("package.json");
+        package_json.ensure_dir().unwrap();
+        package_json
+            .create_with_contents(json!({ "version": version, "license": license }).to_string())
+            .unwrap();
+    }
+
+    #[test_case("MIT", LicenseExpression::License("MIT".into()) ; "single license")]
+    #[test_case(
+        "(MIT OR Apache-2.0)",
+        LicenseExpression::Or(vec![
+            LicenseExpression::License("MIT".into()),
+            LicenseExpression::License("Apache-2.0".into()),
+        ]) ; "or"
+    )]
+    #[test_case(
+        "MIT AND (BSD-2-Clause or ISC)",
+        LicenseExpression::And(vec![
+            LicenseExpression::License("MIT".into()),
+            LicenseExpression::Or(vec![
+                LicenseExpression::License("BSD-2-Clause".into()),
+                LicenseExpression::License("ISC".into()),
+            ]),
+        ]) ; "nested"
+    )]
+    #[test_case(
+        "GPL-2.0-only WITH Classpath-exception-2.0",
+        LicenseExpression::License("GPL-2.0-only".into()) ; "exception"
+    )]
+    #[test_case(
+        "SEE LICENSE IN LICENSE.md",
+        LicenseExpression::License("SEE LICENSE IN LICENSE.md".into()) ; "not an expression"
+    )]
+    fn test_parse_expression(expression: &str, expected: LicenseExpression) {
+        assert_eq!(LicenseExpression::parse(expression), expected);
+    }
+
+    #[test_case("(MIT OR GPL-3.0-only)", true ; "any alternative")]
+    #[test_case("MIT AND GPL-3.0-only", false ; "every term")]
+    #[test_case("GPL-3.0-only", false ; "single license")]
+    fn test_is_satisfied_by(expression: &str, expected: bool) {
+        let is_acceptable = |license: &str| license == "MIT";
+        assert_eq!(
+            LicenseExpression::parse(expression).is_satisfied_by(&is_acceptable),
+            expected
+        );
+    }
+
+    #[test_case(json!({ "license": "MIT" }), Some("MIT") ; "string")]
+    #[test_case(json!({ "license": { "type": "ISC" } }), Some("ISC") ; "object")]
+    #[test_case(
+        json!({ "licenses": [{ "type": "MIT" }, { "type": "Apache-2.0" }] }),
+        Some("(MIT OR Apache-2.0)") ; "array"
+    )]
+    #[test_case(json!({ "name": "foo" }), None ; "missing")]
+    fn test_declared_license(package_json: serde_json::Value, expected: Option<&str>) {
+        assert_eq!(declared_license(&package_json).as_deref(), expected);
+    }
+
+    #[test_case(None, &["GPL-3.0-only"], &[], Some("GPL-3.0-only"), Some("denied") ; "denied")]
+    #[test_case(Some(&["MIT"]), &[], &[], Some("ISC"), Some("not allowed") ; "not allowed")]
+    #[test_case(Some(&["MIT"]), &[], &[], None, Some("unlicensed") ; "unlicensed with allow")]
+    #[test_case(None, &["GPL-3.0-only"], &[], None, None ; "unlicensed without allow")]
+    #[test_case(Some(&["MIT"]), &[], &["foo"], Some("ISC"), None ; "ignored")]
+    #[test_case(Some(&["MIT"]), &[], &[], Some("(ISC OR MIT)"), None ; "allowed alternative")]
+    #[test_case(
+        Some(&["MIT"]),
+        &["GPL-3.0-only"],
+        &[],
+        Some("MIT AND GPL-3.0-only"),
+        Some("denied") ; "denied term"
+    )]
+    #[test_case(Some(&["mit"]), &[], &[], Some("MIT"), None ; "case insensitive")]
+    fn test_check(
+        allow: Option<&[&str]>,
+        deny: &[&str],
+        ignore: &[&str],
+        license: Option<&str>,
+        expected: Option<&str>,
+    ) {
+        let config = config(allow, deny, ignore);
+        let diagnostic = Policies::new(&config).check("foo", "1.0.0", license, "`web`".to_string());
+        let actual = diagnostic.map(|diagnostic| match diagnostic {
+            LicenseDiagnostic::Denied { .. } => "denied",
+            LicenseDiagnostic::NotAllowed { .. } => "not allowed",
+            LicenseDiagnostic::Unlicensed { .. } => "unlicensed",
+            LicenseDiagnostic::NotInstalled { .. } => "not installed",
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test_case(None, &[], &[], false ; "no policies")]
+    #[test_case(Some(&["MIT"]), &[], &[], true ; "allow")]
+    #[test_case(None, &["GPL-3.0-only"], &[], true ; "deny")]
+    #[test_case(Some(&["MIT"]), &[], &["foo"], false ; "ignored")]
+    fn test_check_not_installed(
+        allow: Option<&[&str]>,
+        deny: &[&str],
+        ignore: &[&str],
+        expected: bool,
+    ) {
+        let config = config(allow, deny, ignore);
+        let diagnostic =
+            Policies::new(&config).check_not_installed("foo", "1.0.0", "`web`".to_string());
+        assert_eq!(
+            matches!(diagnostic, Some(LicenseDiagnostic::NotInstalled { .. })),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_find_npm_nested() {
+        let tmp = TempDir::new().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        write_package(&repo_root, "node_modules/is-number", "7.0.0", "MIT");
+        write_package(
+            &repo_root,
+            "node_modules/is-odd/node_modules/is-number",
+            "6.0.0",
+            "ISC",
+        );
+        write_package(
+            &repo_root,
+            "apps/web/node_modules/is-number",
+            "5.0.0",
+            "Apache-2.0",
+        );
+
+        let mut installed = InstalledPackages::new(&repo_root);
+        let nested = installed
+            .find(
+                "apps/docs",
+                &dependency(
+                    "is-number",
+                    "node_modules/is-odd/node_modules/is-number",
+                    "6.0.0",
+                ),
+            )
+            .unwrap();
+        assert_eq!(nested.version, "6.0.0");
+        assert_eq!(nested.license.as_deref(), Some("ISC"));
+
+        let workspace = installed
+            .find(
+                "apps/web",
+                &dependency("is-number", "apps/web/node_modules/is-number", "5.0.0"),
+            )
+            .unwrap();
+        assert_eq!(workspace.license.as_deref(), Some("Apache-2.0"));
+
+        let hoisted = installed
+            .find(
+                "apps/web",
+                &dependency("is-number", "node_modules/is-number", "7.0.0"),
+            )
+            .unwrap();
+        assert_eq!(hoisted.license.as_deref(), Some("MIT"));
+    }
+
+    #[test]
+    fn test_find_pnpm_store() {
+        let tmp = TempDir::new().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        write_package(
+            &repo_root,
+            "node_modules/.pnpm/@scope+ui@1.0.0/node_modules/@scope/ui",
+            "1.0.0",
+            "MIT",
+        );
+        // A hoisted copy at a different version is skipped
+        write_package(&repo_root, "node_modules/@scope/ui", "2.0.0", "ISC");
+
+        let mut installed = InstalledPackages::new(&repo_root);
+        let found = installed
+            .find(
+                "apps/web",
+                &dependency(
+                    "@scope/ui",
+                    "/@scope/ui@1.0.0(react@18.2.0)",
+                    "1.0.0(react@18.2.0)",
+                ),
+            )
+            .unwrap();
+        assert_eq!(found.version, "1.0.0");
+        assert_eq!(found.license.as_deref(), Some("MIT"));
+
+        assert!(installed
+            .find(
+                "apps/web",
+                &dependency("@scope/ui", "/@scope/ui@3.0.0", "3.0.0"),
+            )
+            .is_none());
+    }
+
+    #[test_case("1.0.0", "1.0.0", true ; "same")]
+    #[test_case("1.0.0", "1.0.0(react@18.2.0)", true ; "pnpm peer suffix")]
+    #[test_case("1.0.0", "1.0.0+abc123", true ; "bun patch")]
+    #[test_case("1.0.0", "1.0.1", false ; "different")]
+    #[test_case("1.0.0", "1.0.0-beta.1", false ; "prerelease")]
+    fn test_is_same_version(installed: &str, resolved: &str, expected: bool) {
+        assert_eq!(is_same_version(installed, resolved), expected);
+    }
+}
@@ -13,7 +13,7 @@ use crate::{
         Command, ContinueMode, DryRunMode, EnvMode, ExecutionArgs, LogOrder, LogPrefix,
         OutputLogsMode, RunArgs,
     },
-    config::{ConfigurationOptions, CONFIG_FILE},
+    config::{AnalyticsSinkKind, ConfigurationOptions, CONFIG_FILE},
     turbo_json::{FutureFlags, UIMode},
     Args,
 };
@@ -39,6 +39,12 @@ pub enum Error {
          `remoteCacheReadOnly`) at the same time."
     )]
     OverlappingCacheOptions,
+    #[error(
+        "Invalid OpenTelemetry header: `{0}`. Headers should be comma separated `key=value` pairs."
+    )]
+    InvalidOtelHeader(String),
+    #[error("`TURBO_ANALYTICS_URL` must be set to use the `webhook` analytics sink.")]
+    MissingAnalyticsUrl,
     #[error(transparent)]
     Path(#[from] turbopath::PathError),
     #[error(transparent)]
@@ -90,6 +96,9 @@ impl Opts {
 
         if self.scope_opts.affected_range.is_some() {
             cmd.push_str(" --affected");
+            if self.scope_opts.affected_by_imports {
+                cmd.push_str(" --affected-by-imports");
+            }
         }
 
         if self.run_opts.parallel {
@@ -146,11 +155,15 @@ impl Opts {
             }) => (execution_args, run_args),
             Some(Command::Watch { execution_args, .. }) => (execution_args, &Box::default()),
             Some(Command::Ls {
-                affected, filter, ..
+                affected,
+                affected_by_imports,
+                filter,
+                ..
             }) => {
                 let execution_args = ExecutionArgs {
                     filter: filter.clone(),
                     affected: *affected,
+                    affected_by_imports: *affected_by_imports,
                     ..Default::default()
                 };
 
@@ -240,6 +253,51 @@ pub struct RunOpts {
     pub summarize: bool,
     pub is_github_actions: bool,
     pub ui_mode: UIMode,
+    pub(crate) otel: Option<OtelOpts>,
+    pub(crate) analytics: AnalyticsOpts,
+    pub(crate) compare: Option<CompareOpts>,
+    pub(crate) run_history: Option<RunHistoryOpts>,
+}
+
+/// Where cache usage analytics events are sent.
+#[derive(Clone, Debug, Default, Serialize)]
+pub enum AnalyticsOpts {
+    #[default]
+    Vercel,
+    File(AbsoluteSystemPathBuf),
+    Webhook {
+        url: String,
+        // Usually carries credentials for the endpoint
+        #[serde(skip)]
+        auth_header: Option<String>,
+    },
+    Stdout,
+}
+
+/// Options for comparing a run with a previous run summary.
+#[derive(Clone, Debug, Serialize)]
+pub struct CompareOpts {
+    // A run ID or `last`
+    pub run_id: String,
+    // Percentage change in a task's duration that gets reported
+    pub threshold: f64,
+    pub fail_on_regression: bool,
+}
+
+/// Retention limits for the run history.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct RunHistoryOpts {
+    pub max_runs: u64,
+    pub max_age_days: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct OtelOpts {
+    pub endpoint: String,
+    // Headers usually carry credentials for the collector
+    #[serde(skip)]
+    pub headers: Vec<(String, String)>,
+    pub service_name: String,
 }
 
 /// Projection of `RunOpts` that only includes information necessary to compute
@@ -310,6 +368,7 @@ impl<'a> From<OptsInputs<'a>> for RepoOpts {
 }
 
 const DEFAULT_CONCURRENCY: u32 = 10;
+const DEFAULT_ANALYTICS_FILE: &str = "analytics.jsonl";
 
 impl<'a> TryFrom<OptsInputs<'a>> for RunOpts {
     type Error = self::Error;
@@ -351,6 +410,62 @@ impl<'a> TryFrom<OptsInputs<'a>> for RunOpts {
             ),
         };
 
+        let otel = inputs
+            .config
+            .otel_endpoint()
+            .map(|endpoint| -> Result<_, Error> {
+                Ok(OtelOpts {
+                    endpoint: endpoint.to_string(),
+                    headers: inputs
+                        .config
+                        .otel_headers()
+                        .map(parse_otel_headers)
+                        .transpose()?
+                        .unwrap_or_default(),
+                    service_name: inputs.config.otel_service_name().to_string(),
+                })
+            })
+            .transpose()?;
+
+        let analytics = match inputs.config.analytics_sink() {
+            AnalyticsSinkKind::Vercel => AnalyticsOpts::Vercel,
+            AnalyticsSinkKind::File => AnalyticsOpts::File(
+                inputs
+                    .config
+                    .analytics_file()
+                    .map(|file| AbsoluteSystemPathBuf::from_unknown(inputs.repo_root, file))
+                    .unwrap_or_else(|| {
+                        inputs
+                            .repo_root
+                            .join_components(&[".turbo", DEFAULT_ANALYTICS_FILE])
+                    }),
+            ),
+            AnalyticsSinkKind::Webhook => AnalyticsOpts::Webhook {
+                url: inputs
+                    .config
+                    .analytics_url()
+                    .ok_or(Error::MissingAnalyticsUrl)?
+                    .to_string(),
+                auth_header: inputs.config.analytics_auth_header().map(str::to_string),
+            },
+            AnalyticsSinkKind::Stdout => AnalyticsOpts::Stdout,
+        };
+
+        let compare = inputs
+            .run_args
+            .compare_to
+            .as_ref()
+            .map(|run_id| CompareOpts {
+                run_id: run_id.clone(),
+                threshold: inputs.run_args.compare_threshold,
+                fail_on_regression: inputs.run_args.fail_on_regression,
+            });
+
+        let run_history = inputs.config.run_history().then(|| RunHistoryOpts {
+            max_runs: inputs.config.run_history_max_runs(),
+            max_age_days: inputs.config.run_history_max_age_days(),
+        });
+
         Ok(Self {
             tasks: inputs.execution_args.tasks.clone(),
             log_prefix,
@@ -371,6 +486,10 @@ impl<'a> TryFrom<OptsInputs<'a>> for RunOpts {
             cache_dir: inputs.config.cache_dir().into(),
             is_github_actions,
             ui_mode: inputs.config.ui(),
+            otel,
+            analytics,
+            compare,
+            run_history,
         })
     }
 }
@@ -396,6 +515,22 @@ fn parse_concurrency(concurrency_raw: &str) -> Result<u32, self::Error> {
     }
 }
 
+// Parses headers in the same `key=value,key2=value2` format as
+// `OTEL_EXPORTER_OTLP_HEADERS`
+fn parse_otel_headers(headers_raw: &str) -> Result<Vec<(String, String)>, self::Error> {
+    headers_raw
+        .split(',')
+        .map(str::trim)
+        .filter(|header| !header.is_empty())
+        .map(|header| match header.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(Error::InvalidOtelHeader(header.to_string())),
+        })
+        .collect()
+}
+
 impl From<LogPrefix> for ResolvedLogPrefix {
     fn from(value: LogPrefix) -> Self {
         match value {
@@ -412,6 +547,8 @@ pub struct ScopeOpts {
     pub global_deps: Vec<String>,
     pub filter_patterns: Vec<String>,
     pub affected_range: Option<(Option<String>, Option<String>)>,
+    /// Narrow `affected_range` to the dependents that import changed files
+    pub affected_by_imports: bool,
 }
 
 impl<'a> TryFrom<OptsInputs<'a>> for ScopeOpts {
@@ -438,6 +575,7 @@ impl<'a> TryFrom<OptsInputs<'a>> for ScopeOpts {
             global_deps: inputs.execution_args.global_deps.clone(),
             pkg_inference_root,
             affected_range,
+            affected_by_imports: inputs.execution_args.affected_by_imports,
             filter_patterns: inputs.execution_args.filter.clone(),
         })
     }
@@ -697,6 +835,10 @@ mod test {
             dry_run: opts_input.dry_run,
             graph: None,
             ui_mode: UIMode::Stream,
+            otel: None,
+            analytics: super::AnalyticsOpts::Vercel,
+            compare: None,
+            run_history: None,
             single_package: false,
             log_prefix: crate::opts::ResolvedLogPrefix::Task,
             log_order: crate::opts::ResolvedLogOrder::Stream,
@@ -718,6 +860,7 @@ mod test {
             affected_range: opts_input
                 .affected
                 .map(|(base, head)| (Some(base), Some(head))),
+            affected_by_imports: false,
         };
         let config = ConfigurationOptions::default();
         let root_turbo_json_path = config
@@ -960,4 +1103,16 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_otel_headers() {
+        assert_eq!(
+            super::parse_otel_headers("x-api-key=secret, x-team = acme,").unwrap(),
+            vec![
+                ("x-api-key".to_string(), "secret".to_string()),
+                ("x-team".to_string(), "acme".to_string()),
+            ]
+        );
+        assert!(super::parse_otel_headers("x-api-key").is_err());
+    }
 }
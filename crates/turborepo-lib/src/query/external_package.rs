@@ -1,18 +1,55 @@
-use std::sync::Arc;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
 
-use async_graphql::Object;
+use async_graphql::{Object, SimpleObject};
+use turborepo_repository::package_graph::PackageName;
 
-use crate::run::Run;
+use crate::{
+    query::{package::Package, Array, Error},
+    run::Run,
+};
 
 #[derive(Clone)]
 pub struct ExternalPackage {
     run: Arc<Run>,
     package: turborepo_lockfiles::Package,
+    // Shared between all the packages returned by a query so we only resolve
+    // the lockfile once
+    usages: Option<Arc<Vec<ExternalPackageUsage>>>,
+}
+
+/// A workspace's dependency on an external package, either declared in its
+/// `package.json` or through other external packages or workspaces
+struct ExternalPackageUsage {
+    workspace: PackageName,
+    name: String,
+    specifier: String,
+    package: turborepo_lockfiles::Package,
+    direct: bool,
 }
 
 impl ExternalPackage {
     pub fn new(run: Arc<Run>, package: turborepo_lockfiles::Package) -> Self {
-        Self { run, package }
+        Self {
+            run,
+            package,
+            usages: None,
+        }
+    }
+
+    fn with_usages(
+        run: Arc<Run>,
+        package: turborepo_lockfiles::Package,
+        usages: Arc<Vec<ExternalPackageUsage>>,
+    ) -> Self {
+        Self {
+            run,
+            package,
+            usages: Some(usages),
+        }
     }
 
     /// Converts the lockfile key to a human friendly name
@@ -23,11 +60,234 @@ impl ExternalPackage {
             .and_then(|lockfile| lockfile.human_name(&self.package))
             .unwrap_or_else(|| self.package.key.clone())
     }
+
+    /// The external packages that match `name`, or all of them, that any
+    /// workspace depends on
+    pub fn resolve_all(run: Arc<Run>, name: Option<&str>) -> Result<Array<Self>, Error> {
+        let usages = Arc::new(external_package_usages(&run)?);
+        let packages = usages
+            .iter()
+            .filter(|usage| name.is_none_or(|name| usage.name == name))
+            .map(|usage| &usage.package)
+            .collect::<HashSet<_>>();
+
+        let mut packages = packages
+            .into_iter()
+            .map(|package| Self::with_usages(run.clone(), package.clone(), usages.clone()))
+            .collect::<Array<_>>();
+        packages.sort_by_cached_key(|package| package.human_name());
+        Ok(packages)
+    }
+
+    /// The external packages that workspaces depend on at more than one
+    /// version
+    pub fn duplicates(run: Arc<Run>) -> Result<Array<DuplicatedExternalPackage>, Error> {
+        let usages = Arc::new(external_package_usages(&run)?);
+        let mut packages_by_name = BTreeMap::<&str, HashSet<&turborepo_lockfiles::Package>>::new();
+        for usage in usages.iter() {
+            packages_by_name
+                .entry(usage.name.as_str())
+                .or_default()
+                .insert(&usage.package);
+        }
+
+        Ok(packages_by_name
+            .into_iter()
+            .filter(|(_, packages)| {
+                packages
+                    .iter()
+                    .map(|package| &package.version)
+                    .collect::<HashSet<_>>()
+                    .len()
+                    > 1
+            })
+            .map(|(name, packages)| {
+                let mut versions = packages
+                    .into_iter()
+                    .map(|package| Self::with_usages(run.clone(), package.clone(), usages.clone()))
+                    .collect::<Array<_>>();
+                versions.sort_by(|a, b| compare_versions(&a.package.version, &b.package.version));
+                DuplicatedExternalPackage {
+                    name: name.to_string(),
+                    versions,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Orders lockfile versions by semver. Versions can have suffixes, like pnpm's
+/// peer dependencies, and ones that still aren't valid semver are sorted last.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |version: &str| {
+        let version = version.split_once('(').map_or(version, |(v, _)| v);
+        semver::Version::parse(version).ok()
+    };
+    match (parse(a), parse(b)) {
+        (Some(a_version), Some(b_version)) => a_version.cmp(&b_version).then_with(|| a.cmp(b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Resolves every external package that each workspace depends on, directly or
+/// through other external packages or workspaces, using the lockfile.
+fn external_package_usages(run: &Run) -> Result<Vec<ExternalPackageUsage>, Error> {
+    let package_graph = run.pkg_dep_graph();
+    if package_graph.lockfile().is_none() {
+        return Err(Error::NoLockfile);
+    }
+
+    let mut resolved_by_workspace = HashMap::new();
+    for (workspace, _) in package_graph.packages() {
+        match package_graph.resolved_external_dependencies(workspace) {
+            Ok(resolved) => {
+                resolved_by_workspace.insert(workspace, resolved);
+            }
+            Err(turborepo_lockfiles::Error::MissingWorkspace(_)) => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let mut usages = Vec::new();
+    for (workspace, _) in package_graph.packages() {
+        // The workspace's own dependencies come first, and direct dependencies
+        // are resolved before transitive ones, so a package that is also
+        // declared directly is marked as direct
+        let closure = std::iter::once(workspace).chain(
+            package_graph
+                .workspace_closure(workspace)
+                .into_iter()
+                .filter(|dependency| *dependency != workspace),
+        );
+        let mut seen = HashSet::new();
+        for member in closure {
+            for dependency in resolved_by_workspace.get(member).into_iter().flatten() {
+                if !seen.insert(&dependency.package) {
+                    continue;
+                }
+                usages.push(ExternalPackageUsage {
+                    workspace: workspace.clone(),
+                    name: dependency.name.clone(),
+                    specifier: dependency.specifier.clone(),
+                    package: dependency.package.clone(),
+                    direct: member == workspace && dependency.parent.is_none(),
+                });
+            }
+        }
+    }
+
+    Ok(usages)
+}
+
+/// A dependency of an external package, as listed in the lockfile
+#[derive(SimpleObject)]
+pub struct LockfileDependency {
+    name: String,
+    /// The version range that the package depends on
+    specifier: String,
+}
+
+/// A workspace that depends on an external package
+#[derive(SimpleObject)]
+pub struct ExternalPackageDependent {
+    package: Package,
+    /// Whether the workspace declares the dependency in its `package.json`,
+    /// rather than getting it through other external packages or workspaces
+    direct: bool,
+    /// The version range that resolved to the external package
+    specifier: String,
+}
+
+/// An external package that is resolved to more than one version
+#[derive(SimpleObject)]
+pub struct DuplicatedExternalPackage {
+    name: String,
+    versions: Array<ExternalPackage>,
 }
 
 #[Object]
 impl ExternalPackage {
     async fn name(&self) -> String {
-        self.human_name().to_string()
+        self.human_name()
+    }
+
+    /// The key of the package in the lockfile
+    async fn key(&self) -> String {
+        self.package.key.clone()
+    }
+
+    /// The version that the lockfile resolves the package to
+    async fn version(&self) -> String {
+        self.package.version.clone()
+    }
+
+    /// The dependencies of the package, as listed in the lockfile
+    async fn dependencies(&self) -> Result<Array<LockfileDependency>, Error> {
+        let lockfile = self
+            .run
+            .pkg_dep_graph()
+            .lockfile()
+            .ok_or(Error::NoLockfile)?;
+        // npm lists dependencies by their lockfile key, so they're converted
+        // to package names the same way resolved packages are
+        let mut dependencies = lockfile
+            .all_dependencies(&self.package.key)?
+            .into_iter()
+            .flatten()
+            .map(|(name, specifier)| {
+                let package = turborepo_lockfiles::Package::new(name, specifier);
+                LockfileDependency {
+                    name: lockfile
+                        .package_name(&package)
+                        .unwrap_or_else(|| package.key.clone()),
+                    specifier: package.version,
+                }
+            })
+            .collect::<Array<_>>();
+        dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(dependencies)
+    }
+
+    /// The workspaces that depend on this version of the package, directly or
+    /// transitively
+    async fn dependents(&self) -> Result<Array<ExternalPackageDependent>, Error> {
+        let usages = match &self.usages {
+            Some(usages) => usages.clone(),
+            None => Arc::new(external_package_usages(&self.run)?),
+        };
+
+        let mut dependents = usages
+            .iter()
+            .filter(|usage| usage.package == self.package)
+            .map(|usage| {
+                Ok(ExternalPackageDependent {
+                    package: Package::new(self.run.clone(), usage.workspace.clone())?,
+                    direct: usage.direct,
+                    specifier: usage.specifier.clone(),
+                })
+            })
+            .collect::<Result<Array<_>, Error>>()?;
+        dependents.sort_by(|a, b| a.package.get_name().cmp(b.package.get_name()));
+        Ok(dependents)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+
+    use test_case::test_case;
+
+    use super::compare_versions;
+
+    #[test_case("9.0.0", "10.0.0", Ordering::Less ; "numeric")]
+    #[test_case("1.0.0-beta.1", "1.0.0", Ordering::Less ; "prerelease")]
+    #[test_case("1.0.0(react@18.2.0)", "1.0.1", Ordering::Less ; "pnpm peer suffix")]
+    #[test_case("1.0.0(react@18.2.0)", "1.0.0(react@17.0.2)", Ordering::Greater ; "same version")]
+    #[test_case("github:user/repo", "1.0.0", Ordering::Greater ; "not semver")]
+    fn test_compare_versions(a: &str, b: &str, expected: Ordering) {
+        assert_eq!(compare_versions(a, b), expected);
     }
 }
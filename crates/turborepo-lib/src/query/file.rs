@@ -201,8 +201,14 @@ impl File {
         if let Some(import_type) = import_type {
             tracer.set_import_type(import_type.into());
         }
+        if let Some(workspace_packages) = self.run.yarn_pnp_workspace_packages() {
+            tracer.set_yarn_pnp(workspace_packages);
+        }
+        let cache = self.run.import_cache();
+        tracer.set_cache(cache.clone());
 
         let mut result = tracer.trace(depth).await;
+        self.run.save_import_cache(&cache).await;
         if emit_errors.unwrap_or(true) {
             result.emit_errors();
         }
@@ -225,8 +231,14 @@ impl File {
         if let Some(import_type) = import_type {
             tracer.set_import_type(import_type.into());
         }
+        if let Some(workspace_packages) = self.run.yarn_pnp_workspace_packages() {
+            tracer.set_yarn_pnp(workspace_packages);
+        }
+        let cache = self.run.import_cache();
+        tracer.set_cache(cache.clone());
 
         let mut result = tracer.reverse_trace().await;
+        self.run.save_import_cache(&cache).await;
         result.emit_errors();
         // Remove the file itself from the result
         result.files.remove(&self.path);
@@ -14,7 +14,9 @@ use std::{
 
 use async_graphql::{http::GraphiQLSource, *};
 use axum::{response, response::IntoResponse};
-use external_package::ExternalPackage;
+use external_package::{
+    DuplicatedExternalPackage, ExternalPackage, ExternalPackageDependent, LockfileDependency,
+};
 use itertools::Itertools;
 use package::Package;
 use package_graph::{Edge, PackageGraph};
@@ -28,7 +30,10 @@ use turborepo_signals::SignalHandler;
 
 use crate::{
     get_version,
-    query::{file::File, task::RepositoryTask},
+    query::{
+        file::File,
+        task::{RepositoryTask, TaskRun},
+    },
     run::{builder::RunBuilder, Run},
 };
 
@@ -63,6 +68,12 @@ pub enum Error {
     Parse(swc_ecma_parser::error::Error),
     #[error(transparent)]
     SignalListener(#[from] turborepo_signals::listeners::Error),
+    #[error(transparent)]
+    RunHistory(#[from] crate::run::history::Error),
+    #[error("No lockfile found.")]
+    NoLockfile,
+    #[error(transparent)]
+    Lockfile(#[from] turborepo_lockfiles::Error),
 }
 
 pub struct RepositoryQuery {
@@ -153,10 +164,14 @@ impl RepositoryQuery {
 
 #[derive(Debug, SimpleObject)]
 #[graphql(concrete(name = "RepositoryTasks", params(RepositoryTask)))]
+#[graphql(concrete(name = "TaskRuns", params(TaskRun)))]
 #[graphql(concrete(name = "Packages", params(Package)))]
 #[graphql(concrete(name = "ChangedPackages", params(ChangedPackage)))]
 #[graphql(concrete(name = "Files", params(File)))]
 #[graphql(concrete(name = "ExternalPackages", params(ExternalPackage)))]
+#[graphql(concrete(name = "ExternalPackageDependents", params(ExternalPackageDependent)))]
+#[graphql(concrete(name = "DuplicatedExternalPackages", params(DuplicatedExternalPackage)))]
+#[graphql(concrete(name = "LockfileDependencies", params(LockfileDependency)))]
 #[graphql(concrete(name = "Diagnostics", params(Diagnostic)))]
 #[graphql(concrete(name = "Edges", params(Edge)))]
 pub struct Array<T: OutputType> {
@@ -524,14 +539,19 @@ struct ChangedPackage {
 
 #[Object]
 impl RepositoryQuery {
+    /// Gets the packages affected by changes between `base` and `head`. With
+    /// `traceImports`, dependents of changed packages are only included if
+    /// they import one of the changed files.
     async fn affected_packages(
         &self,
         base: Option<String>,
         head: Option<String>,
         filter: Option<PackagePredicate>,
+        trace_imports: Option<bool>,
     ) -> Result<Array<ChangedPackage>, Error> {
         let mut opts = self.run.opts().clone();
         opts.scope_opts.affected_range = Some((base, head));
+        opts.scope_opts.affected_by_imports = trace_imports.unwrap_or(false);
 
         let mut packages = RunBuilder::calculate_filtered_packages(
             self.run.repo_root(),
@@ -539,7 +559,8 @@ impl RepositoryQuery {
             self.run.pkg_dep_graph(),
             self.run.scm(),
             self.run.root_turbo_json(),
-        )?
+        )
+        .await?
         .into_iter()
         .map(|(package, reason)| {
             Ok(ChangedPackage {
@@ -624,6 +645,21 @@ impl RepositoryQuery {
 
         Ok(packages)
     }
+
+    /// The external packages that workspaces depend on, directly or
+    /// transitively, as resolved by the lockfile. Pass `name` to only get
+    /// the versions of a single package.
+    async fn external_packages(
+        &self,
+        name: Option<String>,
+    ) -> Result<Array<ExternalPackage>, Error> {
+        ExternalPackage::resolve_all(self.run.clone(), name.as_deref())
+    }
+
+    /// The external packages that workspaces depend on at more than one version
+    async fn duplicate_external_packages(&self) -> Result<Array<DuplicatedExternalPackage>, Error> {
+        ExternalPackage::duplicates(self.run.clone())
+    }
 }
 
 pub async fn graphiql() -> impl IntoResponse {
@@ -1,13 +1,16 @@
 use std::sync::Arc;
 
-use async_graphql::Object;
+use async_graphql::{Object, SimpleObject};
 use turborepo_errors::Spanned;
 use turborepo_task_id::TaskId;
 
 use crate::{
     engine::TaskNode,
     query::{package::Package, Array, Error},
-    run::Run,
+    run::{
+        history::{RunHistory, TaskRecord},
+        Run,
+    },
 };
 
 pub struct RepositoryTask {
@@ -28,6 +31,15 @@ impl RepositoryTask {
         })
     }
 
+    // The task's ID and the history it's recorded in
+    fn run_history(&self) -> (String, RunHistory) {
+        let task_id = TaskId::new(self.package.get_name().as_ref(), &self.name);
+        (
+            task_id.to_string(),
+            RunHistory::new(self.package.run().repo_root()),
+        )
+    }
+
     fn collect_and_sort<'a>(
         &self,
         task_id: &TaskId<'a>,
@@ -69,6 +81,24 @@ impl RepositoryTask {
         self.script.as_ref().map(|script| script.value.to_string())
     }
 
+    /// Previous executions of the task from the run history, most recent first
+    async fn history(&self, limit: Option<usize>) -> Result<Array<TaskRun>, Error> {
+        let (task_id, history) = self.run_history();
+        Ok(history
+            .task_runs(&task_id)?
+            .into_iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .map(TaskRun::from)
+            .collect())
+    }
+
+    /// The average duration in milliseconds of the task's successful
+    /// executions that missed the cache
+    async fn average_duration(&self) -> Result<Option<i64>, Error> {
+        let (task_id, history) = self.run_history();
+        Ok(history.average_duration(&task_id)?)
+    }
+
     async fn direct_dependents(&self) -> Result<Array<RepositoryTask>, Error> {
         let task_id = TaskId::from_static(self.package.get_name().to_string(), self.name.clone());
 
@@ -168,3 +198,27 @@ impl RepositoryTask {
         )
     }
 }
+
+/// An execution of a task recorded in the run history
+#[derive(SimpleObject)]
+pub struct TaskRun {
+    hash: String,
+    cache_hit: bool,
+    /// Milliseconds since the Unix epoch
+    start_time: i64,
+    /// Duration in milliseconds
+    duration: i64,
+    exit_code: Option<i32>,
+}
+
+impl From<TaskRecord> for TaskRun {
+    fn from(task: TaskRecord) -> Self {
+        Self {
+            duration: task.duration(),
+            hash: task.hash,
+            cache_hit: task.cache_hit,
+            start_time: task.start_time,
+            exit_code: task.exit_code,
+        }
+    }
+}
@@ -110,6 +110,118 @@ pub fn set_path(
     Ok(output)
 }
 
+/**
+ * Given a JSONC document, the key of a top level object, a member key, and
+ * a _pre-serialized_ JSON value, `insert_sorted_member` will return a JSONC
+ * document with the member added to that object.
+ *
+ * Unlike `set_path`, the member is inserted in alphabetical order and
+ * follows the indentation of the document, which is how package managers
+ * write objects like `dependencies` in a `package.json`.
+ *
+ * - If the member exists, its value is replaced using `set_path`.
+ * - If the object does not exist, it is added to the end of the document.
+ */
+pub fn insert_sorted_member(
+    json_document_string: &str,
+    object_key: &str,
+    key: &str,
+    json_value: &str,
+) -> Result<String, RewriteError> {
+    let root = get_root(json_document_string)?;
+    let jsonc_parser::ast::Value::Object(root_object) = &root else {
+        return Err(RewriteError::NoRoot);
+    };
+    let member = format!("\"{key}\": {json_value}");
+    let indent_unit = root_object
+        .properties
+        .first()
+        .and_then(|property| line_indentation(json_document_string, property.range.start))
+        .filter(|indent| !indent.is_empty())
+        .unwrap_or("  ");
+
+    let object_property = root_object
+        .properties
+        .iter()
+        .rev()
+        .find(|property| property.name.as_str() == object_key);
+
+    let mut output = json_document_string.to_owned();
+    let Some(object_property) = object_property else {
+        let object =
+            format!("\"{object_key}\": {{\n{indent_unit}{indent_unit}{member}\n{indent_unit}}}");
+        match root_object.properties.last() {
+            Some(last) => {
+                output.insert_str(last.range.end, &format!(",\n{indent_unit}{object}"));
+            }
+            None => output.replace_range(
+                root_object.range.start..root_object.range.end,
+                &format!("{{\n{indent_unit}{object}\n}}"),
+            ),
+        }
+        return Ok(output);
+    };
+
+    let jsonc_parser::ast::Value::Object(object) = &object_property.value else {
+        return set_path(json_document_string, &[object_key, key], json_value);
+    };
+    if object
+        .properties
+        .iter()
+        .any(|property| property.name.as_str() == key)
+    {
+        return set_path(json_document_string, &[object_key, key], json_value);
+    }
+
+    let next_property = object
+        .properties
+        .iter()
+        .find(|property| property.name.as_str() > key);
+    match (next_property, object.properties.last()) {
+        (Some(next), _) => {
+            // Members that share a line are separated by a space, otherwise
+            // we start a new line with the same indentation
+            let separator = match line_indentation(json_document_string, next.range.start) {
+                Some(indent) => format!("\n{indent}"),
+                None => " ".to_string(),
+            };
+            output.insert_str(next.range.start, &format!("{member},{separator}"));
+        }
+        (None, Some(last)) => {
+            let separator = match line_indentation(json_document_string, last.range.start) {
+                Some(indent) => format!("\n{indent}"),
+                None => " ".to_string(),
+            };
+            output.insert_str(last.range.end, &format!(",{separator}{member}"));
+        }
+        (None, None) => {
+            let indent = line_indentation(json_document_string, object_property.range.start)
+                .unwrap_or_default();
+            output.replace_range(
+                object.range.start..object.range.end,
+                &format!("{{\n{indent}{indent_unit}{member}\n{indent}}}"),
+            );
+        }
+    }
+
+    Ok(output)
+}
+
+/**
+ * line_indentation returns the whitespace before `offset` if nothing else
+ * precedes it on its line.
+ */
+fn line_indentation(json_document_string: &str, offset: usize) -> Option<&str> {
+    let line_start = json_document_string[..offset]
+        .rfind('\n')
+        .map_or(0, |idx| idx + 1);
+    let indentation = &json_document_string[line_start..offset];
+    indentation
+        .chars()
+        .all(char::is_whitespace)
+        .then_some(indentation)
+}
+
 /**
  * get_root returns the document root, or information on the error
  * encountered with the input json_document_string.
@@ -352,7 +464,7 @@ fn find_all_paths<'a>(
 mod test {
     use pretty_assertions::assert_str_eq;
 
-    use crate::rewrite_json::{set_path, unset_path};
+    use crate::rewrite_json::{insert_sorted_member, set_path, unset_path};
 
     macro_rules! set_tests {
         ($($name:ident: $value:expr,)*) => {
@@ -379,6 +491,81 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_insert_sorted_member() {
+        let package_json = r#"{
+  "name": "web",
+  "dependencies": {
+    "next": "^14.0.0",
+    "react": "^18.2.0"
+  }
+}
+"#;
+        assert_str_eq!(
+            r#"{
+  "name": "web",
+  "dependencies": {
+    "@repo/ui": "workspace:*",
+    "next": "^14.0.0",
+    "react": "^18.2.0"
+  }
+}
+"#,
+            insert_sorted_member(package_json, "dependencies", "@repo/ui", "\"workspace:*\"")
+                .unwrap()
+        );
+        assert_str_eq!(
+            r#"{
+  "name": "web",
+  "dependencies": {
+    "next": "^14.0.0",
+    "react": "^18.2.0",
+    "zod": "^3.22.0"
+  }
+}
+"#,
+            insert_sorted_member(package_json, "dependencies", "zod", "\"^3.22.0\"").unwrap()
+        );
+        assert_str_eq!(
+            r#"{
+  "name": "web",
+  "dependencies": {
+    "next": "^14.0.0",
+    "react": "^18.2.0"
+  },
+  "devDependencies": {
+    "vitest": "^1.0.0"
+  }
+}
+"#,
+            insert_sorted_member(package_json, "devDependencies", "vitest", "\"^1.0.0\"").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_insert_sorted_member_inline() {
+        assert_str_eq!(
+            r#"{ "dependencies": { "a": "1", "b": "2", "c": "3" } }"#,
+            insert_sorted_member(
+                r#"{ "dependencies": { "a": "1", "c": "3" } }"#,
+                "dependencies",
+                "b",
+                "\"2\""
+            )
+            .unwrap()
+        );
+        assert_str_eq!(
+            "{\n    \"dependencies\": {\n        \"a\": \"1\"\n    }\n}",
+            insert_sorted_member(
+                "{\n    \"dependencies\": {}\n}",
+                "dependencies",
+                "a",
+                "\"1\""
+            )
+            .unwrap()
+        );
+    }
+
     set_tests! {
         empty_object: (
             "{}",
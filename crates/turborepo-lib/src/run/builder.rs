@@ -8,7 +8,10 @@ use std::{
 use chrono::Local;
 use tracing::{debug, warn};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
-use turborepo_analytics::{start_analytics, AnalyticsHandle, AnalyticsSender};
+use turborepo_analytics::{
+    start_analytics, start_analytics_with_sink, AnalyticsHandle, AnalyticsSender, FileSink,
+    StdoutSink, WebhookSink,
+};
 use turborepo_api_client::{APIAuth, APIClient};
 use turborepo_cache::AsyncCache;
 use turborepo_env::EnvironmentVariableMap;
@@ -22,7 +25,7 @@ use turborepo_repository::{
 };
 use turborepo_scm::SCM;
 use turborepo_signals::{SignalHandler, SignalSubscriber};
-use turborepo_task_id::TaskName;
+use turborepo_task_id::{TaskId, TaskName};
 use turborepo_telemetry::events::{
     command::CommandEventBuilder,
     generic::{DaemonInitStatus, GenericEventBuilder},
@@ -46,7 +49,7 @@ use crate::{
     config::resolve_turbo_config_path,
     engine::{Engine, EngineBuilder},
     microfrontends::MicrofrontendsConfigs,
-    opts::Opts,
+    opts::{AnalyticsOpts, Opts},
     run::{scope, task_access::TaskAccess, Error, Run, RunCache},
     shim::TurboState,
     turbo_json::{TurboJson, TurboJsonLoader, TurboJsonReader, UIMode},
@@ -66,6 +69,9 @@ pub struct RunBuilder {
     // We will then prune away any tasks that do not depend on tasks inside
     // this package.
     entrypoint_packages: Option<HashSet<PackageName>>,
+    // In watch mode, we can also narrow down to the tasks whose inputs changed.
+    // Any task that does not depend on one of these tasks is pruned.
+    entrypoint_tasks: Option<HashSet<TaskId<'static>>>,
     should_print_prelude_override: Option<bool>,
     // In query, we don't want to validate the engine. Defaults to `true`
     should_validate_engine: bool,
@@ -106,6 +112,7 @@ impl RunBuilder {
             api_auth,
             analytics_sender: None,
             entrypoint_packages: None,
+            entrypoint_tasks: None,
             should_print_prelude_override: None,
             should_validate_engine: true,
             add_all_tasks: false,
@@ -117,6 +124,11 @@ impl RunBuilder {
         self
     }
 
+    pub fn with_entrypoint_tasks(mut self, entrypoint_tasks: HashSet<TaskId<'static>>) -> Self {
+        self.entrypoint_tasks = Some(entrypoint_tasks);
+        self
+    }
+
     pub fn hide_prelude(mut self) -> Self {
         self.should_print_prelude_override = Some(false);
         self
@@ -149,7 +161,7 @@ impl RunBuilder {
         self
     }
 
-    pub fn calculate_filtered_packages(
+    pub async fn calculate_filtered_packages(
         repo_root: &AbsoluteSystemPath,
         opts: &Opts,
         pkg_dep_graph: &PackageGraph,
@@ -164,6 +176,22 @@ impl RunBuilder {
             root_turbo_json,
         )?;
 
+        if let Some(affected_range) = opts
+            .scope_opts
+            .affected_range
+            .as_ref()
+            .filter(|_| opts.scope_opts.affected_by_imports)
+        {
+            filtered_pkgs = scope::filter_affected_by_imports(
+                repo_root,
+                pkg_dep_graph,
+                scm,
+                affected_range,
+                filtered_pkgs,
+            )
+            .await?;
+        }
+
         if is_all_packages {
             for target in opts.run_opts.tasks.iter() {
                 let mut task_name = TaskName::from(target.as_str());
@@ -190,14 +218,24 @@ impl RunBuilder {
     // Starts analytics and returns handle. This is not included in the main `build`
     // function because we don't want the handle stored in the `Run` struct.
     pub fn start_analytics(&self) -> (Option<AnalyticsSender>, Option<AnalyticsHandle>) {
-        // If there's no API auth, we don't want to record analytics
-        let Some(api_auth) = self.api_auth.clone() else {
-            return (None, None);
+        let (sender, handle) = match &self.opts.run_opts.analytics {
+            AnalyticsOpts::Vercel => {
+                // If there's no API auth, we don't want to record analytics
+                let Some(api_auth) = self.api_auth.clone() else {
+                    return (None, None);
+                };
+                if !api_auth.is_linked() {
+                    return (None, None);
+                }
+                start_analytics(api_auth, self.api_client.clone())
+            }
+            AnalyticsOpts::File(path) => start_analytics_with_sink(FileSink::new(path.clone())),
+            AnalyticsOpts::Webhook { url, auth_header } => {
+                start_analytics_with_sink(WebhookSink::new(url.clone(), auth_header.clone()))
+            }
+            AnalyticsOpts::Stdout => start_analytics_with_sink(StdoutSink::new()),
         };
-        api_auth
-            .is_linked()
-            .then(|| start_analytics(api_auth, self.api_client.clone()))
-            .unzip()
+        (Some(sender), Some(handle))
     }
 
     #[tracing::instrument(skip(self, signal_handler))]
@@ -458,7 +496,8 @@ impl RunBuilder {
             &pkg_dep_graph,
             &scm,
             &root_turbo_json,
-        )?;
+        )
+        .await?;
 
         let env_at_execution_start = EnvironmentVariableMap::infer();
         let mut engine = self.build_engine(
@@ -559,6 +598,10 @@ impl RunBuilder {
             engine = engine.create_engine_for_subgraph(entrypoint_packages);
         }
 
+        if let Some(entrypoint_tasks) = &self.entrypoint_tasks {
+            engine = engine.create_engine_for_tasks(entrypoint_tasks);
+        }
+
         if !self.opts.run_opts.parallel && self.should_validate_engine {
             engine
                 .validate(
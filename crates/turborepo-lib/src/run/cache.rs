@@ -11,7 +11,7 @@ use turbopath::{
     AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf,
 };
 use turborepo_cache::{
-    http::UploadMap, AsyncCache, CacheError, CacheHitMetadata, CacheOpts, CacheSource,
+    http::UploadMap, AsyncCache, CacheConfig, CacheError, CacheHitMetadata, CacheOpts, CacheSource,
 };
 use turborepo_repository::package_graph::PackageInfo;
 use turborepo_scm::SCM;
@@ -51,8 +51,7 @@ pub struct RunCache {
     task_output_logs: Option<OutputLogsMode>,
     cache: AsyncCache,
     warnings: Arc<Mutex<Vec<String>>>,
-    reads_disabled: bool,
-    writes_disabled: bool,
+    cache_config: CacheConfig,
     repo_root: AbsoluteSystemPathBuf,
     color_selector: ColorSelector,
     daemon_client: Option<DaemonClient<DaemonConnector>>,
@@ -87,8 +86,7 @@ impl RunCache {
             task_output_logs,
             cache,
             warnings: Default::default(),
-            reads_disabled: !cache_opts.cache.remote.read && !cache_opts.cache.local.read,
-            writes_disabled: !cache_opts.cache.remote.write && !cache_opts.cache.local.write,
+            cache_config: cache_opts.cache,
             repo_root: repo_root.to_owned(),
             color_selector,
             daemon_client,
@@ -117,6 +115,9 @@ impl RunCache {
         }
 
         let caching_disabled = !task_definition.cache;
+        let cache_config = task_definition.cache_config(self.cache_config, |var| {
+            std::env::var_os(var).is_some_and(|value| !value.is_empty())
+        });
 
         TaskCache {
             expanded_outputs: Vec::new(),
@@ -126,6 +127,7 @@ impl RunCache {
             task_id,
             task_output_logs,
             caching_disabled,
+            cache_config,
             log_file_path,
             daemon_client: self.daemon_client.clone(),
             ui: self.ui,
@@ -153,6 +155,8 @@ pub struct TaskCache {
     hash: String,
     task_output_logs: OutputLogsMode,
     caching_disabled: bool,
+    // The run's cache configuration with this task's `cacheMode` applied
+    cache_config: CacheConfig,
     log_file_path: AbsoluteSystemPathBuf,
     daemon_client: Option<DaemonClient<DaemonConnector>>,
     ui: ColorConfig,
@@ -169,6 +173,14 @@ impl TaskCache {
         self.caching_disabled
     }
 
+    fn reads_disabled(&self) -> bool {
+        !self.cache_config.local.read && !self.cache_config.remote.read
+    }
+
+    fn writes_disabled(&self) -> bool {
+        !self.cache_config.local.write && !self.cache_config.remote.write
+    }
+
     /// Will read log file and write to output a line at a time
     pub fn replay_log_file(&self, output: &mut impl CacheOutput) -> Result<(), Error> {
         if self.log_file_path.exists() {
@@ -196,7 +208,7 @@ impl TaskCache {
     pub fn output_writer<W: Write>(&self, writer: W) -> Result<LogWriter<W>, Error> {
         let mut log_writer = LogWriter::default();
 
-        if !self.caching_disabled && !self.run_cache.writes_disabled {
+        if !self.caching_disabled && !self.writes_disabled() {
             log_writer.with_log_file(&self.log_file_path)?;
         }
 
@@ -211,7 +223,10 @@ impl TaskCache {
     }
 
     pub async fn exists(&self) -> Result<Option<CacheHitMetadata>, CacheError> {
-        self.run_cache.cache.exists(&self.hash).await
+        self.run_cache
+            .cache
+            .exists_with_config(&self.hash, self.cache_config)
+            .await
     }
 
     pub async fn restore_outputs(
@@ -219,7 +234,7 @@ impl TaskCache {
         terminal_output: &mut impl CacheOutput,
         telemetry: &PackageTaskEventBuilder,
     ) -> Result<Option<CacheHitMetadata>, Error> {
-        if self.caching_disabled || self.run_cache.reads_disabled {
+        if self.caching_disabled || self.reads_disabled() {
             if !matches!(
                 self.task_output_logs,
                 OutputLogsMode::None | OutputLogsMode::ErrorsOnly
@@ -267,7 +282,7 @@ impl TaskCache {
             let cache_status = self
                 .run_cache
                 .cache
-                .fetch(&self.run_cache.repo_root, &self.hash)
+                .fetch_with_config(&self.run_cache.repo_root, &self.hash, self.cache_config)
                 .await?;
 
             let Some((cache_hit_metadata, restored_files)) = cache_status else {
@@ -360,7 +375,7 @@ impl TaskCache {
         duration: Duration,
         telemetry: &PackageTaskEventBuilder,
     ) -> Result<(), Error> {
-        if self.caching_disabled || self.run_cache.writes_disabled {
+        if self.caching_disabled || self.writes_disabled() {
             return Ok(());
         }
 
@@ -396,11 +411,12 @@ impl TaskCache {
         relative_paths.sort();
         self.run_cache
             .cache
-            .put(
+            .put_with_config(
                 self.run_cache.repo_root.clone(),
                 self.hash.clone(),
                 relative_paths.clone(),
                 duration.as_millis() as u64,
+                self.cache_config,
             )
             .await?;
 
@@ -0,0 +1,355 @@
+//! The run history is a local record of every run, kept as an append-only log
+//! with one JSON line per run in `.turbo/history/runs.jsonl`. Each entry
+//! holds the hash, cache outcome and timings of the run's tasks, so that
+//! anything that wants historical durations can read them without needing
+//! `--summarize`. Runs that fall outside of the retention limits are dropped
+//! by rewriting the log, which only happens once enough of them have built up
+//! so that most runs just append a line.
+//!
+//! `turbo query` is currently the only reader. The TUI and the scheduler don't
+//! use historical durations yet.
+
+use std::{
+    fs::OpenOptions,
+    io::{self, BufRead, BufReader, Write},
+};
+
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+use thiserror::Error;
+use tracing::debug;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+
+use crate::opts::RunHistoryOpts;
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+// The share of `max_runs` that can be waiting to be dropped before the log is
+// rewritten
+const COMPACTION_SLACK_DIVISOR: u64 = 10;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Failed to access run history: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to serialize run history: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRecord {
+    pub id: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub exit_code: i32,
+    pub tasks: Vec<TaskRecord>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskRecord {
+    pub task_id: String,
+    pub hash: String,
+    pub cache_hit: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_source: Option<String>,
+    pub time_saved: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub exit_code: Option<i32>,
+}
+
+impl TaskRecord {
+    pub fn duration(&self) -> i64 {
+        self.end_time - self.start_time
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+pub struct RunHistory {
+    path: AbsoluteSystemPathBuf,
+}
+
+impl RunHistory {
+    pub fn new(repo_root: &AbsoluteSystemPath) -> Self {
+        Self {
+            path: repo_root.join_components(&[".turbo", "history", "runs.jsonl"]),
+        }
+    }
+
+    /// All recorded runs, oldest first
+    pub fn runs(&self) -> Result<Vec<RunRecord>, Error> {
+        Ok(self.read()?.0)
+    }
+
+    // Also returns whether any entries were invalid
+    fn read(&self) -> Result<(Vec<RunRecord>, bool), Error> {
+        let file = match self.path.open() {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), false)),
+            Err(e) => return Err(e.into()),
+        };
+        let mut runs = Vec::new();
+        let mut has_invalid = false;
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            // A line can be cut short if turbo was killed while writing it, so
+            // we skip anything we can't parse rather than losing the history
+            match serde_json::from_str(&line) {
+                Ok(run) => runs.push(run),
+                Err(e) => {
+                    debug!("skipping invalid run history entry: {e}");
+                    has_invalid = true;
+                }
+            }
+        }
+        Ok((runs, has_invalid))
+    }
+
+    /// Every recorded execution of a task, most recent first
+    pub fn task_runs(&self, task_id: &str) -> Result<Vec<TaskRecord>, Error> {
+        Ok(self
+            .runs()?
+            .into_iter()
+            .rev()
+            .filter_map(|run| run.tasks.into_iter().find(|task| task.task_id == task_id))
+            .collect())
+    }
+
+    /// The mean duration in milliseconds of a task's successful executions
+    /// that missed the cache
+    pub fn average_duration(&self, task_id: &str) -> Result<Option<i64>, Error> {
+        Ok(average_duration(&self.task_runs(task_id)?))
+    }
+
+    /// Records a run, dropping any runs that are outside of the retention
+    /// limits as of `now`, a timestamp in milliseconds. Runs are only dropped
+    /// once more than a tenth of `max_runs` are outside of the limits, so the
+    /// log can briefly hold a few more runs than the limits allow.
+    pub fn record(
+        &self,
+        run: &RunRecord,
+        retention: RunHistoryOpts,
+        now: i64,
+    ) -> Result<(), Error> {
+        let (mut runs, has_invalid) = self.read()?;
+        let total = runs.len();
+        retain(&mut runs, retention, now);
+        // Leave room for the run we're recording
+        let max_runs = retention.max_runs.max(1) as usize;
+        if runs.len() >= max_runs {
+            runs.drain(..=runs.len() - max_runs);
+        }
+
+        self.path.ensure_dir()?;
+        let mut line = serde_json::to_vec(run)?;
+        line.push(b'\n');
+        let slack = (retention.max_runs / COMPACTION_SLACK_DIVISOR).max(1) as usize;
+        if total - runs.len() <= slack && !has_invalid {
+            let mut options = OpenOptions::new();
+            options.create(true).append(true);
+            let mut file = self.path.open_with_options(options)?;
+            file.write_all(&line)?;
+            return Ok(());
+        }
+
+        // Write the compacted log to a temporary file next to the existing one
+        // and swap them so that readers never see a partially written log.
+        // Each writer has its own temporary file, so a concurrent run can only
+        // lose its entry if it appends while this one is compacting.
+        let mut file = NamedTempFile::new_in(
+            self.path
+                .parent()
+                .expect("run history is in a directory")
+                .as_std_path(),
+        )?;
+        for run in &runs {
+            serde_json::to_writer(&mut file, run)?;
+            file.write_all(b"\n")?;
+        }
+        file.write_all(&line)?;
+        file.persist(self.path.as_std_path()).map_err(|e| e.error)?;
+        Ok(())
+    }
+}
+
+fn retain(runs: &mut Vec<RunRecord>, retention: RunHistoryOpts, now: i64) {
+    let cutoff = now.saturating_sub((retention.max_age_days as i64).saturating_mul(MILLIS_PER_DAY));
+    runs.retain(|run| run.end_time >= cutoff);
+}
+
+fn average_duration(task_runs: &[TaskRecord]) -> Option<i64> {
+    let durations = task_runs
+        .iter()
+        .filter(|task| !task.cache_hit && task.succeeded())
+        .map(|task| task.duration())
+        .collect::<Vec<_>>();
+    if durations.is_empty() {
+        return None;
+    }
+    Some(durations.iter().sum::<i64>() / durations.len() as i64)
+}
+
+#[cfg(test)]
+mod test {
+    use turbopath::AbsoluteSystemPathBuf;
+
+    use super::{RunHistory, RunRecord, TaskRecord, MILLIS_PER_DAY};
+    use crate::opts::RunHistoryOpts;
+
+    fn run(id: &str, end_time: i64, duration: i64, cache_hit: bool) -> RunRecord {
+        RunRecord {
+            id: id.to_string(),
+            start_time: end_time - duration,
+            end_time,
+            exit_code: 0,
+            tasks: vec![TaskRecord {
+                task_id: "web#build".to_string(),
+                hash: format!("hash-{id}"),
+                cache_hit,
+                cache_source: cache_hit.then(|| "LOCAL".to_string()),
+                time_saved: 0,
+                start_time: end_time - duration,
+                end_time,
+                exit_code: Some(0),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_record_and_read() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        let history = RunHistory::new(&repo_root);
+        let retention = RunHistoryOpts {
+            max_runs: 10,
+            max_age_days: 30,
+        };
+        assert!(history.runs().unwrap().is_empty());
+
+        history
+            .record(&run("a", 1_000, 300, false), retention, 2_000)
+            .unwrap();
+        history
+            .record(&run("b", 2_000, 100, false), retention, 3_000)
+            .unwrap();
+        history
+            .record(&run("c", 3_000, 5, true), retention, 4_000)
+            .unwrap();
+
+        let ids = history
+            .runs()
+            .unwrap()
+            .into_iter()
+            .map(|run| run.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        let task_runs = history.task_runs("web#build").unwrap();
+        assert_eq!(task_runs[0].hash, "hash-c");
+        // Cache hits don't count towards the average
+        assert_eq!(history.average_duration("web#build").unwrap(), Some(200));
+        assert_eq!(history.average_duration("docs#build").unwrap(), None);
+    }
+
+    #[test]
+    fn test_retention() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        let history = RunHistory::new(&repo_root);
+        let retention = RunHistoryOpts {
+            max_runs: 2,
+            max_age_days: 1,
+        };
+        let now = 10 * MILLIS_PER_DAY;
+
+        // Too old to keep
+        history
+            .record(
+                &run("a", now - 2 * MILLIS_PER_DAY, 100, false),
+                retention,
+                now,
+            )
+            .unwrap();
+        history
+            .record(&run("b", now - 3, 100, false), retention, now)
+            .unwrap();
+        history
+            .record(&run("c", now - 2, 100, false), retention, now)
+            .unwrap();
+        history
+            .record(&run("d", now - 1, 100, false), retention, now)
+            .unwrap();
+
+        let ids = history
+            .runs()
+            .unwrap()
+            .into_iter()
+            .map(|run| run.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["c", "d"]);
+    }
+
+    #[test]
+    fn test_compacts_after_slack() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        let history = RunHistory::new(&repo_root);
+        let retention = RunHistoryOpts {
+            max_runs: 10,
+            max_age_days: 30,
+        };
+        let record = |id: usize| {
+            history
+                .record(&run(&id.to_string(), id as i64, 1, false), retention, 100)
+                .unwrap()
+        };
+
+        for id in 0..11 {
+            record(id);
+        }
+        // A single run over the limit is left until more build up
+        assert_eq!(history.runs().unwrap().len(), 11);
+
+        record(11);
+        let ids = history
+            .runs()
+            .unwrap()
+            .into_iter()
+            .map(|run| run.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, (2..12).map(|id| id.to_string()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_skips_truncated_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        let history = RunHistory::new(&repo_root);
+        let mut contents = serde_json::to_string(&run("a", 1_000, 100, false)).unwrap();
+        contents.push_str("\n{\"id\": \"b\", \"sta");
+        let path = repo_root.join_components(&[".turbo", "history", "runs.jsonl"]);
+        path.ensure_dir().unwrap();
+        path.create_with_contents(contents).unwrap();
+
+        assert_eq!(history.runs().unwrap().len(), 1);
+
+        // Recording another run shouldn't append to the truncated entry
+        let retention = RunHistoryOpts {
+            max_runs: 10,
+            max_age_days: 30,
+        };
+        history
+            .record(&run("c", 2_000, 100, false), retention, 3_000)
+            .unwrap();
+        let ids = history
+            .runs()
+            .unwrap()
+            .into_iter()
+            .map(|run| run.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["a", "c"]);
+    }
+}
@@ -5,10 +5,12 @@ mod cache;
 mod error;
 pub(crate) mod global_hash;
 mod graph_visualizer;
+pub(crate) mod history;
 pub(crate) mod package_discovery;
 pub(crate) mod scope;
 pub(crate) mod summary;
 pub mod task_access;
+mod trace_imports;
 mod ui;
 pub mod watch;
 
@@ -30,7 +32,10 @@ use turborepo_api_client::{APIAuth, APIClient};
 use turborepo_ci::Vendor;
 use turborepo_env::EnvironmentVariableMap;
 use turborepo_process::ProcessManager;
-use turborepo_repository::package_graph::{PackageGraph, PackageName, PackageNode};
+use turborepo_repository::{
+    package_graph::{PackageGraph, PackageName, PackageNode},
+    package_manager::{berry, PackageManager},
+};
 use turborepo_scm::SCM;
 use turborepo_signals::{listeners::get_signal, SignalHandler};
 use turborepo_telemetry::events::generic::GenericEventBuilder;
@@ -47,7 +52,10 @@ use crate::{
     opts::Opts,
     run::{global_hash::get_global_hash_inputs, summary::RunTracker, task_access::TaskAccess},
     task_graph::Visitor,
-    task_hash::{get_external_deps_hash, get_internal_deps_hash, PackageInputsHashes},
+    task_hash::{
+        get_external_deps_hash, get_internal_deps_hash, task_hashes_key, DaemonTaskHashes,
+        PackageInputsHashes,
+    },
     turbo_json::{TurboJson, TurboJsonLoader, UIMode},
     DaemonClient, DaemonConnector,
 };
@@ -207,6 +215,13 @@ impl Run {
         &self.pkg_dep_graph
     }
 
+    /// Returns the workspace packages and their directories if the repository
+    /// is installed with Yarn Plug'n'Play, in which case they can't be resolved
+    /// through `node_modules`.
+    pub fn yarn_pnp_workspace_packages(&self) -> Option<Vec<(String, AbsoluteSystemPathBuf)>> {
+        yarn_pnp_workspace_packages(&self.repo_root, &self.pkg_dep_graph)
+    }
+
     pub fn engine(&self) -> &Engine {
         &self.engine
     }
@@ -379,17 +394,6 @@ impl Run {
             return Ok(0);
         }
 
-        let workspaces = self.pkg_dep_graph.packages().collect();
-        let package_inputs_hashes = PackageInputsHashes::calculate_file_hashes(
-            &self.scm,
-            self.engine.tasks().par_bridge(),
-            workspaces,
-            self.engine.task_definitions(),
-            &self.repo_root,
-            &self.run_telemetry,
-            &self.daemon,
-        )?;
-
         let root_workspace = self
             .pkg_dep_graph
             .package_info(&PackageName::Root)
@@ -450,6 +454,40 @@ impl Run {
             env
         };
 
+        let workspaces = self.pkg_dep_graph.packages().collect();
+        let task_hashes_key = task_hashes_key(
+            &global_hash,
+            &self.opts.run_opts,
+            &self.env_at_execution_start,
+            &self.engine,
+            &workspaces,
+        );
+        // The daemon keeps task input hashes warm, so we ask it for all of them
+        // up front instead of hashing each task's inputs separately.
+        let daemon_task_hashes = match self.daemon.clone() {
+            Some(mut daemon) => {
+                DaemonTaskHashes::fetch(
+                    &mut daemon,
+                    task_hashes_key.clone(),
+                    self.engine.task_definitions(),
+                    &workspaces,
+                )
+                .await
+            }
+            None => DaemonTaskHashes::default(),
+        };
+        let package_inputs_hashes = PackageInputsHashes::calculate_file_hashes(
+            &self.scm,
+            self.engine.tasks().par_bridge(),
+            workspaces,
+            self.engine.task_definitions(),
+            &self.repo_root,
+            &self.run_telemetry,
+            &self.daemon,
+            daemon_task_hashes,
+        )?;
+        let input_hashes = package_inputs_hashes.hashes().clone();
+
         let run_tracker = RunTracker::new(
             self.start_at,
             self.opts.synthesize_command(),
@@ -507,7 +545,31 @@ impl Run {
             writeln!(std::io::stderr(), "{error_prefix}{err}").ok();
         }
 
-        visitor
+        // Let the daemon remember this run's task hashes so the next run with the
+        // same inputs can skip computing them.
+        if let Some(mut daemon) = self
+            .daemon
+            .clone()
+            .filter(|_| self.opts.run_opts.dry_run.is_none())
+        {
+            let task_hashes = visitor
+                .task_hashes()
+                .into_iter()
+                .map(|(task_id, hash)| (task_id.to_string(), hash))
+                .collect();
+            let input_hashes = input_hashes
+                .into_iter()
+                .map(|(task_id, hash)| (task_id.to_string(), hash))
+                .collect();
+            if let Err(e) = daemon
+                .notify_task_hashes(task_hashes_key, task_hashes, input_hashes)
+                .await
+            {
+                debug!("failed to record task hashes with the daemon: {e}");
+            }
+        }
+
+        let exit_code = visitor
             .finish(
                 exit_code,
                 &self.filtered_pkgs,
@@ -532,3 +594,26 @@ impl RunStopper {
         self.manager.stop().await;
     }
 }
+
+/// See [`Run::yarn_pnp_workspace_packages`]. Available separately for code
+/// that runs before a `Run` is built.
+pub(crate) fn yarn_pnp_workspace_packages(
+    repo_root: &AbsoluteSystemPath,
+    pkg_dep_graph: &PackageGraph,
+) -> Option<Vec<(String, AbsoluteSystemPathBuf)>> {
+    if *pkg_dep_graph.package_manager() != PackageManager::Berry || !berry::uses_pnp(repo_root) {
+        return None;
+    }
+
+    Some(
+        pkg_dep_graph
+            .packages()
+            .filter_map(|(name, info)| match name {
+                PackageName::Root => None,
+                PackageName::Other(name) => {
+                    Some((name.clone(), repo_root.resolve(info.package_path())))
+                }
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,332 @@
+//! Narrows `--affected` from packages to files. Instead of including every
+//! dependent of a changed package, we trace imports to only include the
+//! dependents that import one of the changed files.
+
+use std::collections::{HashMap, HashSet};
+
+use tracing::debug;
+use turbo_trace::Tracer;
+use turbopath::{AbsoluteSystemPath, AnchoredSystemPathBuf};
+use turborepo_repository::{
+    change_mapper::PackageInclusionReason,
+    package_graph::{PackageGraph, PackageName, PackageNode},
+};
+use turborepo_scm::{git::InvalidRange, SCM};
+
+use crate::run::{
+    scope::ResolutionError,
+    trace_imports::{load_import_cache, write_import_cache},
+    yarn_pnp_workspace_packages,
+};
+
+/// Removes the packages that were only included because they depend on a
+/// changed package, but don't import any of the files that changed in it.
+///
+/// A changed package is only narrowed when all of its changes are to source
+/// files that still exist. Otherwise all of its dependents are kept, since
+/// they can be affected without importing a changed file.
+pub async fn filter_affected_by_imports(
+    turbo_root: &AbsoluteSystemPath,
+    pkg_graph: &PackageGraph,
+    scm: &SCM,
+    affected_range: &(Option<String>, Option<String>),
+    packages: HashMap<PackageName, PackageInclusionReason>,
+) -> Result<HashMap<PackageName, PackageInclusionReason>, ResolutionError> {
+    let (from_ref, to_ref) = affected_range;
+    let changed_files = match scm.changed_files(
+        turbo_root,
+        from_ref.as_deref(),
+        to_ref.as_deref(),
+        true,
+        true,
+        true,
+    )? {
+        Ok(changed_files) => changed_files,
+        Err(InvalidRange { .. }) => return Ok(packages),
+    };
+
+    Ok(retain_importers(turbo_root, pkg_graph, changed_files, packages).await)
+}
+
+// The files that the tracer parses for imports. Dependents can't import any
+// other file, so changes to them can't be traced.
+const TRACEABLE_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs", "css", "scss"];
+
+fn is_traceable(turbo_root: &AbsoluteSystemPath, file: &AnchoredSystemPathBuf) -> bool {
+    file.as_path()
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| TRACEABLE_EXTENSIONS.contains(&extension))
+        && turbo_root.resolve(file).exists()
+}
+
+async fn retain_importers(
+    turbo_root: &AbsoluteSystemPath,
+    pkg_graph: &PackageGraph,
+    changed_files: impl IntoIterator<Item = AnchoredSystemPathBuf>,
+    mut packages: HashMap<PackageName, PackageInclusionReason>,
+) -> HashMap<PackageName, PackageInclusionReason> {
+    // The changed files in each package, or `None` if the package has a change
+    // that tracing can't account for
+    let mut changed_packages: HashMap<&PackageName, Option<Vec<AnchoredSystemPathBuf>>> =
+        HashMap::new();
+    for file in changed_files {
+        let Some((package, _)) = pkg_graph.package_containing(&file) else {
+            continue;
+        };
+        let is_traceable = is_traceable(turbo_root, &file);
+        let files = changed_packages
+            .entry(package)
+            .or_insert_with(|| Some(Vec::new()));
+        if !is_traceable {
+            *files = None;
+        } else if let Some(files) = files {
+            files.push(file);
+        }
+    }
+
+    let (traced, untraced): (Vec<_>, Vec<_>) = changed_packages
+        .into_iter()
+        .partition(|(_, files)| files.is_some());
+    let untraced_packages = untraced
+        .into_iter()
+        .map(|(package, _)| PackageNode::Workspace(package.clone()))
+        .collect::<HashSet<_>>();
+    let traced_files = traced
+        .into_iter()
+        .flat_map(|(_, files)| files.into_iter().flatten())
+        .map(|file| turbo_root.resolve(&file))
+        .collect::<Vec<_>>();
+
+    let importing_packages = if traced_files.is_empty() {
+        HashSet::new()
+    } else {
+        let cache = load_import_cache(turbo_root);
+        let mut tracer = Tracer::new(turbo_root.to_owned(), traced_files, None);
+        tracer.set_transitive();
+        tracer.set_cache(cache.clone());
+        if let Some(workspace_packages) = yarn_pnp_workspace_packages(turbo_root, pkg_graph) {
+            tracer.set_yarn_pnp(workspace_packages);
+        }
+        let result = tracer.reverse_trace().await;
+        write_import_cache(turbo_root, &cache);
+        for error in &result.errors {
+            debug!("error while tracing affected imports: {error}");
+        }
+
+        result
+            .files
+            .keys()
+            .filter_map(|file| turbo_root.anchor(file).ok())
+            .filter_map(|file| pkg_graph.package_containing(&file))
+            .map(|(package, _)| package)
+            .cloned()
+            .collect::<HashSet<_>>()
+    };
+
+    packages.retain(|package, reason| {
+        if !matches!(reason, PackageInclusionReason::DependencyChanged { .. })
+            || importing_packages.contains(package)
+        {
+            return true;
+        }
+        let node = PackageNode::Workspace(package.clone());
+        let is_kept = pkg_graph
+            .dependencies(&node)
+            .iter()
+            .any(|dependency| untraced_packages.contains(*dependency));
+        if !is_kept {
+            debug!("{package} does not import any changed files, removing from affected packages");
+        }
+        is_kept
+    });
+
+    packages
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeSet, HashMap};
+
+    use tempfile::TempDir;
+    use turbopath::{
+        AbsoluteSystemPathBuf, AnchoredSystemPathBuf, RelativeUnixPath, RelativeUnixPathBuf,
+    };
+    use turborepo_errors::Spanned;
+    use turborepo_repository::{
+        change_mapper::PackageInclusionReason,
+        discovery::{DiscoveryResponse, Error, PackageDiscovery},
+        package_graph::{PackageGraph, PackageName},
+        package_json::PackageJson,
+        package_manager::PackageManager,
+    };
+
+    use super::retain_importers;
+
+    struct MockDiscovery;
+    impl PackageDiscovery for MockDiscovery {
+        async fn discover_packages(&self) -> Result<DiscoveryResponse, Error> {
+            Ok(DiscoveryResponse {
+                package_manager: PackageManager::Npm,
+                workspaces: vec![],
+            })
+        }
+
+        async fn discover_packages_blocking(&self) -> Result<DiscoveryResponse, Error> {
+            self.discover_packages().await
+        }
+    }
+
+    // `web` imports `button.ts` from `ui` directly, `docs` imports it through
+    // `ui`'s `index.ts`, and `admin` only imports `card.ts`
+    const FILES: &[(&str, &str)] = &[
+        (
+            "packages/ui/src/button.ts",
+            "export const Button = 'button';\n",
+        ),
+        ("packages/ui/src/card.ts", "export const Card = 'card';\n"),
+        (
+            "packages/ui/src/index.ts",
+            "export { Button } from './button';\n",
+        ),
+        ("packages/ui/tsconfig.json", "{}\n"),
+        (
+            "apps/web/src/page.ts",
+            "import { Button } from '../../../packages/ui/src/button';\n",
+        ),
+        (
+            "apps/docs/src/page.ts",
+            "import { Button } from '../../../packages/ui/src/index';\n",
+        ),
+        (
+            "apps/admin/src/page.ts",
+            "import { Card } from '../../../packages/ui/src/card';\n",
+        ),
+    ];
+
+    async fn setup() -> (TempDir, AbsoluteSystemPathBuf, PackageGraph) {
+        let tmp = tempfile::tempdir().unwrap();
+        // The resolver returns real paths, so the root has to be one too
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap();
+        for (path, contents) in FILES {
+            let file = repo_root.join_unix_path(RelativeUnixPath::new(path).unwrap());
+            file.ensure_dir().unwrap();
+            file.create_with_contents(contents).unwrap();
+        }
+
+        let package_jsons = [
+            ("packages/ui", "ui", None),
+            ("apps/web", "web", Some("ui")),
+            ("apps/docs", "docs", Some("ui")),
+            ("apps/admin", "admin", Some("ui")),
+        ]
+        .into_iter()
+        .map(|(path, name, dependency)| {
+            let package_json_path = repo_root
+                .join_unix_path(RelativeUnixPathBuf::new(format!("{path}/package.json")).unwrap());
+            package_json_path
+                .create_with_contents(format!("{{\"name\": \"{name}\"}}"))
+                .unwrap();
+            let package_json = PackageJson {
+                name: Some(Spanned::new(name.to_string())),
+                dependencies: dependency
+                    .map(|dependency| [(dependency.to_string(), "*".to_string())].into()),
+                ..Default::default()
+            };
+            (package_json_path, package_json)
+        })
+        .collect::<HashMap<_, _>>();
+
+        let pkg_graph = PackageGraph::builder(&repo_root, PackageJson::default())
+            .with_package_discovery(MockDiscovery)
+            .with_package_jsons(Some(package_jsons))
+            .build()
+            .await
+            .unwrap();
+
+        (tmp, repo_root, pkg_graph)
+    }
+
+    // Runs the filter on `ui` and its dependents after `changed_file` changed,
+    // returning the packages that are kept
+    async fn kept_packages(changed_file: &str) -> BTreeSet<String> {
+        let (_tmp, repo_root, pkg_graph) = setup().await;
+        let changed_file = AnchoredSystemPathBuf::from_raw(changed_file).unwrap();
+        let ui = PackageName::from("ui".to_string());
+        let mut packages = HashMap::from([(
+            ui.clone(),
+            PackageInclusionReason::FileChanged {
+                file: changed_file.clone(),
+            },
+        )]);
+        for dependent in ["web", "docs", "admin"] {
+            packages.insert(
+                PackageName::from(dependent.to_string()),
+                PackageInclusionReason::DependencyChanged {
+                    dependency: ui.clone(),
+                },
+            );
+        }
+
+        retain_importers(&repo_root, &pkg_graph, [changed_file], packages)
+            .await
+            .into_keys()
+            .map(|package| package.to_string())
+            .collect()
+    }
+
+    fn packages(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_keeps_importing_dependents() {
+        assert_eq!(
+            kept_packages("packages/ui/src/button.ts").await,
+            packages(&["docs", "ui", "web"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_drops_dependents_that_do_not_import() {
+        assert_eq!(
+            kept_packages("packages/ui/src/card.ts").await,
+            packages(&["admin", "ui"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_follows_import_chains() {
+        assert_eq!(
+            kept_packages("packages/ui/src/index.ts").await,
+            packages(&["docs", "ui"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keeps_all_dependents_for_package_json() {
+        assert_eq!(
+            kept_packages("packages/ui/package.json").await,
+            packages(&["admin", "docs", "ui", "web"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keeps_all_dependents_for_deleted_file() {
+        assert_eq!(
+            kept_packages("packages/ui/src/removed.ts").await,
+            packages(&["admin", "docs", "ui", "web"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keeps_all_dependents_for_untraceable_file() {
+        assert_eq!(
+            kept_packages("packages/ui/tsconfig.json").await,
+            packages(&["admin", "docs", "ui", "web"])
+        );
+    }
+}
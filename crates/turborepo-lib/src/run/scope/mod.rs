@@ -1,11 +1,13 @@
 mod change_detector;
 pub mod filter;
+mod imports;
 mod simple_glob;
 pub mod target_selector;
 
 use std::collections::HashMap;
 
 use filter::{FilterResolver, PackageInference};
+pub use imports::filter_affected_by_imports;
 use turbopath::AbsoluteSystemPath;
 use turborepo_repository::{
     change_mapper::PackageInclusionReason,
@@ -0,0 +1,463 @@
+//! Compares a run with a previous run summary from `.turbo/runs`.
+//!
+//! We report tasks that got slower or faster, tasks that started failing,
+//! tasks that lost their cache hit without their hash changing, and tasks
+//! whose hash changed. The first three count as regressions.
+
+use std::{collections::HashMap, fs, io};
+
+use chrono::Duration;
+use console::Style;
+use serde::Deserialize;
+use thiserror::Error;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_ui::{color, cprintln, ColorConfig, BOLD, BOLD_CYAN, BOLD_GREEN, BOLD_RED, GREY};
+
+use super::{
+    duration::TurboDuration, execution::TaskExecutionSummary, task::TaskCacheSummary, RunSummary,
+};
+
+/// Compares against the most recent run summary
+pub const LAST_RUN: &str = "last";
+
+// Changes smaller than this are usually noise from scheduling and process
+// startup, so they aren't reported no matter the percentage
+const MIN_DURATION_CHANGE_MS: i64 = 100;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("No run summaries found in {0}. Use `--summarize` to save one.")]
+    NoRuns(AbsoluteSystemPathBuf),
+    #[error("Could not find a run summary for `{0}`.")]
+    RunNotFound(String),
+    #[error("Failed to read run summary: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to parse run summary at {0}: {1}")]
+    Parse(AbsoluteSystemPathBuf, #[source] serde_json::Error),
+}
+
+/// The parts of a run summary that are compared.
+#[derive(Debug, Deserialize)]
+pub struct RunSnapshot {
+    id: String,
+    execution: Option<ExecutionSnapshot>,
+    tasks: Vec<TaskSnapshot>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecutionSnapshot {
+    start_time: i64,
+    end_time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskSnapshot {
+    task_id: String,
+    hash: String,
+    cache: TaskCacheSummary,
+    execution: Option<TaskExecutionSummary>,
+}
+
+impl RunSnapshot {
+    /// Loads a saved run summary by its ID, or the most recent one for `last`
+    pub fn load(repo_root: &AbsoluteSystemPath, run_id: &str) -> Result<Self, Error> {
+        let runs_dir = repo_root.join_components(&[".turbo", "runs"]);
+        let path = if run_id == LAST_RUN {
+            latest_run(&runs_dir)?
+        } else if run_id.contains(['/', '\\']) {
+            return Err(Error::RunNotFound(run_id.to_string()));
+        } else {
+            runs_dir.join_component(&format!("{run_id}.json"))
+        };
+
+        let contents = match path.read_to_string() {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(Error::RunNotFound(run_id.to_string()))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_str(&contents).map_err(|e| Error::Parse(path, e))
+    }
+
+    fn duration(&self) -> Option<i64> {
+        self.execution
+            .as_ref()
+            .map(|execution| execution.end_time - execution.start_time)
+    }
+
+    fn cache_hit_ratio(&self) -> f64 {
+        if self.tasks.is_empty() {
+            return 0.0;
+        }
+        let hits = self.tasks.iter().filter(|task| task.cache.is_hit()).count();
+        hits as f64 / self.tasks.len() as f64
+    }
+}
+
+impl From<&RunSummary<'_>> for RunSnapshot {
+    fn from(run_summary: &RunSummary<'_>) -> Self {
+        let tasks = run_summary
+            .tasks
+            .iter()
+            .map(|task| TaskSnapshot {
+                // Saved single package summaries only use the task name
+                task_id: if run_summary.monorepo {
+                    task.task_id.to_string()
+                } else {
+                    task.task_id.task().to_string()
+                },
+                hash: task.shared.hash.clone(),
+                cache: task.shared.cache.clone(),
+                execution: task.shared.execution.clone(),
+            })
+            .collect();
+        Self {
+            id: run_summary.id.to_string(),
+            execution: run_summary
+                .execution
+                .as_ref()
+                .map(|execution| ExecutionSnapshot {
+                    start_time: execution.start_time,
+                    end_time: execution.end_time,
+                }),
+            tasks,
+        }
+    }
+}
+
+// Run IDs are KSUIDs, which sort by the time they were created
+fn latest_run(runs_dir: &AbsoluteSystemPath) -> Result<AbsoluteSystemPathBuf, Error> {
+    let entries = match fs::read_dir(runs_dir.as_std_path()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(Error::NoRuns(runs_dir.to_owned()))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut latest: Option<String> = None;
+    for entry in entries {
+        let Ok(file_name) = entry?.file_name().into_string() else {
+            continue;
+        };
+        if file_name.ends_with(".json") && latest.as_ref().is_none_or(|latest| file_name > *latest)
+        {
+            latest = Some(file_name);
+        }
+    }
+    latest
+        .map(|file_name| runs_dir.join_component(&file_name))
+        .ok_or_else(|| Error::NoRuns(runs_dir.to_owned()))
+}
+
+#[derive(Debug, PartialEq)]
+struct DurationChange {
+    task_id: String,
+    before: i64,
+    after: i64,
+}
+
+impl DurationChange {
+    fn percent(&self) -> f64 {
+        if self.before == 0 {
+            return f64::INFINITY;
+        }
+        (self.after - self.before) as f64 * 100.0 / self.before as f64
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct HashChange {
+    task_id: String,
+    before: String,
+    after: String,
+}
+
+#[derive(Debug)]
+pub struct Comparison {
+    baseline_id: String,
+    duration: Option<(i64, i64)>,
+    cache_hit_ratio: (f64, f64),
+    slower: Vec<DurationChange>,
+    faster: Vec<DurationChange>,
+    newly_failing: Vec<String>,
+    lost_cache_hits: Vec<String>,
+    changed_hashes: Vec<HashChange>,
+}
+
+impl Comparison {
+    /// Compares `current` with `baseline`. Task durations are only reported
+    /// if they changed by at least `threshold` percent.
+    pub fn new(baseline: &RunSnapshot, current: &RunSnapshot, threshold: f64) -> Self {
+        let baseline_tasks = baseline
+            .tasks
+            .iter()
+            .map(|task| (task.task_id.as_str(), task))
+            .collect::<HashMap<_, _>>();
+
+        let mut comparison = Self {
+            baseline_id: baseline.id.clone(),
+            duration: baseline.duration().zip(current.duration()),
+            cache_hit_ratio: (baseline.cache_hit_ratio(), current.cache_hit_ratio()),
+            slower: Vec::new(),
+            faster: Vec::new(),
+            newly_failing: Vec::new(),
+            lost_cache_hits: Vec::new(),
+            changed_hashes: Vec::new(),
+        };
+
+        for task in &current.tasks {
+            let before = baseline_tasks.get(task.task_id.as_str());
+            let failed = task
+                .execution
+                .as_ref()
+                .is_some_and(|execution| execution.is_failure());
+            let failed_before = before
+                .and_then(|before| before.execution.as_ref())
+                .is_some_and(|execution| execution.is_failure());
+            if failed && !failed_before {
+                comparison.newly_failing.push(task.task_id.clone());
+            }
+            let Some(before) = before else {
+                continue;
+            };
+
+            if before.hash != task.hash {
+                comparison.changed_hashes.push(HashChange {
+                    task_id: task.task_id.clone(),
+                    before: before.hash.clone(),
+                    after: task.hash.clone(),
+                });
+            } else if before.cache.is_hit() && !task.cache.is_hit() {
+                comparison.lost_cache_hits.push(task.task_id.clone());
+            }
+
+            // Durations are only comparable if the task actually ran both times
+            let (Some(execution_before), Some(execution)) = (&before.execution, &task.execution)
+            else {
+                continue;
+            };
+            if before.cache.is_hit()
+                || task.cache.is_hit()
+                || execution_before.is_failure()
+                || execution.is_failure()
+            {
+                continue;
+            }
+            let change = DurationChange {
+                task_id: task.task_id.clone(),
+                before: execution_before.end_time - execution_before.start_time,
+                after: execution.end_time - execution.start_time,
+            };
+            if (change.after - change.before).abs() < MIN_DURATION_CHANGE_MS
+                || change.percent().abs() < threshold
+            {
+                continue;
+            }
+            if change.after > change.before {
+                comparison.slower.push(change);
+            } else {
+                comparison.faster.push(change);
+            }
+        }
+
+        comparison.slower.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+        comparison.faster.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+        comparison.newly_failing.sort();
+        comparison.lost_cache_hits.sort();
+        comparison
+            .changed_hashes
+            .sort_by(|a, b| a.task_id.cmp(&b.task_id));
+        comparison
+    }
+
+    pub fn has_regressions(&self) -> bool {
+        !self.slower.is_empty()
+            || !self.newly_failing.is_empty()
+            || !self.lost_cache_hits.is_empty()
+    }
+
+    pub fn print(&self, ui: ColorConfig) {
+        cprintln!(ui, BOLD_CYAN, "Comparison with run {}", self.baseline_id);
+        if let Some((before, after)) = self.duration {
+            println!(
+                "  Time:       {} -> {}",
+                format_duration(before),
+                color!(ui, BOLD, "{}", format_duration(after))
+            );
+        }
+        let (ratio_before, ratio_after) = self.cache_hit_ratio;
+        let ratio_style: &Style = if ratio_after < ratio_before {
+            &BOLD_RED
+        } else {
+            &BOLD
+        };
+        println!(
+            "  Cache hits: {:.1}% -> {}",
+            ratio_before * 100.0,
+            color!(ui, ratio_style, "{:.1}%", ratio_after * 100.0)
+        );
+
+        if !self.slower.is_empty() {
+            cprintln!(ui, BOLD_RED, "\nSlower tasks");
+            for change in &self.slower {
+                print_duration_change(ui, change);
+            }
+        }
+        if !self.faster.is_empty() {
+            cprintln!(ui, BOLD_GREEN, "\nFaster tasks");
+            for change in &self.faster {
+                print_duration_change(ui, change);
+            }
+        }
+        if !self.newly_failing.is_empty() {
+            cprintln!(ui, BOLD_RED, "\nNewly failing tasks");
+            for task_id in &self.newly_failing {
+                println!("  {task_id}");
+            }
+        }
+        if !self.lost_cache_hits.is_empty() {
+            cprintln!(ui, BOLD_RED, "\nCache misses with unchanged hashes");
+            for task_id in &self.lost_cache_hits {
+                println!("  {task_id}");
+            }
+        }
+        if !self.changed_hashes.is_empty() {
+            cprintln!(ui, BOLD, "\nChanged hashes");
+            for change in &self.changed_hashes {
+                println!(
+                    "  {} {}",
+                    change.task_id,
+                    color!(ui, GREY, "{} -> {}", change.before, change.after)
+                );
+            }
+        }
+        println!();
+    }
+}
+
+fn print_duration_change(ui: ColorConfig, change: &DurationChange) {
+    println!(
+        "  {} {} -> {} {}",
+        change.task_id,
+        format_duration(change.before),
+        format_duration(change.after),
+        color!(ui, GREY, "({:+.1}%)", change.percent())
+    );
+}
+
+fn format_duration(millis: i64) -> String {
+    TurboDuration::from(Duration::milliseconds(millis)).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use turbopath::AbsoluteSystemPathBuf;
+
+    use super::{Comparison, DurationChange, RunSnapshot, LAST_RUN};
+
+    fn snapshot(id: &str, tasks: serde_json::Value) -> RunSnapshot {
+        serde_json::from_value(json!({
+            "id": id,
+            "execution": { "startTime": 0, "endTime": 10_000 },
+            "tasks": tasks,
+        }))
+        .unwrap()
+    }
+
+    fn task(
+        task_id: &str,
+        hash: &str,
+        hit: bool,
+        duration: i64,
+        exit_code: i32,
+    ) -> serde_json::Value {
+        json!({
+            "taskId": task_id,
+            "hash": hash,
+            "cache": {
+                "local": hit,
+                "remote": false,
+                "status": if hit { "HIT" } else { "MISS" },
+                "timeSaved": 0,
+            },
+            "execution": { "startTime": 0, "endTime": duration, "exitCode": exit_code },
+        })
+    }
+
+    #[test]
+    fn test_comparison() {
+        let baseline = snapshot(
+            "before",
+            json!([
+                task("web#build", "aaa", false, 1_000, 0),
+                task("docs#build", "bbb", false, 2_000, 0),
+                task("ui#build", "ccc", true, 10, 0),
+                task("ui#lint", "ddd", false, 500, 0),
+                task("api#build", "eee", false, 1_000, 0),
+            ]),
+        );
+        let current = snapshot(
+            "after",
+            json!([
+                task("web#build", "aaa", false, 1_500, 0),
+                task("docs#build", "fff", false, 1_000, 0),
+                task("ui#build", "ccc", false, 900, 0),
+                task("ui#lint", "ddd", false, 100, 1),
+                // Within the threshold
+                task("api#build", "eee", false, 1_050, 0),
+            ]),
+        );
+
+        let comparison = Comparison::new(&baseline, &current, 10.0);
+        assert_eq!(
+            comparison.slower,
+            vec![DurationChange {
+                task_id: "web#build".to_string(),
+                before: 1_000,
+                after: 1_500
+            }]
+        );
+        assert_eq!(comparison.faster.len(), 1);
+        assert_eq!(comparison.faster[0].task_id, "docs#build");
+        assert_eq!(comparison.newly_failing, vec!["ui#lint"]);
+        assert_eq!(comparison.lost_cache_hits, vec!["ui#build"]);
+        assert_eq!(comparison.changed_hashes.len(), 1);
+        assert_eq!(comparison.changed_hashes[0].task_id, "docs#build");
+        assert_eq!(comparison.cache_hit_ratio, (0.2, 0.0));
+        assert!(comparison.has_regressions());
+    }
+
+    #[test]
+    fn test_comparison_without_regressions() {
+        let baseline = snapshot("before", json!([task("web#build", "aaa", false, 2_000, 0)]));
+        let current = snapshot("after", json!([task("web#build", "aaa", false, 1_000, 0)]));
+
+        let comparison = Comparison::new(&baseline, &current, 10.0);
+        assert_eq!(comparison.faster[0].percent(), -50.0);
+        assert!(!comparison.has_regressions());
+    }
+
+    #[test]
+    fn test_load_last_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        let runs_dir = repo_root.join_components(&[".turbo", "runs"]);
+        runs_dir.create_dir_all().unwrap();
+        for id in ["2abc", "2abd", "1zzz"] {
+            runs_dir
+                .join_component(&format!("{id}.json"))
+                .create_with_contents(json!({ "id": id, "tasks": [] }).to_string())
+                .unwrap();
+        }
+
+        let last = RunSnapshot::load(&repo_root, LAST_RUN).unwrap();
+        assert_eq!(last.id, "2abd");
+        let run = RunSnapshot::load(&repo_root, "1zzz").unwrap();
+        assert_eq!(run.id, "1zzz");
+        assert!(RunSnapshot::load(&repo_root, "3abc").is_err());
+    }
+}
@@ -3,10 +3,12 @@
 //! A tracker tracks the live data and then gets turned into a summary for
 //! displaying it We have this split because the tracker representation is not
 //! exactly what we want to display to the user.
+mod compare;
 #[allow(dead_code)]
 mod duration;
 mod execution;
 mod global_hash;
+mod otel;
 mod scm;
 mod task;
 mod task_factory;
@@ -27,7 +29,7 @@ use turborepo_env::EnvironmentVariableMap;
 use turborepo_repository::package_graph::{PackageGraph, PackageName};
 use turborepo_scm::SCM;
 use turborepo_task_id::TaskId;
-use turborepo_ui::{color, cprintln, cwriteln, ColorConfig, BOLD, BOLD_CYAN, GREY};
+use turborepo_ui::{color, cprintln, cwriteln, ColorConfig, BOLD, BOLD_CYAN, BOLD_RED, GREY};
 
 use self::{
     execution::TaskState, task::SinglePackageTaskSummary, task_factory::TaskSummaryFactory,
@@ -36,11 +38,14 @@ use crate::{
     cli,
     cli::{DryRunMode, EnvMode},
     engine::Engine,
-    opts::RunOpts,
-    run::summary::{
-        execution::{ExecutionSummary, ExecutionTracker},
-        scm::SCMState,
-        task::TaskSummary,
+    opts::{CompareOpts, RunHistoryOpts, RunOpts},
+    run::{
+        history::{RunHistory, RunRecord, TaskRecord},
+        summary::{
+            execution::{ExecutionSummary, ExecutionTracker},
+            scm::SCMState,
+            task::TaskSummary,
+        },
     },
     task_hash::TaskHashTracker,
 };
@@ -97,6 +102,10 @@ pub struct RunSummary<'a> {
     #[serde(skip)]
     should_save: bool,
     #[serde(skip)]
+    compare: Option<&'a CompareOpts>,
+    #[serde(skip)]
+    run_history: Option<RunHistoryOpts>,
+    #[serde(skip)]
     run_type: RunType,
 }
 
@@ -156,7 +165,8 @@ impl RunTracker {
         task_factory: TaskSummaryFactory<'a>,
     ) -> Result<RunSummary<'a>, Error> {
         let single_package = run_opts.single_package;
-        let should_save = run_opts.summarize;
+        // Comparing with the `last` run only works if runs are saved
+        let should_save = run_opts.summarize || run_opts.compare.is_some();
 
         let run_type = match run_opts.dry_run {
             None => RunType::Real,
@@ -196,6 +206,8 @@ impl RunTracker {
             monorepo: !single_package,
             repo_root,
             should_save,
+            compare: run_opts.compare.as_ref(),
+            run_history: run_opts.run_history,
             run_type,
         })
     }
@@ -226,7 +238,7 @@ impl RunTracker {
         hash_tracker: TaskHashTracker,
         env_at_execution_start: &'a EnvironmentVariableMap,
         is_watch: bool,
-    ) -> Result<(), Error> {
+    ) -> Result<i32, Error> {
         let end_time = Local::now();
 
         let task_factory = TaskSummaryFactory::new(
@@ -252,9 +264,22 @@ impl RunTracker {
             )
             .await?;
 
-        run_summary
+        let otel_export = run_opts
+            .otel
+            .as_ref()
+            .filter(|_| matches!(run_summary.run_type, RunType::Real))
+            .and_then(|otel_opts| otel::export(otel_opts, &run_summary));
+
+        let result = run_summary
             .finish(end_time, exit_code, pkg_dep_graph, ui, is_watch)
-            .await
+            .await;
+
+        if let Some(otel_export) = otel_export {
+            // Failures are logged by the export itself
+            let _ = otel_export.await;
+        }
+
+        result
     }
 
     pub fn track_task(&self, task_id: TaskId<'static>) -> TaskTracker<()> {
@@ -311,6 +336,8 @@ impl<'a> From<&'a RunSummary<'a>> for SinglePackageRunSummary<'a> {
 }
 
 impl<'a> RunSummary<'a> {
+    /// Returns the exit code for the run, which is only changed from
+    /// `exit_code` if a comparison found regressions.
     #[tracing::instrument(skip(self, pkg_dep_graph, ui))]
     async fn finish(
         mut self,
@@ -319,17 +346,31 @@ impl<'a> RunSummary<'a> {
         pkg_dep_graph: &PackageGraph,
         ui: ColorConfig,
         is_watch: bool,
-    ) -> Result<(), Error> {
+    ) -> Result<i32, Error> {
         if matches!(self.run_type, RunType::DryJson | RunType::DryText) {
-            return self.close_dry_run(pkg_dep_graph, ui);
+            self.close_dry_run(pkg_dep_graph, ui)?;
+            return Ok(exit_code);
         }
 
+        // Load the run we're comparing with before saving this one, otherwise
+        // `last` would refer to this run
+        let baseline = self
+            .compare
+            .map(|opts| compare::RunSnapshot::load(self.repo_root, &opts.run_id));
+
         if self.should_save {
             if let Err(err) = self.save() {
                 warn!("Error writing run summary: {}", err)
             }
         }
 
+        if let Some((retention, run)) = self.run_history.zip(self.history_record()) {
+            let history = RunHistory::new(self.repo_root);
+            if let Err(err) = history.record(&run, retention, end_time.timestamp_millis()) {
+                warn!("Error writing run history: {}", err)
+            }
+        }
+
         if !is_watch {
             if let Some(execution) = &self.execution {
                 let path = self.get_path();
@@ -338,7 +379,22 @@ impl<'a> RunSummary<'a> {
             }
         }
 
-        Ok(())
+        if let (Some(opts), Some(baseline)) = (self.compare, baseline) {
+            match baseline {
+                Ok(baseline) => {
+                    let current = compare::RunSnapshot::from(&self);
+                    let comparison = compare::Comparison::new(&baseline, &current, opts.threshold);
+                    comparison.print(ui);
+                    if opts.fail_on_regression && comparison.has_regressions() && exit_code == 0 {
+                        cprintln!(ui, BOLD_RED, "Failing run because of regressions");
+                        return Ok(1);
+                    }
+                }
+                Err(err) => warn!("Unable to compare runs: {}", err),
+            }
+        }
+
+        Ok(exit_code)
     }
 
     fn print_errors(errors: &[Error]) {
@@ -710,6 +766,34 @@ impl<'a> RunSummary<'a> {
             .collect()
     }
 
+    fn history_record(&self) -> Option<RunRecord> {
+        let execution = self.execution.as_ref()?;
+        let tasks = self
+            .tasks
+            .iter()
+            .filter_map(|task| {
+                let task_execution = task.shared.execution.as_ref()?;
+                Some(TaskRecord {
+                    task_id: task.task_id.to_string(),
+                    hash: task.shared.hash.clone(),
+                    cache_hit: task.shared.cache.is_hit(),
+                    cache_source: task.shared.cache.source().map(str::to_string),
+                    time_saved: task.shared.cache.time_saved(),
+                    start_time: task_execution.start_time,
+                    end_time: task_execution.end_time,
+                    exit_code: task_execution.exit_code,
+                })
+            })
+            .collect();
+        Some(RunRecord {
+            id: self.id.to_string(),
+            start_time: execution.start_time,
+            end_time: execution.end_time,
+            exit_code: execution.exit_code,
+            tasks,
+        })
+    }
+
     fn save(&mut self) -> Result<(), Error> {
         let json = self.format_json()?;
 
@@ -0,0 +1,421 @@
+//! Exports a finished run to an OpenTelemetry collector.
+//!
+//! We send a span for the run with a child span for each task, along with a
+//! few run level metrics, using the JSON encoding of OTLP over HTTP. Export
+//! failures are logged and never affect the outcome of the run.
+//!
+//! The export happens in the background while the run summary is printed, and
+//! requests time out quickly so an unreachable collector can't hold up the end
+//! of the run.
+
+use std::time::Duration;
+
+use futures::future::join_all;
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use super::{task::TaskSummary, RunSummary};
+use crate::opts::OtelOpts;
+
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(2);
+
+// https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding
+const SPAN_KIND_INTERNAL: u8 = 1;
+const STATUS_CODE_OK: u8 = 1;
+const STATUS_CODE_ERROR: u8 = 2;
+
+/// Starts exporting the run in the background. The returned handle should be
+/// awaited before exiting so the requests aren't dropped.
+pub fn export(opts: &OtelOpts, run_summary: &RunSummary<'_>) -> Option<JoinHandle<()>> {
+    let execution = run_summary.execution.as_ref()?;
+    let tasks = run_summary
+        .tasks
+        .iter()
+        .filter_map(TaskSpan::new)
+        .collect::<Vec<_>>();
+
+    let trace_id = hex::encode(rand::random::<[u8; 16]>());
+    let run_span_id = hex::encode(rand::random::<[u8; 8]>());
+
+    let mut spans = vec![json!({
+        "traceId": trace_id,
+        "spanId": run_span_id,
+        "name": "turbo run",
+        "kind": SPAN_KIND_INTERNAL,
+        "startTimeUnixNano": unix_nanos(execution.start_time),
+        "endTimeUnixNano": unix_nanos(execution.end_time),
+        "attributes": [
+            attribute("turbo.run.id", run_summary.id.to_string()),
+            attribute("turbo.version", run_summary.turbo_version),
+            attribute("turbo.exit_code", execution.exit_code),
+        ],
+        "status": status(execution.exit_code == 0),
+    })];
+    spans.extend(
+        tasks
+            .iter()
+            .map(|task| task.to_span(&trace_id, &run_span_id)),
+    );
+
+    let resource = json!({
+        "attributes": [attribute("service.name", opts.service_name.as_str())]
+    });
+    let scope = json!({ "name": "turborepo", "version": run_summary.turbo_version });
+    let traces = json!({
+        "resourceSpans": [{
+            "resource": resource,
+            "scopeSpans": [{ "scope": scope, "spans": spans }]
+        }]
+    });
+    let metrics = json!({
+        "resourceMetrics": [{
+            "resource": resource,
+            "scopeMetrics": [{
+                "scope": scope,
+                "metrics": metrics(&tasks, execution.end_time)
+            }]
+        }]
+    });
+
+    Some(send(opts, traces, metrics))
+}
+
+// Sends the encoded traces and metrics to the collector in the background
+fn send(opts: &OtelOpts, traces: Value, metrics: Value) -> JoinHandle<()> {
+    let client = reqwest::Client::new();
+    let endpoint = opts.endpoint.trim_end_matches('/');
+    let requests = [("v1/traces", traces), ("v1/metrics", metrics)].map(|(path, body)| {
+        let url = format!("{endpoint}/{path}");
+        let mut request = client.post(&url).timeout(EXPORT_TIMEOUT).json(&body);
+        for (key, value) in &opts.headers {
+            request = request.header(key, value);
+        }
+        async move {
+            match request.send().await.and_then(|res| res.error_for_status()) {
+                Ok(_) => debug!("exported run to {url}"),
+                Err(e) => warn!("failed to export run to {url}: {e}"),
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        join_all(requests).await;
+    })
+}
+
+/// The parts of a task summary that end up on its span.
+#[derive(Debug)]
+struct TaskSpan {
+    task_id: String,
+    task: String,
+    package: String,
+    hash: String,
+    cache_hit: bool,
+    cache_source: Option<&'static str>,
+    time_saved: u64,
+    start_time: i64,
+    end_time: i64,
+    exit_code: Option<i32>,
+    failed: bool,
+}
+
+impl TaskSpan {
+    // Tasks that never started don't get a span
+    fn new(task: &TaskSummary) -> Option<Self> {
+        let execution = task.shared.execution.as_ref()?;
+        Some(Self {
+            task_id: task.task_id.to_string(),
+            task: task.task.clone(),
+            package: task.package.clone(),
+            hash: task.shared.hash.clone(),
+            cache_hit: task.shared.cache.is_hit(),
+            cache_source: task.shared.cache.source(),
+            time_saved: task.shared.cache.time_saved(),
+            start_time: execution.start_time,
+            end_time: execution.end_time,
+            exit_code: execution.exit_code,
+            failed: execution.is_failure(),
+        })
+    }
+
+    fn to_span(&self, trace_id: &str, parent_span_id: &str) -> Value {
+        let mut attributes = vec![
+            attribute("turbo.task.id", self.task_id.as_str()),
+            attribute("turbo.task.name", self.task.as_str()),
+            attribute("turbo.package", self.package.as_str()),
+            attribute("turbo.task.hash", self.hash.as_str()),
+            attribute(
+                "turbo.cache.status",
+                if self.cache_hit { "HIT" } else { "MISS" },
+            ),
+            attribute("turbo.cache.time_saved_ms", self.time_saved),
+        ];
+        if let Some(source) = self.cache_source {
+            attributes.push(attribute("turbo.cache.source", source));
+        }
+        if let Some(exit_code) = self.exit_code {
+            attributes.push(attribute("turbo.task.exit_code", exit_code));
+        }
+
+        json!({
+            "traceId": trace_id,
+            "spanId": hex::encode(rand::random::<[u8; 8]>()),
+            "parentSpanId": parent_span_id,
+            "name": self.task_id,
+            "kind": SPAN_KIND_INTERNAL,
+            "startTimeUnixNano": unix_nanos(self.start_time),
+            "endTimeUnixNano": unix_nanos(self.end_time),
+            "attributes": attributes,
+            "status": status(!self.failed),
+        })
+    }
+}
+
+fn metrics(tasks: &[TaskSpan], end_time: i64) -> Value {
+    let cached = tasks.iter().filter(|task| task.cache_hit).count();
+    let hit_ratio = if tasks.is_empty() {
+        0.0
+    } else {
+        cached as f64 / tasks.len() as f64
+    };
+    let time_saved: u64 = tasks.iter().map(|task| task.time_saved).sum();
+    let time = unix_nanos(end_time);
+
+    json!([
+        {
+            "name": "turbo.run.cache_hit_ratio",
+            "unit": "1",
+            "gauge": { "dataPoints": [{ "timeUnixNano": time, "asDouble": hit_ratio }] }
+        },
+        {
+            "name": "turbo.run.time_saved",
+            "unit": "ms",
+            "gauge": { "dataPoints": [{ "timeUnixNano": time, "asInt": time_saved.to_string() }] }
+        },
+        {
+            "name": "turbo.run.tasks",
+            "unit": "{task}",
+            "gauge": { "dataPoints": [{ "timeUnixNano": time, "asInt": tasks.len().to_string() }] }
+        }
+    ])
+}
+
+// OTLP JSON encodes 64 bit integers as strings
+fn unix_nanos(millis: i64) -> String {
+    (millis.max(0) as u128 * 1_000_000).to_string()
+}
+
+fn status(ok: bool) -> Value {
+    json!({ "code": if ok { STATUS_CODE_OK } else { STATUS_CODE_ERROR } })
+}
+
+fn attribute(key: &str, value: impl Into<AttributeValue>) -> Value {
+    let value = match value.into() {
+        AttributeValue::String(value) => json!({ "stringValue": value }),
+        AttributeValue::Int(value) => json!({ "intValue": value.to_string() }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+enum AttributeValue {
+    String(String),
+    Int(i64),
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<i32> for AttributeValue {
+    fn from(value: i32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<u64> for AttributeValue {
+    fn from(value: u64) -> Self {
+        Self::Int(value as i64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use serde_json::{json, Value};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+        sync::mpsc,
+    };
+
+    use super::{metrics, send, TaskSpan, EXPORT_TIMEOUT};
+    use crate::opts::OtelOpts;
+
+    fn task_span(task_id: &str, cache_hit: bool, time_saved: u64) -> TaskSpan {
+        TaskSpan {
+            task_id: task_id.to_string(),
+            task: "build".to_string(),
+            package: "web".to_string(),
+            hash: "abc123".to_string(),
+            cache_hit,
+            cache_source: cache_hit.then_some("LOCAL"),
+            time_saved,
+            start_time: 1_000,
+            end_time: 2_000,
+            exit_code: Some(0),
+            failed: false,
+        }
+    }
+
+    #[test]
+    fn test_metrics() {
+        let tasks = [
+            task_span("web#build", true, 300),
+            task_span("docs#build", false, 0),
+        ];
+        let metrics = metrics(&tasks, 2_000);
+        assert_eq!(
+            metrics[0]["gauge"]["dataPoints"][0],
+            json!({ "timeUnixNano": "2000000000", "asDouble": 0.5 })
+        );
+        assert_eq!(metrics[1]["gauge"]["dataPoints"][0]["asInt"], json!("300"));
+    }
+
+    #[test]
+    fn test_task_span() {
+        let span = task_span("web#build", true, 300).to_span("trace", "parent");
+        assert_eq!(span["parentSpanId"], json!("parent"));
+        assert_eq!(span["startTimeUnixNano"], json!("1000000000"));
+        assert_eq!(span["status"]["code"], json!(1));
+        assert!(span["attributes"].as_array().unwrap().contains(
+            &json!({ "key": "turbo.cache.source", "value": { "stringValue": "LOCAL" } })
+        ));
+    }
+
+    fn otel_opts(endpoint: String) -> OtelOpts {
+        OtelOpts {
+            endpoint,
+            headers: vec![("x-api-key".to_string(), "secret".to_string())],
+            service_name: "turborepo".to_string(),
+        }
+    }
+
+    // Reads a single request, returning its headers and body
+    async fn read_request(stream: &mut TcpStream) -> (String, String) {
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+                let content_length = headers
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or_default();
+                if body.len() >= content_length || n == 0 {
+                    return (headers.to_string(), body.to_string());
+                }
+            }
+            assert_ne!(n, 0, "connection closed before the request was read");
+        }
+    }
+
+    // A collector that records each request it receives and responds with
+    // `200 OK`
+    async fn collector() -> (String, mpsc::UnboundedReceiver<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    // Recorded before responding so it's seen once the export finishes
+                    let _ = tx.send(read_request(&mut stream).await);
+                    stream
+                        .write_all(
+                            b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                        )
+                        .await
+                        .unwrap();
+                });
+            }
+        });
+        (endpoint, rx)
+    }
+
+    #[tokio::test]
+    async fn test_send_delivers_to_collector() {
+        let (endpoint, mut requests) = collector().await;
+        let traces = json!({ "resourceSpans": [] });
+        let metrics = json!({ "resourceMetrics": [] });
+
+        let handle = send(&otel_opts(endpoint), traces.clone(), metrics.clone());
+        tokio::time::timeout(EXPORT_TIMEOUT, handle)
+            .await
+            .expect("export should finish within the export timeout")
+            .unwrap();
+
+        let mut received = Vec::new();
+        while let Ok((headers, body)) = requests.try_recv() {
+            assert!(
+                headers
+                    .lines()
+                    .any(|line| line.eq_ignore_ascii_case("x-api-key: secret")),
+                "{headers}"
+            );
+            let path = headers.split_whitespace().nth(1).unwrap().to_string();
+            received.push((path, serde_json::from_str::<Value>(&body).unwrap()));
+        }
+        received.sort_by(|(a, _), (b, _)| a.cmp(b));
+        assert_eq!(
+            received,
+            vec![
+                ("/v1/metrics".to_string(), metrics),
+                ("/v1/traces".to_string(), traces),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_does_not_wait_on_unresponsive_collector() {
+        // Accepts connections but never responds
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                connections.push(stream);
+            }
+        });
+
+        let start = Instant::now();
+        let handle = send(&otel_opts(endpoint), json!({}), json!({}));
+        // The export runs in the background, so the run can finish while the
+        // collector is unresponsive
+        assert!(!handle.is_finished());
+
+        tokio::time::timeout(EXPORT_TIMEOUT + Duration::from_secs(1), handle)
+            .await
+            .expect("export should give up after the export timeout")
+            .unwrap();
+        assert!(start.elapsed() >= EXPORT_TIMEOUT);
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::BTreeMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use turbopath::{AnchoredSystemPathBuf, RelativeUnixPathBuf};
 use turborepo_cache::CacheHitMetadata;
 use turborepo_env::{DetailedMap, EnvironmentVariableMap};
@@ -12,7 +12,7 @@ use crate::{
     task_graph::{TaskDefinition, TaskOutputs},
 };
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskCacheSummary {
     // Deprecated, but keeping around for --dry=json
@@ -27,14 +27,14 @@ pub struct TaskCacheSummary {
     time_saved: u64,
 }
 
-#[derive(Debug, Serialize, Copy, Clone)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
 #[serde(rename_all = "UPPERCASE")]
 enum CacheStatus {
     Hit,
     Miss,
 }
 
-#[derive(Debug, Serialize, Copy, Clone)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
 #[serde(rename_all = "UPPERCASE")]
 enum CacheSource {
     Local,
@@ -122,6 +122,21 @@ pub struct TaskEnvVarSummary {
 }
 
 impl TaskCacheSummary {
+    pub fn is_hit(&self) -> bool {
+        matches!(self.status, CacheStatus::Hit)
+    }
+
+    pub fn source(&self) -> Option<&'static str> {
+        self.source.map(|source| match source {
+            CacheSource::Local => "LOCAL",
+            CacheSource::Remote => "REMOTE",
+        })
+    }
+
+    pub fn time_saved(&self) -> u64 {
+        self.time_saved
+    }
+
     pub fn cache_miss() -> Self {
         Self {
             local: false,
@@ -289,6 +304,7 @@ impl From<TaskDefinition> for TaskSummaryTaskDefinition {
             interactive,
             env_mode,
             with: _,
+            cache_mode: _,
         } = value;
 
         let mut outputs = inclusions;
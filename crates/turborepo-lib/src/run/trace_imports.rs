@@ -0,0 +1,79 @@
+//! Keeps the imports found when tracing files between invocations, so that
+//! `turbo boundaries` and `turbo query` only parse the files that changed.
+//! Imports are stored on disk under `.turbo`, and in the daemon if it's
+//! running.
+
+use std::{collections::HashMap, sync::Arc};
+
+use tracing::debug;
+use turbo_trace::{FileImport, ImportCache};
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+
+use crate::run::Run;
+
+fn import_cache_path(repo_root: &AbsoluteSystemPath) -> AbsoluteSystemPathBuf {
+    repo_root.join_components(&[".turbo", "trace-imports.json"])
+}
+
+/// Loads the imports that were cached on disk.
+pub(crate) fn load_import_cache(repo_root: &AbsoluteSystemPath) -> Arc<ImportCache> {
+    Arc::new(ImportCache::load(&import_cache_path(repo_root)))
+}
+
+/// Writes the cache to disk if any files had to be parsed.
+pub(crate) fn write_import_cache(repo_root: &AbsoluteSystemPath, cache: &ImportCache) {
+    if let Err(e) = cache.save(&import_cache_path(repo_root)) {
+        debug!("failed to save traced imports: {e}");
+    }
+}
+
+impl Run {
+    pub(crate) fn import_cache(&self) -> Arc<ImportCache> {
+        load_import_cache(&self.repo_root)
+    }
+
+    /// Fills in the imports for `keys` that aren't cached on disk from the
+    /// daemon.
+    pub(crate) async fn fetch_daemon_imports(&self, cache: &ImportCache, keys: Vec<String>) {
+        let Some(mut daemon) = self.daemon.clone() else {
+            return;
+        };
+        let keys = keys
+            .into_iter()
+            .filter(|key| !cache.contains(key))
+            .collect::<Vec<_>>();
+        if keys.is_empty() {
+            return;
+        }
+
+        match daemon.get_trace_imports(keys).await {
+            Ok(imports) => cache.extend(imports.into_iter().filter_map(|(key, imports)| {
+                let imports = serde_json::from_str::<Vec<FileImport>>(&imports).ok()?;
+                Some((key, imports))
+            })),
+            Err(e) => debug!("failed to get traced imports from the daemon: {e}"),
+        }
+    }
+
+    /// Saves the imports of the files that had to be parsed, both on disk and
+    /// in the daemon.
+    pub(crate) async fn save_import_cache(&self, cache: &ImportCache) {
+        write_import_cache(&self.repo_root, cache);
+
+        let Some(mut daemon) = self.daemon.clone() else {
+            return;
+        };
+        let imports = cache
+            .added()
+            .into_iter()
+            .filter_map(|(key, imports)| Some((key, serde_json::to_string(&imports).ok()?)))
+            .collect::<HashMap<_, _>>();
+        if imports.is_empty() {
+            return;
+        }
+
+        if let Err(e) = daemon.notify_trace_imports(imports).await {
+            debug!("failed to record traced imports with the daemon: {e}");
+        }
+    }
+}
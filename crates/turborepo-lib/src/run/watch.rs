@@ -1,28 +1,48 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     ops::DerefMut as _,
     sync::{Arc, Mutex},
 };
 
-use futures::StreamExt;
+use futures::{stream::BoxStream, StreamExt};
 use miette::{Diagnostic, SourceSpan};
 use thiserror::Error;
 use tokio::{select, sync::Notify, task::JoinHandle};
-use tracing::{instrument, trace, warn};
+use tracing::{debug, instrument, trace, warn};
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath};
+use turborepo_filewatch::{
+    hash_watcher::{HashSpec, InputGlobs},
+    WatchError,
+};
 use turborepo_repository::package_graph::PackageName;
 use turborepo_signals::{listeners::get_signal, SignalHandler};
+use turborepo_task_id::TaskId;
 use turborepo_telemetry::events::command::CommandEventBuilder;
 use turborepo_ui::sender::UISender;
 
 use crate::{
     commands::CommandBase,
     config::resolve_turbo_config_path,
-    daemon::{proto, DaemonConnectorError, DaemonError},
+    daemon::{
+        package_change_event_to_proto, proto, DaemonClient, DaemonConnectorError, DaemonError,
+        FileWatching,
+    },
     get_version, opts,
     run::{self, builder::RunBuilder, scope::target_selector::InvalidSelectorError, Run},
+    task_graph::TaskInputs,
     DaemonConnector, DaemonPaths,
 };
 
+/// Where watch mode gets package change events and file hashes from.
+#[derive(Clone)]
+enum WatchBackend {
+    /// Use the daemon, starting it if necessary.
+    Daemon(DaemonConnector),
+    /// Run the file watchers inside of this process. Used when the daemon is
+    /// disabled or fails to start.
+    InProcess(FileWatching),
+}
+
 #[derive(Debug)]
 enum ChangedPackages {
     All,
@@ -44,11 +64,33 @@ impl ChangedPackages {
     }
 }
 
+#[derive(Debug)]
+enum ChangedTasks {
+    All,
+    Some(HashSet<TaskId<'static>>),
+}
+
+impl ChangedTasks {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            ChangedTasks::All => false,
+            ChangedTasks::Some(tasks) => tasks.is_empty(),
+        }
+    }
+}
+
 pub struct WatchClient {
     run: Arc<Run>,
     watched_packages: HashSet<PackageName>,
+    // The file hashes of each task's inputs as of the last time its package
+    // changed. Used to only re-run tasks whose inputs actually changed.
+    task_input_hashes: HashMap<TaskId<'static>, HashMap<String, String>>,
     persistent_tasks_handle: Option<RunHandle>,
-    connector: DaemonConnector,
+    backend: WatchBackend,
+    // Set when the user explicitly asked for the daemon with `--daemon`, in
+    // which case we don't fall back to watching in-process.
+    daemon_required: bool,
+    custom_turbo_json_path: Option<AbsoluteSystemPathBuf>,
     base: CommandBase,
     telemetry: CommandEventBuilder,
     handler: SignalHandler,
@@ -69,6 +111,8 @@ pub enum Error {
     Daemon(#[from] DaemonError),
     #[error("Failed to connect to daemon.")]
     DaemonConnector(#[from] DaemonConnectorError),
+    #[error("Failed to start file watching.")]
+    FileWatching(#[from] WatchError),
     #[error("Failed to decode message from daemon.")]
     Decode(#[from] prost::DecodeError),
     #[error("Could not get current executable.")]
@@ -109,6 +153,13 @@ pub enum Error {
     SignalListener(#[from] turborepo_signals::listeners::Error),
 }
 
+// The in-process watcher's cookies can't go inside the daemon's cookie
+// directory, `.turbo/cookies`, because the daemon accepts cookies at any depth
+// under it and clears it when it starts.
+fn in_process_cookie_dir(repo_root: &AbsoluteSystemPath) -> AbsoluteSystemPathBuf {
+    repo_root.join_components(&[".turbo", "watch-cookies"])
+}
+
 impl WatchClient {
     pub async fn new(
         base: CommandBase,
@@ -120,10 +171,6 @@ impl WatchClient {
 
         let standard_config_path = resolve_turbo_config_path(&base.repo_root)?;
 
-        if matches!(base.opts.run_opts.daemon, Some(false)) {
-            warn!("daemon is required for watch, ignoring request to disable daemon");
-        }
-
         let new_base = base.clone();
         let run = Arc::new(
             RunBuilder::new(new_base)?
@@ -148,18 +195,26 @@ impl WatchClient {
                 None
             };
 
-        let connector = DaemonConnector {
-            can_start_server: true,
-            can_kill_server: true,
-            paths: DaemonPaths::from_repo_root(&base.repo_root),
-            custom_turbo_json_path,
+        let backend = if matches!(base.opts.run_opts.daemon, Some(false)) {
+            Self::in_process_backend(&base.repo_root, custom_turbo_json_path.clone())?
+        } else {
+            WatchBackend::Daemon(DaemonConnector {
+                can_start_server: true,
+                can_kill_server: true,
+                paths: DaemonPaths::from_repo_root(&base.repo_root),
+                custom_turbo_json_path: custom_turbo_json_path.clone(),
+            })
         };
+        let daemon_required = matches!(base.opts.run_opts.daemon, Some(true));
 
         Ok(Self {
             base,
             run,
             watched_packages,
-            connector,
+            task_input_hashes: HashMap::new(),
+            backend,
+            daemon_required,
+            custom_turbo_json_path,
             handler,
             telemetry,
             experimental_write_cache,
@@ -169,11 +224,86 @@ impl WatchClient {
         })
     }
 
-    pub async fn start(&mut self) -> Result<(), Error> {
-        let connector = self.connector.clone();
-        let mut client = connector.connect().await?;
+    fn in_process_backend(
+        repo_root: &AbsoluteSystemPath,
+        custom_turbo_json_path: Option<AbsoluteSystemPathBuf>,
+    ) -> Result<WatchBackend, Error> {
+        debug!("watching files in-process");
+        let file_watching = FileWatching::new_with_cookie_dir(
+            repo_root.to_owned(),
+            in_process_cookie_dir(repo_root),
+            custom_turbo_json_path,
+        )?;
+        Ok(WatchBackend::InProcess(file_watching))
+    }
 
-        let mut events = client.package_changes().await?;
+    /// Connects to the daemon if `backend` uses it. If we fail to connect,
+    /// `backend` is replaced with an in-process one unless the daemon was
+    /// explicitly requested.
+    async fn connect_or_fall_back(
+        backend: &mut WatchBackend,
+        daemon_required: bool,
+        repo_root: &AbsoluteSystemPath,
+        custom_turbo_json_path: Option<AbsoluteSystemPathBuf>,
+    ) -> Result<Option<DaemonClient<DaemonConnector>>, Error> {
+        let WatchBackend::Daemon(connector) = backend else {
+            return Ok(None);
+        };
+        match connector.clone().connect().await {
+            Ok(client) => Ok(Some(client)),
+            Err(err) if !daemon_required => {
+                warn!("failed to connect to daemon, watching files in-process: {err}");
+                *backend = Self::in_process_backend(repo_root, custom_turbo_json_path)?;
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Subscribes to package change events. If we fail to connect to the
+    /// daemon, we fall back to watching files in-process unless the daemon
+    /// was explicitly requested.
+    async fn package_change_events(
+        &mut self,
+    ) -> Result<BoxStream<'static, Result<proto::PackageChangeEvent, Error>>, Error> {
+        if let Some(mut client) = Self::connect_or_fall_back(
+            &mut self.backend,
+            self.daemon_required,
+            &self.base.repo_root,
+            self.custom_turbo_json_path.clone(),
+        )
+        .await?
+        {
+            let events = client.package_changes().await?;
+            return Ok(events.map(|event| event.map_err(Error::from)).boxed());
+        }
+
+        let WatchBackend::InProcess(file_watching) = &self.backend else {
+            unreachable!("daemon backend handled above");
+        };
+        let package_changes_rx = file_watching
+            .get_or_init_package_changes_watcher()
+            .package_changes()
+            .await;
+
+        // The daemon always starts a subscription with a rediscovery, so we do the same
+        let rediscover = proto::PackageChangeEvent {
+            event: Some(proto::package_change_event::Event::RediscoverPackages(
+                proto::RediscoverPackages {},
+            )),
+        };
+        let changes = futures::stream::unfold(package_changes_rx, |mut rx| async move {
+            let event = package_change_event_to_proto(rx.recv().await);
+            Some((Ok(event), rx))
+        });
+
+        Ok(futures::stream::once(async { Ok(rediscover) })
+            .chain(changes)
+            .boxed())
+    }
+
+    pub async fn start(&mut self) -> Result<(), Error> {
+        let mut events = self.package_change_events().await?;
 
         let signal_subscriber = self.handler.subscribe().ok_or(Error::NoSignalHandler)?;
 
@@ -206,6 +336,12 @@ impl WatchClient {
                 };
 
                 if let Some(changed_packages) = some_changed_packages {
+                    let changed_tasks = self.resolve_changed_tasks(changed_packages).await;
+                    if changed_tasks.is_empty() {
+                        trace!("no task inputs changed, skipping run");
+                        continue;
+                    }
+
                     // Clean up currently running tasks
                     if let Some(RunHandle { stopper, run_task }) = run_handle.take() {
                         // Shut down the tasks for the run
@@ -214,7 +350,7 @@ impl WatchClient {
                         // finish to ensure all messages are flushed.
                         let _ = run_task.await;
                     }
-                    run_handle = Some(self.execute_run(changed_packages).await?);
+                    run_handle = Some(self.execute_run(changed_tasks).await?);
                 }
             }
         };
@@ -266,6 +402,131 @@ impl WatchClient {
         Ok(())
     }
 
+    /// Narrows down a set of changed packages to the tasks whose inputs
+    /// changed. A package change only tells us that some file in the package
+    /// changed, so we re-hash each task's `inputs` and compare against the
+    /// hashes from the previous change.
+    async fn resolve_changed_tasks(&mut self, changed_packages: ChangedPackages) -> ChangedTasks {
+        match changed_packages {
+            ChangedPackages::All => ChangedTasks::All,
+            ChangedPackages::Some(packages) => {
+                let packages = packages
+                    .into_iter()
+                    .filter(|pkg| {
+                        // If not in the watched packages set, ignore
+                        self.watched_packages.contains(pkg)
+                    })
+                    .collect();
+                ChangedTasks::Some(self.hash_task_inputs(&packages).await)
+            }
+        }
+    }
+
+    /// Hashes the inputs of every task in `packages`, records the new hashes
+    /// and returns the tasks whose hashes differ from the recorded ones.
+    /// Tasks that we fail to hash are always considered changed.
+    async fn hash_task_inputs(
+        &mut self,
+        packages: &HashSet<PackageName>,
+    ) -> HashSet<TaskId<'static>> {
+        let run = self.run.clone();
+        let mut client = match &self.backend {
+            WatchBackend::Daemon(connector) => match connector.clone().connect().await {
+                Ok(client) => Some(client),
+                Err(err) => {
+                    debug!("unable to hash task inputs, falling back to package changes: {err}");
+                    None
+                }
+            },
+            WatchBackend::InProcess(_) => None,
+        };
+
+        let mut changed_tasks = HashSet::new();
+        for task_id in run.engine.task_ids() {
+            let package = PackageName::from(task_id.package());
+            if !packages.contains(&package) {
+                continue;
+            }
+
+            let file_hashes = match (&self.backend, &mut client) {
+                (WatchBackend::InProcess(file_watching), _) => {
+                    Self::get_task_file_hashes_in_process(file_watching, &run, &package, task_id)
+                        .await
+                }
+                (WatchBackend::Daemon(_), Some(client)) => {
+                    Self::get_task_file_hashes(client, &run, &package, task_id).await
+                }
+                (WatchBackend::Daemon(_), None) => None,
+            };
+
+            match file_hashes {
+                Some(file_hashes) => {
+                    if self.task_input_hashes.get(task_id) != Some(&file_hashes) {
+                        changed_tasks.insert(task_id.clone());
+                    }
+                    self.task_input_hashes.insert(task_id.clone(), file_hashes);
+                }
+                None => {
+                    self.task_input_hashes.remove(task_id);
+                    changed_tasks.insert(task_id.clone());
+                }
+            }
+        }
+
+        changed_tasks
+    }
+
+    async fn get_task_file_hashes(
+        client: &mut DaemonClient<DaemonConnector>,
+        run: &Run,
+        package: &PackageName,
+        task_id: &TaskId<'static>,
+    ) -> Option<HashMap<String, String>> {
+        let package_path = run.pkg_dep_graph.package_info(package)?.package_path();
+        let definition = run.engine.task_definition(task_id)?;
+        client
+            .get_file_hashes(package_path, &definition.inputs)
+            .await
+            .inspect_err(|err| debug!("unable to hash inputs for {task_id}: {err}"))
+            .ok()
+            .map(|response| response.file_hashes)
+    }
+
+    async fn get_task_file_hashes_in_process(
+        file_watching: &FileWatching,
+        run: &Run,
+        package: &PackageName,
+        task_id: &TaskId<'static>,
+    ) -> Option<HashMap<String, String>> {
+        let package_path = run.pkg_dep_graph.package_info(package)?.package_path();
+        let definition = run.engine.task_definition(task_id)?;
+        let hash_spec = Self::hash_spec(package_path, &definition.inputs)
+            .inspect_err(|err| debug!("invalid inputs for {task_id}: {err}"))
+            .ok()?;
+        let file_hashes = file_watching
+            .hash_watcher
+            .get_file_hashes(hash_spec)
+            .await
+            .inspect_err(|err| debug!("unable to hash inputs for {task_id}: {err}"))
+            .ok()?;
+        Some(
+            file_hashes
+                .into_iter()
+                .map(|(path, hash)| (path.to_string(), hash))
+                .collect(),
+        )
+    }
+
+    fn hash_spec(
+        package_path: &AnchoredSystemPath,
+        inputs: &TaskInputs,
+    ) -> Result<HashSpec, turborepo_filewatch::globwatcher::GlobError> {
+        Ok(HashSpec {
+            package_path: package_path.to_owned(),
+            inputs: InputGlobs::from_raw(inputs.globs.clone(), inputs.default)?,
+        })
+    }
+
     /// Shut down any resources that run as part of watch.
     pub async fn shutdown(&mut self) {
         if let Some(sender) = &self.ui_sender {
@@ -280,26 +541,18 @@ impl WatchClient {
         }
     }
 
-    /// Executes a run with the given changed packages. Splits the run into two
+    /// Executes a run with the given changed tasks. Splits the run into two
     /// parts:
     /// 1. The persistent tasks that are not allowed to be interrupted
     /// 2. The non-persistent tasks and the persistent tasks that are allowed to
     ///    be interrupted
     ///
     /// Returns a handle to the task running (2)
-    async fn execute_run(&mut self, changed_packages: ChangedPackages) -> Result<RunHandle, Error> {
+    async fn execute_run(&mut self, changed_tasks: ChangedTasks) -> Result<RunHandle, Error> {
         // Should we recover here?
-        trace!("handling run with changed packages: {changed_packages:?}");
-        match changed_packages {
-            ChangedPackages::Some(packages) => {
-                let packages = packages
-                    .into_iter()
-                    .filter(|pkg| {
-                        // If not in the watched packages set, ignore
-                        self.watched_packages.contains(pkg)
-                    })
-                    .collect();
-
+        trace!("handling run with changed tasks: {changed_tasks:?}");
+        match changed_tasks {
+            ChangedTasks::Some(tasks) => {
                 let mut opts = self.base.opts().clone();
                 if !self.experimental_write_cache {
                     opts.cache_opts.cache.remote.write = false;
@@ -317,7 +570,7 @@ impl WatchClient {
                 let telemetry = self.telemetry.clone();
 
                 let run = RunBuilder::new(new_base)?
-                    .with_entrypoint_packages(packages)
+                    .with_entrypoint_tasks(tasks)
                     .hide_prelude()
                     .build(&signal_handler, telemetry)
                     .await?;
@@ -335,7 +588,7 @@ impl WatchClient {
                     run_task: tokio::spawn(async move { run.run(ui_sender, true).await }),
                 })
             }
-            ChangedPackages::All => {
+            ChangedTasks::All => {
                 let mut opts = self.base.opts().clone();
                 if !self.experimental_write_cache {
                     opts.cache_opts.cache.remote.write = false;
@@ -358,6 +611,11 @@ impl WatchClient {
 
                 self.watched_packages = self.run.get_relevant_packages();
 
+                // Record a fresh baseline of input hashes for the new task graph
+                self.task_input_hashes.clear();
+                let watched_packages = self.watched_packages.clone();
+                self.hash_task_inputs(&watched_packages).await;
+
                 // Clean up currently running persistent tasks
                 if let Some(RunHandle { stopper, run_task }) = self.persistent_tasks_handle.take() {
                     // Shut down the tasks for the run
@@ -409,3 +667,74 @@ impl WatchClient {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use turbopath::{AbsoluteSystemPathBuf, PathRelation};
+
+    use super::{in_process_cookie_dir, WatchBackend, WatchClient};
+    use crate::{daemon::DaemonConnectorError, run::watch::Error, DaemonConnector};
+
+    #[test]
+    fn test_in_process_cookie_dir_is_outside_daemon_cookie_dir() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp_dir.path()).unwrap();
+        let daemon_cookie_dir = repo_root.join_components(&[".turbo", "cookies"]);
+        let cookie_dir = in_process_cookie_dir(&repo_root);
+
+        assert_eq!(
+            repo_root.relation_to_path(&cookie_dir),
+            PathRelation::Parent
+        );
+        assert_eq!(
+            daemon_cookie_dir.relation_to_path(&cookie_dir),
+            PathRelation::Divergent
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_process_backend() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp_dir.path()).unwrap();
+
+        let mut backend = WatchClient::in_process_backend(&repo_root, None).unwrap();
+        assert!(matches!(backend, WatchBackend::InProcess(_)));
+
+        // An in-process backend never connects to the daemon
+        let client = WatchClient::connect_or_fall_back(&mut backend, true, &repo_root, None)
+            .await
+            .unwrap();
+        assert!(client.is_none());
+        assert!(matches!(backend, WatchBackend::InProcess(_)));
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_in_process_backend() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp_dir.path()).unwrap();
+        // The daemon isn't running and can't be started
+        let mut backend =
+            WatchBackend::Daemon(DaemonConnector::new(false, false, &repo_root, None));
+
+        let client = WatchClient::connect_or_fall_back(&mut backend, false, &repo_root, None)
+            .await
+            .unwrap();
+        assert!(client.is_none());
+        assert!(matches!(backend, WatchBackend::InProcess(_)));
+    }
+
+    #[tokio::test]
+    async fn test_does_not_fall_back_when_daemon_is_required() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp_dir.path()).unwrap();
+        let mut backend =
+            WatchBackend::Daemon(DaemonConnector::new(false, false, &repo_root, None));
+
+        let result = WatchClient::connect_or_fall_back(&mut backend, true, &repo_root, None).await;
+        assert!(matches!(
+            result,
+            Err(Error::DaemonConnector(DaemonConnectorError::NotRunning))
+        ));
+        assert!(matches!(backend, WatchBackend::Daemon(_)));
+    }
+}
@@ -0,0 +1,125 @@
+//! CycloneDX 1.5 JSON, see https://cyclonedx.org/docs/1.5/json
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+
+use super::{HashAlgorithm, Sbom};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Bom<'a> {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: Metadata<'a>,
+    components: Vec<Component<'a>>,
+    dependencies: Vec<Dependency<'a>>,
+}
+
+#[derive(Serialize)]
+struct Metadata<'a> {
+    timestamp: String,
+    tools: Tools<'a>,
+    component: Component<'a>,
+}
+
+#[derive(Serialize)]
+struct Tools<'a> {
+    components: [Tool<'a>; 1],
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    name: &'static str,
+    version: &'a str,
+}
+
+#[derive(Serialize)]
+struct Component<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(rename = "bom-ref")]
+    bom_ref: &'a str,
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purl: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<Hash<'a>>,
+}
+
+#[derive(Serialize)]
+struct Hash<'a> {
+    alg: &'static str,
+    content: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Dependency<'a> {
+    #[serde(rename = "ref")]
+    reference: &'a str,
+    depends_on: Vec<&'a str>,
+}
+
+pub(super) fn render(sbom: &Sbom, turbo_version: &str) -> Result<String, serde_json::Error> {
+    let bom = Bom {
+        bom_format: "CycloneDX",
+        spec_version: "1.5",
+        version: 1,
+        metadata: Metadata {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            tools: Tools {
+                components: [Tool {
+                    kind: "application",
+                    name: "turbo",
+                    version: turbo_version,
+                }],
+            },
+            component: Component::new(&sbom.package, "application"),
+        },
+        components: sbom
+            .components
+            .iter()
+            .map(|component| Component::new(component, "library"))
+            .collect(),
+        dependencies: sbom
+            .dependencies
+            .iter()
+            .map(|(reference, depends_on)| Dependency {
+                reference,
+                depends_on: depends_on.iter().map(String::as_str).collect(),
+            })
+            .collect(),
+    };
+
+    serde_json::to_string_pretty(&bom)
+}
+
+impl<'a> Component<'a> {
+    fn new(component: &'a super::Component, kind: &'static str) -> Self {
+        Self {
+            kind,
+            bom_ref: &component.id,
+            name: &component.name,
+            version: component.version.as_deref(),
+            purl: component.purl.as_deref(),
+            hashes: component
+                .hash
+                .iter()
+                .map(|hash| Hash {
+                    alg: match hash.algorithm {
+                        HashAlgorithm::Sha1 => "SHA-1",
+                        HashAlgorithm::Sha256 => "SHA-256",
+                        HashAlgorithm::Sha384 => "SHA-384",
+                        HashAlgorithm::Sha512 => "SHA-512",
+                    },
+                    content: &hash.hex,
+                })
+                .collect(),
+        }
+    }
+}
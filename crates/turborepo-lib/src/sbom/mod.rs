@@ -0,0 +1,238 @@
+//! Software bills of materials, generated by `turbo sbom`. The components of a
+//! package are the internal packages that it depends on, along with every
+//! external package in their dependency closures as resolved by the lockfile.
+
+mod cyclonedx;
+mod spdx;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use miette::Diagnostic;
+use thiserror::Error;
+use turborepo_lockfiles::{Lockfile, ResolvedDependency};
+use turborepo_repository::package_graph::{PackageInfo, PackageName, PackageNode};
+
+use crate::{cli::SbomFormat, run::Run};
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error("Could not find package `{0}` in the workspace.")]
+    MissingPackage(String),
+    #[error("Generating an SBOM requires a lockfile.")]
+    #[diagnostic(help("run your package manager's install command to create one"))]
+    NoLockfile,
+    #[error(transparent)]
+    Lockfile(#[from] turborepo_lockfiles::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// A package that is included in an SBOM
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Component {
+    // Identifies the component within the SBOM
+    id: String,
+    name: String,
+    version: Option<String>,
+    // Only external packages have a package URL
+    purl: Option<String>,
+    hash: Option<Hash>,
+}
+
+/// A hash of a package's tarball, decoded from the lockfile's integrity field
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hash {
+    algorithm: HashAlgorithm,
+    hex: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+pub struct Sbom {
+    package: Component,
+    components: Vec<Component>,
+    // The ids of the components that each component directly depends on
+    dependencies: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Sbom {
+    pub fn render(&self, format: SbomFormat, turbo_version: &str) -> Result<String, Error> {
+        Ok(match format {
+            SbomFormat::CyclonedxJson => cyclonedx::render(self, turbo_version)?,
+            SbomFormat::SpdxJson => spdx::render(self, turbo_version)?,
+        })
+    }
+}
+
+impl Run {
+    /// Collects the components of `package` from the package graph and the
+    /// lockfile.
+    pub fn sbom(&self, package: &str) -> Result<Sbom, Error> {
+        let package_graph = self.pkg_dep_graph();
+        let lockfile = package_graph.lockfile().ok_or(Error::NoLockfile)?;
+        let name = PackageName::from(package.to_string());
+        if matches!(name, PackageName::Root) || package_graph.package_info(&name).is_none() {
+            return Err(Error::MissingPackage(package.to_string()));
+        }
+
+        let mut components = BTreeMap::new();
+        let mut dependencies: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for workspace in package_graph.workspace_closure(&name) {
+            let Some(info) = package_graph.package_info(workspace) else {
+                continue;
+            };
+            let component = Component::workspace(workspace, info);
+            let workspace_id = component.id.clone();
+            components.insert(workspace_id.clone(), component);
+
+            let workspace_dependencies = dependencies.entry(workspace_id.clone()).or_default();
+            let internal_dependencies = package_graph
+                .immediate_dependencies(&PackageNode::Workspace(workspace.clone()))
+                .unwrap_or_default();
+            workspace_dependencies.extend(
+                internal_dependencies
+                    .into_iter()
+                    .filter_map(workspace_name)
+                    .map(workspace_id_for),
+            );
+
+            let resolved = package_graph.resolved_external_dependencies(workspace)?;
+
+            // Packages are resolved after the package that depends on them, so
+            // the ids of their parents are always known
+            let mut ids = HashMap::new();
+            for dependency in &resolved {
+                let component = Component::external(lockfile, dependency);
+                let id = component.id.clone();
+                components.entry(id.clone()).or_insert(component);
+                ids.insert(dependency.package.key.as_str(), id.clone());
+
+                let parent_id = match &dependency.parent {
+                    Some(parent) => ids.get(parent.as_str()).cloned(),
+                    None => Some(workspace_id.clone()),
+                };
+                if let Some(parent_id) = parent_id {
+                    dependencies
+                        .entry(parent_id)
+                        .or_default()
+                        .insert(id.clone());
+                }
+                dependencies.entry(id).or_default();
+            }
+        }
+
+        let package = components
+            .remove(&workspace_id_for(&name))
+            .expect("package was added to the components");
+        Ok(Sbom {
+            package,
+            components: components.into_values().collect(),
+            dependencies,
+        })
+    }
+}
+
+fn workspace_name(node: &PackageNode) -> Option<&PackageName> {
+    match node {
+        PackageNode::Workspace(name @ PackageName::Other(_)) => Some(name),
+        _ => None,
+    }
+}
+
+fn workspace_id_for(name: &PackageName) -> String {
+    format!("workspace:{name}")
+}
+
+impl Component {
+    fn workspace(name: &PackageName, info: &PackageInfo) -> Self {
+        Self {
+            id: workspace_id_for(name),
+            name: name.to_string(),
+            version: info.package_json.version.clone(),
+            purl: None,
+            hash: None,
+        }
+    }
+
+    fn external(lockfile: &dyn Lockfile, dependency: &ResolvedDependency) -> Self {
+        // pnpm includes the versions of peer dependencies after the version
+        let version = match dependency.package.version.split_once('(') {
+            Some((version, _)) => version,
+            None => &dependency.package.version,
+        };
+        let purl = purl(&dependency.name, version);
+        Self {
+            id: purl.clone(),
+            name: dependency.name.clone(),
+            version: Some(version.to_string()),
+            purl: Some(purl),
+            hash: lockfile
+                .integrity(&dependency.package)
+                .and_then(|integrity| Hash::from_integrity(&integrity)),
+        }
+    }
+}
+
+// The package URL of an npm package, see https://github.com/package-url/purl-spec
+fn purl(name: &str, version: &str) -> String {
+    // The `@` of a scope has to be encoded
+    let name = match name.strip_prefix('@') {
+        Some(name) => format!("%40{name}"),
+        None => name.to_string(),
+    };
+    format!("pkg:npm/{name}@{version}")
+}
+
+impl Hash {
+    /// Decodes the first supported hash from a Subresource Integrity string,
+    /// e.g. `sha512-<base64>`
+    fn from_integrity(integrity: &str) -> Option<Self> {
+        integrity.split_whitespace().find_map(|hash| {
+            let (algorithm, digest) = hash.split_once('-')?;
+            let algorithm = match algorithm {
+                "sha1" => HashAlgorithm::Sha1,
+                "sha256" => HashAlgorithm::Sha256,
+                "sha384" => HashAlgorithm::Sha384,
+                "sha512" => HashAlgorithm::Sha512,
+                _ => return None,
+            };
+            let digest = STANDARD.decode(digest).ok()?;
+            Some(Self {
+                algorithm,
+                hex: hex::encode(digest),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::{purl, Hash, HashAlgorithm};
+
+    #[test_case("react", "18.2.0", "pkg:npm/react@18.2.0" ; "unscoped")]
+    #[test_case("@babel/core", "7.21.0", "pkg:npm/%40babel/core@7.21.0" ; "scoped")]
+    fn test_purl(name: &str, version: &str, expected: &str) {
+        assert_eq!(purl(name, version), expected);
+    }
+
+    #[test]
+    fn test_hash_from_integrity() {
+        let hash = Hash::from_integrity("sha512-3q2+7w==").unwrap();
+        assert_eq!(hash.algorithm, HashAlgorithm::Sha512);
+        assert_eq!(hash.hex, "deadbeef");
+
+        let hash = Hash::from_integrity("md5-3q2+7w== sha1-3q2+7w==").unwrap();
+        assert_eq!(hash.algorithm, HashAlgorithm::Sha1);
+
+        assert_eq!(Hash::from_integrity("not a hash"), None);
+    }
+}
@@ -0,0 +1,214 @@
+//! SPDX 2.3 JSON, see https://spdx.github.io/spdx-spec/v2.3
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::{Component, HashAlgorithm, Sbom};
+
+const DOCUMENT_ID: &str = "SPDXRef-DOCUMENT";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Document<'a> {
+    spdx_version: &'static str,
+    data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+    name: &'a str,
+    document_namespace: String,
+    creation_info: CreationInfo,
+    packages: Vec<Package<'a>>,
+    relationships: Vec<Relationship<'a>>,
+}
+
+#[derive(Serialize)]
+struct CreationInfo {
+    created: String,
+    creators: [String; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Package<'a> {
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'a str,
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version_info: Option<&'a str>,
+    download_location: &'static str,
+    files_analyzed: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checksums: Vec<Checksum<'a>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    external_refs: Vec<ExternalRef<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Checksum<'a> {
+    algorithm: &'static str,
+    checksum_value: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExternalRef<'a> {
+    reference_category: &'static str,
+    reference_type: &'static str,
+    reference_locator: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Relationship<'a> {
+    spdx_element_id: &'a str,
+    relationship_type: &'static str,
+    related_spdx_element: &'a str,
+}
+
+pub(super) fn render(sbom: &Sbom, turbo_version: &str) -> Result<String, serde_json::Error> {
+    let components = std::iter::once(&sbom.package)
+        .chain(&sbom.components)
+        .collect::<Vec<_>>();
+    let spdx_ids = spdx_ids(&components);
+
+    // The namespace only has to be unique to the contents of the document
+    let mut hasher = Sha256::new();
+    for component in &components {
+        hasher.update(&component.id);
+    }
+    let document_namespace = format!(
+        "https://turborepo.com/spdx/{}-{:x}",
+        spdx_ids[sbom.package.id.as_str()],
+        hasher.finalize()
+    );
+
+    let packages = components
+        .iter()
+        .map(|component| Package::new(component, &spdx_ids[component.id.as_str()]))
+        .collect();
+
+    let mut relationships = vec![Relationship {
+        spdx_element_id: DOCUMENT_ID,
+        relationship_type: "DESCRIBES",
+        related_spdx_element: &spdx_ids[sbom.package.id.as_str()],
+    }];
+    for (id, dependencies) in &sbom.dependencies {
+        let Some(spdx_id) = spdx_ids.get(id.as_str()) else {
+            continue;
+        };
+        relationships.extend(
+            dependencies
+                .iter()
+                .filter_map(|dependency| spdx_ids.get(dependency.as_str()))
+                .map(|dependency| Relationship {
+                    spdx_element_id: spdx_id,
+                    relationship_type: "DEPENDS_ON",
+                    related_spdx_element: dependency,
+                }),
+        );
+    }
+
+    let document = Document {
+        spdx_version: "SPDX-2.3",
+        data_license: "CC0-1.0",
+        spdx_id: DOCUMENT_ID,
+        name: &sbom.package.name,
+        document_namespace,
+        creation_info: CreationInfo {
+            created: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            creators: [format!("Tool: turbo-{turbo_version}")],
+        },
+        packages,
+        relationships,
+    };
+
+    serde_json::to_string_pretty(&document)
+}
+
+// SPDX ids can only contain letters, numbers, `.` and `-`, so replace anything
+// else and deduplicate any ids that end up the same
+fn spdx_ids<'a>(components: &[&'a Component]) -> HashMap<&'a str, String> {
+    let mut seen = HashSet::new();
+    components
+        .iter()
+        .map(|component| {
+            let sanitized = component
+                .id
+                .chars()
+                .map(|c| match c {
+                    'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' => c,
+                    _ => '-',
+                })
+                .collect::<String>();
+            let mut spdx_id = format!("SPDXRef-{sanitized}");
+            let mut suffix = 1;
+            while !seen.insert(spdx_id.clone()) {
+                suffix += 1;
+                spdx_id = format!("SPDXRef-{sanitized}-{suffix}");
+            }
+            (component.id.as_str(), spdx_id)
+        })
+        .collect()
+}
+
+impl<'a> Package<'a> {
+    fn new(component: &'a Component, spdx_id: &'a str) -> Self {
+        Self {
+            spdx_id,
+            name: &component.name,
+            version_info: component.version.as_deref(),
+            download_location: "NOASSERTION",
+            files_analyzed: false,
+            checksums: component
+                .hash
+                .iter()
+                .map(|hash| Checksum {
+                    algorithm: match hash.algorithm {
+                        HashAlgorithm::Sha1 => "SHA1",
+                        HashAlgorithm::Sha256 => "SHA256",
+                        HashAlgorithm::Sha384 => "SHA384",
+                        HashAlgorithm::Sha512 => "SHA512",
+                    },
+                    checksum_value: &hash.hex,
+                })
+                .collect(),
+            external_refs: component
+                .purl
+                .iter()
+                .map(|purl| ExternalRef {
+                    reference_category: "PACKAGE-MANAGER",
+                    reference_type: "purl",
+                    reference_locator: purl,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{spdx_ids, Component};
+
+    fn component(id: &str) -> Component {
+        Component {
+            id: id.to_string(),
+            name: id.to_string(),
+            version: None,
+            purl: None,
+            hash: None,
+        }
+    }
+
+    #[test]
+    fn test_spdx_ids() {
+        let a = component("pkg:npm/%40a/b-c@1.0.0");
+        let b = component("pkg:npm/%40a-b/c@1.0.0");
+        let ids = spdx_ids(&[&a, &b]);
+        assert_eq!(ids[a.id.as_str()], "SPDXRef-pkg-npm--40a-b-c-1.0.0");
+        assert_eq!(ids[b.id.as_str()], "SPDXRef-pkg-npm--40a-b-c-1.0.0-2");
+    }
+}
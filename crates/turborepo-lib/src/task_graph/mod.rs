@@ -5,6 +5,7 @@ use std::str::FromStr;
 use globwalk::{GlobError, ValidatedGlob};
 use serde::{Deserialize, Serialize};
 use turbopath::{AnchoredSystemPath, AnchoredSystemPathBuf, RelativeUnixPathBuf};
+use turborepo_cache::{CacheActions, CacheConfig};
 use turborepo_errors::Spanned;
 use turborepo_task_id::{TaskId, TaskName};
 pub use visitor::{Error as VisitorError, Visitor};
@@ -62,6 +63,20 @@ pub struct TaskDefinition {
     // It will also not affect the task's hash aside from the definition getting folded into the
     // hash.
     pub with: Option<Vec<Spanned<TaskName<'static>>>>,
+
+    // Task level overrides of the run's cache actions from `cacheMode`, applied
+    // in order
+    pub cache_mode: Vec<CacheModeRule>,
+}
+
+// A single `cacheMode` rule. Rules can only take away cache actions that the
+// run allows, never add them back.
+#[derive(Debug, PartialEq, Clone, Eq, Default)]
+pub struct CacheModeRule {
+    pub local: Option<CacheActions>,
+    pub remote: Option<CacheActions>,
+    // Only apply this rule if this environment variable is set
+    pub when_env: Option<String>,
 }
 
 // TaskOutputs represents the patterns for including and excluding files from
@@ -96,6 +111,7 @@ impl Default for TaskDefinition {
             interactive: Default::default(),
             env_mode: Default::default(),
             with: Default::default(),
+            cache_mode: Default::default(),
         }
     }
 }
@@ -160,6 +176,32 @@ impl TaskDefinition {
 
         repo_relative_globs
     }
+
+    /// Resolves `cacheMode` against the cache configuration of the run. Later
+    /// matching rules replace earlier ones per cache.
+    pub fn cache_config(
+        &self,
+        run_config: CacheConfig,
+        is_env_set: impl Fn(&str) -> bool,
+    ) -> CacheConfig {
+        let mut local = None;
+        let mut remote = None;
+        for rule in self
+            .cache_mode
+            .iter()
+            .filter(|rule| rule.when_env.as_deref().is_none_or(&is_env_set))
+        {
+            local = rule.local.or(local);
+            remote = rule.remote.or(remote);
+        }
+
+        CacheConfig {
+            local: local.map_or(run_config.local, |local| run_config.local.restrict(&local)),
+            remote: remote.map_or(run_config.remote, |remote| {
+                run_config.remote.restrict(&remote)
+            }),
+        }
+    }
 }
 
 impl TaskInputs {
@@ -208,9 +250,19 @@ mod test {
     use std::path::MAIN_SEPARATOR_STR;
 
     use pretty_assertions::assert_eq;
+    use test_case::test_case;
 
     use super::*;
 
+    const READ_ONLY: CacheActions = CacheActions {
+        read: true,
+        write: false,
+    };
+    const DISABLED: CacheActions = CacheActions {
+        read: false,
+        write: false,
+    };
+
     #[test]
     fn test_relative_output_globs() {
         let task_defn = TaskDefinition {
@@ -267,4 +319,61 @@ mod test {
         .unwrap();
         assert_eq!(build_log, build_expected);
     }
+
+    #[test_case(vec![], &[], CacheConfig::default() ; "no rules")]
+    #[test_case(
+        vec![CacheModeRule { remote: Some(READ_ONLY), ..Default::default() }],
+        &[],
+        CacheConfig { local: CacheActions::enabled(), remote: READ_ONLY }
+        ; "remote read only"
+    )]
+    #[test_case(
+        vec![CacheModeRule { local: Some(DISABLED), when_env: Some("CI".into()), ..Default::default() }],
+        &[],
+        CacheConfig::default()
+        ; "condition not met"
+    )]
+    #[test_case(
+        vec![
+            CacheModeRule { remote: Some(READ_ONLY), ..Default::default() },
+            CacheModeRule { local: Some(DISABLED), when_env: Some("CI".into()), ..Default::default() },
+        ],
+        &["CI"],
+        CacheConfig { local: DISABLED, remote: READ_ONLY }
+        ; "condition met"
+    )]
+    #[test_case(
+        vec![
+            CacheModeRule { remote: Some(DISABLED), ..Default::default() },
+            CacheModeRule { remote: Some(READ_ONLY), when_env: Some("CI".into()), ..Default::default() },
+        ],
+        &["CI"],
+        CacheConfig { local: CacheActions::enabled(), remote: READ_ONLY }
+        ; "later rule wins"
+    )]
+    fn test_cache_mode(rules: Vec<CacheModeRule>, env: &[&str], expected: CacheConfig) {
+        let task_defn = TaskDefinition {
+            cache_mode: rules,
+            ..Default::default()
+        };
+        assert_eq!(
+            task_defn.cache_config(CacheConfig::default(), |var| env.contains(&var)),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_cache_mode_cannot_widen() {
+        let task_defn = TaskDefinition {
+            cache_mode: vec![CacheModeRule {
+                remote: Some(CacheActions::enabled()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            task_defn.cache_config(CacheConfig::remote_read_only(), |_| false),
+            CacheConfig::remote_read_only()
+        );
+    }
 }
@@ -116,7 +116,13 @@ impl<'a> CommandProvider for PackageGraphCommandProvider<'a> {
         }
         let package_manager_binary = self.package_manager_binary.as_deref().map_err(|e| *e)?;
         let mut cmd = Command::new(package_manager_binary);
-        let mut args = vec!["run".to_string(), task_id.task().to_string()];
+        let mut args = vec![
+            self.package_graph
+                .package_manager()
+                .run_subcommand()
+                .to_string(),
+            task_id.task().to_string(),
+        ];
         if let Some(pass_through_args) = self.task_args.args_for_task(task_id) {
             args.extend(
                 self.package_graph
@@ -242,7 +248,7 @@ impl<'a, T: PackageInfoProvider> CommandProvider for MicroFrontendProxyProvider<
             let package_manager = self.package_graph.package_manager();
             let mut proxy_args = vec![mfe_path.as_str(), "--names"];
             proxy_args.extend(local_apps);
-            let mut args = vec!["run", "proxy"];
+            let mut args = vec![package_manager.run_subcommand(), "proxy"];
             if let Some(sep) = package_manager.arg_separator(&proxy_args) {
                 args.push(sep);
             }
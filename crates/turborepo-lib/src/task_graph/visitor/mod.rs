@@ -4,7 +4,7 @@ mod exec;
 mod output;
 use std::{
     borrow::Cow,
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     io::Write,
     sync::{Arc, Mutex, OnceLock},
 };
@@ -369,7 +369,8 @@ impl<'a> Visitor<'a> {
     }
 
     /// Finishes visiting the tasks, creates the run summary, and either
-    /// prints, saves, or sends it to spaces.
+    /// prints, saves, or sends it to spaces. Returns the final exit code of
+    /// the run.
     #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(skip(
         self,
@@ -386,7 +387,7 @@ impl<'a> Visitor<'a> {
         engine: &Engine,
         env_at_execution_start: &EnvironmentVariableMap,
         pkg_inference_root: Option<&AnchoredSystemPath>,
-    ) -> Result<(), Error> {
+    ) -> Result<i32, Error> {
         let Self {
             package_graph,
             color_config: ui,
@@ -534,6 +535,11 @@ impl<'a> Visitor<'a> {
         self.task_hasher.into_task_hash_tracker_state()
     }
 
+    /// The hashes of every task that has been hashed so far
+    pub fn task_hashes(&self) -> HashMap<TaskId<'static>, String> {
+        self.task_hasher.task_hash_tracker().task_hashes()
+    }
+
     pub fn dry_run(&mut self) {
         self.dry = true;
         // No need to start a UI on dry run
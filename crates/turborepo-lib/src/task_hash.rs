@@ -5,14 +5,17 @@ use std::{
 
 use rayon::prelude::*;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 use tracing::{debug, Span};
-use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, AnchoredSystemPathBuf};
+use turbopath::{
+    AbsoluteSystemPath, AnchoredSystemPath, AnchoredSystemPathBuf, RelativeUnixPathBuf,
+};
 use turborepo_cache::CacheHitMetadata;
 use turborepo_env::{BySource, DetailedMap, EnvironmentVariableMap};
 use turborepo_frameworks::{infer_framework, Slug as FrameworkSlug};
 use turborepo_repository::package_graph::{PackageInfo, PackageName};
-use turborepo_scm::SCM;
+use turborepo_scm::{GitHashes, SCM};
 use turborepo_task_id::TaskId;
 use turborepo_telemetry::events::{
     generic::GenericEventBuilder, task::PackageTaskEventBuilder, EventBuilder,
@@ -20,7 +23,7 @@ use turborepo_telemetry::events::{
 
 use crate::{
     cli::EnvMode,
-    engine::TaskNode,
+    engine::{Engine, TaskNode},
     hash::{FileHashes, LockFilePackages, TaskHashable, TurboHash},
     opts::RunOpts,
     task_graph::TaskDefinition,
@@ -61,14 +64,182 @@ impl TaskHashable<'_> {
     }
 }
 
+/// Computes a key for everything other than the contents of task inputs that
+/// goes into the task hashes of a run. Two runs with the same key and the same
+/// input hashes for every task will produce the same task hashes.
+pub fn task_hashes_key(
+    global_hash: &str,
+    run_opts: &RunOpts,
+    env_at_execution_start: &EnvironmentVariableMap,
+    engine: &Engine,
+    workspaces: &HashMap<&PackageName, &PackageInfo>,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(global_hash);
+    hasher.update(to_key_bytes(&(
+        &run_opts.pass_through_args,
+        run_opts.env_mode,
+        run_opts.framework_inference,
+        run_opts.single_package,
+    )));
+    for pair in env_at_execution_start.to_hashable() {
+        hasher.update(pair);
+    }
+
+    let mut task_ids = engine.task_definitions().keys().collect::<Vec<_>>();
+    task_ids.sort();
+    for task_id in task_ids {
+        hasher.update(task_id.to_string());
+        hasher.update(to_key_bytes(&TaskDefinitionKey::from(
+            &engine.task_definitions()[task_id],
+        )));
+
+        let mut dependencies = engine
+            .dependencies(task_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|dependency| dependency.to_string())
+            .collect::<Vec<_>>();
+        dependencies.sort();
+        hasher.update(dependencies.join(","));
+
+        // Framework inference and external dependencies both come from the
+        // package.json, which isn't necessarily one of the task's inputs
+        if let Some(workspace) = workspaces.get(&task_id.to_workspace_name()) {
+            hasher.update(workspace.package_path().as_str());
+            hasher.update(get_external_deps_hash(&workspace.transitive_dependencies));
+            hasher.update(to_key_bytes(&(
+                &workspace.package_json.dependencies,
+                &workspace.package_json.dev_dependencies,
+            )));
+        }
+    }
+
+    hex::encode(hasher.finalize())
+}
+
+/// The parts of a task definition that go into its hash or decide which files
+/// are hashed as its inputs. Spans and settings that only change how the task
+/// runs are left out, so they don't change the key.
+#[derive(Serialize)]
+struct TaskDefinitionKey<'a> {
+    output_inclusions: &'a [String],
+    output_exclusions: &'a [String],
+    env: &'a [String],
+    pass_through_env: Option<&'a [String]>,
+    env_mode: Option<EnvMode>,
+    input_globs: &'a [String],
+    default_inputs: bool,
+}
+
+impl<'a> From<&'a TaskDefinition> for TaskDefinitionKey<'a> {
+    fn from(definition: &'a TaskDefinition) -> Self {
+        Self {
+            output_inclusions: &definition.outputs.inclusions,
+            output_exclusions: &definition.outputs.exclusions,
+            env: &definition.env,
+            pass_through_env: definition.pass_through_env.as_deref(),
+            env_mode: definition.env_mode,
+            input_globs: &definition.inputs.globs,
+            default_inputs: definition.inputs.default,
+        }
+    }
+}
+
+fn to_key_bytes(value: &impl Serialize) -> Vec<u8> {
+    serde_json::to_vec(value).expect("task hash key parts are serializable")
+}
+
+/// File and task hashes precomputed by the daemon for a run.
+#[derive(Debug, Default)]
+pub struct DaemonTaskHashes {
+    file_hashes: HashMap<TaskId<'static>, GitHashes>,
+    task_hashes: HashMap<TaskId<'static>, String>,
+}
+
+impl DaemonTaskHashes {
+    /// Asks the daemon to hash the inputs of every task in a single request.
+    /// Any failure results in an empty set of hashes, leaving the run to hash
+    /// everything locally.
+    pub async fn fetch(
+        daemon: &mut DaemonClient<DaemonConnector>,
+        key: String,
+        task_definitions: &HashMap<TaskId<'static>, TaskDefinition>,
+        workspaces: &HashMap<&PackageName, &PackageInfo>,
+    ) -> Self {
+        let task_ids = task_definitions
+            .keys()
+            .map(|task_id| (task_id.to_string(), task_id.clone()))
+            .collect::<HashMap<_, _>>();
+        let tasks = task_definitions.iter().filter_map(|(task_id, definition)| {
+            let workspace = workspaces.get(&task_id.to_workspace_name())?;
+            let package_path = workspace
+                .package_json_path
+                .parent()
+                .unwrap_or_else(|| AnchoredSystemPath::new("").unwrap());
+            Some((task_id.to_string(), package_path, &definition.inputs))
+        });
+
+        let response = match daemon.get_task_hashes(key, tasks).await {
+            Ok(response) => response,
+            Err(e) => {
+                debug!("daemon task hashing failed: {e}");
+                return Self::default();
+            }
+        };
+
+        let file_hashes = response
+            .file_hashes
+            .into_iter()
+            .filter_map(|(task_id, hashes)| {
+                let task_id = task_ids.get(&task_id)?.clone();
+                let hashes = hashes
+                    .file_hashes
+                    .into_iter()
+                    .map(|(path, hash)| {
+                        (
+                            RelativeUnixPathBuf::new(path)
+                                .expect("daemon returns relative unix paths"),
+                            hash,
+                        )
+                    })
+                    .collect();
+                Some((task_id, hashes))
+            })
+            .collect();
+        let task_hashes = response
+            .task_hashes
+            .into_iter()
+            .filter_map(|(task_id, hash)| Some((task_ids.get(&task_id)?.clone(), hash)))
+            .collect::<HashMap<_, _>>();
+        if !task_hashes.is_empty() {
+            debug!("using {} task hashes from the daemon", task_hashes.len());
+        }
+
+        Self {
+            file_hashes,
+            task_hashes,
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct PackageInputsHashes {
     hashes: HashMap<TaskId<'static>, String>,
     expanded_hashes: HashMap<TaskId<'static>, FileHashes>,
+    task_hashes: HashMap<TaskId<'static>, String>,
 }
 
 impl PackageInputsHashes {
-    #[tracing::instrument(skip(all_tasks, workspaces, task_definitions, repo_root, scm))]
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip(
+        all_tasks,
+        workspaces,
+        task_definitions,
+        repo_root,
+        scm,
+        daemon_task_hashes
+    ))]
     pub fn calculate_file_hashes<'a>(
         scm: &SCM,
         all_tasks: impl ParallelIterator<Item = &'a TaskNode>,
@@ -77,6 +248,7 @@ impl PackageInputsHashes {
         repo_root: &AbsoluteSystemPath,
         telemetry: &GenericEventBuilder,
         daemon: &Option<DaemonClient<DaemonConnector>>,
+        daemon_task_hashes: DaemonTaskHashes,
     ) -> Result<PackageInputsHashes, Error> {
         tracing::trace!(scm_manual=%scm.is_manual(), "scm running in {} mode", if scm.is_manual() { "manual" } else { "git" });
 
@@ -119,7 +291,10 @@ impl PackageInputsHashes {
                 let scm_telemetry = package_task_event.child();
                 // Try hashing with the daemon, if we have a connection. If we don't, or if we
                 // timeout or get an error, fallback to local hashing
-                let hash_object = if cfg!(feature = "daemon-file-hashing") {
+                let hash_object = if let Some(hashes) = daemon_task_hashes.file_hashes.get(task_id)
+                {
+                    Some(hashes.clone())
+                } else if cfg!(feature = "daemon-file-hashing") {
                     let handle = tokio::runtime::Handle::current();
                     let mut daemon = daemon
                         .as_ref() // Option::ref
@@ -211,11 +386,25 @@ impl PackageInputsHashes {
             })
             .collect::<Result<_, _>>()?;
 
+        // Task hashes from the daemon are only valid if computed from the same
+        // inputs we're using
+        let task_hashes = daemon_task_hashes
+            .task_hashes
+            .into_iter()
+            .filter(|(task_id, _)| daemon_task_hashes.file_hashes.contains_key(task_id))
+            .collect();
+
         Ok(PackageInputsHashes {
             hashes,
             expanded_hashes,
+            task_hashes,
         })
     }
+
+    /// The hash of each task's inputs
+    pub fn hashes(&self) -> &HashMap<TaskId<'static>, String> {
+        &self.hashes
+    }
 }
 
 #[derive(Default, Debug, Clone)]
@@ -241,6 +430,7 @@ pub struct TaskHashTrackerState {
 /// Caches package-inputs hashes, and package-task hashes.
 pub struct TaskHasher<'a> {
     hashes: HashMap<TaskId<'static>, String>,
+    precomputed_task_hashes: HashMap<TaskId<'static>, String>,
     run_opts: &'a RunOpts,
     env_at_execution_start: &'a EnvironmentVariableMap,
     global_env: EnvironmentVariableMap,
@@ -259,9 +449,11 @@ impl<'a> TaskHasher<'a> {
         let PackageInputsHashes {
             hashes,
             expanded_hashes,
+            task_hashes,
         } = package_inputs_hashes;
         Self {
             hashes,
+            precomputed_task_hashes: task_hashes,
             run_opts,
             env_at_execution_start,
             global_hash,
@@ -334,6 +526,16 @@ impl<'a> TaskHasher<'a> {
             }
         };
 
+        if let Some(task_hash) = self.precomputed_task_hashes.get(task_id) {
+            self.task_hash_tracker.insert_hash(
+                task_id.clone(),
+                env_vars,
+                task_hash.clone(),
+                framework_slug,
+            );
+            return Ok(task_hash.clone());
+        }
+
         let hashable_env_pairs = env_vars.all.to_hashable();
         let outputs = task_definition.hashable_outputs(task_id);
         let task_dependency_hashes = self.calculate_dependency_hashes(dependency_set)?;
@@ -571,6 +773,11 @@ impl TaskHashTracker {
         state.package_task_hashes.get(task_id).cloned()
     }
 
+    pub fn task_hashes(&self) -> HashMap<TaskId<'static>, String> {
+        let state = self.state.lock().expect("hash tracker mutex poisoned");
+        state.package_task_hashes.clone()
+    }
+
     fn insert_hash(
         &self,
         task_id: TaskId<'static>,
@@ -635,8 +842,176 @@ impl TaskHashTracker {
 
 #[cfg(test)]
 mod test {
+    use test_case::test_case;
+    use turborepo_errors::Spanned;
+    use turborepo_task_id::TaskName;
+
     use super::*;
 
+    fn definition_key(definition: &TaskDefinition) -> Vec<u8> {
+        to_key_bytes(&TaskDefinitionKey::from(definition))
+    }
+
+    #[test]
+    fn test_task_definition_key_is_stable() {
+        let definition = TaskDefinition {
+            env: vec!["NODE_ENV".to_string()],
+            task_dependencies: vec![Spanned::new(TaskName::from("build"))],
+            ..Default::default()
+        };
+        assert_eq!(
+            definition_key(&definition),
+            definition_key(&definition.clone())
+        );
+
+        // Moving the dependency in turbo.json only changes its span
+        let mut moved = definition.clone();
+        moved.task_dependencies = vec![Spanned::new(TaskName::from("build"))
+            .with_range(10..17)
+            .with_text("{ \"dependsOn\": [\"build\"] }")];
+        assert_eq!(definition_key(&definition), definition_key(&moved));
+
+        // Settings that don't affect the hash don't change the key
+        let mut persistent = definition.clone();
+        persistent.persistent = true;
+        persistent.interruptible = true;
+        assert_eq!(definition_key(&definition), definition_key(&persistent));
+    }
+
+    #[test_case(|definition| definition.env.push("API_URL".to_string()) ; "env")]
+    #[test_case(|definition| definition.pass_through_env = Some(vec!["HOME".to_string()]) ; "pass through env")]
+    #[test_case(|definition| definition.env_mode = Some(EnvMode::Loose) ; "env mode")]
+    #[test_case(|definition| definition.outputs.inclusions.push("dist/**".to_string()) ; "outputs")]
+    #[test_case(|definition| definition.inputs.globs.push("src/**".to_string()) ; "inputs")]
+    #[test_case(|definition| definition.inputs.default = !definition.inputs.default ; "default inputs")]
+    fn test_task_definition_key_changes(change: fn(&mut TaskDefinition)) {
+        let definition = TaskDefinition::default();
+        let mut changed = definition.clone();
+        change(&mut changed);
+        assert_ne!(definition_key(&definition), definition_key(&changed));
+    }
+
+    // Hashes `web#build`'s inputs the way a run does, asking the daemon first
+    async fn hash_inputs(
+        daemon: &mut DaemonClient<DaemonConnector>,
+        repo_root: &AbsoluteSystemPath,
+        task_definitions: &HashMap<TaskId<'static>, TaskDefinition>,
+        workspaces: &HashMap<&PackageName, &PackageInfo>,
+    ) -> PackageInputsHashes {
+        let task_id = TaskId::new("web", "build");
+        // The daemon might still be starting up, in which case the run would
+        // hash everything locally
+        let mut daemon_task_hashes = DaemonTaskHashes::default();
+        for _ in 0..10 {
+            daemon_task_hashes =
+                DaemonTaskHashes::fetch(daemon, "key".to_string(), task_definitions, workspaces)
+                    .await;
+            if daemon_task_hashes.file_hashes.contains_key(&task_id) {
+                break;
+            }
+        }
+        assert!(daemon_task_hashes.file_hashes.contains_key(&task_id));
+
+        let tasks = [TaskNode::Task(task_id)];
+        PackageInputsHashes::calculate_file_hashes(
+            &SCM::new(repo_root),
+            tasks.par_iter(),
+            workspaces.clone(),
+            task_definitions,
+            repo_root,
+            &GenericEventBuilder::new(),
+            &None,
+            daemon_task_hashes,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_second_run_uses_daemon_task_hashes() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tempdir.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap()
+            .join_component("repo");
+        let web = repo_root.join_components(&["packages", "web"]);
+        web.create_dir_all().unwrap();
+        repo_root
+            .join_component("package.json")
+            .create_with_contents(r#"{"workspaces": ["packages/*"]}"#)
+            .unwrap();
+        repo_root
+            .join_component("package-lock.json")
+            .create_with_contents("")
+            .unwrap();
+        web.join_component("package.json")
+            .create_with_contents(r#"{"name": "web"}"#)
+            .unwrap();
+        web.join_component("index.js")
+            .create_with_contents("export default 1;")
+            .unwrap();
+
+        let task_id = TaskId::new("web", "build");
+        let task_definitions = HashMap::from([(
+            task_id.clone(),
+            TaskDefinition {
+                inputs: crate::task_graph::TaskInputs {
+                    globs: Vec::new(),
+                    default: true,
+                },
+                ..Default::default()
+            },
+        )]);
+        let name = PackageName::from("web");
+        let info = PackageInfo {
+            package_json_path: repo_root
+                .anchor(&web.join_component("package.json"))
+                .unwrap(),
+            ..Default::default()
+        };
+        let workspaces = HashMap::from([(&name, &info)]);
+
+        let mut daemon = crate::daemon::InProcessDaemon::serve(&repo_root).await;
+
+        // The first run has to compute its task hashes, and tells the daemon
+        // about them once it's done
+        let first = hash_inputs(
+            &mut daemon.client,
+            &repo_root,
+            &task_definitions,
+            &workspaces,
+        )
+        .await;
+        assert!(first.task_hashes.is_empty());
+        daemon
+            .client
+            .notify_task_hashes(
+                "key".to_string(),
+                HashMap::from([(task_id.to_string(), "0123456789abcdef".to_string())]),
+                first
+                    .hashes()
+                    .iter()
+                    .map(|(task_id, hash)| (task_id.to_string(), hash.clone()))
+                    .collect(),
+            )
+            .await
+            .unwrap();
+
+        // The next run with the same inputs gets them back
+        let second = hash_inputs(
+            &mut daemon.client,
+            &repo_root,
+            &task_definitions,
+            &workspaces,
+        )
+        .await;
+        assert_eq!(second.hashes(), first.hashes());
+        assert_eq!(
+            second.task_hashes,
+            HashMap::from([(task_id, "0123456789abcdef".to_string())])
+        );
+    }
+
     #[test]
     fn test_hash_tracker_is_send_and_sync() {
         // We need the tracker to implement these traits as multiple tasks will query
@@ -146,6 +146,7 @@ impl ProcessedTaskDefinition {
         set_field!(self, other, interruptible);
         set_field!(self, other, interactive);
         set_field!(self, other, env_mode);
+        set_field!(self, other, cache_mode);
     }
 }
 
@@ -196,6 +197,7 @@ mod test {
             interactive: None,
             env_mode: None,
             with: None,
+            cache_mode: None,
         }
     }
 
@@ -231,6 +233,7 @@ mod test {
             interactive: None,
             env_mode: None,
             with: None,
+            cache_mode: None,
         }
     }
 
@@ -248,6 +251,7 @@ mod test {
             interactive: None,
             env_mode: None,
             with: None,
+            cache_mode: None,
         }
     }
 
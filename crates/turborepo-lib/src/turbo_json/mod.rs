@@ -2,6 +2,7 @@ use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt::Display,
     ops::{Deref, DerefMut},
+    str::FromStr,
     sync::Arc,
 };
 
@@ -12,6 +13,7 @@ use miette::{NamedSource, SourceSpan};
 use serde::{Deserialize, Serialize};
 use struct_iterable::Iterable;
 use turbopath::{AbsoluteSystemPath, RelativeUnixPath};
+use turborepo_cache::CacheActions;
 use turborepo_errors::Spanned;
 use turborepo_repository::package_graph::ROOT_PKG_NAME;
 use turborepo_task_id::{TaskId, TaskName};
@@ -21,7 +23,7 @@ use crate::{
     cli::{EnvMode, OutputLogsMode},
     config::{Error, InvalidEnvPrefixError},
     run::task_access::TaskAccessTraceFile,
-    task_graph::{TaskDefinition, TaskInputs, TaskOutputs},
+    task_graph::{CacheModeRule, TaskDefinition, TaskInputs, TaskOutputs},
 };
 
 mod extend;
@@ -34,7 +36,10 @@ pub use future_flags::FutureFlags;
 pub use loader::{TurboJsonLoader, TurboJsonReader};
 pub use processed::ProcessedTaskDefinition;
 
-use crate::{boundaries::BoundariesConfig, config::UnnecessaryPackageTaskSyntaxError};
+use crate::{
+    boundaries::BoundariesConfig, config::UnnecessaryPackageTaskSyntaxError, deps::DepsConfig,
+    licenses::LicensesConfig,
+};
 
 const ENV_PIPELINE_DELIMITER: &str = "$";
 const TOPOLOGICAL_PIPELINE_DELIMITER: &str = "^";
@@ -61,6 +66,8 @@ pub struct TurboJson {
     path: Option<Arc<str>>,
     pub(crate) tags: Option<Spanned<Vec<Spanned<String>>>>,
     pub(crate) boundaries: Option<Spanned<BoundariesConfig>>,
+    pub(crate) deps: Option<Spanned<DepsConfig>>,
+    pub(crate) licenses: Option<Spanned<LicensesConfig>>,
     pub(crate) extends: Spanned<Vec<String>>,
     pub(crate) global_deps: Vec<String>,
     pub(crate) global_env: Vec<String>,
@@ -93,6 +100,30 @@ pub struct RawRemoteCacheOptions {
     pub upload_timeout: Option<Spanned<u64>>,
 }
 
+// Iterable is required to enumerate allowed keys
+#[derive(Clone, Debug, Default, Iterable, Serialize, Deserializable)]
+#[serde(rename_all = "camelCase")]
+pub struct RawOtelOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<Spanned<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<Spanned<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_name: Option<Spanned<String>>,
+}
+
+// Iterable is required to enumerate allowed keys
+#[derive(Clone, Debug, Default, Iterable, Serialize, Deserializable)]
+#[serde(rename_all = "camelCase")]
+pub struct RawRunHistoryOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<Spanned<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_runs: Option<Spanned<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<Spanned<u64>>,
+}
+
 #[derive(Serialize, Default, Debug, Clone, Iterable, Deserializable)]
 #[serde(rename_all = "camelCase")]
 // The raw deserialized turbo.json file.
@@ -125,6 +156,12 @@ pub struct RawTurboJson {
     // Configuration options when interfacing with the remote cache
     #[serde(skip_serializing_if = "Option::is_none")]
     pub remote_cache: Option<RawRemoteCacheOptions>,
+    // Configuration options for exporting runs to an OpenTelemetry collector
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otel: Option<RawOtelOptions>,
+    // Configuration options for the local run history
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_history: Option<RawRunHistoryOptions>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "ui")]
     pub ui: Option<Spanned<UIMode>>,
     #[serde(
@@ -148,6 +185,14 @@ pub struct RawTurboJson {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub boundaries: Option<Spanned<BoundariesConfig>>,
 
+    // Dependency version policies checked by `turbo deps check`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deps: Option<Spanned<DepsConfig>>,
+
+    // License policies checked by `turbo licenses`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub licenses: Option<Spanned<LicensesConfig>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub concurrency: Option<Spanned<String>>,
 
@@ -257,6 +302,65 @@ pub struct RawTaskDefinition {
     // This can currently only be set internally and isn't a part of turbo.json
     #[serde(skip_serializing_if = "Option::is_none")]
     with: Option<Vec<Spanned<UnescapedString>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_mode: Option<Spanned<RawCacheModeRules>>,
+}
+
+/// The `cacheMode` of a task, either a single rule or a list of rules
+#[derive(Serialize, Default, Debug, PartialEq, Clone)]
+#[serde(transparent)]
+pub struct RawCacheModeRules(pub Vec<Spanned<RawCacheMode>>);
+
+#[derive(Serialize, Default, Debug, PartialEq, Clone, Iterable)]
+#[serde(rename_all = "camelCase")]
+pub struct RawCacheMode {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local: Option<Spanned<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<Spanned<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<Spanned<RawCacheModeCondition>>,
+}
+
+#[derive(Serialize, Default, Debug, PartialEq, Clone, Deserializable)]
+#[deserializable(unknown_fields = "deny")]
+pub struct RawCacheModeCondition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<Spanned<String>>,
+}
+
+impl RawCacheMode {
+    fn into_rule(self) -> Result<CacheModeRule, Error> {
+        let parse_actions = |actions: Spanned<String>| {
+            CacheActions::from_str(actions.as_str()).map_err(|_| {
+                let (span, text) = actions.span_and_text("turbo.json");
+                Error::InvalidTaskCacheMode {
+                    actions: actions.value.clone(),
+                    span,
+                    text,
+                }
+            })
+        };
+        let local = self.local.map(parse_actions).transpose()?;
+        let remote = self.remote.map(parse_actions).transpose()?;
+        let when_env = match self.when {
+            Some(when) => {
+                let (span, text) = when.span_and_text("turbo.json");
+                let env = when
+                    .into_inner()
+                    .env
+                    .ok_or(Error::MissingCacheModeCondition { span, text })?;
+                Some(env.into_inner())
+            }
+            None => None,
+        };
+
+        Ok(CacheModeRule {
+            local,
+            remote,
+            when_env,
+        })
+    }
 }
 
 impl TaskOutputs {
@@ -371,6 +475,19 @@ impl TaskDefinition {
 
         let with = processed.with.map(|with_tasks| with_tasks.tasks);
 
+        let cache_mode = processed
+            .cache_mode
+            .map(|rules| {
+                rules
+                    .into_inner()
+                    .0
+                    .into_iter()
+                    .map(|rule| rule.into_inner().into_rule())
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
         Ok(TaskDefinition {
             outputs,
             cache,
@@ -385,6 +502,7 @@ impl TaskDefinition {
             interactive,
             env_mode: processed.env_mode.map(|mode| *mode.as_inner()),
             with,
+            cache_mode,
         })
     }
 
@@ -552,6 +670,8 @@ impl TryFrom<RawTurboJson> for TurboJson {
                 .unwrap_or_default()
                 .map(|s| s.into_iter().map(|s| s.into()).collect()),
             boundaries: raw_turbo.boundaries,
+            deps: raw_turbo.deps,
+            licenses: raw_turbo.licenses,
             future_flags: raw_turbo
                 .future_flags
                 .map(|f| f.into_inner())
@@ -885,6 +1005,7 @@ mod tests {
             interruptible: Some(Spanned::new(true).with_range(342..346)),
             env_mode: None,
             with: None,
+            cache_mode: None,
         },
         TaskDefinition {
           env: vec!["OS".to_string()],
@@ -903,6 +1024,7 @@ mod tests {
           interruptible: true,
           env_mode: None,
           with: None,
+          cache_mode: vec![],
         }
       ; "full"
     )]
@@ -931,6 +1053,7 @@ mod tests {
             interactive: None,
             env_mode: None,
             with: None,
+            cache_mode: None,
         },
         TaskDefinition {
             env: vec!["OS".to_string()],
@@ -949,6 +1072,7 @@ mod tests {
             interactive: false,
             env_mode: None,
             with: None,
+            cache_mode: vec![],
         }
       ; "full (windows)"
     )]
@@ -991,6 +1115,40 @@ mod tests {
         }
     ; "with task"
     )]
+    #[test_case(
+        r#"{ "cacheMode": [{ "remote": "r" }, { "when": { "env": "CI" }, "local": "" }] }"#,
+        RawTaskDefinition {
+            cache_mode: Some(Spanned::new(RawCacheModeRules(vec![
+                Spanned::new(RawCacheMode {
+                    remote: Some(Spanned::new("r".to_string()).with_range(28..31)),
+                    ..RawCacheMode::default()
+                }).with_range(16..33),
+                Spanned::new(RawCacheMode {
+                    local: Some(Spanned::new("".to_string()).with_range(71..73)),
+                    when: Some(Spanned::new(RawCacheModeCondition {
+                        env: Some(Spanned::new("CI".to_string()).with_range(54..58)),
+                    }).with_range(45..60)),
+                    ..RawCacheMode::default()
+                }).with_range(35..75),
+            ])).with_range(15..76)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            cache_mode: vec![
+                CacheModeRule {
+                    remote: Some(CacheActions { read: true, write: false }),
+                    ..CacheModeRule::default()
+                },
+                CacheModeRule {
+                    local: Some(CacheActions { read: false, write: false }),
+                    when_env: Some("CI".to_string()),
+                    ..CacheModeRule::default()
+                },
+            ],
+            ..TaskDefinition::default()
+        }
+    ; "cache mode"
+    )]
     fn test_deserialize_task_definition(
         task_definition_content: &str,
         expected_raw_task_definition: RawTaskDefinition,
@@ -17,8 +17,13 @@ use turborepo_task_id::TaskName;
 use turborepo_unescape::UnescapedString;
 
 use crate::{
-    boundaries::{BoundariesConfig, Permissions, Rule},
-    turbo_json::{Pipeline, RawRemoteCacheOptions, RawTaskDefinition, RawTurboJson, Spanned},
+    boundaries::{BoundariesConfig, FileRule, Permissions, Rule},
+    deps::DepsConfig,
+    licenses::LicensesConfig,
+    turbo_json::{
+        Pipeline, RawCacheMode, RawCacheModeCondition, RawCacheModeRules, RawOtelOptions,
+        RawRemoteCacheOptions, RawRunHistoryOptions, RawTaskDefinition, RawTurboJson, Spanned,
+    },
 };
 
 #[derive(Debug, Error, Diagnostic)]
@@ -90,6 +95,101 @@ impl DeserializationVisitor for PipelineVisitor {
     }
 }
 
+impl Deserializable for RawCacheModeRules {
+    fn deserialize(
+        value: &impl DeserializableValue,
+        name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self> {
+        value.deserialize(CacheModeRulesVisitor, name, diagnostics)
+    }
+}
+
+// `cacheMode` accepts either a single rule or a list of rules
+struct CacheModeRulesVisitor;
+
+impl DeserializationVisitor for CacheModeRulesVisitor {
+    type Output = RawCacheModeRules;
+
+    const EXPECTED_TYPE: VisitableType = VisitableType::MAP.union(VisitableType::ARRAY);
+
+    fn visit_map(
+        self,
+        members: impl Iterator<Item = Option<(impl DeserializableValue, impl DeserializableValue)>>,
+        range: TextRange,
+        name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        let rule = CacheModeVisitor.visit_map(members, range, name, diagnostics)?;
+        Some(RawCacheModeRules(
+            vec![Spanned::new(rule).with_range(range)],
+        ))
+    }
+
+    fn visit_array(
+        self,
+        items: impl Iterator<Item = Option<impl DeserializableValue>>,
+        _range: TextRange,
+        name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        let rules = items
+            .flatten()
+            .filter_map(|item| Spanned::<RawCacheMode>::deserialize(&item, name, diagnostics))
+            .collect();
+        Some(RawCacheModeRules(rules))
+    }
+}
+
+impl Deserializable for RawCacheMode {
+    fn deserialize(
+        value: &impl DeserializableValue,
+        name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self> {
+        value.deserialize(CacheModeVisitor, name, diagnostics)
+    }
+}
+
+struct CacheModeVisitor;
+
+impl DeserializationVisitor for CacheModeVisitor {
+    type Output = RawCacheMode;
+
+    const EXPECTED_TYPE: VisitableType = VisitableType::MAP;
+
+    fn visit_map(
+        self,
+        members: impl Iterator<Item = Option<(impl DeserializableValue, impl DeserializableValue)>>,
+        _range: TextRange,
+        _name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        let mut result = RawCacheMode::default();
+        for (key, value) in members.flatten() {
+            let Some(key_text) = String::deserialize(&key, "", diagnostics) else {
+                continue;
+            };
+            match key_text.as_str() {
+                "local" => {
+                    result.local = Deserializable::deserialize(&value, &key_text, diagnostics)
+                }
+                "remote" => {
+                    result.remote = Deserializable::deserialize(&value, &key_text, diagnostics)
+                }
+                "when" => result.when = Deserializable::deserialize(&value, &key_text, diagnostics),
+                unknown_key => diagnostics.push(create_unknown_key_diagnostic_from_struct(
+                    &result,
+                    unknown_key,
+                    key.range(),
+                )),
+            }
+        }
+
+        Some(result)
+    }
+}
+
 impl WithMetadata for RawTurboJson {
     fn add_text(&mut self, text: Arc<str>) {
         self.span.add_text(text.clone());
@@ -105,11 +205,21 @@ impl WithMetadata for RawTurboJson {
         if let Some(boundaries) = &mut self.boundaries {
             boundaries.value.add_text(text.clone());
         }
+        self.deps.add_text(text.clone());
+        if let Some(deps) = &mut self.deps {
+            deps.value.add_text(text.clone());
+        }
+        self.licenses.add_text(text.clone());
+        if let Some(licenses) = &mut self.licenses {
+            licenses.value.add_text(text.clone());
+        }
 
         self.tasks.add_text(text.clone());
         self.cache_dir.add_text(text.clone());
         self.pipeline.add_text(text.clone());
         self.remote_cache.add_text(text.clone());
+        self.otel.add_text(text.clone());
+        self.run_history.add_text(text.clone());
         self.ui.add_text(text.clone());
         self.allow_no_package_manager.add_text(text.clone());
         self.daemon.add_text(text.clone());
@@ -133,10 +243,20 @@ impl WithMetadata for RawTurboJson {
         if let Some(boundaries) = &mut self.boundaries {
             boundaries.value.add_path(path.clone());
         }
+        self.deps.add_path(path.clone());
+        if let Some(deps) = &mut self.deps {
+            deps.value.add_path(path.clone());
+        }
+        self.licenses.add_path(path.clone());
+        if let Some(licenses) = &mut self.licenses {
+            licenses.value.add_path(path.clone());
+        }
         self.tasks.add_path(path.clone());
         self.cache_dir.add_path(path.clone());
         self.pipeline.add_path(path.clone());
         self.remote_cache.add_path(path.clone());
+        self.otel.add_path(path.clone());
+        self.run_history.add_path(path.clone());
         self.ui.add_path(path.clone());
         self.allow_no_package_manager.add_path(path.clone());
         self.daemon.add_path(path.clone());
@@ -178,6 +298,13 @@ impl WithMetadata for BoundariesConfig {
                 dep.add_text(text.clone());
             }
         }
+        self.files.add_text(text.clone());
+        if let Some(files) = &mut self.files {
+            for rule in files.as_inner_mut().values_mut() {
+                rule.add_text(text.clone());
+                rule.value.add_text(text.clone());
+            }
+        }
     }
 
     fn add_path(&mut self, path: Arc<str>) {
@@ -194,6 +321,87 @@ impl WithMetadata for BoundariesConfig {
                 dep.add_path(path.clone());
             }
         }
+        self.files.add_path(path.clone());
+        if let Some(files) = &mut self.files {
+            for rule in files.as_inner_mut().values_mut() {
+                rule.add_path(path.clone());
+                rule.value.add_path(path.clone());
+            }
+        }
+    }
+}
+
+impl WithMetadata for DepsConfig {
+    fn add_text(&mut self, text: Arc<str>) {
+        for versions in [&mut self.pinned, &mut self.allowed_ranges]
+            .into_iter()
+            .flatten()
+        {
+            versions.add_text(text.clone());
+            for version in versions.as_inner_mut().values_mut() {
+                version.add_text(text.clone());
+            }
+        }
+        self.single_version.add_text(text.clone());
+        self.workspace_protocol.add_text(text.clone());
+        for dependencies in [&mut self.ignore, &mut self.allow_unused]
+            .into_iter()
+            .flatten()
+        {
+            dependencies.add_text(text.clone());
+            for dependency in dependencies.as_inner_mut() {
+                dependency.add_text(text.clone());
+            }
+        }
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        for versions in [&mut self.pinned, &mut self.allowed_ranges]
+            .into_iter()
+            .flatten()
+        {
+            versions.add_path(path.clone());
+            for version in versions.as_inner_mut().values_mut() {
+                version.add_path(path.clone());
+            }
+        }
+        self.single_version.add_path(path.clone());
+        self.workspace_protocol.add_path(path.clone());
+        for dependencies in [&mut self.ignore, &mut self.allow_unused]
+            .into_iter()
+            .flatten()
+        {
+            dependencies.add_path(path.clone());
+            for dependency in dependencies.as_inner_mut() {
+                dependency.add_path(path.clone());
+            }
+        }
+    }
+}
+
+impl WithMetadata for LicensesConfig {
+    fn add_text(&mut self, text: Arc<str>) {
+        for licenses in [&mut self.allow, &mut self.deny, &mut self.ignore]
+            .into_iter()
+            .flatten()
+        {
+            licenses.add_text(text.clone());
+            for license in licenses.as_inner_mut() {
+                license.add_text(text.clone());
+            }
+        }
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        for licenses in [&mut self.allow, &mut self.deny, &mut self.ignore]
+            .into_iter()
+            .flatten()
+        {
+            licenses.add_path(path.clone());
+            for license in licenses.as_inner_mut() {
+                license.add_path(path.clone());
+            }
+        }
     }
 }
 
@@ -223,6 +431,22 @@ impl WithMetadata for Rule {
     }
 }
 
+impl WithMetadata for FileRule {
+    fn add_text(&mut self, text: Arc<str>) {
+        self.imports.add_text(text.clone());
+        if let Some(imports) = &mut self.imports {
+            imports.value.add_text(text);
+        }
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        self.imports.add_path(path.clone());
+        if let Some(imports) = &mut self.imports {
+            imports.value.add_path(path);
+        }
+    }
+}
+
 impl WithMetadata for Permissions {
     fn add_text(&mut self, text: Arc<str>) {
         self.allow.add_text(text.clone());
@@ -263,7 +487,11 @@ impl WithMetadata for RawTaskDefinition {
         self.outputs.add_text(text.clone());
         self.output_logs.add_text(text.clone());
         self.interactive.add_text(text.clone());
-        self.with.add_text(text);
+        self.with.add_text(text.clone());
+        self.cache_mode.add_text(text.clone());
+        if let Some(cache_mode) = &mut self.cache_mode {
+            cache_mode.value.add_text(text);
+        }
     }
 
     fn add_path(&mut self, path: Arc<str>) {
@@ -279,7 +507,57 @@ impl WithMetadata for RawTaskDefinition {
         self.outputs.add_path(path.clone());
         self.output_logs.add_path(path.clone());
         self.interactive.add_path(path.clone());
-        self.with.add_path(path);
+        self.with.add_path(path.clone());
+        self.cache_mode.add_path(path.clone());
+        if let Some(cache_mode) = &mut self.cache_mode {
+            cache_mode.value.add_path(path);
+        }
+    }
+}
+
+impl WithMetadata for RawCacheModeRules {
+    fn add_text(&mut self, text: Arc<str>) {
+        for rule in &mut self.0 {
+            rule.add_text(text.clone());
+            rule.value.add_text(text.clone());
+        }
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        for rule in &mut self.0 {
+            rule.add_path(path.clone());
+            rule.value.add_path(path.clone());
+        }
+    }
+}
+
+impl WithMetadata for RawCacheMode {
+    fn add_text(&mut self, text: Arc<str>) {
+        self.local.add_text(text.clone());
+        self.remote.add_text(text.clone());
+        self.when.add_text(text.clone());
+        if let Some(when) = &mut self.when {
+            when.value.add_text(text);
+        }
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        self.local.add_path(path.clone());
+        self.remote.add_path(path.clone());
+        self.when.add_path(path.clone());
+        if let Some(when) = &mut self.when {
+            when.value.add_path(path);
+        }
+    }
+}
+
+impl WithMetadata for RawCacheModeCondition {
+    fn add_text(&mut self, text: Arc<str>) {
+        self.env.add_text(text);
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        self.env.add_path(path);
     }
 }
 
@@ -309,6 +587,34 @@ impl WithMetadata for RawRemoteCacheOptions {
     }
 }
 
+impl WithMetadata for RawOtelOptions {
+    fn add_text(&mut self, text: Arc<str>) {
+        self.enabled.add_text(text.clone());
+        self.endpoint.add_text(text.clone());
+        self.service_name.add_text(text);
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        self.enabled.add_path(path.clone());
+        self.endpoint.add_path(path.clone());
+        self.service_name.add_path(path);
+    }
+}
+
+impl WithMetadata for RawRunHistoryOptions {
+    fn add_text(&mut self, text: Arc<str>) {
+        self.enabled.add_text(text.clone());
+        self.max_runs.add_text(text.clone());
+        self.max_age_days.add_text(text);
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        self.enabled.add_path(path.clone());
+        self.max_runs.add_path(path.clone());
+        self.max_age_days.add_path(path);
+    }
+}
+
 impl RawTurboJson {
     // A simple helper for tests
     #[cfg(test)]
@@ -6,7 +6,7 @@ use turborepo_errors::Spanned;
 use turborepo_task_id::TaskName;
 use turborepo_unescape::UnescapedString;
 
-use super::{FutureFlags, RawTaskDefinition};
+use super::{FutureFlags, RawCacheModeRules, RawTaskDefinition};
 use crate::{
     cli::{EnvMode, OutputLogsMode},
     config::Error,
@@ -341,6 +341,7 @@ pub struct ProcessedTaskDefinition {
     pub interactive: Option<Spanned<bool>>,
     pub env_mode: Option<Spanned<EnvMode>>,
     pub with: Option<ProcessedWith>,
+    pub cache_mode: Option<Spanned<RawCacheModeRules>>,
 }
 
 impl ProcessedTaskDefinition {
@@ -380,6 +381,7 @@ impl ProcessedTaskDefinition {
                 .with
                 .map(|with| ProcessedWith::new(with, future_flags))
                 .transpose()?,
+            cache_mode: raw_task.cache_mode,
         })
     }
 }
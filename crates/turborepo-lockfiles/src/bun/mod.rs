@@ -217,6 +217,18 @@ impl Lockfile for BunLockfile {
         let entry = self.data.packages.get(&package.key)?;
         Some(entry.ident.clone())
     }
+
+    fn package_name(&self, package: &crate::Package) -> Option<String> {
+        // Keys are idents of the form `name@version`, where the name might be
+        // scoped
+        let idx = package.key.get(1..)?.find('@')? + 1;
+        Some(package.key[..idx].to_string())
+    }
+
+    fn integrity(&self, package: &crate::Package) -> Option<String> {
+        let entry_key = self.key_to_entry.get(&package.key)?;
+        self.data.packages.get(entry_key)?.checksum.clone()
+    }
 }
 
 impl BunLockfile {
@@ -540,6 +552,37 @@ mod test {
         assert_eq!(actual, expected);
     }
 
+    #[test_case(
+        "apps/web",
+        "is-odd",
+        "3.0.0",
+        "sha512-204vE5IJ0Cd6pA6x1dMyLooGk6/xeKuq90imFuJN/ndMDBP4Sk9tJpBlTedHPvt6KDbtTDTsjVzzgctFqNV7FQ==" ; "nested"
+    )]
+    #[test_case(
+        "apps/docs",
+        "is-odd",
+        "3.0.1",
+        "sha512-CQpnWPrDwmP1+SMHXZhtLtJv90yiyVfluGsX5iNCVkrhQtU3TQHsUWPG9wkdk9Lgd5yNpAg9jQEo90CBaXgWMA==" ; "hoisted"
+    )]
+    fn test_integrity(workspace: &str, name: &str, version: &str, expected: &str) {
+        let lockfile = BunLockfile::from_str(BASIC_LOCKFILE).unwrap();
+        let package = lockfile
+            .resolve_package(workspace, name, version)
+            .unwrap()
+            .unwrap();
+        assert_eq!(lockfile.integrity(&package).as_deref(), Some(expected));
+    }
+
+    #[test_case("is-odd@3.0.1", Some("is-odd") ; "unscoped")]
+    #[test_case("@types/react@18.3.0", Some("@types/react") ; "scoped")]
+    #[test_case("@repo/ui@workspace:packages/ui", Some("@repo/ui") ; "workspace")]
+    #[test_case("is-odd", None ; "no version")]
+    fn test_package_name(key: &str, expected: Option<&str>) {
+        let lockfile = BunLockfile::from_str(BASIC_LOCKFILE).unwrap();
+        let package = crate::Package::new(key, "");
+        assert_eq!(lockfile.package_name(&package).as_deref(), expected);
+    }
+
     #[test]
     fn test_patch_is_captured_in_package() {
         let lockfile = BunLockfile::from_str(PATCH_LOCKFILE).unwrap();
@@ -0,0 +1,434 @@
+use std::{any::Any, collections::HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Error, Lockfile, Package};
+
+type Map<K, V> = std::collections::BTreeMap<K, V>;
+
+// Lockfile versions that use the flat `specifiers`/`jsr`/`npm` layout
+const SUPPORTED_VERSIONS: &[&str] = &["4", "5"];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DenoLockfile {
+    version: String,
+    // Maps a requested specifier e.g. `npm:chalk@5` to the resolved version
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    specifiers: Map<String, String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    jsr: Map<String, DenoPackage>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    npm: Map<String, DenoPackage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    workspace: Option<DenoWorkspace>,
+    // We want to reserialize any additional fields e.g. `remote` or `redirects`,
+    // but we don't use them.
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct DenoPackage {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    dependencies: Vec<String>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct DenoWorkspace {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    dependencies: Vec<String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    members: Map<String, DenoWorkspaceMember>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct DenoWorkspaceMember {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    dependencies: Vec<String>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Registry {
+    Jsr,
+    Npm,
+}
+
+impl Registry {
+    fn prefix(&self) -> &'static str {
+        match self {
+            Registry::Jsr => "jsr:",
+            Registry::Npm => "npm:",
+        }
+    }
+
+    // Splits a specifier into its registry and the rest of the specifier
+    fn parse(specifier: &str) -> Option<(Self, &str)> {
+        if let Some(rest) = specifier.strip_prefix("jsr:") {
+            Some((Registry::Jsr, rest))
+        } else {
+            specifier
+                .strip_prefix("npm:")
+                .map(|rest| (Registry::Npm, rest))
+        }
+    }
+}
+
+impl Lockfile for DenoLockfile {
+    #[tracing::instrument(skip(self))]
+    fn resolve_package(
+        &self,
+        _workspace_path: &str,
+        name: &str,
+        version: &str,
+    ) -> Result<Option<Package>, Error> {
+        // all_dependencies returns lockfile keys so we can skip resolution
+        if let Some(package) = self.package_for_key(name) {
+            return Ok(Some(package));
+        }
+
+        // Workspace packages mark JSR dependencies with a `jsr:` prefix
+        let (registry, range) = match Registry::parse(version) {
+            Some((Registry::Jsr, range)) => (Registry::Jsr, range),
+            _ => (
+                Registry::Npm,
+                version.strip_prefix("npm:").unwrap_or(version),
+            ),
+        };
+
+        Ok(self.resolve_specifier(registry, name, Some(range)))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn all_dependencies(&self, key: &str) -> Result<Option<HashMap<String, String>>, Error> {
+        let Some((registry, _)) = Registry::parse(key) else {
+            return Ok(None);
+        };
+        let Some(entry) = self.entry(key) else {
+            return Ok(None);
+        };
+
+        Ok(Some(
+            entry
+                .dependencies
+                .iter()
+                .filter_map(|dependency| {
+                    // npm packages list their dependencies without a registry prefix
+                    let (registry, specifier) =
+                        Registry::parse(dependency).unwrap_or((registry, dependency.as_str()));
+                    let (name, version) = split_name_version(specifier);
+                    let package = self.resolve_specifier(registry, name, version)?;
+                    Some((package.key, package.version))
+                })
+                .collect(),
+        ))
+    }
+
+    fn subgraph(
+        &self,
+        workspace_packages: &[String],
+        packages: &[String],
+    ) -> Result<Box<dyn Lockfile>, Error> {
+        let mut jsr = Map::new();
+        let mut npm = Map::new();
+        for key in packages {
+            let (entry, (registry, id)) = self
+                .entry(key)
+                .zip(Registry::parse(key))
+                .ok_or_else(|| Error::MissingPackage(key.clone()))?;
+            let packages = match registry {
+                Registry::Jsr => &mut jsr,
+                Registry::Npm => &mut npm,
+            };
+            packages.insert(id.to_string(), entry.clone());
+        }
+
+        let specifiers = self
+            .specifiers
+            .iter()
+            .filter(|(specifier, resolved)| {
+                let Some((registry, rest)) = Registry::parse(specifier) else {
+                    return false;
+                };
+                let (name, _) = split_name_version(rest);
+                let id = format!("{name}@{resolved}");
+                match registry {
+                    Registry::Jsr => jsr.contains_key(&id),
+                    Registry::Npm => npm.contains_key(&id),
+                }
+            })
+            .map(|(specifier, resolved)| (specifier.clone(), resolved.clone()))
+            .collect();
+
+        let workspace = self.workspace.as_ref().map(|workspace| DenoWorkspace {
+            dependencies: workspace.dependencies.clone(),
+            members: workspace
+                .members
+                .iter()
+                .filter(|(path, _)| workspace_packages.contains(path))
+                .map(|(path, member)| (path.clone(), member.clone()))
+                .collect(),
+            other: workspace.other.clone(),
+        });
+
+        Ok(Box::new(Self {
+            version: self.version.clone(),
+            specifiers,
+            jsr,
+            npm,
+            workspace,
+            other: self.other.clone(),
+        }))
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        let mut contents = serde_json::to_vec_pretty(&self)?;
+        contents.push(b'\n');
+        Ok(contents)
+    }
+
+    fn global_change(&self, other: &dyn Lockfile) -> bool {
+        let any_other = other as &dyn Any;
+        if let Some(other) = any_other.downcast_ref::<Self>() {
+            self.version != other.version
+                || self.workspace.as_ref().map(|w| &w.dependencies)
+                    != other.workspace.as_ref().map(|w| &w.dependencies)
+        } else {
+            true
+        }
+    }
+
+    fn turbo_version(&self) -> Option<String> {
+        self.npm.keys().find_map(|id| match split_name_version(id) {
+            ("turbo", Some(version)) => Some(version.to_string()),
+            _ => None,
+        })
+    }
+
+    fn human_name(&self, package: &Package) -> Option<String> {
+        let (_, id) = Registry::parse(&package.key)?;
+        Some(id.to_string())
+    }
+
+    fn package_name(&self, package: &Package) -> Option<String> {
+        let (_, id) = Registry::parse(&package.key)?;
+        let (name, _) = split_name_version(id);
+        Some(name.to_string())
+    }
+}
+
+impl DenoLockfile {
+    pub fn load(content: &[u8]) -> Result<Self, Error> {
+        let lockfile: DenoLockfile = serde_json::from_slice(content)?;
+        if !SUPPORTED_VERSIONS.contains(&lockfile.version.as_str()) {
+            return Err(Error::UnsupportedDenoVersion(lockfile.version));
+        }
+        Ok(lockfile)
+    }
+
+    fn entry(&self, key: &str) -> Option<&DenoPackage> {
+        match Registry::parse(key)? {
+            (Registry::Jsr, id) => self.jsr.get(id),
+            (Registry::Npm, id) => self.npm.get(id),
+        }
+    }
+
+    fn package_for_key(&self, key: &str) -> Option<Package> {
+        self.entry(key)?;
+        let (_, id) = Registry::parse(key)?;
+        let (_, version) = split_name_version(id);
+        Some(Package::new(key, version.unwrap_or_default()))
+    }
+
+    fn resolve_specifier(
+        &self,
+        registry: Registry,
+        name: &str,
+        version: Option<&str>,
+    ) -> Option<Package> {
+        let prefix = registry.prefix();
+        match version {
+            Some(version) => {
+                let specifier = format!("{prefix}{name}@{version}");
+                let resolved = self
+                    .specifiers
+                    .get(&specifier)
+                    .map(|resolved| resolved.as_str())
+                    // Dependencies of npm packages are exact versions
+                    .unwrap_or(version);
+                self.package_for_key(&format!("{prefix}{name}@{resolved}"))
+            }
+            // The lockfile only omits the version if a single version is present
+            None => {
+                let packages = match registry {
+                    Registry::Jsr => &self.jsr,
+                    Registry::Npm => &self.npm,
+                };
+                let id = packages
+                    .keys()
+                    .find(|id| split_name_version(id).0 == name)?;
+                self.package_for_key(&format!("{prefix}{id}"))
+            }
+        }
+    }
+}
+
+// Splits `name@version` while accounting for scoped packages
+fn split_name_version(id: &str) -> (&str, Option<&str>) {
+    match id.rfind('@') {
+        Some(0) | None => (id, None),
+        Some(idx) => (&id[..idx], Some(&id[idx + 1..])),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use test_case::test_case;
+
+    use super::*;
+
+    const FIXTURE: &[u8] = include_bytes!("../fixtures/deno.lock");
+
+    #[test_case("@std/assert@1", ("@std/assert", Some("1")) ; "scoped")]
+    #[test_case("@std/internal", ("@std/internal", None) ; "scoped without version")]
+    #[test_case("chalk@5.3.0", ("chalk", Some("5.3.0")) ; "unscoped")]
+    #[test_case("chalk", ("chalk", None) ; "unscoped without version")]
+    fn test_split_name_version(id: &str, expected: (&str, Option<&str>)) {
+        assert_eq!(split_name_version(id), expected);
+    }
+
+    #[test_case("chalk", "5", Some(("npm:chalk@5.3.0", "5.3.0")) ; "npm specifier")]
+    #[test_case("@std/assert", "jsr:1", Some(("jsr:@std/assert@1.0.8", "1.0.8")) ; "jsr specifier")]
+    #[test_case("chalk", "5.3.0", Some(("npm:chalk@5.3.0", "5.3.0")) ; "exact version")]
+    #[test_case("chalk", "4", None ; "unresolved specifier")]
+    fn test_resolve_package(name: &str, version: &str, expected: Option<(&str, &str)>) {
+        let lockfile = DenoLockfile::load(FIXTURE).unwrap();
+        let actual = lockfile.resolve_package("apps/cli", name, version).unwrap();
+        assert_eq!(
+            actual,
+            expected.map(|(key, version)| Package::new(key, version))
+        );
+    }
+
+    #[test]
+    fn test_all_dependencies() {
+        let lockfile = DenoLockfile::load(FIXTURE).unwrap();
+        let deps = lockfile
+            .all_dependencies("npm:wrap-ansi@9.0.0")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            deps,
+            [
+                ("npm:ansi-styles@6.2.1", "6.2.1"),
+                ("npm:string-width@7.2.0", "7.2.0"),
+                ("npm:strip-ansi@7.1.0", "7.1.0"),
+            ]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>()
+        );
+
+        let deps = lockfile
+            .all_dependencies("jsr:@std/assert@1.0.8")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            deps,
+            [("jsr:@std/internal@1.0.5".to_string(), "1.0.5".to_string())]
+                .into_iter()
+                .collect::<HashMap<_, _>>()
+        );
+    }
+
+    #[test]
+    fn test_transitive_closure() {
+        let lockfile = DenoLockfile::load(FIXTURE).unwrap();
+        let closure = crate::transitive_closure(
+            &lockfile,
+            "packages/ui",
+            [("wrap-ansi".to_string(), "9".to_string())]
+                .into_iter()
+                .collect(),
+            false,
+        )
+        .unwrap();
+        let keys = closure
+            .into_iter()
+            .map(|pkg| pkg.key)
+            .collect::<HashSet<_>>();
+        assert_eq!(
+            keys,
+            [
+                "npm:ansi-regex@6.1.0",
+                "npm:ansi-styles@6.2.1",
+                "npm:string-width@7.2.0",
+                "npm:strip-ansi@7.1.0",
+                "npm:wrap-ansi@9.0.0",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<HashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn test_subgraph() {
+        let lockfile = DenoLockfile::load(FIXTURE).unwrap();
+        let pruned = lockfile
+            .subgraph(
+                &["apps/cli".into()],
+                &[
+                    "jsr:@std/assert@1.0.8".into(),
+                    "jsr:@std/internal@1.0.5".into(),
+                    "npm:chalk@5.3.0".into(),
+                ],
+            )
+            .unwrap();
+        let pruned = DenoLockfile::load(&pruned.encode().unwrap()).unwrap();
+
+        assert_eq!(
+            pruned.specifiers.keys().collect::<Vec<_>>(),
+            [
+                "jsr:@std/assert@1",
+                "jsr:@std/internal@^1.0.5",
+                "npm:chalk@5"
+            ]
+        );
+        assert_eq!(pruned.npm.keys().collect::<Vec<_>>(), ["chalk@5.3.0"]);
+        assert_eq!(
+            pruned.workspace.unwrap().members.keys().collect::<Vec<_>>(),
+            ["apps/cli"]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let lockfile = DenoLockfile::load(FIXTURE).unwrap();
+        let encoded = lockfile.encode().unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&encoded).unwrap(),
+            serde_json::from_slice::<Value>(FIXTURE).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_turbo_version() {
+        let lockfile = DenoLockfile::load(FIXTURE).unwrap();
+        assert_eq!(lockfile.turbo_version().as_deref(), Some("2.3.0"));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let result = DenoLockfile::load(br#"{"version": "3", "packages": {}}"#);
+        assert!(matches!(result, Err(Error::UnsupportedDenoVersion(_))));
+    }
+}
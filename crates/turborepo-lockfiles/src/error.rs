@@ -16,6 +16,8 @@ pub enum Error {
     Yaml(#[from] serde_yaml::Error),
     #[error("Turborepo doesn't support npm lockfiles without a 'packages' field")]
     UnsupportedNpmVersion,
+    #[error("Turborepo doesn't support version '{0}' of deno.lock")]
+    UnsupportedDenoVersion(String),
     #[error(transparent)]
     Pnpm(#[from] crate::pnpm::Error),
     #[error(transparent)]
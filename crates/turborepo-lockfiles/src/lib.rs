@@ -4,6 +4,7 @@
 
 mod berry;
 mod bun;
+mod deno;
 mod error;
 mod npm;
 mod pnpm;
@@ -11,11 +12,12 @@ mod yarn1;
 
 use std::{
     any::Any,
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
 };
 
 pub use berry::{Error as BerryError, *};
 pub use bun::BunLockfile;
+pub use deno::DenoLockfile;
 pub use error::Error;
 pub use npm::*;
 pub use pnpm::{PnpmLockfile, pnpm_global_change, pnpm_subgraph};
@@ -30,6 +32,20 @@ pub struct Package {
     pub version: String,
 }
 
+/// A package in a workspace's transitive closure, along with how it was
+/// depended on
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ResolvedDependency {
+    pub name: String,
+    /// The version range that resolved to the package, as listed in the
+    /// lockfile
+    pub specifier: String,
+    pub package: Package,
+    /// The key of the package that depends on this one, or `None` if the
+    /// workspace declares it directly
+    pub parent: Option<String>,
+}
+
 // This trait will only be used when migrating the Go lockfile implementations
 // to Rust. Once the migration is complete we will leverage petgraph for doing
 // our graph calculations.
@@ -73,6 +89,21 @@ pub trait Lockfile: Send + Sync + Any + std::fmt::Debug {
     fn human_name(&self, package: &Package) -> Option<String> {
         None
     }
+
+    /// The name of the package, as it's declared in `package.json` files.
+    /// Only needed for lockfiles where `all_dependencies` returns keys instead
+    /// of names.
+    #[allow(unused)]
+    fn package_name(&self, package: &Package) -> Option<String> {
+        None
+    }
+
+    /// The integrity hash of the package's tarball, in the Subresource
+    /// Integrity format e.g. `sha512-...`, if the lockfile records one.
+    #[allow(unused)]
+    fn integrity(&self, package: &Package) -> Option<String> {
+        None
+    }
 }
 
 /// Takes a lockfile, and a map of workspace directory paths -> (package name,
@@ -160,6 +191,48 @@ fn transitive_closure_helper<L: Lockfile + ?Sized>(
     Ok(())
 }
 
+/// Like `transitive_closure`, but keeps the name of each package and the
+/// packages that depend on it. A package is listed once for each package that
+/// depends on it, and the packages that the workspace declares directly are
+/// listed first.
+#[tracing::instrument(skip_all)]
+pub fn resolved_dependencies<L: Lockfile + ?Sized>(
+    lockfile: &L,
+    workspace_path: &str,
+    unresolved_deps: HashMap<String, String>,
+) -> Result<Vec<ResolvedDependency>, Error> {
+    let mut resolved = Vec::new();
+    let mut seen = HashSet::new();
+    // Breadth first, so that the direct dependencies are resolved first
+    let mut queue = unresolved_deps
+        .into_iter()
+        .map(|(name, specifier)| (name, specifier, None))
+        .collect::<VecDeque<_>>();
+
+    while let Some((name, specifier, parent)) = queue.pop_front() {
+        let Some(package) = lockfile.resolve_package(workspace_path, &name, &specifier)? else {
+            continue;
+        };
+        if seen.insert(package.clone())
+            && let Some(deps) = lockfile.all_dependencies(&package.key)?
+        {
+            queue.extend(
+                deps.into_iter()
+                    .map(|(name, specifier)| (name, specifier, Some(package.key.clone()))),
+            );
+        }
+
+        resolved.push(ResolvedDependency {
+            name: lockfile.package_name(&package).unwrap_or(name),
+            specifier,
+            package,
+            parent,
+        });
+    }
+
+    Ok(resolved)
+}
+
 impl Package {
     pub fn new(key: impl Into<String>, version: impl Into<String>) -> Self {
         let key = key.into();
@@ -159,6 +159,17 @@ impl Lockfile for NpmLockfile {
         let name = package.key.split("node_modules/").last()?;
         Some(format!("{name}@{version}"))
     }
+
+    fn package_name(&self, package: &Package) -> Option<String> {
+        let name = package.key.split("node_modules/").last()?;
+        Some(name.to_string())
+    }
+
+    fn integrity(&self, package: &Package) -> Option<String> {
+        let npm_package = self.packages.get(&package.key)?;
+        let integrity = npm_package.other.get("integrity")?.as_str()?;
+        Some(integrity.to_string())
+    }
 }
 
 impl NpmLockfile {
@@ -339,6 +350,51 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_integrity() -> Result<(), Error> {
+        let lockfile = NpmLockfile::load(include_bytes!("../fixtures/npm-lock.json"))?;
+        let turbo = lockfile.resolve_package("", "turbo", "")?.unwrap();
+        assert_eq!(
+            lockfile.integrity(&turbo).as_deref(),
+            Some(
+                "sha512-PVQSDl0STC9WXIyHcYUWs9gXsf8JjQig/FuHfuB8N6+XlgCGB3mPbfMEE6zrChGz2hufH4/\
+                 guKRX1XJuNL6XTA=="
+            )
+        );
+        assert_eq!(
+            lockfile.integrity(&Package::new("node_modules/missing", "")),
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolved_dependencies() -> Result<(), Error> {
+        let lockfile = NpmLockfile::load(include_bytes!("../fixtures/npm-lock.json"))?;
+        let resolved = crate::resolved_dependencies(
+            &lockfile,
+            "",
+            [("table".to_string(), "^6.8.0".to_string())]
+                .into_iter()
+                .collect(),
+        )?;
+
+        let table = &resolved[0];
+        assert_eq!(table.name, "table");
+        assert_eq!(table.package.key, "node_modules/table");
+        assert_eq!(table.parent, None);
+
+        let ajv = resolved
+            .iter()
+            .find(|dependency| dependency.package.key == "node_modules/table/node_modules/ajv")
+            .unwrap();
+        assert_eq!(ajv.name, "ajv");
+        assert_eq!(ajv.parent.as_deref(), Some("node_modules/table"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_all_dependencies() -> Result<(), Error> {
         let lockfile = NpmLockfile::load(include_bytes!("../fixtures/npm-lock.json"))?;
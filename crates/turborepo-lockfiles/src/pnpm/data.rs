@@ -550,6 +550,17 @@ impl crate::Lockfile for PnpmLockfile {
             Some(package.key.strip_prefix('/')?.to_owned())
         }
     }
+
+    fn integrity(&self, package: &crate::Package) -> Option<String> {
+        let entry = if self.snapshots.is_some() {
+            // Remove peer suffix to find the key for the package entry
+            let dp = DepPath::parse(self.version(), &package.key).ok()?;
+            self.get_packages(&self.format_key(dp.name, dp.version))
+        } else {
+            self.get_packages(&package.key)
+        }?;
+        entry.resolution.integrity.clone()
+    }
 }
 
 impl DependencyInfo {
@@ -1333,6 +1344,15 @@ c:
         assert_eq!(lockfile.turbo_version().as_deref(), expected);
     }
 
+    #[test_case(PNPM8, "/is-odd@3.0.1", Some("sha512-CQpnWPrDwmP1+SMHXZhtLtJv90yiyVfluGsX5iNCVkrhQtU3TQHsUWPG9wkdk9Lgd5yNpAg9jQEo90CBaXgWMA==") ; "v6")]
+    #[test_case(PNPM_V7_PEER, "ajv-keywords@5.1.0(ajv@8.11.0)", Some("sha512-YCS/JNFAUyr5vAuhk1DWm1CBxRHW9LbJ2ozWeemrIqpbsqKjHVxYPyi5GC0rjZIT5JxJ3virVTS8wk4i/Z+krw==") ; "v7 with peer suffix")]
+    #[test_case(PNPM8, "/missing@1.0.0", None ; "missing package")]
+    fn test_integrity(lockfile: &[u8], key: &str, expected: Option<&str>) {
+        let lockfile = PnpmLockfile::from_bytes(lockfile).unwrap();
+        let package = crate::Package::new(key, "");
+        assert_eq!(lockfile.integrity(&package).as_deref(), expected);
+    }
+
     #[test]
     fn test_catalog_support() {
         let lockfile =
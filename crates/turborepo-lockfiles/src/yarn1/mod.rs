@@ -134,6 +134,10 @@ impl Lockfile for Yarn1Lockfile {
         let version = &entry.version;
         Some(format!("{name}@{version}"))
     }
+
+    fn integrity(&self, package: &crate::Package) -> Option<String> {
+        self.inner.get(&package.key)?.integrity.clone()
+    }
 }
 
 pub fn yarn_subgraph(contents: &[u8], packages: &[String]) -> Result<Vec<u8>, crate::Error> {
@@ -195,6 +199,46 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_integrity() {
+        let lockfile = Yarn1Lockfile::from_str(MINIMAL).unwrap();
+        let package = lockfile
+            .resolve_package("", "nextjs", "^0.0.3")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            lockfile.integrity(&package).as_deref(),
+            Some(
+                "sha512-mYbDUo4/sRAZ8TqK63PCpYnFiLg7BICG/ot9+guOrUKd4/\
+                 Fo71ZmEQ41IZbH6nqbQvG7SXTBuofJXAIWfNho0w=="
+            )
+        );
+    }
+
+    #[test]
+    fn test_resolved_dependency_names() {
+        // Keys include the version range, so packages are named by the
+        // dependency that resolved to them
+        let lockfile = Yarn1Lockfile::from_str(MINIMAL).unwrap();
+        let resolved = crate::resolved_dependencies(
+            &lockfile,
+            "",
+            [("nextjs".to_string(), "^0.0.3".to_string())]
+                .into_iter()
+                .collect(),
+        )
+        .unwrap();
+        assert_eq!(
+            resolved,
+            vec![crate::ResolvedDependency {
+                name: "nextjs".into(),
+                specifier: "^0.0.3".into(),
+                package: crate::Package::new("nextjs@^0.0.3", "0.0.3"),
+                parent: None,
+            }]
+        );
+    }
+
     #[test_case(MINIMAL, "1.9.3" ; "minimal lockfile")]
     #[test_case(FULL, "1.4.6" ; "full lockfile")]
     fn test_turbo_version(lockfile: &str, expected: &str) {
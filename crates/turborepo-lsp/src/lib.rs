@@ -599,7 +599,7 @@ impl LanguageServer for Backend {
         let package_jsons = packages
             .workspaces
             .into_iter()
-            .flat_map(|wd| PackageJson::load(&wd.package_json).ok()) // if we can't parse a package.json, then we can't infer its tasks
+            .flat_map(|wd| PackageJson::load_manifest(&wd.package_json).ok()) // if we can't parse a package.json, then we can't infer its tasks
             .collect::<Vec<_>>();
 
         let tasks = package_jsons
@@ -702,7 +702,7 @@ impl Backend {
         let tasks = workspaces.map(|workspaces| {
             workspaces
                 .filter_map(|wd| {
-                    let package_json = PackageJson::load(&wd.package_json).ok()?; // if we can't load a package.json, then we can't infer its tasks
+                    let package_json = PackageJson::load_manifest(&wd.package_json).ok()?; // if we can't load a package.json, then we can't infer its tasks
                     let package_json_name = if (&*repo_root)
                         == wd
                             .package_json
@@ -11,7 +11,7 @@ use turbopath::{
     AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf,
 };
 use turborepo_graph_utils as graph;
-use turborepo_lockfiles::Lockfile;
+use turborepo_lockfiles::{Lockfile, ResolvedDependency};
 
 use crate::{
     discovery::LocalPackageDiscoveryBuilder, package_json::PackageJson,
@@ -22,6 +22,7 @@ pub mod builder;
 mod dep_splitter;
 
 pub use builder::{Error, PackageGraphBuilder};
+use dep_splitter::DependencySplitter;
 
 pub const ROOT_PKG_NAME: &str = "//";
 
@@ -223,6 +224,21 @@ impl PackageGraph {
         self.packages.get(package)
     }
 
+    /// Gets the innermost package, other than the root, that contains `path`
+    pub fn package_containing(
+        &self,
+        path: &AnchoredSystemPath,
+    ) -> Option<(&PackageName, &PackageInfo)> {
+        self.packages
+            .iter()
+            .filter(|(name, _)| !matches!(name, PackageName::Root))
+            .filter(|(_, info)| {
+                path.ancestors()
+                    .any(|ancestor| ancestor == info.package_path())
+            })
+            .max_by_key(|(_, info)| info.package_path().as_str().len())
+    }
+
     pub fn get_package_by_index(&self, index: NodeIndex) -> Option<&PackageNode> {
         self.graph.node_weight(index)
     }
@@ -558,6 +574,81 @@ impl PackageGraph {
         }))
     }
 
+    /// Like `immediate_dependencies`, but only includes the workspaces that
+    /// `package` declares in `dependencies` or `optionalDependencies`. These
+    /// are resolved the same way as the edges of the graph, so aliased
+    /// `workspace:` dependencies are included.
+    pub fn immediate_prod_dependencies(
+        &self,
+        package: &PackageName,
+    ) -> Option<HashSet<&PackageNode>> {
+        let info = self.packages.get(package)?;
+        let package_json_path = self.repo_root.resolve(&info.package_json_path);
+        let splitter = DependencySplitter::new(
+            &self.repo_root,
+            package_json_path.parent()?,
+            &self.packages,
+            &self.package_manager,
+        );
+        let prod_dependencies = info
+            .package_json
+            .dependencies
+            .iter()
+            .flatten()
+            .chain(info.package_json.optional_dependencies.iter().flatten())
+            .filter_map(|(name, version)| splitter.is_internal(name, version))
+            .map(PackageNode::Workspace)
+            .collect::<HashSet<_>>();
+
+        let mut dependencies =
+            self.immediate_dependencies(&PackageNode::Workspace(package.clone()))?;
+        dependencies.retain(|dependency| prod_dependencies.contains(*dependency));
+        Some(dependencies)
+    }
+
+    /// Returns `package` along with every workspace that it depends on,
+    /// directly or transitively, excluding the root.
+    pub fn workspace_closure<'a>(&'a self, package: &'a PackageName) -> Vec<&'a PackageName> {
+        let mut closure = self
+            .dependencies(&PackageNode::Workspace(package.clone()))
+            .into_iter()
+            .filter_map(|node| match node {
+                PackageNode::Workspace(name @ PackageName::Other(_)) => Some(name),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        closure.push(package);
+        closure
+    }
+
+    /// Resolves the external packages that `package` depends on using the
+    /// lockfile, including those it only depends on through other external
+    /// packages. Direct dependencies are returned first, and every package
+    /// comes after the package that depends on it.
+    ///
+    /// Returns an empty list if there is no lockfile or `package` isn't in the
+    /// graph.
+    pub fn resolved_external_dependencies(
+        &self,
+        package: &PackageName,
+    ) -> Result<Vec<ResolvedDependency>, turborepo_lockfiles::Error> {
+        let (Some(lockfile), Some(info)) = (self.lockfile(), self.package_info(package)) else {
+            return Ok(Vec::new());
+        };
+        let workspace_path = info.package_path().to_unix();
+        let unresolved_deps = info
+            .unresolved_external_dependencies
+            .iter()
+            .flatten()
+            .map(|(name, version)| (name.to_owned(), version.to_owned()))
+            .collect();
+        turborepo_lockfiles::resolved_dependencies(
+            lockfile,
+            workspace_path.as_str(),
+            unresolved_deps,
+        )
+    }
+
     // Returns a map of package name and version for external dependencies
     #[allow(dead_code)]
     fn external_dependencies(
@@ -876,6 +967,80 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_resolved_external_dependencies() {
+        let root =
+            AbsoluteSystemPathBuf::new(if cfg!(windows) { r"C:\repo" } else { "/repo" }).unwrap();
+        let pkg_graph = PackageGraph::builder(
+            &root,
+            PackageJson::from_value(json!({ "name": "root" })).unwrap(),
+        )
+        .with_package_discovery(MockDiscovery)
+        .with_package_jsons(Some({
+            let mut map = HashMap::new();
+            map.insert(
+                root.join_components(&["package_a", "package.json"]),
+                PackageJson::from_value(json!({
+                    "name": "foo",
+                    "dependencies": {
+                        "a": "1",
+                        "bar": "workspace:*"
+                    }
+                }))
+                .unwrap(),
+            );
+            map.insert(
+                root.join_components(&["package_b", "package.json"]),
+                PackageJson::from_value(json!({
+                    "name": "bar",
+                    "dependencies": {
+                        "b": "1",
+                    }
+                }))
+                .unwrap(),
+            );
+            map
+        }))
+        .with_lockfile(Some(Box::new(MockLockfile {})))
+        .build()
+        .await
+        .unwrap();
+
+        let foo = PackageName::from("foo");
+        let bar = PackageName::from("bar");
+        let mut closure = pkg_graph.workspace_closure(&foo);
+        closure.sort();
+        assert_eq!(closure, vec![&bar, &foo]);
+        assert_eq!(pkg_graph.workspace_closure(&bar), vec![&bar]);
+
+        // Only the external dependencies that `foo` declares are resolved, the
+        // ones from `bar` are left to its own call
+        let resolved = pkg_graph.resolved_external_dependencies(&foo).unwrap();
+        assert_eq!(
+            resolved,
+            vec![
+                turborepo_lockfiles::ResolvedDependency {
+                    name: "a".into(),
+                    specifier: "1".into(),
+                    package: turborepo_lockfiles::Package::new("key:a", "1"),
+                    parent: None,
+                },
+                turborepo_lockfiles::ResolvedDependency {
+                    name: "c".into(),
+                    specifier: "1".into(),
+                    package: turborepo_lockfiles::Package::new("key:c", "1"),
+                    parent: Some("key:a".into()),
+                },
+            ]
+        );
+        assert!(
+            pkg_graph
+                .resolved_external_dependencies(&PackageName::from("missing"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[tokio::test]
     async fn test_circular_dependency() {
         let root =
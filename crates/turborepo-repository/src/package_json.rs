@@ -4,7 +4,7 @@ use std::{
 };
 
 use anyhow::Result;
-use biome_deserialize::{Text, json::deserialize_from_json_str};
+use biome_deserialize::{Deserializable, Text, json::deserialize_from_json_str};
 use biome_deserialize_macros::Deserializable;
 use biome_diagnostics::DiagnosticExt;
 use biome_json_parser::JsonParserOptions;
@@ -14,6 +14,8 @@ use turbopath::{AbsoluteSystemPath, RelativeUnixPathBuf};
 use turborepo_errors::{ParseDiagnostic, Spanned, WithMetadata};
 use turborepo_unescape::UnescapedString;
 
+pub const DENO_JSON: &str = "deno.json";
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PackageJson {
@@ -77,6 +79,21 @@ pub struct RawPnpmConfig {
     pub other: BTreeMap<Text, serde_json::Value>,
 }
 
+/// A Deno workspace member's `deno.json`, which we map onto a package.json so
+/// the rest of turbo doesn't need to know about Deno.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserializable)]
+pub struct RawDenoJson {
+    pub name: Option<Spanned<UnescapedString>>,
+    pub version: Option<UnescapedString>,
+    // Tasks are either a command or an object with a `command` field
+    pub tasks: BTreeMap<String, serde_json::Value>,
+    pub imports: BTreeMap<String, UnescapedString>,
+    // Either a list of members or an object with a `members` field
+    pub workspace: Option<serde_json::Value>,
+    #[deserializable(rest)]
+    pub other: BTreeMap<Text, serde_json::Value>,
+}
+
 #[derive(Debug, thiserror::Error, Diagnostic)]
 pub enum Error {
     #[error("Unable to read package.json: {0}")]
@@ -88,6 +105,20 @@ pub enum Error {
     Parse(#[related] Vec<ParseDiagnostic>),
 }
 
+impl WithMetadata for RawDenoJson {
+    fn add_text(&mut self, text: Arc<str>) {
+        if let Some(ref mut name) = self.name {
+            name.add_text(text);
+        }
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        if let Some(ref mut name) = self.name {
+            name.add_path(path);
+        }
+    }
+}
+
 impl WithMetadata for RawPackageJson {
     fn add_text(&mut self, text: Arc<str>) {
         if let Some(ref mut package_manager) = self.package_manager {
@@ -157,6 +188,146 @@ impl From<RawPnpmConfig> for PnpmConfig {
     }
 }
 
+impl From<RawDenoJson> for PackageJson {
+    fn from(raw: RawDenoJson) -> Self {
+        let scripts = raw
+            .tasks
+            .into_iter()
+            .filter_map(|(name, task)| {
+                let command = match task {
+                    serde_json::Value::String(command) => command,
+                    serde_json::Value::Object(mut task) => match task.remove("command")? {
+                        serde_json::Value::String(command) => command,
+                        _ => return None,
+                    },
+                    _ => return None,
+                };
+                Some((name, Spanned::new(command)))
+            })
+            .collect();
+        // Only registry imports are dependencies, URL and path imports are left
+        // for Deno to resolve.
+        let dependencies: BTreeMap<_, _> = raw
+            .imports
+            .values()
+            .filter_map(|specifier| deno_dependency(specifier.as_str()))
+            .collect();
+
+        Self {
+            name: raw.name.map(|s| s.map(|s| s.into())),
+            version: raw.version.map(|s| s.into()),
+            dependencies: (!dependencies.is_empty()).then_some(dependencies),
+            scripts,
+            other: raw
+                .other
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl RawDenoJson {
+    pub fn load(path: &AbsoluteSystemPath) -> Result<RawDenoJson, Error> {
+        tracing::trace!("loading deno.json from {}", path);
+        let contents = path.read_to_string()?;
+        Self::load_from_str(&contents, path.as_str())
+    }
+
+    pub fn load_from_str(contents: &str, path: &str) -> Result<RawDenoJson, Error> {
+        // deno.json allows comments and trailing commas
+        parse(
+            contents,
+            path,
+            JsonParserOptions::default()
+                .with_allow_comments()
+                .with_allow_trailing_commas(),
+        )
+    }
+
+    /// Returns the workspace member globs if this is a workspace root
+    pub fn workspace_members(&self) -> Option<Vec<String>> {
+        let members = match self.workspace.as_ref()? {
+            serde_json::Value::Array(members) => members,
+            serde_json::Value::Object(workspace) => workspace.get("members")?.as_array()?,
+            _ => return None,
+        };
+        Some(
+            members
+                .iter()
+                .filter_map(|member| member.as_str())
+                .map(|member| {
+                    member
+                        .strip_prefix("./")
+                        .unwrap_or(member)
+                        .trim_end_matches('/')
+                        .to_string()
+                })
+                .collect(),
+        )
+    }
+}
+
+// Converts a `npm:` or `jsr:` import specifier into a package name and version.
+// JSR versions keep their protocol so they aren't mistaken for npm packages.
+fn deno_dependency(specifier: &str) -> Option<(String, String)> {
+    let (protocol, package) = specifier.split_once(':')?;
+    if protocol != "npm" && protocol != "jsr" {
+        return None;
+    }
+    let package = package.trim_start_matches('/');
+    // Skip over the leading `@` of a scoped package when looking for the version
+    let (name, version) = match package.get(1..)?.find('@') {
+        Some(idx) => (&package[..=idx], &package[idx + 2..]),
+        None => {
+            let segments = if package.starts_with('@') { 2 } else { 1 };
+            let end = package
+                .match_indices('/')
+                .nth(segments - 1)
+                .map_or(package.len(), |(idx, _)| idx);
+            (&package[..end], "*")
+        }
+    };
+    // Imports may point at a subpath of a package e.g. `jsr:@std/path@1/posix`
+    let version = version.split('/').next().unwrap_or(version);
+    let version = match protocol {
+        "jsr" => format!("jsr:{version}"),
+        _ => version.to_string(),
+    };
+    Some((name.to_string(), version))
+}
+
+fn parse<T: Deserializable + WithMetadata>(
+    contents: &str,
+    path: &str,
+    options: JsonParserOptions,
+) -> Result<T, Error> {
+    let (result, errors): (Option<T>, _) =
+        deserialize_from_json_str(contents, options, path).consume();
+    if !errors.is_empty() {
+        return Err(Error::Parse(
+            errors
+                .into_iter()
+                .map(|d| {
+                    d.with_file_source_code(contents)
+                        .with_file_path(path)
+                        .as_ref()
+                        .into()
+                })
+                .collect(),
+        ));
+    }
+
+    // We expect a result if there are no errors
+    let mut result = result.expect("no parse errors produced but no result");
+
+    result.add_path(path.into());
+    result.add_text(contents.into());
+
+    Ok(result)
+}
+
 impl PackageJson {
     pub fn load(path: &AbsoluteSystemPath) -> Result<PackageJson, Error> {
         tracing::trace!("loading package.json from {}", path);
@@ -164,29 +335,17 @@ impl PackageJson {
         Self::load_from_str(&contents, path.as_str())
     }
 
-    pub fn load_from_str(contents: &str, path: &str) -> Result<PackageJson, Error> {
-        let (result, errors): (Option<RawPackageJson>, _) =
-            deserialize_from_json_str(contents, JsonParserOptions::default(), path).consume();
-        if !errors.is_empty() {
-            return Err(Error::Parse(
-                errors
-                    .into_iter()
-                    .map(|d| {
-                        d.with_file_source_code(contents)
-                            .with_file_path(path)
-                            .as_ref()
-                            .into()
-                    })
-                    .collect(),
-            ));
+    /// Loads a workspace manifest, reading `deno.json` files as their
+    /// package.json equivalent.
+    pub fn load_manifest(path: &AbsoluteSystemPath) -> Result<PackageJson, Error> {
+        match path.file_name() {
+            Some(DENO_JSON) => Ok(RawDenoJson::load(path)?.into()),
+            _ => Self::load(path),
         }
+    }
 
-        // We expect a result if there are no errors
-        let mut package_json = result.expect("no parse errors produced but no result");
-
-        package_json.add_path(path.into());
-        package_json.add_text(contents.into());
-
+    pub fn load_from_str(contents: &str, path: &str) -> Result<PackageJson, Error> {
+        let package_json: RawPackageJson = parse(contents, path, JsonParserOptions::default())?;
         Ok(package_json.into())
     }
 
@@ -252,4 +411,74 @@ mod test {
         let actual = serde_json::to_value(package_json).unwrap();
         assert_eq!(actual, json);
     }
+
+    #[test_case("npm:chalk@^5", Some(("chalk", "^5")) ; "npm")]
+    #[test_case("npm:@types/node@22", Some(("@types/node", "22")) ; "scoped npm")]
+    #[test_case("npm:chalk", Some(("chalk", "*")) ; "npm without version")]
+    #[test_case("jsr:@std/assert@^1.0.0", Some(("@std/assert", "jsr:^1.0.0")) ; "jsr")]
+    #[test_case("jsr:@std/path@1/posix", Some(("@std/path", "jsr:1")) ; "jsr subpath")]
+    #[test_case("jsr:@std/path/posix", Some(("@std/path", "jsr:*")) ; "jsr subpath without version")]
+    #[test_case("https://deno.land/std/path/mod.ts", None ; "url")]
+    #[test_case("./src/mod.ts", None ; "relative path")]
+    fn test_deno_dependency(specifier: &str, expected: Option<(&str, &str)>) {
+        assert_eq!(
+            deno_dependency(specifier),
+            expected.map(|(name, version)| (name.to_string(), version.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_deno_json() {
+        let deno_json = RawDenoJson::load_from_str(
+            r#"{
+                // comments are allowed
+                "name": "@acme/api",
+                "version": "1.0.0",
+                "tasks": {
+                    "dev": "deno run --watch main.ts",
+                    "build": { "command": "deno compile main.ts", "description": "compile" },
+                },
+                "imports": {
+                    "@std/assert": "jsr:@std/assert@^1.0.0",
+                    "chalk": "npm:chalk@5",
+                    "utils/": "./utils/"
+                },
+                "exports": "./main.ts"
+            }"#,
+            "deno.json",
+        )
+        .unwrap();
+        assert_eq!(deno_json.workspace_members(), None);
+
+        let package_json = PackageJson::from(deno_json);
+        assert_eq!(
+            package_json.name.as_deref().map(|s| s.as_str()),
+            Some("@acme/api")
+        );
+        assert_eq!(package_json.version.as_deref(), Some("1.0.0"));
+        assert_eq!(
+            package_json.command("dev"),
+            Some("deno run --watch main.ts")
+        );
+        assert_eq!(package_json.command("build"), Some("deno compile main.ts"));
+        assert_eq!(
+            package_json.dependencies,
+            Some(BTreeMap::from([
+                ("@std/assert".to_string(), "jsr:^1.0.0".to_string()),
+                ("chalk".to_string(), "5".to_string()),
+            ]))
+        );
+        assert_eq!(package_json.other.get("exports"), Some(&json!("./main.ts")));
+    }
+
+    #[test_case(json!({"workspace": ["./packages/a", "apps/*/"]}), Some(vec!["packages/a", "apps/*"]) ; "list")]
+    #[test_case(json!({"workspace": {"members": ["./packages/*"]}}), Some(vec!["packages/*"]) ; "object")]
+    #[test_case(json!({"name": "@acme/api"}), None ; "not a workspace")]
+    fn test_deno_workspace_members(json: serde_json::Value, expected: Option<Vec<&str>>) {
+        let deno_json = RawDenoJson::load_from_str(&json.to_string(), "deno.json").unwrap();
+        assert_eq!(
+            deno_json.workspace_members(),
+            expected.map(|members| members.into_iter().map(String::from).collect())
+        );
+    }
 }
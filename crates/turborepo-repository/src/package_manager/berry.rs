@@ -10,6 +10,15 @@ pub fn link_workspace_packages(repo_root: &AbsoluteSystemPath) -> bool {
     yarnrc_config.enable_transparent_workspaces
 }
 
+/// Returns whether dependencies are installed using Plug'n'Play, in which case
+/// there is no `node_modules` directory.
+pub fn uses_pnp(repo_root: &AbsoluteSystemPath) -> bool {
+    let yarnrc_config = yarnrc::YarnRc::from_file(repo_root)
+        .inspect_err(|e| debug!("unable to read yarnrc: {e}"))
+        .unwrap_or_default();
+    yarnrc_config.node_linker == "pnp"
+}
+
 #[cfg(test)]
 mod test {
     use test_case::test_case;
@@ -31,4 +40,20 @@ mod test {
         let actual = link_workspace_packages(repo_root);
         assert_eq!(actual, expected);
     }
+
+    #[test_case(None, true)]
+    #[test_case(Some("pnp"), true)]
+    #[test_case(Some("node-modules"), false)]
+    #[test_case(Some("pnpm"), false)]
+    fn test_uses_pnp(node_linker: Option<&str>, expected: bool) {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::from_std_path(tmpdir.path()).unwrap();
+        if let Some(node_linker) = node_linker {
+            repo_root
+                .join_component(yarnrc::YARNRC_FILENAME)
+                .create_with_contents(format!("nodeLinker: {node_linker}"))
+                .unwrap();
+        }
+        assert_eq!(uses_pnp(repo_root), expected);
+    }
 }
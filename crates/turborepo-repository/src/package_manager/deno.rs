@@ -0,0 +1,121 @@
+use tracing::debug;
+use turbopath::AbsoluteSystemPath;
+
+use crate::{
+    package_json::{DENO_JSON, RawDenoJson},
+    package_manager::{Error, PackageManager},
+};
+
+pub const LOCKFILE: &str = "deno.lock";
+pub const WORKSPACE_CONFIGURATION_PATH: &str = DENO_JSON;
+
+pub struct DenoDetector<'a> {
+    repo_root: &'a AbsoluteSystemPath,
+    found: bool,
+}
+
+impl<'a> DenoDetector<'a> {
+    pub fn new(repo_root: &'a AbsoluteSystemPath) -> Self {
+        Self {
+            repo_root,
+            found: false,
+        }
+    }
+}
+
+impl Iterator for DenoDetector<'_> {
+    type Item = Result<PackageManager, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.found {
+            return None;
+        }
+        self.found = true;
+
+        // A deno.json with workspace members marks a Deno workspace even before
+        // anything has been installed
+        let is_deno = self.repo_root.join_component(LOCKFILE).exists()
+            || get_configured_workspace_globs(self.repo_root).is_some();
+        is_deno.then_some(Ok(PackageManager::Deno))
+    }
+}
+
+pub fn get_configured_workspace_globs(repo_root: &AbsoluteSystemPath) -> Option<Vec<String>> {
+    let deno_json = RawDenoJson::load(&repo_root.join_component(WORKSPACE_CONFIGURATION_PATH))
+        .inspect_err(|e| debug!("unable to read deno.json: {e}"))
+        .ok()?;
+    deno_json
+        .workspace_members()
+        .filter(|members| !members.is_empty())
+}
+
+pub fn get_default_exclusions() -> &'static [&'static str] {
+    ["**/node_modules/**", "**/.git"].as_slice()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use tempfile::tempdir;
+    use turbopath::AbsoluteSystemPathBuf;
+
+    use super::*;
+
+    #[test]
+    fn test_detect_deno_lockfile() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+
+        repo_root_path.join_component(LOCKFILE).create()?;
+        let package_manager = PackageManager::detect_package_manager(&repo_root_path)?;
+        assert_eq!(package_manager, PackageManager::Deno);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_deno_workspace() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+
+        repo_root_path
+            .join_component(WORKSPACE_CONFIGURATION_PATH)
+            .create_with_contents(r#"{ "workspace": ["./packages/*"] }"#)?;
+        let package_manager = PackageManager::detect_package_manager(&repo_root_path)?;
+        assert_eq!(package_manager, PackageManager::Deno);
+        assert_eq!(
+            get_configured_workspace_globs(&repo_root_path),
+            Some(vec!["packages/*".to_string()])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_npm_with_stray_deno_lockfile() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+
+        repo_root_path
+            .join_component(crate::package_manager::npm::LOCKFILE)
+            .create()?;
+        repo_root_path.join_component(LOCKFILE).create()?;
+        let package_manager = PackageManager::detect_package_manager(&repo_root_path)?;
+        assert_eq!(package_manager, PackageManager::Npm);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deno_json_without_workspace() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+
+        repo_root_path
+            .join_component(WORKSPACE_CONFIGURATION_PATH)
+            .create_with_contents(r#"{ "tasks": { "dev": "deno run main.ts" } }"#)?;
+        assert!(DenoDetector::new(&repo_root_path).next().is_none());
+
+        Ok(())
+    }
+}
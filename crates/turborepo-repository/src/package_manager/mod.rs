@@ -1,5 +1,6 @@
 pub mod berry;
 pub mod bun;
+pub mod deno;
 pub mod npm;
 pub mod npmrc;
 pub mod pnpm;
@@ -13,6 +14,7 @@ use std::{
 };
 
 use bun::BunDetector;
+use deno::DenoDetector;
 use itertools::{Either, Itertools};
 use lazy_regex::{Lazy, lazy_regex};
 use miette::{Diagnostic, NamedSource, SourceSpan};
@@ -71,6 +73,7 @@ pub enum PackageManager {
     Pnpm6,
     Yarn,
     Bun,
+    Deno,
 }
 
 #[derive(Debug, Error)]
@@ -108,6 +111,10 @@ impl Display for MissingWorkspaceError {
                 "package.json: no workspaces found. Turborepo requires bun workspaces to be \
                  defined in the root package.json"
             }
+            PackageManager::Deno => {
+                "deno.json: no workspace members found. Turborepo requires Deno workspaces to be \
+                 defined in the root deno.json"
+            }
         };
         write!(f, "{err}")
     }
@@ -197,8 +204,9 @@ impl From<std::convert::Infallible> for Error {
     }
 }
 
-static PACKAGE_MANAGER_PATTERN: Lazy<Regex> =
-    lazy_regex!(r"(?P<manager>bun|npm|pnpm|yarn)@(?P<version>\d+\.\d+\.\d+(-.+)?|https?://.+)");
+static PACKAGE_MANAGER_PATTERN: Lazy<Regex> = lazy_regex!(
+    r"(?P<manager>bun|deno|npm|pnpm|yarn)@(?P<version>\d+\.\d+\.\d+(-.+)?|https?://.+)"
+);
 
 impl PackageManager {
     pub fn supported_managers() -> &'static [Self] {
@@ -210,6 +218,7 @@ impl PackageManager {
             Self::Yarn,
             Self::Berry,
             Self::Bun,
+            Self::Deno,
         ]
         .as_slice()
     }
@@ -223,6 +232,7 @@ impl PackageManager {
             PackageManager::Pnpm9 => "pnpm9",
             PackageManager::Yarn => "yarn",
             PackageManager::Bun => "bun",
+            PackageManager::Deno => "deno",
         }
     }
 
@@ -232,6 +242,36 @@ impl PackageManager {
             PackageManager::Pnpm | PackageManager::Pnpm6 | PackageManager::Pnpm9 => "pnpm",
             PackageManager::Yarn | PackageManager::Berry => "yarn",
             PackageManager::Bun => "bun",
+            PackageManager::Deno => "deno",
+        }
+    }
+
+    /// The subcommand used to run a package's script e.g. `npm run build`
+    pub fn run_subcommand(&self) -> &'static str {
+        match self {
+            PackageManager::Deno => "task",
+            PackageManager::Npm
+            | PackageManager::Pnpm
+            | PackageManager::Pnpm6
+            | PackageManager::Pnpm9
+            | PackageManager::Yarn
+            | PackageManager::Berry
+            | PackageManager::Bun => "run",
+        }
+    }
+
+    /// The files that can define a workspace package, in order of precedence
+    pub fn manifest_names(&self) -> &'static [&'static str] {
+        match self {
+            // Deno workspace members can use either a deno.json or a package.json
+            PackageManager::Deno => &[package_json::DENO_JSON, "package.json"],
+            PackageManager::Npm
+            | PackageManager::Pnpm
+            | PackageManager::Pnpm6
+            | PackageManager::Pnpm9
+            | PackageManager::Yarn
+            | PackageManager::Berry
+            | PackageManager::Bun => &["package.json"],
         }
     }
 
@@ -250,7 +290,7 @@ impl PackageManager {
                 .for_each(|inclusion| exclusions.push(format!("{inclusion}/node_modules/**")));
         }
 
-        let globs = WorkspaceGlobs::new(inclusions, exclusions)?;
+        let globs = WorkspaceGlobs::with_manifests(inclusions, exclusions, self.manifest_names())?;
         Ok(globs)
     }
 
@@ -263,6 +303,7 @@ impl PackageManager {
             PackageManager::Bun => ["**/node_modules", "**/.git"].as_slice(),
             PackageManager::Berry => ["**/node_modules", "**/.git", "**/.yarn"].as_slice(),
             PackageManager::Yarn => [].as_slice(), // yarn does its own handling above
+            PackageManager::Deno => deno::get_default_exclusions(),
         };
         ignores.iter().map(|s| s.to_string())
     }
@@ -278,6 +319,8 @@ impl PackageManager {
                 pnpm::get_configured_workspace_globs(root_path)
                     .ok_or_else(|| Error::Workspace(MissingWorkspaceError::from(self.clone())))?
             }
+            PackageManager::Deno => deno::get_configured_workspace_globs(root_path)
+                .ok_or_else(|| Error::Workspace(MissingWorkspaceError::from(self.clone())))?,
             PackageManager::Berry
             | PackageManager::Npm
             | PackageManager::Yarn
@@ -329,7 +372,11 @@ impl PackageManager {
         pkg: &PackageJson,
     ) -> Result<Self, Error> {
         let Some(package_manager) = &pkg.package_manager else {
-            return Err(Error::MissingPackageManager);
+            // Deno doesn't use the `packageManager` field so we look for its workspace
+            // configuration instead
+            return DenoDetector::new(repo_root)
+                .next()
+                .ok_or(Error::MissingPackageManager)?;
         };
 
         let (manager, version) = Self::parse_package_manager_string(package_manager)?;
@@ -338,6 +385,7 @@ impl PackageManager {
             match manager {
                 "npm" => Ok(PackageManager::Npm),
                 "bun" => Ok(PackageManager::Bun),
+                "deno" => Ok(PackageManager::Deno),
                 "yarn" => Ok(YarnDetector::new(repo_root)
                     .next()
                     .ok_or_else(|| Error::MissingPackageManager)??),
@@ -360,6 +408,7 @@ impl PackageManager {
             match manager {
                 "npm" => Ok(PackageManager::Npm),
                 "bun" => Ok(PackageManager::Bun),
+                "deno" => Ok(PackageManager::Deno),
                 "yarn" => Ok(YarnDetector::detect_berry_or_yarn(&version)?),
                 "pnpm" => Ok(PnpmDetector::detect_pnpm6_or_pnpm(&version)?),
                 _ => unreachable!(
@@ -372,11 +421,17 @@ impl PackageManager {
     /// Try to detect package manager based on configuration files and binaries
     /// installed on the system.
     pub fn detect_package_manager(repo_root: &AbsoluteSystemPath) -> Result<Self, Error> {
-        let detected_package_managers = PnpmDetector::new(repo_root)
+        let mut detected_package_managers = PnpmDetector::new(repo_root)
             .chain(NpmDetector::new(repo_root))
             .chain(YarnDetector::new(repo_root))
             .chain(BunDetector::new(repo_root))
             .collect::<Result<Vec<_>, Error>>()?;
+        // Running Deno in a repository that uses another package manager can
+        // leave a stray deno.lock behind, so Deno is only detected on its own
+        if detected_package_managers.is_empty() {
+            detected_package_managers =
+                DenoDetector::new(repo_root).collect::<Result<Vec<_>, Error>>()?;
+        }
 
         match detected_package_managers.as_slice() {
             [] => Err(NoPackageManager.into()),
@@ -432,6 +487,7 @@ impl PackageManager {
             PackageManager::Bun => bun::LOCKFILE,
             PackageManager::Pnpm | PackageManager::Pnpm6 | PackageManager::Pnpm9 => pnpm::LOCKFILE,
             PackageManager::Yarn | PackageManager::Berry => yarn::LOCKFILE,
+            PackageManager::Deno => deno::LOCKFILE,
         }
     }
 
@@ -440,6 +496,7 @@ impl PackageManager {
             PackageManager::Pnpm | PackageManager::Pnpm6 | PackageManager::Pnpm9 => {
                 Some(pnpm::WORKSPACE_CONFIGURATION_PATH)
             }
+            PackageManager::Deno => Some(deno::WORKSPACE_CONFIGURATION_PATH),
             PackageManager::Npm
             | PackageManager::Berry
             | PackageManager::Yarn
@@ -477,6 +534,7 @@ impl PackageManager {
             PackageManager::Bun => {
                 Box::new(turborepo_lockfiles::BunLockfile::from_bytes(contents)?)
             }
+            PackageManager::Deno => Box::new(turborepo_lockfiles::DenoLockfile::load(contents)?),
             PackageManager::Berry => Box::new(turborepo_lockfiles::BerryLockfile::load(
                 contents,
                 Some(turborepo_lockfiles::BerryManifest::with_resolutions(
@@ -501,8 +559,11 @@ impl PackageManager {
             PackageManager::Pnpm9 | PackageManager::Pnpm6 | PackageManager::Pnpm => {
                 pnpm::prune_patches(package_json, patches, repo_root)
             }
-            PackageManager::Yarn | PackageManager::Npm | PackageManager::Bun => {
-                unreachable!("bun, npm, and yarn 1 don't have a concept of patches")
+            PackageManager::Yarn
+            | PackageManager::Npm
+            | PackageManager::Bun
+            | PackageManager::Deno => {
+                unreachable!("bun, deno, npm, and yarn 1 don't have a concept of patches")
             }
         }
     }
@@ -525,7 +586,11 @@ impl PackageManager {
                 }
             }
             PackageManager::Npm | PackageManager::Pnpm6 => Some("--"),
-            PackageManager::Pnpm | PackageManager::Pnpm9 | PackageManager::Berry => None,
+            // deno task forwards all arguments to the task
+            PackageManager::Pnpm
+            | PackageManager::Pnpm9
+            | PackageManager::Berry
+            | PackageManager::Deno => None,
         }
     }
 
@@ -543,7 +608,10 @@ impl PackageManager {
                     .expect("attempted to extract pnpm version from non-pnpm package manager");
                 pnpm::link_workspace_packages(pnpm_version, repo_root)
             }
-            PackageManager::Yarn | PackageManager::Bun | PackageManager::Npm => true,
+            PackageManager::Yarn
+            | PackageManager::Bun
+            | PackageManager::Npm
+            | PackageManager::Deno => true,
         }
     }
 }
@@ -617,6 +685,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_deno_manifests() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::from_std_path(tmpdir.path()).unwrap();
+        repo_root
+            .join_component("deno.json")
+            .create_with_contents(r#"{ "workspace": ["./packages/*"] }"#)
+            .unwrap();
+        let manifests = [
+            ["packages", "api", "deno.json"],
+            ["packages", "ui", "package.json"],
+            ["packages", "both", "deno.json"],
+            ["packages", "both", "package.json"],
+        ];
+        for manifest in manifests {
+            let path = repo_root.join_components(&manifest);
+            path.ensure_dir().unwrap();
+            path.create_with_contents("{}").unwrap();
+        }
+
+        let found: HashSet<AbsoluteSystemPathBuf> = PackageManager::Deno
+            .get_package_jsons(repo_root)
+            .unwrap()
+            .collect();
+        let expected = HashSet::from_iter([
+            repo_root.join_components(&["packages", "api", "deno.json"]),
+            repo_root.join_components(&["packages", "ui", "package.json"]),
+            repo_root.join_components(&["packages", "both", "deno.json"]),
+        ]);
+        assert_eq!(found, expected);
+    }
+
     #[test]
     fn test_get_workspace_ignores() {
         let root = repo_root();
@@ -640,6 +740,7 @@ mod tests {
                 PackageManager::Npm => &["**/node_modules/**"],
                 PackageManager::Berry => &["**/node_modules", "**/.git", "**/.yarn"],
                 PackageManager::Bun => &["**/node_modules", "**/.git"],
+                PackageManager::Deno => &["**/node_modules/**", "**/.git"],
                 PackageManager::Yarn => &["apps/*/node_modules/**", "packages/*/node_modules/**"],
                 PackageManager::Pnpm | PackageManager::Pnpm6 | PackageManager::Pnpm9 => &[
                     "**/node_modules/**",
@@ -726,6 +827,13 @@ mod tests {
                 expected_version: "1.0.1".to_owned(),
                 expected_error: false,
             },
+            TestCase {
+                name: "supports deno".to_owned(),
+                package_manager: Spanned::new("deno@2.1.4".to_owned()),
+                expected_manager: "deno".to_owned(),
+                expected_version: "2.1.4".to_owned(),
+                expected_error: false,
+            },
             TestCase {
                 name: "supports custom URL".to_owned(),
                 package_manager: Spanned::new("npm@https://some-npm-fork".to_owned()),
@@ -829,6 +937,7 @@ mod tests {
     #[test_case(PackageManager::Npm)]
     #[test_case(PackageManager::Yarn)]
     #[test_case(PackageManager::Bun)]
+    #[test_case(PackageManager::Deno)]
     fn test_link_workspace_packages_enabled_by_default(pm: PackageManager) {
         let tmpdir = tempfile::tempdir().unwrap();
         let repo_root = AbsoluteSystemPath::from_std_path(tmpdir.path()).unwrap();
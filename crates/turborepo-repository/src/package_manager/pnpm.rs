@@ -192,7 +192,8 @@ impl TryFrom<&'_ PackageManager> for PnpmVersion {
             PackageManager::Berry
             | PackageManager::Yarn
             | PackageManager::Npm
-            | PackageManager::Bun => Err(NotPnpmError {
+            | PackageManager::Bun
+            | PackageManager::Deno => Err(NotPnpmError {
                 package_manager: value.clone(),
             }),
         }
@@ -24,16 +24,26 @@ pub struct YarnRc {
     /// the package in the dependency graph
     #[serde(default = "default_enable_transparent_workspaces")]
     pub enable_transparent_workspaces: bool,
+    /// Used by Yarn(Berry) as `nodeLinker`.
+    /// Determines how packages are installed, when set to `pnp` there is no
+    /// `node_modules` directory to resolve imports against.
+    #[serde(default = "default_node_linker")]
+    pub node_linker: String,
 }
 
 fn default_enable_transparent_workspaces() -> bool {
     true
 }
 
+fn default_node_linker() -> String {
+    "pnp".to_string()
+}
+
 impl Default for YarnRc {
     fn default() -> YarnRc {
         YarnRc {
             enable_transparent_workspaces: default_enable_transparent_workspaces(),
+            node_linker: default_node_linker(),
         }
     }
 }
@@ -65,7 +75,8 @@ mod test {
         assert_eq!(
             empty,
             YarnRc {
-                enable_transparent_workspaces: true
+                enable_transparent_workspaces: true,
+                node_linker: "pnp".to_string(),
             }
         );
     }
@@ -76,18 +87,26 @@ mod test {
         assert_eq!(
             empty,
             YarnRc {
-                enable_transparent_workspaces: false
+                enable_transparent_workspaces: false,
+                node_linker: "pnp".to_string(),
             }
         );
     }
 
+    #[test]
+    fn test_parses_node_linker() {
+        let yarnrc = YarnRc::from_reader(b"nodeLinker: node-modules".as_slice()).unwrap();
+        assert_eq!(yarnrc.node_linker, "node-modules");
+    }
+
     #[test]
     fn test_parses_additional_settings() {
         let empty = YarnRc::from_reader(b"httpProxy: \"http://my-proxy.com\"".as_slice()).unwrap();
         assert_eq!(
             empty,
             YarnRc {
-                enable_transparent_workspaces: true
+                enable_transparent_workspaces: true,
+                node_linker: "pnp".to_string(),
             }
         );
     }
@@ -19,12 +19,16 @@ pub enum Error {
     WalkError(#[from] globwalk::WalkError),
 }
 
+const PACKAGE_JSON: &str = "package.json";
+
 // WorkspaceGlobs is suitable for finding package.json files via globwalk
 #[derive(Clone)]
 pub struct WorkspaceGlobs {
     directory_inclusions: Any<'static>,
     directory_exclusions: Any<'static>,
     package_json_inclusions: Vec<ValidatedGlob>,
+    // Files that mark a directory as a workspace package, in order of precedence
+    manifest_names: &'static [&'static str],
     pub raw_inclusions: Vec<String>,
     pub raw_exclusions: Vec<String>,
     validated_exclusions: Vec<ValidatedGlob>,
@@ -77,6 +81,17 @@ fn any_with_contextual_error(
 
 impl WorkspaceGlobs {
     pub fn new<S: Into<String>>(inclusions: Vec<S>, exclusions: Vec<S>) -> Result<Self, Error> {
+        Self::with_manifests(inclusions, exclusions, &[PACKAGE_JSON])
+    }
+
+    /// Creates globs that find workspace packages by any of `manifest_names`.
+    /// If a package contains more than one of them, the earliest in
+    /// `manifest_names` is used.
+    pub fn with_manifests<S: Into<String>>(
+        inclusions: Vec<S>,
+        exclusions: Vec<S>,
+        manifest_names: &'static [&'static str],
+    ) -> Result<Self, Error> {
         // take ownership of the inputs
         let raw_inclusions: Vec<String> = inclusions
             .into_iter()
@@ -84,13 +99,13 @@ impl WorkspaceGlobs {
             .collect::<Vec<String>>();
         let package_json_inclusions = raw_inclusions
             .iter()
-            .map(|s| {
+            .cartesian_product(manifest_names)
+            .map(|(s, manifest_name)| {
                 let mut s: String = s.clone();
-                if s.ends_with('/') {
-                    s.push_str("package.json");
-                } else {
-                    s.push_str("/package.json");
+                if !s.ends_with('/') {
+                    s.push('/');
                 }
+                s.push_str(manifest_name);
                 ValidatedGlob::from_str(&s)
             })
             .collect::<Result<Vec<ValidatedGlob>, _>>()?;
@@ -120,6 +135,7 @@ impl WorkspaceGlobs {
                 raw_exclusions.clone(),
             )?,
             package_json_inclusions,
+            manifest_names,
             validated_exclusions,
             raw_exclusions,
             raw_inclusions,
@@ -154,7 +170,26 @@ impl WorkspaceGlobs {
             &self.validated_exclusions,
             globwalk::WalkType::Files,
         )?;
-        Ok(files.into_iter())
+
+        // Only keep the manifest with the highest precedence for each package
+        let manifests = files
+            .iter()
+            .filter(|file| {
+                let (Some(package_dir), Some(precedence)) = (
+                    file.parent(),
+                    self.manifest_names
+                        .iter()
+                        .position(|name| file.file_name() == Some(*name)),
+                ) else {
+                    return true;
+                };
+                self.manifest_names[..precedence]
+                    .iter()
+                    .all(|name| !files.contains(&package_dir.join_component(name)))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        Ok(manifests.into_iter())
     }
 }
 
@@ -175,4 +210,22 @@ mod test {
             &["scripts/package.json", "packages/**/package.json"]
         );
     }
+
+    #[test]
+    fn test_workspace_globs_multiple_manifests() {
+        let globs = WorkspaceGlobs::with_manifests(
+            vec!["packages/*"],
+            vec![],
+            &["deno.json", "package.json"],
+        )
+        .unwrap();
+        assert_eq!(
+            &globs
+                .package_json_inclusions
+                .iter()
+                .map(|i| i.as_str())
+                .collect::<Vec<_>>(),
+            &["packages/*/deno.json", "packages/*/package.json"]
+        );
+    }
 }
@@ -119,6 +119,9 @@ pub fn display_update_check(
                 style("pnpm dlx @turbo/codemod@latest update").cyan().bold()
             }
             PackageManager::Bun => style("bunx @turbo/codemod@latest update").cyan().bold(),
+            PackageManager::Deno => style("deno run -A npm:@turbo/codemod@latest update")
+                .cyan()
+                .bold(),
         };
 
         let msg = format!(
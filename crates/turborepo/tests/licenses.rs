@@ -0,0 +1,14 @@
+mod common;
+
+#[test]
+fn test_licenses() -> Result<(), anyhow::Error> {
+    check_json_output!(
+        "external_dependencies",
+        "npm@10.5.0",
+        "licenses",
+        "get licenses" => ["--output=json"],
+        "get licenses of `ui`" => ["ui", "--output=json"],
+    );
+
+    Ok(())
+}
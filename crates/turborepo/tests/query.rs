@@ -132,3 +132,18 @@ fn test_task_queries() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_external_package_queries() -> Result<(), anyhow::Error> {
+    check_json_output!(
+        "external_dependencies",
+        "npm@10.5.0",
+        "query",
+        "get external packages" => ["query { externalPackages { items { name version } } }"],
+        "get duplicate external packages" => ["query { duplicateExternalPackages { items { name versions { items { version } } } } }"],
+        "get dependents of `is-number`" => ["query { externalPackages(name: \"is-number\") { items { version dependents { items { package { name } direct specifier } } } } }"],
+        "get dependencies of `is-odd`" => ["query { externalPackages(name: \"is-odd\") { items { version dependencies { items { name specifier } } } } }"],
+    );
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::setup_fixture;
+
+#[test]
+fn test_sbom() -> Result<(), anyhow::Error> {
+    let tempdir = tempfile::tempdir()?;
+    setup_fixture("external_dependencies", "npm@10.5.0", tempdir.path())?;
+
+    for format in ["cyclonedx-json", "spdx-json"] {
+        let output = assert_cmd::Command::cargo_bin("turbo")?
+            .args(["sbom", "web", "--format", format])
+            .current_dir(tempdir.path())
+            .output()?;
+        println!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+        assert!(output.status.success());
+
+        let mut sbom: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        // Redact the fields that change between runs and turbo versions
+        if format == "cyclonedx-json" {
+            sbom["metadata"]["timestamp"] = "[timestamp]".into();
+            sbom["metadata"]["tools"]["components"][0]["version"] = "[version]".into();
+        } else {
+            sbom["creationInfo"]["created"] = "[timestamp]".into();
+            sbom["creationInfo"]["creators"][0] = "Tool: turbo-[version]".into();
+        }
+
+        insta::assert_json_snapshot!(format!("external_dependencies_web_{format}"), sbom);
+    }
+
+    Ok(())
+}
@@ -60,7 +60,7 @@ export interface WorkspaceSchema extends BaseSchema {
   /**
    * Configuration for `turbo boundaries` that is specific to this package
    */
-  boundaries?: BoundariesConfig;
+  boundaries?: WorkspaceBoundariesConfig;
 }
 
 export interface RootSchema extends BaseSchema {
@@ -114,6 +114,24 @@ export interface RootSchema extends BaseSchema {
    */
   remoteCache?: RemoteCache;
 
+  /**
+   * Configuration options for exporting runs to an OpenTelemetry collector.
+   *
+   * Documentation: https://turborepo.com/docs/reference/configuration#opentelemetry
+   *
+   * @defaultValue `{}`
+   */
+  otel?: OtelOptions;
+
+  /**
+   * Configuration options for the local run history.
+   *
+   * Documentation: https://turborepo.com/docs/reference/configuration#run-history
+   *
+   * @defaultValue `{}`
+   */
+  runHistory?: RunHistoryOptions;
+
   /**
    * Enable use of the UI for `turbo`.
    *
@@ -182,6 +200,20 @@ export interface RootSchema extends BaseSchema {
    */
   boundaries?: RootBoundariesConfig;
 
+  /**
+   * Dependency version policies checked by `turbo deps check`.
+   *
+   * Documentation: https://turborepo.com/docs/reference/configuration#deps
+   */
+  deps?: DepsConfig;
+
+  /**
+   * License policies checked by `turbo licenses`.
+   *
+   * Documentation: https://turborepo.com/docs/reference/configuration#licenses
+   */
+  licenses?: LicensesConfig;
+
   /**
    * When set to `true`, disables the update notification that appears when a new version of `turbo` is available.
    *
@@ -338,6 +370,16 @@ export interface Pipeline {
    */
   interruptible?: boolean;
 
+  /**
+   * Restrict which caches this task reads from and writes to. Each cache takes
+   * `"r"`, `"w"`, `"rw"` or `""`, and can only take away actions allowed by
+   * `--cache`. A list of rules is applied in order, and rules with a `when`
+   * condition only apply if the given environment variable is set.
+   *
+   * Documentation: https://turborepo.com/docs/reference/configuration#cachemode
+   */
+  cacheMode?: CacheMode | Array<CacheMode>;
+
   /**
    * A list of tasks that will run alongside this task.
    *
@@ -350,6 +392,18 @@ export interface Pipeline {
   with?: Array<string>;
 }
 
+export interface CacheMode {
+  /** Actions allowed for the local cache. */
+  local?: string;
+  /** Actions allowed for the Remote Cache. */
+  remote?: string;
+  /** Only apply this rule when the condition is met. */
+  when?: {
+    /** The name of an environment variable that must be set. */
+    env: string;
+  };
+}
+
 export interface RemoteCache {
   /**
    * Indicates if signature verification is enabled for requests to the remote cache. When
@@ -425,6 +479,48 @@ export interface RemoteCache {
   teamSlug?: string;
 }
 
+export interface OtelOptions {
+  /**
+   * Indicates if runs are exported to the collector. When `false`, nothing
+   * is exported even if an endpoint is configured.
+   *
+   * @defaultValue `true`
+   */
+  enabled?: boolean;
+  /**
+   * The base URL of an OpenTelemetry collector that accepts OTLP over HTTP.
+   * Traces are sent to `/v1/traces` and metrics to `/v1/metrics` under this URL.
+   */
+  endpoint?: string;
+  /**
+   * The `service.name` resource attribute reported to the collector.
+   *
+   * @defaultValue `"turborepo"`
+   */
+  serviceName?: string;
+}
+
+export interface RunHistoryOptions {
+  /**
+   * Indicates if runs are recorded in the local run history.
+   *
+   * @defaultValue `true`
+   */
+  enabled?: boolean;
+  /**
+   * The maximum number of runs to keep. The oldest runs are removed first.
+   *
+   * @defaultValue `100`
+   */
+  maxRuns?: number;
+  /**
+   * The number of days to keep runs for.
+   *
+   * @defaultValue `30`
+   */
+  maxAgeDays?: number;
+}
+
 export interface Permissions {
   /**
    * Lists which tags are allowed. Any tag not included will be banned
@@ -458,6 +554,24 @@ export interface BoundariesConfig {
   implicitDependencies?: Array<string>;
 }
 
+export interface FileRule {
+  /**
+   * Restricts which files in the package the matching files can import.
+   * `allow` and `deny` are lists of globs relative to the package.
+   */
+  imports?: Permissions;
+}
+
+export type FileRulesMap = Record<string, FileRule>;
+
+export interface WorkspaceBoundariesConfig extends BoundariesConfig {
+  /**
+   * Rules for the files in this package, keyed by a glob relative to the
+   * package. Restricts which files in the package the matching files can import.
+   */
+  files?: FileRulesMap;
+}
+
 export interface RootBoundariesConfig extends BoundariesConfig {
   /**
    * The boundaries rules for tags. Restricts which packages
@@ -466,6 +580,56 @@ export interface RootBoundariesConfig extends BoundariesConfig {
   tags?: BoundariesRulesMap;
 }
 
+export interface DepsConfig {
+  /**
+   * Dependencies that every workspace must declare with exactly this version.
+   */
+  pinned?: Record<string, string>;
+  /**
+   * Dependencies whose resolved version must satisfy this semver range.
+   */
+  allowedRanges?: Record<string, string>;
+  /**
+   * When `true`, every workspace must resolve each external dependency to
+   * the same version.
+   *
+   * @defaultValue `false`
+   */
+  singleVersion?: boolean;
+  /**
+   * When `true`, internal packages must be declared with the `workspace:`
+   * protocol.
+   *
+   * @defaultValue `false`
+   */
+  workspaceProtocol?: boolean;
+  /**
+   * Dependencies that are exempt from `singleVersion`.
+   */
+  ignore?: Array<string>;
+  /**
+   * Dependencies that `turbo deps unused` shouldn't report, like tools that
+   * are only used through a config file.
+   */
+  allowUnused?: Array<string>;
+}
+
+export interface LicensesConfig {
+  /**
+   * SPDX license identifiers that dependencies may use. When set, any other
+   * license is reported.
+   */
+  allow?: Array<string>;
+  /**
+   * SPDX license identifiers that dependencies may not use.
+   */
+  deny?: Array<string>;
+  /**
+   * Dependencies that are exempt from the license policies.
+   */
+  ignore?: Array<string>;
+}
+
 export const isRootSchemaV2 = (schema: Schema): schema is RootSchema =>
   !("extends" in schema);
 
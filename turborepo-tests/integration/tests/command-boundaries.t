@@ -4,8 +4,9 @@ Setup
 Ignore all errors
   $ ${TURBO} boundaries --ignore=all
   Checking packages...
-  patching apps(\\|/)my-app(\\|/)(index|types).ts (re)
-  patching apps(\\|/)my-app(\\|/)(index|types).ts (re)
+  patching apps(\\|/)my-app(\\|/)(index.ts|types.ts|styles.css) (re)
+  patching apps(\\|/)my-app(\\|/)(index.ts|types.ts|styles.css) (re)
+  patching apps(\\|/)my-app(\\|/)(index.ts|types.ts|styles.css) (re)
   [1]
 
   $ git diff
@@ -27,6 +28,14 @@ Ignore all errors
    import { walkThePlank } from "module-package";
    
    // Import from a package that is not specified, but we have `@boundaries-ignore` on it
+  diff --git a/apps/my-app/styles.css b/apps/my-app/styles.css
+  index 8d76f98..60b5a02 100644
+  --- a/apps/my-app/styles.css
+  +++ b/apps/my-app/styles.css
+  @@ -1,2 +1,3 @@
+  +/* @boundaries-ignore automatically added by `turbo boundaries --ignore=all` */
+   @import "tailwindcss";
+   @import "./theme.css";
   diff --git a/apps/my-app/types.ts b/apps/my-app/types.ts
   index ce28692..3615d9c 100644
   --- a/apps/my-app/types.ts
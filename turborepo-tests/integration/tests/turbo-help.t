@@ -11,10 +11,12 @@ Test help flag
     bin         Get the path to the Turbo binary
     completion  Generate the autocompletion script for the specified shell
     daemon      Runs the Turborepo background daemon
+    deps        Enforce dependency version policies across workspaces
     generate    Generate a new app / package
     telemetry   Enable or disable anonymous telemetry
     scan        Turbo your monorepo by running a number of 'repo lints' to identify common issues, suggest fixes, and improve performance
     ls          EXPERIMENTAL: List packages in your monorepo
+    licenses    Report the licenses of external dependencies and check them against the policies in turbo.json
     link        Link your local directory to a Vercel organization and enable remote caching
     login       Login to your Vercel account
     logout      Logout to your Vercel account
@@ -22,6 +24,7 @@ Test help flag
     prune       Prepare a subset of your monorepo
     run         Run tasks across projects in your monorepo
     query       Query your monorepo using GraphQL. If no query is provided, spins up a GraphQL server with GraphiQL
+    sbom        Generate a software bill of materials for a package
     watch       Arguments used in run and watch
     unlink      Unlink the current directory from your Vercel organization and disable Remote Caching
   
@@ -92,6 +95,12 @@ Test help flag
             File to write turbo's performance profile output into. All identifying data omitted from the profile
         --summarize [<SUMMARIZE>]
             Generate a summary of the turbo run [possible values: true, false]
+        --compare-to <RUN_ID>
+            Compare this run with a previous run summary from `.turbo/runs`. Pass a run ID, or `last` to use the most recent run
+        --compare-threshold <COMPARE_THRESHOLD>
+            The percentage a task's duration has to change by to be reported by `--compare-to` [default: 10]
+        --fail-on-regression
+            Exit with a non-zero code if `--compare-to` finds regressions
         --parallel
             Execute all tasks in parallel
         --cache-dir <CACHE_DIR>
@@ -135,10 +144,12 @@ Test help flag
     bin         Get the path to the Turbo binary
     completion  Generate the autocompletion script for the specified shell
     daemon      Runs the Turborepo background daemon
+    deps        Enforce dependency version policies across workspaces
     generate    Generate a new app / package
     telemetry   Enable or disable anonymous telemetry
     scan        Turbo your monorepo by running a number of 'repo lints' to identify common issues, suggest fixes, and improve performance
     ls          EXPERIMENTAL: List packages in your monorepo
+    licenses    Report the licenses of external dependencies and check them against the policies in turbo.json
     link        Link your local directory to a Vercel organization and enable remote caching
     login       Login to your Vercel account
     logout      Logout to your Vercel account
@@ -146,6 +157,7 @@ Test help flag
     prune       Prepare a subset of your monorepo
     run         Run tasks across projects in your monorepo
     query       Query your monorepo using GraphQL. If no query is provided, spins up a GraphQL server with GraphiQL
+    sbom        Generate a software bill of materials for a package
     watch       Arguments used in run and watch
     unlink      Unlink the current directory from your Vercel organization and disable Remote Caching
   
@@ -264,6 +276,17 @@ Test help flag
             
             [possible values: true, false]
   
+        --compare-to <RUN_ID>
+            Compare this run with a previous run summary from `.turbo/runs`. Pass a run ID, or `last` to use the most recent run
+  
+        --compare-threshold <COMPARE_THRESHOLD>
+            The percentage a task's duration has to change by to be reported by `--compare-to`
+            
+            [default: 10]
+  
+        --fail-on-regression
+            Exit with a non-zero code if `--compare-to` finds regressions
+  
         --parallel
             Execute all tasks in parallel
   